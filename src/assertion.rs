@@ -0,0 +1,319 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Balance assertions, taken from bank statements.
+//!
+//! Assertions are written in YAML:
+//!
+//! ```yaml
+//! assertions:
+//!   - account: Assets:Bank:Checking
+//!     date: 2024-01-31
+//!     balance: 2657.50
+//! ```
+//!
+//! or in TOML:
+//!
+//! ```toml
+//! [[assertions]]
+//! account = "Assets:Bank:Checking"
+//! date = 2024-01-31
+//! balance = "2657.50"
+//! ```
+//!
+//! The balance of an account on a date includes all its splits posted up to the
+//! end of that day, in the commodity of the account and with the sign used in
+//! the book: liabilities are negative.
+
+use std::{fmt, path::Path};
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    book::{Account, Book, Commodity, Numeric, Split, Transaction},
+    helpers::{deserialize_date, deserialize_numeric},
+};
+
+/// The maximum number of likely culprits reported for a failed assertion.
+const MAX_CULPRITS: usize = 5;
+
+/// A balance assertion.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Assertion {
+    /// The full path of the account.
+    pub account: String,
+    /// The date of the balance.
+    #[serde(deserialize_with = "deserialize_date")]
+    pub date: NaiveDate,
+    /// The expected balance.
+    #[serde(deserialize_with = "deserialize_numeric")]
+    pub balance: Numeric,
+}
+
+/// The content of an assertion file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AssertionFile {
+    /// The assertions.
+    assertions: Vec<Assertion>,
+}
+
+/// Errors that can occur when checking assertions.
+#[derive(Debug, Error)]
+pub enum AssertionError {
+    /// The format of the file is not supported.
+    #[error("unsupported assertion file: {0}")]
+    UnsupportedFormat(String),
+    /// The YAML file is invalid.
+    #[error("invalid assertion file: {0}")]
+    Yaml(#[from] serde_yaml::Error),
+    /// The TOML file is invalid.
+    #[error("invalid assertion file: {0}")]
+    Toml(#[from] toml::de::Error),
+    /// An account does not exist.
+    #[error("unknown account {0}")]
+    UnknownAccount(String),
+}
+
+/// The result of checking an assertion.
+#[derive(Debug)]
+pub struct Outcome<'a> {
+    /// The assertion.
+    pub assertion: &'a Assertion,
+    /// The account of the assertion.
+    pub account: &'a Account,
+    /// The actual balance of the account.
+    pub actual: Numeric,
+    /// The transactions most likely to explain a mismatch.
+    pub culprits: Vec<Culprit<'a>>,
+}
+
+/// A split that may explain a mismatch.
+#[derive(Debug)]
+pub struct Culprit<'a> {
+    /// The transaction of the split.
+    pub transaction: &'a Transaction,
+    /// The split.
+    pub split: &'a Split,
+    /// Why the split is suspected.
+    pub reason: Reason,
+}
+
+/// Why a split may explain a mismatch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Reason {
+    /// The split is posted after the date with the missing amount.
+    PostedAfter,
+    /// The split is posted before the date with the extra amount.
+    Extra,
+    /// The split has the opposite sign of the expected amount.
+    WrongSign,
+    /// The split has two adjacent digits swapped.
+    SwappedDigits,
+    /// The split is close to the date.
+    Nearby,
+}
+
+impl Assertion {
+    /// Checks the assertion against the book.
+    ///
+    /// On a mismatch, the splits of the account posted less than `days` days
+    /// around the date are searched for likely culprits.
+    pub fn check<'a>(
+        &'a self,
+        book: &'a Book,
+        days: i64,
+    ) -> Result<Outcome<'a>, AssertionError> {
+        let account = book.find_account(&self.account).ok_or_else(|| {
+            AssertionError::UnknownAccount(self.account.clone())
+        })?;
+
+        let splits: Vec<_> = book
+            .regular_transactions()
+            .into_iter()
+            .flat_map(|transaction| {
+                transaction
+                    .splits
+                    .iter()
+                    .filter(|split| split.account == account.guid)
+                    .map(move |split| (transaction, split))
+            })
+            .collect();
+
+        let actual = splits
+            .iter()
+            .filter(|(transaction, _)| transaction.date() <= self.date)
+            .map(|(_, split)| split.quantity)
+            .sum();
+
+        let mut outcome = Outcome {
+            assertion: self,
+            account,
+            actual,
+            culprits: vec![],
+        };
+
+        if !outcome.is_ok() {
+            let fraction = book
+                .account_commodity(account.guid)
+                .map_or(100, Commodity::fraction);
+            outcome.culprits = culprits(
+                &splits,
+                self.date,
+                outcome.difference(),
+                days,
+                fraction,
+            );
+        }
+
+        Ok(outcome)
+    }
+}
+
+impl Outcome<'_> {
+    /// Returns the expected balance minus the actual one.
+    pub fn difference(&self) -> Numeric {
+        self.assertion.balance - self.actual
+    }
+
+    /// Returns whether the assertion holds.
+    pub fn is_ok(&self) -> bool {
+        self.difference().is_zero()
+    }
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::PostedAfter => {
+                "posted after the date with the missing amount"
+            }
+            Self::Extra => "duplicated or posted too early",
+            Self::WrongSign => "its sign may be wrong",
+            Self::SwappedDigits => "its digits may be swapped",
+            Self::Nearby => "posted near the date",
+        })
+    }
+}
+
+/// Parses an assertion file, in YAML or TOML given its extension.
+pub fn parse(
+    path: &Path,
+    input: &str,
+) -> Result<Vec<Assertion>, AssertionError> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default();
+
+    let file: AssertionFile = match extension {
+        "yaml" | "yml" => serde_yaml::from_str(input)?,
+        "toml" => toml::from_str(input)?,
+        _ => {
+            return Err(AssertionError::UnsupportedFormat(
+                path.display().to_string(),
+            ))
+        }
+    };
+
+    Ok(file.assertions)
+}
+
+/// Returns the splits most likely to explain a difference, by relevance.
+fn culprits<'a>(
+    splits: &[(&'a Transaction, &'a Split)],
+    date: NaiveDate,
+    difference: Numeric,
+    days: i64,
+    fraction: i128,
+) -> Vec<Culprit<'a>> {
+    let mut culprits: Vec<_> = splits
+        .iter()
+        .filter(|(transaction, _)| {
+            (transaction.date() - date).num_days().abs() <= days
+        })
+        .map(|&(transaction, split)| Culprit {
+            transaction,
+            split,
+            reason: reason(
+                transaction.date() <= date,
+                split.quantity,
+                difference,
+                fraction,
+            ),
+        })
+        .collect();
+
+    culprits.sort_by_key(|culprit| {
+        (
+            culprit.reason,
+            (culprit.transaction.date() - date).num_days().abs(),
+        )
+    });
+    culprits.truncate(MAX_CULPRITS);
+    culprits
+}
+
+/// Returns why a split may explain a difference.
+///
+/// `counted` tells whether the split is included in the balance.
+fn reason(
+    counted: bool,
+    amount: Numeric,
+    difference: Numeric,
+    fraction: i128,
+) -> Reason {
+    if !counted {
+        if amount == difference {
+            Reason::PostedAfter
+        } else {
+            Reason::Nearby
+        }
+    } else if amount == -difference {
+        Reason::Extra
+    } else if amount + amount == -difference {
+        Reason::WrongSign
+    } else if amount.fits_fraction(fraction)
+        && difference.fits_fraction(fraction)
+        && swapped_digits(
+            amount.num_at(fraction),
+            (amount + difference).num_at(fraction),
+        )
+    {
+        Reason::SwappedDigits
+    } else {
+        Reason::Nearby
+    }
+}
+
+/// Returns whether two amounts only differ by two swapped adjacent digits.
+fn swapped_digits(actual: i128, expected: i128) -> bool {
+    if actual.signum() != expected.signum() {
+        return false;
+    }
+
+    let actual = actual.abs().to_string().into_bytes();
+    let expected = expected.abs().to_string().into_bytes();
+
+    actual.len() == expected.len()
+        && (1..actual.len()).any(|index| {
+            let mut swapped = actual.clone();
+            swapped.swap(index - 1, index);
+            swapped != actual && swapped == expected
+        })
+}
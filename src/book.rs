@@ -0,0 +1,287 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The GnuCash book model.
+
+mod account;
+mod budget;
+mod business;
+mod commodity;
+mod draft;
+mod guid;
+mod lot;
+mod numeric;
+mod price;
+mod recurrence;
+mod schedule;
+mod slot;
+mod sqlite;
+mod transaction;
+mod xml;
+
+use std::{collections::BTreeMap, iter};
+
+pub use self::{
+    account::{Account, AccountType, ACCOUNT_SEPARATOR},
+    budget::{Budget, BudgetAmount},
+    business::{
+        Address, AmountType, BillTerm, BillTermType, Business, Contact,
+        Customer, DiscountHow, Employee, Entry, Invoice, Job, Order, Owner,
+        OwnerType, PaymentType, TaxIncluded, TaxTable, TaxTableEntry, Vendor,
+    },
+    commodity::Commodity,
+    draft::{DraftError, PostingDraft, PriceSpec, TransactionDraft},
+    guid::Guid,
+    lot::Lot,
+    numeric::{Numeric, MAX_FRACTION},
+    price::Price,
+    recurrence::{Recurrence, WeekendAdjust},
+    schedule::ScheduledTransaction,
+    slot::{Frame, SlotValue, SLOT_SEPARATOR},
+    sqlite::{Database, DatabaseError},
+    transaction::{post_date, ReconcileState, Split, Transaction},
+    xml::{
+        read_account_template, read_xml, write_account_template, write_xml,
+        AccountTemplate, XmlError,
+    },
+};
+
+/// The slot of the book holding its counters.
+const COUNTERS_SLOT: &str = "counters";
+
+/// A GnuCash book.
+#[derive(Debug, Clone)]
+pub struct Book {
+    /// The GUID of the book.
+    pub guid: Guid,
+    /// The GUID of the root account.
+    pub root_account: Guid,
+    /// The GUID of the root of the scheduled transaction templates.
+    pub root_template: Guid,
+    /// The commodities, by GUID.
+    pub commodities: BTreeMap<Guid, Commodity>,
+    /// The accounts, by GUID.
+    pub accounts: BTreeMap<Guid, Account>,
+    /// The transactions, by GUID.
+    ///
+    /// This includes the templates of scheduled transactions.
+    pub transactions: BTreeMap<Guid, Transaction>,
+    /// The prices, by GUID.
+    pub prices: BTreeMap<Guid, Price>,
+    /// The lots, by GUID.
+    pub lots: BTreeMap<Guid, Lot>,
+    /// The budgets, by GUID.
+    pub budgets: BTreeMap<Guid, Budget>,
+    /// The scheduled transactions, by GUID.
+    pub schedules: BTreeMap<Guid, ScheduledTransaction>,
+    /// The business objects.
+    pub business: Business,
+    /// The slots of the book, like its options and features.
+    pub slots: Frame,
+}
+
+impl Book {
+    /// Returns the commodity with the given GUID.
+    pub fn commodity(&self, guid: Guid) -> Option<&Commodity> {
+        self.commodities.get(&guid)
+    }
+
+    /// Returns the currency with the given ISO code.
+    pub fn currency(&self, mnemonic: &str) -> Option<&Commodity> {
+        self.commodities.values().find(|commodity| {
+            commodity.is_currency() && commodity.mnemonic == mnemonic
+        })
+    }
+
+    /// Returns the account with the given GUID.
+    pub fn account(&self, guid: Guid) -> Option<&Account> {
+        self.accounts.get(&guid)
+    }
+
+    /// Returns the commodity of the account with the given GUID.
+    pub fn account_commodity(&self, guid: Guid) -> Option<&Commodity> {
+        self.account(guid)
+            .and_then(|account| account.commodity)
+            .and_then(|commodity| self.commodity(commodity))
+    }
+
+    /// Returns the children of the given account.
+    pub fn children(&self, parent: Guid) -> impl Iterator<Item = &Account> {
+        self.accounts
+            .values()
+            .filter(move |account| account.parent == Some(parent))
+    }
+
+    /// Returns whether `guid` is `ancestor` or one of its descendants.
+    pub fn is_under(&self, guid: Guid, ancestor: Guid) -> bool {
+        let mut current = self.account(guid);
+
+        while let Some(account) = current {
+            if account.guid == ancestor {
+                return true;
+            }
+
+            current = account.parent.and_then(|parent| self.account(parent));
+        }
+
+        false
+    }
+
+    /// Returns the full path of an account, like `Assets:Bank:Checking`.
+    pub fn account_path(&self, guid: Guid) -> String {
+        let mut names = vec![];
+        let mut current = self.account(guid);
+
+        while let Some(account) = current {
+            if account.parent.is_none() {
+                break;
+            }
+
+            names.push(account.name.as_str());
+            current = account.parent.and_then(|parent| self.account(parent));
+        }
+
+        names.reverse();
+        names.join(&ACCOUNT_SEPARATOR.to_string())
+    }
+
+    /// Returns whether the account is in the template tree.
+    pub fn is_template_account(&self, guid: Guid) -> bool {
+        let mut current = self.account(guid);
+
+        while let Some(account) = current {
+            if account.guid == self.root_template {
+                return true;
+            }
+
+            current = account.parent.and_then(|parent| self.account(parent));
+        }
+
+        false
+    }
+
+    /// Returns whether the transaction is the template of a scheduled
+    /// transaction.
+    pub fn is_template_transaction(&self, transaction: &Transaction) -> bool {
+        transaction
+            .splits
+            .iter()
+            .any(|split| self.is_template_account(split.account))
+    }
+
+    /// Returns the regular transactions, sorted by date.
+    ///
+    /// The templates of scheduled transactions are not included.
+    pub fn regular_transactions(&self) -> Vec<&Transaction> {
+        let mut transactions: Vec<_> = self
+            .transactions
+            .values()
+            .filter(|transaction| !self.is_template_transaction(transaction))
+            .collect();
+
+        transactions.sort_by_key(|transaction| {
+            (transaction.post_date, transaction.enter_date)
+        });
+
+        transactions
+    }
+
+    /// Returns the kind and slots of the object with the given GUID.
+    ///
+    /// Objects without slots in GnuCash, like prices, are not searched for.
+    pub fn slots(&self, guid: Guid) -> Option<(&'static str, &Frame)> {
+        let commodities = self
+            .commodities
+            .values()
+            .map(|commodity| (commodity.guid, "commodity", &commodity.slots));
+        let accounts = self
+            .accounts
+            .values()
+            .map(|account| (account.guid, "account", &account.slots));
+        let transactions = self.transactions.values().map(|transaction| {
+            (transaction.guid, "transaction", &transaction.slots)
+        });
+        let splits = self
+            .transactions
+            .values()
+            .flat_map(|transaction| &transaction.splits)
+            .map(|split| (split.guid, "split", &split.slots));
+        let lots = self.lots.values().map(|lot| (lot.guid, "lot", &lot.slots));
+        let budgets = self
+            .budgets
+            .values()
+            .map(|budget| (budget.guid, "budget", &budget.slots));
+        let schedules = self.schedules.values().map(|schedule| {
+            (schedule.guid, "scheduled transaction", &schedule.slots)
+        });
+
+        iter::once((self.guid, "book", &self.slots))
+            .chain(commodities)
+            .chain(accounts)
+            .chain(transactions)
+            .chain(splits)
+            .chain(lots)
+            .chain(budgets)
+            .chain(schedules)
+            .chain(self.business.slots())
+            .find(|(object, _, _)| *object == guid)
+            .map(|(_, kind, slots)| (kind, slots))
+    }
+
+    /// Returns the prices, sorted by date.
+    pub fn sorted_prices(&self) -> Vec<&Price> {
+        let mut prices: Vec<_> = self.prices.values().collect();
+        prices.sort_by_key(|price| price.date);
+        prices
+    }
+
+    /// Returns the last value of a counter of the book, like `gncInvoice`.
+    ///
+    /// GnuCash uses these counters to generate the IDs of business objects.
+    pub fn counter(&self, name: &str) -> i64 {
+        match self.slots.get(&counter_slot(name)) {
+            Some(SlotValue::Int64(counter)) => *counter,
+            _ => 0,
+        }
+    }
+
+    /// Sets the last value of a counter of the book.
+    ///
+    /// A counters slot which is not a frame is left untouched.
+    pub fn set_counter(&mut self, name: &str, value: i64) {
+        if matches!(
+            self.slots.get(COUNTERS_SLOT),
+            None | Some(SlotValue::Frame(_))
+        ) {
+            self.slots
+                .insert(&counter_slot(name), SlotValue::Int64(value));
+        }
+    }
+
+    /// Finds an account given its full path, like `Assets:Bank:Checking`.
+    pub fn find_account(&self, path: &str) -> Option<&Account> {
+        path.split(ACCOUNT_SEPARATOR)
+            .try_fold(self.account(self.root_account)?, |parent, name| {
+                self.children(parent.guid)
+                    .find(|account| account.name == name)
+            })
+            .filter(|account| account.guid != self.root_account)
+    }
+}
+
+/// Returns the path of the slot of a counter.
+fn counter_slot(name: &str) -> String {
+    format!("{COUNTERS_SLOT}{SLOT_SEPARATOR}{name}")
+}
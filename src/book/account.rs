@@ -0,0 +1,183 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! GnuCash accounts.
+
+use std::{fmt, str::FromStr};
+
+use thiserror::Error;
+
+use super::{Frame, Guid};
+
+/// The separator between account names in a full account path.
+pub const ACCOUNT_SEPARATOR: char = ':';
+
+/// An account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Account {
+    /// The GUID of the account.
+    pub guid: Guid,
+    /// The name of the account.
+    pub name: String,
+    /// The type of the account.
+    pub kind: AccountType,
+    /// The commodity of the account.
+    ///
+    /// Only the root accounts have no commodity.
+    pub commodity: Option<Guid>,
+    /// The smallest fraction of the commodity used in the account.
+    pub commodity_scu: i64,
+    /// Whether the account uses a non-standard SCU.
+    pub non_std_scu: bool,
+    /// The parent of the account.
+    ///
+    /// Only the root accounts have no parent.
+    pub parent: Option<Guid>,
+    /// The account code.
+    pub code: Option<String>,
+    /// The description of the account.
+    pub description: Option<String>,
+    /// Whether the account is hidden.
+    pub hidden: bool,
+    /// Whether the account is a placeholder.
+    pub placeholder: bool,
+    /// The slots of the account.
+    pub slots: Frame,
+}
+
+/// The type of an account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AccountType {
+    /// A root account.
+    Root,
+    /// A bank account.
+    Bank,
+    /// A cash account.
+    Cash,
+    /// A generic asset account.
+    Asset,
+    /// A credit card account.
+    Credit,
+    /// A generic liability account.
+    Liability,
+    /// A stock account.
+    Stock,
+    /// A mutual fund account.
+    Mutual,
+    /// A currency account (deprecated by GnuCash).
+    Currency,
+    /// An income account.
+    Income,
+    /// An expense account.
+    Expense,
+    /// An equity account.
+    Equity,
+    /// An accounts receivable account.
+    Receivable,
+    /// An accounts payable account.
+    Payable,
+    /// A trading account.
+    Trading,
+    /// A checking account (legacy).
+    Checking,
+    /// A savings account (legacy).
+    Savings,
+    /// A money market account (legacy).
+    MoneyMarket,
+    /// A credit line account (legacy).
+    CreditLine,
+}
+
+/// An error that can occur when parsing an account type.
+#[derive(Debug, Error)]
+#[error("invalid account type: {0:?}")]
+pub struct ParseAccountTypeError(String);
+
+impl AccountType {
+    /// Returns the name of the account type as stored by GnuCash.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Root => "ROOT",
+            Self::Bank => "BANK",
+            Self::Cash => "CASH",
+            Self::Asset => "ASSET",
+            Self::Credit => "CREDIT",
+            Self::Liability => "LIABILITY",
+            Self::Stock => "STOCK",
+            Self::Mutual => "MUTUAL",
+            Self::Currency => "CURRENCY",
+            Self::Income => "INCOME",
+            Self::Expense => "EXPENSE",
+            Self::Equity => "EQUITY",
+            Self::Receivable => "RECEIVABLE",
+            Self::Payable => "PAYABLE",
+            Self::Trading => "TRADING",
+            Self::Checking => "CHECKING",
+            Self::Savings => "SAVINGS",
+            Self::MoneyMarket => "MONEYMRKT",
+            Self::CreditLine => "CREDITLINE",
+        }
+    }
+
+    /// Returns whether the type is an asset or liability type.
+    ///
+    /// Equity and trading accounts are not included, as they only balance the
+    /// other accounts.
+    pub const fn is_balance_sheet(self) -> bool {
+        !matches!(
+            self,
+            Self::Root
+                | Self::Income
+                | Self::Expense
+                | Self::Equity
+                | Self::Trading
+        )
+    }
+}
+
+impl FromStr for AccountType {
+    type Err = ParseAccountTypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ROOT" => Ok(Self::Root),
+            "BANK" => Ok(Self::Bank),
+            "CASH" => Ok(Self::Cash),
+            "ASSET" => Ok(Self::Asset),
+            "CREDIT" => Ok(Self::Credit),
+            "LIABILITY" => Ok(Self::Liability),
+            "STOCK" => Ok(Self::Stock),
+            "MUTUAL" => Ok(Self::Mutual),
+            "CURRENCY" => Ok(Self::Currency),
+            "INCOME" => Ok(Self::Income),
+            "EXPENSE" => Ok(Self::Expense),
+            "EQUITY" => Ok(Self::Equity),
+            "RECEIVABLE" => Ok(Self::Receivable),
+            "PAYABLE" => Ok(Self::Payable),
+            "TRADING" => Ok(Self::Trading),
+            "CHECKING" => Ok(Self::Checking),
+            "SAVINGS" => Ok(Self::Savings),
+            "MONEYMRKT" => Ok(Self::MoneyMarket),
+            "CREDITLINE" => Ok(Self::CreditLine),
+            _ => Err(ParseAccountTypeError(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for AccountType {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
@@ -0,0 +1,48 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! GnuCash budgets.
+
+use super::{Frame, Guid, Numeric, Recurrence};
+
+/// A budget.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Budget {
+    /// The GUID of the budget.
+    pub guid: Guid,
+    /// The name of the budget.
+    pub name: String,
+    /// The description of the budget.
+    pub description: Option<String>,
+    /// The number of periods of the budget.
+    pub num_periods: i64,
+    /// The recurrence defining the periods.
+    pub recurrence: Recurrence,
+    /// The budgeted amounts, sorted by account and period.
+    pub amounts: Vec<BudgetAmount>,
+    /// The slots of the budget.
+    pub slots: Frame,
+}
+
+/// An amount budgeted for an account on a period.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BudgetAmount {
+    /// The account.
+    pub account: Guid,
+    /// The number of the period, starting at 0.
+    pub period: i64,
+    /// The amount.
+    pub amount: Numeric,
+}
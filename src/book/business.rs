@@ -0,0 +1,987 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! GnuCash business objects.
+
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use chrono::{DateTime, Datelike as _, Days, Months, NaiveDate, Utc};
+use thiserror::Error;
+
+use super::{recurrence::last_day_of_month, Frame, Guid, Numeric};
+
+/// The business objects of a book.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Business {
+    /// The billing terms, by GUID.
+    pub bill_terms: BTreeMap<Guid, BillTerm>,
+    /// The tax tables, by GUID.
+    pub tax_tables: BTreeMap<Guid, TaxTable>,
+    /// The customers, by GUID.
+    pub customers: BTreeMap<Guid, Customer>,
+    /// The vendors, by GUID.
+    pub vendors: BTreeMap<Guid, Vendor>,
+    /// The employees, by GUID.
+    pub employees: BTreeMap<Guid, Employee>,
+    /// The jobs, by GUID.
+    pub jobs: BTreeMap<Guid, Job>,
+    /// The invoices, bills and expense vouchers, by GUID.
+    pub invoices: BTreeMap<Guid, Invoice>,
+    /// The entries of invoices and orders, by GUID.
+    pub entries: BTreeMap<Guid, Entry>,
+    /// The orders, by GUID.
+    pub orders: BTreeMap<Guid, Order>,
+}
+
+/// A postal address with contact information.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Address {
+    /// The name of the addressee.
+    pub name: Option<String>,
+    /// The first line of the address.
+    pub addr1: Option<String>,
+    /// The second line of the address.
+    pub addr2: Option<String>,
+    /// The third line of the address.
+    pub addr3: Option<String>,
+    /// The fourth line of the address.
+    pub addr4: Option<String>,
+    /// The phone number.
+    pub phone: Option<String>,
+    /// The fax number.
+    pub fax: Option<String>,
+    /// The email address.
+    pub email: Option<String>,
+}
+
+/// The owner of a job, invoice or order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Owner {
+    /// The type of the owner.
+    pub kind: OwnerType,
+    /// The GUID of the owner.
+    pub guid: Guid,
+}
+
+/// Billing terms, defining when an invoice is due.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BillTerm {
+    /// The GUID of the billing terms.
+    pub guid: Guid,
+    /// The name of the billing terms.
+    pub name: String,
+    /// The description of the billing terms.
+    pub description: String,
+    /// The number of objects using the billing terms.
+    pub refcount: i64,
+    /// Whether the billing terms are hidden.
+    pub invisible: bool,
+    /// The billing terms this one is a copy of.
+    pub parent: Option<Guid>,
+    /// The type of billing terms.
+    pub kind: BillTermType,
+    /// The number of days until the invoice is due, or the day of the month
+    /// for proximo terms.
+    pub due_days: i64,
+    /// The number of days for the discount, or the day of the month for
+    /// proximo terms.
+    pub discount_days: i64,
+    /// The discount, in percents.
+    pub discount: Option<Numeric>,
+    /// The cutoff day of the month for proximo terms.
+    pub cutoff: i64,
+    /// The slots of the billing terms.
+    pub slots: Frame,
+}
+
+/// A tax table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaxTable {
+    /// The GUID of the tax table.
+    pub guid: Guid,
+    /// The name of the tax table.
+    pub name: String,
+    /// The number of objects using the tax table.
+    pub refcount: i64,
+    /// Whether the tax table is hidden.
+    pub invisible: bool,
+    /// The tax table this one is a copy of.
+    pub parent: Option<Guid>,
+    /// The entries of the tax table.
+    pub entries: Vec<TaxTableEntry>,
+    /// The slots of the tax table.
+    pub slots: Frame,
+}
+
+/// An entry of a tax table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaxTableEntry {
+    /// The account receiving the tax.
+    pub account: Guid,
+    /// The amount of the tax.
+    pub amount: Numeric,
+    /// Whether the amount is a value or a percentage.
+    pub kind: AmountType,
+}
+
+/// A customer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Customer {
+    /// The GUID of the customer.
+    pub guid: Guid,
+    /// The name of the customer.
+    pub name: String,
+    /// The ID of the customer.
+    pub id: String,
+    /// The notes about the customer.
+    pub notes: String,
+    /// Whether the customer is active.
+    pub active: bool,
+    /// The discount granted to the customer, in percents.
+    pub discount: Numeric,
+    /// The credit limit of the customer.
+    pub credit: Numeric,
+    /// The currency of the customer.
+    pub currency: Guid,
+    /// Whether to use the tax table of the customer.
+    pub tax_override: bool,
+    /// The billing address.
+    pub address: Address,
+    /// The shipping address.
+    pub ship_address: Address,
+    /// The billing terms of the customer.
+    pub terms: Option<Guid>,
+    /// Whether the prices include taxes.
+    pub tax_included: Option<TaxIncluded>,
+    /// The tax table of the customer.
+    pub tax_table: Option<Guid>,
+    /// The slots of the customer.
+    pub slots: Frame,
+}
+
+/// A vendor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vendor {
+    /// The GUID of the vendor.
+    pub guid: Guid,
+    /// The name of the vendor.
+    pub name: String,
+    /// The ID of the vendor.
+    pub id: String,
+    /// The notes about the vendor.
+    pub notes: String,
+    /// The currency of the vendor.
+    pub currency: Guid,
+    /// Whether the vendor is active.
+    pub active: bool,
+    /// Whether to use the tax table of the vendor.
+    pub tax_override: bool,
+    /// The address of the vendor.
+    pub address: Address,
+    /// The billing terms of the vendor.
+    pub terms: Option<Guid>,
+    /// Whether the prices include taxes.
+    pub tax_included: Option<TaxIncluded>,
+    /// The tax table of the vendor.
+    pub tax_table: Option<Guid>,
+    /// The slots of the vendor.
+    pub slots: Frame,
+}
+
+/// An employee.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Employee {
+    /// The GUID of the employee.
+    pub guid: Guid,
+    /// The username of the employee.
+    pub username: String,
+    /// The ID of the employee.
+    pub id: String,
+    /// The language of the employee.
+    pub language: String,
+    /// The access control list of the employee.
+    pub acl: String,
+    /// Whether the employee is active.
+    pub active: bool,
+    /// The currency of the employee.
+    pub currency: Guid,
+    /// The credit card account of the employee.
+    pub credit_card: Option<Guid>,
+    /// The number of hours in a workday.
+    pub workday: Numeric,
+    /// The hourly rate of the employee.
+    pub rate: Numeric,
+    /// The address of the employee.
+    pub address: Address,
+    /// The slots of the employee.
+    pub slots: Frame,
+}
+
+/// A customer, vendor or employee.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Contact {
+    /// A customer.
+    Customer(Customer),
+    /// A vendor.
+    Vendor(Vendor),
+    /// An employee.
+    Employee(Employee),
+}
+
+/// A job, done for a customer or by a vendor.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Job {
+    /// The GUID of the job.
+    pub guid: Guid,
+    /// The ID of the job.
+    pub id: String,
+    /// The name of the job.
+    pub name: String,
+    /// The reference of the job.
+    pub reference: String,
+    /// Whether the job is active.
+    pub active: bool,
+    /// The owner of the job.
+    pub owner: Option<Owner>,
+    /// The slots of the job.
+    pub slots: Frame,
+}
+
+/// An invoice, bill or expense voucher, depending on its owner.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Invoice {
+    /// The GUID of the invoice.
+    pub guid: Guid,
+    /// The ID of the invoice.
+    pub id: String,
+    /// The date at which the invoice has been opened.
+    pub date_opened: Option<DateTime<Utc>>,
+    /// The date at which the invoice has been posted.
+    pub date_posted: Option<DateTime<Utc>>,
+    /// The notes of the invoice.
+    pub notes: String,
+    /// Whether the invoice is active.
+    pub active: bool,
+    /// The currency of the invoice.
+    pub currency: Guid,
+    /// The owner of the invoice.
+    pub owner: Option<Owner>,
+    /// The billing terms of the invoice.
+    pub terms: Option<Guid>,
+    /// The billing ID of the invoice.
+    pub billing_id: Option<String>,
+    /// The transaction posting the invoice.
+    pub post_txn: Option<Guid>,
+    /// The lot of the invoice in its posting account.
+    pub post_lot: Option<Guid>,
+    /// The account to which the invoice is posted.
+    pub post_account: Option<Guid>,
+    /// The owner to bill for expense vouchers.
+    pub bill_to: Option<Owner>,
+    /// The amount to charge for expense vouchers.
+    pub charge_amount: Option<Numeric>,
+    /// The slots of the invoice.
+    pub slots: Frame,
+}
+
+/// An entry of an invoice, bill or order.
+#[derive(Debug, Clone, PartialEq)]
+#[expect(
+    clippy::struct_excessive_bools,
+    reason = "The entry mirrors the GnuCash entries table."
+)]
+pub struct Entry {
+    /// The GUID of the entry.
+    pub guid: Guid,
+    /// The date of the entry.
+    pub date: DateTime<Utc>,
+    /// The date at which the entry has been entered.
+    pub date_entered: Option<DateTime<Utc>>,
+    /// The description of the entry.
+    pub description: Option<String>,
+    /// The action of the entry.
+    pub action: Option<String>,
+    /// The notes of the entry.
+    pub notes: Option<String>,
+    /// The quantity.
+    pub quantity: Option<Numeric>,
+    /// The income account, for invoices.
+    pub invoice_account: Option<Guid>,
+    /// The unit price, for invoices.
+    pub invoice_price: Option<Numeric>,
+    /// The discount, for invoices.
+    pub invoice_discount: Option<Numeric>,
+    /// The invoice of the entry.
+    pub invoice: Option<Guid>,
+    /// Whether the discount is a value or a percentage.
+    pub invoice_discount_type: Option<AmountType>,
+    /// How the discount is applied relatively to taxes.
+    pub invoice_discount_how: Option<DiscountHow>,
+    /// Whether the entry is taxable, for invoices.
+    pub invoice_taxable: bool,
+    /// Whether the price includes taxes, for invoices.
+    pub invoice_tax_included: bool,
+    /// The tax table, for invoices.
+    pub invoice_tax_table: Option<Guid>,
+    /// The expense account, for bills.
+    pub bill_account: Option<Guid>,
+    /// The unit price, for bills.
+    pub bill_price: Option<Numeric>,
+    /// The bill of the entry.
+    pub bill: Option<Guid>,
+    /// Whether the entry is taxable, for bills.
+    pub bill_taxable: bool,
+    /// Whether the price includes taxes, for bills.
+    pub bill_tax_included: bool,
+    /// The tax table, for bills.
+    pub bill_tax_table: Option<Guid>,
+    /// How the entry has been paid, for expense vouchers.
+    pub bill_payment: Option<PaymentType>,
+    /// Whether the entry can be billed to a customer.
+    pub billable: bool,
+    /// The owner to bill the entry to.
+    pub bill_to: Option<Owner>,
+    /// The order of the entry.
+    pub order: Option<Guid>,
+    /// The slots of the entry.
+    pub slots: Frame,
+}
+
+/// An order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Order {
+    /// The GUID of the order.
+    pub guid: Guid,
+    /// The ID of the order.
+    pub id: String,
+    /// The notes of the order.
+    pub notes: String,
+    /// The reference of the order.
+    pub reference: String,
+    /// Whether the order is active.
+    pub active: bool,
+    /// The date at which the order has been opened.
+    pub date_opened: DateTime<Utc>,
+    /// The date at which the order has been closed.
+    pub date_closed: DateTime<Utc>,
+    /// The owner of the order.
+    pub owner: Owner,
+    /// The slots of the order.
+    pub slots: Frame,
+}
+
+/// The type of owner of a business object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OwnerType {
+    /// A customer.
+    Customer,
+    /// A job.
+    Job,
+    /// A vendor.
+    Vendor,
+    /// An employee.
+    Employee,
+}
+
+/// The type of billing terms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BillTermType {
+    /// Due a number of days after the invoice date.
+    Days,
+    /// Due on a day of the next month.
+    Proximo,
+}
+
+/// Whether an amount is a value or a percentage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AmountType {
+    /// A value.
+    Value,
+    /// A percentage.
+    Percent,
+}
+
+/// Whether prices include taxes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TaxIncluded {
+    /// Prices include taxes.
+    Yes,
+    /// Prices do not include taxes.
+    No,
+    /// Use the global preference.
+    UseGlobal,
+}
+
+/// How a discount is applied relatively to taxes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiscountHow {
+    /// The discount is applied before taxes.
+    PreTax,
+    /// The discount and taxes are applied on the same amount.
+    SameTime,
+    /// The discount is applied after taxes.
+    PostTax,
+}
+
+/// How an expense has been paid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PaymentType {
+    /// In cash.
+    Cash,
+    /// With the credit card of the employee.
+    Card,
+}
+
+/// An error that can occur when parsing a business enumeration.
+#[derive(Debug, Error)]
+#[error("invalid {kind}: {value:?}")]
+pub struct ParseBusinessError {
+    /// The kind of value being parsed.
+    kind: &'static str,
+    /// The invalid value.
+    value: String,
+}
+
+impl Business {
+    /// Returns the GUID, kind and slots of every business object.
+    pub fn slots(&self) -> impl Iterator<Item = (Guid, &'static str, &Frame)> {
+        let bill_terms = self
+            .bill_terms
+            .values()
+            .map(|term| (term.guid, "billing term", &term.slots));
+        let tax_tables = self
+            .tax_tables
+            .values()
+            .map(|table| (table.guid, "tax table", &table.slots));
+        let customers = self
+            .customers
+            .values()
+            .map(|customer| (customer.guid, "customer", &customer.slots));
+        let vendors = self
+            .vendors
+            .values()
+            .map(|vendor| (vendor.guid, "vendor", &vendor.slots));
+        let employees = self
+            .employees
+            .values()
+            .map(|employee| (employee.guid, "employee", &employee.slots));
+        let jobs = self.jobs.values().map(|job| (job.guid, "job", &job.slots));
+        let invoices = self
+            .invoices
+            .values()
+            .map(|invoice| (invoice.guid, "invoice", &invoice.slots));
+        let entries = self
+            .entries
+            .values()
+            .map(|entry| (entry.guid, "entry", &entry.slots));
+        let orders = self
+            .orders
+            .values()
+            .map(|order| (order.guid, "order", &order.slots));
+
+        bill_terms
+            .chain(tax_tables)
+            .chain(customers)
+            .chain(vendors)
+            .chain(employees)
+            .chain(jobs)
+            .chain(invoices)
+            .chain(entries)
+            .chain(orders)
+    }
+
+    /// Returns the owner ultimately responsible for a document.
+    ///
+    /// Jobs are resolved to their customer or vendor.
+    pub fn end_owner(&self, owner: Owner) -> Owner {
+        match owner.kind {
+            OwnerType::Job => self
+                .jobs
+                .get(&owner.guid)
+                .and_then(|job| job.owner)
+                .unwrap_or(owner),
+            OwnerType::Customer | OwnerType::Vendor | OwnerType::Employee => {
+                owner
+            }
+        }
+    }
+
+    /// Returns the name of an owner.
+    ///
+    /// As in GnuCash, employees are named after their address.
+    pub fn owner_name(&self, owner: Owner) -> Option<&str> {
+        match owner.kind {
+            OwnerType::Customer => self
+                .customers
+                .get(&owner.guid)
+                .map(|customer| customer.name.as_str()),
+            OwnerType::Job => {
+                self.jobs.get(&owner.guid).map(|job| job.name.as_str())
+            }
+            OwnerType::Vendor => self
+                .vendors
+                .get(&owner.guid)
+                .map(|vendor| vendor.name.as_str()),
+            OwnerType::Employee => {
+                self.employees.get(&owner.guid).map(|employee| {
+                    employee
+                        .address
+                        .name
+                        .as_deref()
+                        .filter(|name| !name.is_empty())
+                        .unwrap_or(&employee.username)
+                })
+            }
+        }
+    }
+
+    /// Returns the ID of an owner.
+    pub fn owner_id(&self, owner: Owner) -> Option<&str> {
+        match owner.kind {
+            OwnerType::Customer => self
+                .customers
+                .get(&owner.guid)
+                .map(|customer| customer.id.as_str()),
+            OwnerType::Job => {
+                self.jobs.get(&owner.guid).map(|job| job.id.as_str())
+            }
+            OwnerType::Vendor => self
+                .vendors
+                .get(&owner.guid)
+                .map(|vendor| vendor.id.as_str()),
+            OwnerType::Employee => self
+                .employees
+                .get(&owner.guid)
+                .map(|employee| employee.id.as_str()),
+        }
+    }
+
+    /// Finds a customer by name or ID.
+    pub fn find_customer(&self, name_or_id: &str) -> Option<&Customer> {
+        self.customers.values().find(|customer| {
+            customer.name == name_or_id || customer.id == name_or_id
+        })
+    }
+
+    /// Finds a customer, vendor or employee by name or ID.
+    ///
+    /// Jobs are not searched for.
+    pub fn find_owner(
+        &self,
+        kind: OwnerType,
+        name_or_id: &str,
+    ) -> Option<Owner> {
+        let matches = |owner: &Owner| {
+            self.owner_name(*owner) == Some(name_or_id)
+                || self.owner_id(*owner) == Some(name_or_id)
+        };
+
+        let owner = |guid: &Guid| Owner { kind, guid: *guid };
+
+        match kind {
+            OwnerType::Customer => {
+                self.customers.keys().map(owner).find(matches)
+            }
+            OwnerType::Vendor => self.vendors.keys().map(owner).find(matches),
+            OwnerType::Employee => {
+                self.employees.keys().map(owner).find(matches)
+            }
+            OwnerType::Job => None,
+        }
+    }
+
+    /// Returns the currency of an owner.
+    ///
+    /// Jobs use the currency of their owner.
+    pub fn owner_currency(&self, owner: Owner) -> Option<Guid> {
+        let owner = self.end_owner(owner);
+
+        match owner.kind {
+            OwnerType::Customer => self
+                .customers
+                .get(&owner.guid)
+                .map(|customer| customer.currency),
+            OwnerType::Vendor => {
+                self.vendors.get(&owner.guid).map(|vendor| vendor.currency)
+            }
+            OwnerType::Employee => self
+                .employees
+                .get(&owner.guid)
+                .map(|employee| employee.currency),
+            OwnerType::Job => None,
+        }
+    }
+
+    /// Returns the customer, vendor or employee behind an owner.
+    pub fn contact(&self, owner: Owner) -> Option<Contact> {
+        match owner.kind {
+            OwnerType::Customer => self
+                .customers
+                .get(&owner.guid)
+                .cloned()
+                .map(Contact::Customer),
+            OwnerType::Vendor => {
+                self.vendors.get(&owner.guid).cloned().map(Contact::Vendor)
+            }
+            OwnerType::Employee => self
+                .employees
+                .get(&owner.guid)
+                .cloned()
+                .map(Contact::Employee),
+            OwnerType::Job => None,
+        }
+    }
+
+    /// Returns the entries of an invoice, sorted by date.
+    ///
+    /// The entries of invoices are linked through their `invoice` field, while
+    /// those of bills and expense vouchers are linked through `bill`.
+    pub fn invoice_entries(&self, invoice: &Invoice) -> Vec<&Entry> {
+        let mut entries: Vec<_> = self
+            .entries
+            .values()
+            .filter(|entry| {
+                entry.invoice == Some(invoice.guid)
+                    || entry.bill == Some(invoice.guid)
+            })
+            .collect();
+
+        entries.sort_by_key(|entry| (entry.date, entry.date_entered));
+        entries
+    }
+}
+
+impl BillTerm {
+    /// Returns the date at which a document posted on `posted` is due.
+    ///
+    /// This follows GnuCash: proximo terms are due on a day of the next month,
+    /// or of the month after when the document is posted after the cutoff day.
+    /// A cutoff day of zero or less is relative to the end of the month.
+    pub fn due_date(&self, posted: NaiveDate) -> NaiveDate {
+        match self.kind {
+            BillTermType::Days => u64::try_from(self.due_days)
+                .ok()
+                .and_then(|days| posted.checked_add_days(Days::new(days)))
+                .unwrap_or(posted),
+            BillTermType::Proximo => self.proximo_due_date(posted),
+        }
+    }
+
+    /// Returns the due date for proximo terms.
+    fn proximo_due_date(&self, posted: NaiveDate) -> NaiveDate {
+        let days_in_month = last_day_of_month(posted)
+            .map_or(31, |last_day| i64::from(last_day.day()));
+
+        let cutoff = if self.cutoff <= 0 {
+            self.cutoff + days_in_month
+        } else {
+            self.cutoff
+        };
+
+        let months = if i64::from(posted.day()) <= cutoff {
+            1
+        } else {
+            2
+        };
+
+        posted
+            .with_day(1)
+            .and_then(|first| first.checked_add_months(Months::new(months)))
+            .and_then(|first| {
+                let last_day = last_day_of_month(first)?;
+                let day = self.due_days.clamp(1, i64::from(last_day.day()));
+                first.with_day(u32::try_from(day).ok()?)
+            })
+            .unwrap_or(posted)
+    }
+}
+
+impl Contact {
+    /// Returns the contact as an owner.
+    pub const fn owner(&self) -> Owner {
+        match self {
+            Self::Customer(customer) => Owner {
+                kind: OwnerType::Customer,
+                guid: customer.guid,
+            },
+            Self::Vendor(vendor) => Owner {
+                kind: OwnerType::Vendor,
+                guid: vendor.guid,
+            },
+            Self::Employee(employee) => Owner {
+                kind: OwnerType::Employee,
+                guid: employee.guid,
+            },
+        }
+    }
+
+    /// Returns the billing terms of the contact.
+    pub const fn terms(&self) -> Option<Guid> {
+        match self {
+            Self::Customer(customer) => customer.terms,
+            Self::Vendor(vendor) => vendor.terms,
+            Self::Employee(_) => None,
+        }
+    }
+
+    /// Returns the tax table of the contact.
+    pub const fn tax_table(&self) -> Option<Guid> {
+        match self {
+            Self::Customer(customer) => customer.tax_table,
+            Self::Vendor(vendor) => vendor.tax_table,
+            Self::Employee(_) => None,
+        }
+    }
+}
+
+impl Owner {
+    /// Builds an owner from its type code and GUID as stored in SQL books.
+    pub fn from_code(kind: Option<i64>, guid: Option<Guid>) -> Option<Self> {
+        Some(Self {
+            kind: OwnerType::from_code(kind?)?,
+            guid: guid?,
+        })
+    }
+}
+
+impl OwnerType {
+    /// Returns the name of the owner type as stored in XML books.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Customer => "gncCustomer",
+            Self::Job => "gncJob",
+            Self::Vendor => "gncVendor",
+            Self::Employee => "gncEmployee",
+        }
+    }
+
+    /// Returns the code of the owner type as stored in SQL books.
+    pub const fn code(self) -> i64 {
+        match self {
+            Self::Customer => 2,
+            Self::Job => 3,
+            Self::Vendor => 4,
+            Self::Employee => 5,
+        }
+    }
+
+    /// Returns the owner type with the given code.
+    pub const fn from_code(code: i64) -> Option<Self> {
+        match code {
+            2 => Some(Self::Customer),
+            3 => Some(Self::Job),
+            4 => Some(Self::Vendor),
+            5 => Some(Self::Employee),
+            _ => None,
+        }
+    }
+}
+
+impl BillTermType {
+    /// Returns the name of the billing terms type as stored by GnuCash.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Days => "GNC_TERM_TYPE_DAYS",
+            Self::Proximo => "GNC_TERM_TYPE_PROXIMO",
+        }
+    }
+}
+
+impl AmountType {
+    /// Returns the name of the amount type as stored by GnuCash.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Value => "VALUE",
+            Self::Percent => "PERCENT",
+        }
+    }
+
+    /// Returns the code of the amount type as stored in SQL tax tables.
+    pub const fn code(self) -> i64 {
+        match self {
+            Self::Value => 1,
+            Self::Percent => 2,
+        }
+    }
+
+    /// Returns the amount type with the given code.
+    pub const fn from_code(code: i64) -> Option<Self> {
+        match code {
+            1 => Some(Self::Value),
+            2 => Some(Self::Percent),
+            _ => None,
+        }
+    }
+}
+
+impl TaxIncluded {
+    /// Returns the name of the value as stored by GnuCash.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Yes => "YES",
+            Self::No => "NO",
+            Self::UseGlobal => "USEGLOBAL",
+        }
+    }
+
+    /// Returns the code of the value as stored in SQL books.
+    pub const fn code(self) -> i64 {
+        match self {
+            Self::Yes => 1,
+            Self::No => 2,
+            Self::UseGlobal => 3,
+        }
+    }
+
+    /// Returns the value with the given code.
+    pub const fn from_code(code: i64) -> Option<Self> {
+        match code {
+            1 => Some(Self::Yes),
+            2 => Some(Self::No),
+            3 => Some(Self::UseGlobal),
+            _ => None,
+        }
+    }
+}
+
+impl DiscountHow {
+    /// Returns the name of the value as stored by GnuCash.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::PreTax => "PRETAX",
+            Self::SameTime => "SAMETIME",
+            Self::PostTax => "POSTTAX",
+        }
+    }
+}
+
+impl PaymentType {
+    /// Returns the name of the payment type as stored in XML books.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Cash => "CASH",
+            Self::Card => "CARD",
+        }
+    }
+
+    /// Returns the code of the payment type as stored in SQL books.
+    pub const fn code(self) -> i64 {
+        match self {
+            Self::Cash => 1,
+            Self::Card => 2,
+        }
+    }
+
+    /// Returns the payment type with the given code.
+    pub const fn from_code(code: i64) -> Option<Self> {
+        match code {
+            1 => Some(Self::Cash),
+            2 => Some(Self::Card),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for OwnerType {
+    type Err = ParseBusinessError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gncCustomer" => Ok(Self::Customer),
+            "gncJob" => Ok(Self::Job),
+            "gncVendor" => Ok(Self::Vendor),
+            "gncEmployee" => Ok(Self::Employee),
+            _ => Err(ParseBusinessError::new("owner type", s)),
+        }
+    }
+}
+
+impl FromStr for BillTermType {
+    type Err = ParseBusinessError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "GNC_TERM_TYPE_DAYS" => Ok(Self::Days),
+            "GNC_TERM_TYPE_PROXIMO" => Ok(Self::Proximo),
+            _ => Err(ParseBusinessError::new("billing terms type", s)),
+        }
+    }
+}
+
+impl FromStr for AmountType {
+    type Err = ParseBusinessError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "VALUE" => Ok(Self::Value),
+            "PERCENT" => Ok(Self::Percent),
+            _ => Err(ParseBusinessError::new("amount type", s)),
+        }
+    }
+}
+
+impl FromStr for TaxIncluded {
+    type Err = ParseBusinessError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "YES" => Ok(Self::Yes),
+            "NO" => Ok(Self::No),
+            "USEGLOBAL" => Ok(Self::UseGlobal),
+            _ => Err(ParseBusinessError::new("tax included value", s)),
+        }
+    }
+}
+
+impl FromStr for DiscountHow {
+    type Err = ParseBusinessError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "PRETAX" => Ok(Self::PreTax),
+            "SAMETIME" => Ok(Self::SameTime),
+            "POSTTAX" => Ok(Self::PostTax),
+            _ => Err(ParseBusinessError::new("discount application", s)),
+        }
+    }
+}
+
+impl FromStr for PaymentType {
+    type Err = ParseBusinessError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "CASH" => Ok(Self::Cash),
+            "CARD" => Ok(Self::Card),
+            _ => Err(ParseBusinessError::new("payment type", s)),
+        }
+    }
+}
+
+impl ParseBusinessError {
+    /// Builds a new parse error.
+    fn new(kind: &'static str, value: &str) -> Self {
+        Self {
+            kind,
+            value: value.to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for OwnerType {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
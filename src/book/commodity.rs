@@ -0,0 +1,74 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! GnuCash commodities.
+
+use std::fmt;
+
+use super::{Frame, Guid};
+
+/// The namespace of ISO 4217 currencies.
+pub const CURRENCY_NAMESPACE: &str = "CURRENCY";
+
+/// The namespace of the commodity of the template accounts.
+pub const TEMPLATE_NAMESPACE: &str = "template";
+
+/// A commodity, like a currency or a stock.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Commodity {
+    /// The GUID of the commodity.
+    pub guid: Guid,
+    /// The namespace of the commodity, like `CURRENCY` or `NASDAQ`.
+    pub namespace: String,
+    /// The symbol of the commodity, like `EUR` or `AAPL`.
+    pub mnemonic: String,
+    /// The full name of the commodity.
+    pub fullname: Option<String>,
+    /// The CUSIP or ISIN code of the commodity.
+    pub cusip: Option<String>,
+    /// The smallest fraction of the commodity, like `100` for cents.
+    pub fraction: i64,
+    /// Whether to fetch online quotes for the commodity.
+    pub quote_flag: bool,
+    /// The source of the online quotes.
+    pub quote_source: Option<String>,
+    /// The timezone of the online quotes.
+    pub quote_tz: Option<String>,
+    /// The slots of the commodity.
+    pub slots: Frame,
+}
+
+impl Commodity {
+    /// Returns whether the commodity is a currency.
+    pub fn is_currency(&self) -> bool {
+        self.namespace == CURRENCY_NAMESPACE
+    }
+
+    /// Returns whether the commodity is the one of the template accounts.
+    pub fn is_template(&self) -> bool {
+        self.namespace == TEMPLATE_NAMESPACE
+    }
+
+    /// Returns the fraction of the commodity as an `i128`.
+    pub fn fraction(&self) -> i128 {
+        i128::from(self.fraction)
+    }
+}
+
+impl fmt::Display for Commodity {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(&self.mnemonic)
+    }
+}
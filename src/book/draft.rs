@@ -0,0 +1,390 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Drafts of transactions to add to a book.
+
+use chrono::{NaiveDate, Utc};
+use thiserror::Error;
+
+use super::{
+    post_date, Account, Book, Commodity, Frame, Guid, Numeric, ReconcileState,
+    SlotValue, Split, Transaction,
+};
+
+/// A draft of a transaction, with accounts referenced by their full path.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransactionDraft {
+    /// The date at which the transaction is posted.
+    pub date: NaiveDate,
+    /// The number of the transaction.
+    pub num: String,
+    /// The description of the transaction.
+    pub description: String,
+    /// The notes of the transaction.
+    pub notes: Option<String>,
+    /// The ISO code of the transaction currency.
+    ///
+    /// When not set, it is inferred from the accounts of the postings.
+    pub currency: Option<String>,
+    /// The postings of the transaction.
+    pub postings: Vec<PostingDraft>,
+}
+
+/// A draft of a split.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PostingDraft {
+    /// The full path of the account.
+    pub account: String,
+    /// The amount, in the commodity of the account.
+    ///
+    /// At most one posting per transaction can have no amount: it is then
+    /// computed to balance the transaction.
+    pub amount: Option<Numeric>,
+    /// The price of the commodity of the account in the transaction currency.
+    ///
+    /// It is required when the commodity of the account is not the transaction
+    /// currency.
+    pub price: Option<PriceSpec>,
+    /// The memo of the split.
+    pub memo: String,
+    /// The reconcile state of the split.
+    pub reconcile_state: ReconcileState,
+}
+
+/// A price given for a posting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriceSpec {
+    /// A price per unit of the commodity.
+    Unit(Numeric),
+    /// A total price for the amount.
+    ///
+    /// As in ledger, it is the price of the absolute amount: a negative total
+    /// gives a value of the opposite sign of the amount.
+    Total(Numeric),
+}
+
+/// Errors that can occur when building a transaction from a draft.
+#[derive(Debug, Error)]
+pub enum DraftError {
+    /// The transaction has less than two splits.
+    #[error("a transaction needs at least two splits")]
+    NotEnoughSplits,
+    /// An account does not exist.
+    #[error("unknown account: {0}")]
+    UnknownAccount(String),
+    /// An account is a placeholder.
+    #[error("the account {0} is a placeholder")]
+    PlaceholderAccount(String),
+    /// The currency does not exist in the book.
+    #[error("unknown currency: {0}")]
+    UnknownCurrency(String),
+    /// The currency cannot be inferred.
+    #[error("cannot infer the currency of the transaction")]
+    AmbiguousCurrency,
+    /// Several postings have no amount.
+    #[error("only one split can have no amount")]
+    SeveralElidedAmounts,
+    /// An amount is more precise than the SCU of its account.
+    #[error("{amount} is too precise for {account}, which uses 1/{scu}")]
+    TooPrecise {
+        /// The amount.
+        amount: Numeric,
+        /// The account.
+        account: String,
+        /// The SCU of the account.
+        scu: i64,
+    },
+    /// A posting needs a price.
+    #[error(
+        "the account {account} is in {commodity} but the transaction is in \
+        {currency}: a price is needed"
+    )]
+    MissingPrice {
+        /// The account.
+        account: String,
+        /// The commodity of the account.
+        commodity: String,
+        /// The currency of the transaction.
+        currency: String,
+    },
+    /// A posting has a price while it is in the transaction currency.
+    #[error(
+        "{account} is in the transaction currency: it cannot have a price"
+    )]
+    UnneededPrice {
+        /// The account.
+        account: String,
+    },
+    /// The transaction is not balanced.
+    #[error("the transaction is unbalanced by {imbalance} {currency}")]
+    Unbalanced {
+        /// The imbalance.
+        imbalance: Numeric,
+        /// The currency of the transaction.
+        currency: String,
+    },
+}
+
+/// A posting whose account has been resolved.
+struct ResolvedPosting<'a> {
+    /// The draft.
+    draft: &'a PostingDraft,
+    /// The account.
+    account: &'a Account,
+    /// The commodity of the account.
+    commodity: &'a Commodity,
+}
+
+impl TransactionDraft {
+    /// Builds a transaction ready to be inserted in `book`.
+    ///
+    /// The accounts are looked up in the book, the amounts are checked against
+    /// the SCU of their account and the transaction is checked for balance.
+    pub fn build(&self, book: &Book) -> Result<Transaction, DraftError> {
+        if self.postings.len() < 2 {
+            return Err(DraftError::NotEnoughSplits);
+        }
+
+        if self
+            .postings
+            .iter()
+            .filter(|posting| posting.amount.is_none())
+            .count()
+            > 1
+        {
+            return Err(DraftError::SeveralElidedAmounts);
+        }
+
+        let postings = self
+            .postings
+            .iter()
+            .map(|posting| resolve_posting(book, posting))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let currency = self.currency(book, &postings)?;
+
+        let mut splits = Vec::with_capacity(postings.len());
+        let mut elided = None;
+
+        for (index, posting) in postings.iter().enumerate() {
+            if posting.draft.amount.is_some() {
+                splits.push(split(posting, currency)?);
+            } else {
+                elided = Some((index, posting));
+            }
+        }
+
+        let imbalance: Numeric = splits.iter().map(|split| split.value).sum();
+
+        if let Some((index, posting)) = elided {
+            let value = -imbalance;
+            let quantity = elided_quantity(posting, currency, value)?;
+            splits.insert(index, new_split(posting, value, quantity));
+        } else if !imbalance.is_zero() {
+            return Err(DraftError::Unbalanced {
+                imbalance,
+                currency: currency.mnemonic.clone(),
+            });
+        } else {
+            // The transaction is balanced.
+        }
+
+        Ok(Transaction {
+            guid: Guid::new(),
+            currency: currency.guid,
+            num: self.num.clone(),
+            post_date: post_date(self.date),
+            enter_date: Utc::now(),
+            description: self.description.clone(),
+            splits,
+            slots: self.slots(),
+        })
+    }
+
+    /// Returns the slots of the transaction.
+    fn slots(&self) -> Frame {
+        let mut slots = Frame::default();
+        slots.insert("date-posted", SlotValue::GDate(self.date));
+
+        if let Some(notes) = &self.notes {
+            slots.insert("notes", SlotValue::String(notes.clone()));
+        }
+
+        slots
+    }
+
+    /// Returns the currency of the transaction.
+    fn currency<'a>(
+        &self,
+        book: &'a Book,
+        postings: &[ResolvedPosting<'a>],
+    ) -> Result<&'a Commodity, DraftError> {
+        if let Some(mnemonic) = &self.currency {
+            return book
+                .currency(mnemonic)
+                .ok_or_else(|| DraftError::UnknownCurrency(mnemonic.clone()));
+        }
+
+        let mut currencies = postings
+            .iter()
+            .filter(|posting| {
+                posting.draft.price.is_none() && posting.commodity.is_currency()
+            })
+            .map(|posting| posting.commodity);
+
+        let first = currencies.next().ok_or(DraftError::AmbiguousCurrency)?;
+
+        if currencies.all(|currency| currency.guid == first.guid) {
+            Ok(first)
+        } else {
+            Err(DraftError::AmbiguousCurrency)
+        }
+    }
+}
+
+/// Resolves the account of a posting.
+fn resolve_posting<'a>(
+    book: &'a Book,
+    draft: &'a PostingDraft,
+) -> Result<ResolvedPosting<'a>, DraftError> {
+    let account = book
+        .find_account(&draft.account)
+        .ok_or_else(|| DraftError::UnknownAccount(draft.account.clone()))?;
+
+    if account.placeholder {
+        return Err(DraftError::PlaceholderAccount(draft.account.clone()));
+    }
+
+    let commodity = book
+        .account_commodity(account.guid)
+        .ok_or_else(|| DraftError::UnknownAccount(draft.account.clone()))?;
+
+    Ok(ResolvedPosting {
+        draft,
+        account,
+        commodity,
+    })
+}
+
+/// Builds a split for a posting with an amount.
+fn split(
+    posting: &ResolvedPosting<'_>,
+    currency: &Commodity,
+) -> Result<Split, DraftError> {
+    let quantity = posting.draft.amount.unwrap_or_default();
+    check_precision(posting, quantity)?;
+
+    let value = if posting.commodity.guid == currency.guid {
+        if posting.draft.price.is_some() {
+            return Err(DraftError::UnneededPrice {
+                account: posting.draft.account.clone(),
+            });
+        }
+
+        quantity
+    } else {
+        match posting.draft.price {
+            Some(PriceSpec::Unit(price)) => quantity * price,
+            Some(PriceSpec::Total(total)) => {
+                if quantity.is_negative() {
+                    -total
+                } else {
+                    total
+                }
+            }
+            None => return Err(missing_price(posting, currency)),
+        }
+    };
+
+    Ok(new_split(
+        posting,
+        value.round_to(currency.fraction()),
+        quantity,
+    ))
+}
+
+/// Computes the quantity of a posting without amount given its value.
+fn elided_quantity(
+    posting: &ResolvedPosting<'_>,
+    currency: &Commodity,
+    value: Numeric,
+) -> Result<Numeric, DraftError> {
+    if posting.commodity.guid == currency.guid {
+        return Ok(value);
+    }
+
+    match posting.draft.price {
+        Some(PriceSpec::Unit(price)) => price
+            .recip()
+            .map(|inverse| {
+                (value * inverse)
+                    .round_to(i128::from(posting.account.commodity_scu))
+            })
+            .ok_or_else(|| missing_price(posting, currency)),
+        Some(PriceSpec::Total(_)) | None => {
+            Err(missing_price(posting, currency))
+        }
+    }
+}
+
+/// Checks an amount is not more precise than the SCU of the account.
+fn check_precision(
+    posting: &ResolvedPosting<'_>,
+    amount: Numeric,
+) -> Result<(), DraftError> {
+    let scu = posting.account.commodity_scu;
+
+    if amount.fits_fraction(i128::from(scu)) {
+        Ok(())
+    } else {
+        Err(DraftError::TooPrecise {
+            amount,
+            account: posting.draft.account.clone(),
+            scu,
+        })
+    }
+}
+
+/// Builds a new split, with the memo and reconcile state of the posting.
+fn new_split(
+    posting: &ResolvedPosting<'_>,
+    value: Numeric,
+    quantity: Numeric,
+) -> Split {
+    Split {
+        guid: Guid::new(),
+        account: posting.account.guid,
+        memo: posting.draft.memo.clone(),
+        action: String::new(),
+        reconcile_state: posting.draft.reconcile_state,
+        reconcile_date: None,
+        value,
+        quantity,
+        lot: None,
+        slots: Frame::default(),
+    }
+}
+
+/// Returns a missing price error for the posting.
+fn missing_price(
+    posting: &ResolvedPosting<'_>,
+    currency: &Commodity,
+) -> DraftError {
+    DraftError::MissingPrice {
+        account: posting.draft.account.clone(),
+        commodity: posting.commodity.mnemonic.clone(),
+        currency: currency.mnemonic.clone(),
+    }
+}
@@ -0,0 +1,65 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! GnuCash GUIDs.
+
+use std::{fmt, str::FromStr};
+
+use thiserror::Error;
+
+/// A GnuCash GUID.
+///
+/// GnuCash identifies every entity by a 128-bit GUID, written as 32 lowercase
+/// hexadecimal digits without any separator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Guid(u128);
+
+/// An error that can occur when parsing a GUID.
+#[derive(Debug, Error)]
+#[error("invalid GUID: {0:?}")]
+pub struct ParseGuidError(String);
+
+impl Guid {
+    /// Generates a new random GUID.
+    pub fn new() -> Self {
+        Self(uuid::Uuid::new_v4().as_u128())
+    }
+}
+
+impl Default for Guid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FromStr for Guid {
+    type Err = ParseGuidError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() == 32 && s.chars().all(|c| c.is_ascii_hexdigit()) {
+            u128::from_str_radix(s, 16)
+                .map(Self)
+                .map_err(|_error| ParseGuidError(s.to_owned()))
+        } else {
+            Err(ParseGuidError(s.to_owned()))
+        }
+    }
+}
+
+impl fmt::Display for Guid {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{:032x}", self.0)
+    }
+}
@@ -0,0 +1,32 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! GnuCash lots.
+
+use super::{Frame, Guid};
+
+/// A lot, grouping the splits of an account that offset each other, like the
+/// purchase and sale of shares or an invoice and its payments.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Lot {
+    /// The GUID of the lot.
+    pub guid: Guid,
+    /// The account of the lot.
+    pub account: Option<Guid>,
+    /// Whether the lot is closed, i.e. its balance is zero.
+    pub is_closed: bool,
+    /// The slots of the lot.
+    pub slots: Frame,
+}
@@ -0,0 +1,723 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! GnuCash numerics.
+
+use std::{
+    cmp::Ordering,
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+    str::FromStr,
+};
+
+use thiserror::Error;
+
+/// The fraction to which results are rounded when they cannot be represented
+/// exactly.
+///
+/// This is the smallest unit GnuCash supports for a commodity.
+pub const MAX_FRACTION: i128 = 1_000_000_000;
+
+/// A rational number, as used by GnuCash for amounts and prices.
+///
+/// GnuCash stores every amount as a numerator and a denominator to avoid any
+/// rounding error. A `Numeric` is always kept reduced, with a positive
+/// denominator, and both its numerator and denominator within `±i128::MAX`.
+///
+/// Arithmetic is exact as long as the result fits in 128 bits. Otherwise, the
+/// operators are lossy: they round the result to the smallest unit supported
+/// by GnuCash, and saturate beyond ±10^29, far above what GnuCash can store. A
+/// warning is logged when this happens. The `checked_*` methods return `None`
+/// instead, for the callers that must not lose precision.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Numeric {
+    /// The numerator.
+    num: i128,
+    /// The denominator.
+    denom: i128,
+}
+
+/// Errors that can occur when building a numeric.
+#[derive(Debug, Error)]
+pub enum NumericError {
+    /// The denominator is zero.
+    #[error("invalid numeric {0}/0: the denominator is zero")]
+    ZeroDenominator(i128),
+    /// The string is not a valid number.
+    #[error("invalid number: {0:?}")]
+    Parse(String),
+}
+
+impl Numeric {
+    /// Zero.
+    pub const ZERO: Self = Self { num: 0, denom: 1 };
+
+    /// One.
+    pub const ONE: Self = Self { num: 1, denom: 1 };
+
+    /// Builds a new numeric from its numerator and denominator.
+    pub fn new(num: i128, denom: i128) -> Result<Self, NumericError> {
+        if denom == 0 {
+            Err(NumericError::ZeroDenominator(num))
+        } else {
+            Ok(Self::reduced(num, denom))
+        }
+    }
+
+    /// Returns the numerator.
+    pub const fn num(self) -> i128 {
+        self.num
+    }
+
+    /// Returns the denominator.
+    pub const fn denom(self) -> i128 {
+        self.denom
+    }
+
+    /// Returns whether the numeric is zero.
+    pub const fn is_zero(self) -> bool {
+        self.num == 0
+    }
+
+    /// Returns whether the numeric is strictly negative.
+    pub const fn is_negative(self) -> bool {
+        self.num < 0
+    }
+
+    /// Returns whether the numeric is strictly positive.
+    pub const fn is_positive(self) -> bool {
+        self.num > 0
+    }
+
+    /// Returns the absolute value of the numeric.
+    pub const fn abs(self) -> Self {
+        Self {
+            num: self.num.abs(),
+            denom: self.denom,
+        }
+    }
+
+    /// Returns the fraction represented by the numeric as a percentage.
+    pub fn percent(self) -> Self {
+        self * Self::reduced(1, 100)
+    }
+
+    /// Adds another numeric to this one.
+    ///
+    /// Returns `None` if the exact result does not fit in 128 bits.
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let gcd = gcd(self.denom, other.denom);
+        let self_factor = other.denom / gcd;
+        let other_factor = self.denom / gcd;
+
+        let num = self
+            .num
+            .checked_mul(self_factor)?
+            .checked_add(other.num.checked_mul(other_factor)?)?;
+        let denom = self.denom.checked_mul(self_factor)?;
+
+        Self::checked_reduced(num, denom)
+    }
+
+    /// Subtracts another numeric from this one.
+    ///
+    /// Returns `None` if the exact result does not fit in 128 bits.
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        self.checked_add(-other)
+    }
+
+    /// Multiplies the numeric by another one.
+    ///
+    /// Returns `None` if the exact result does not fit in 128 bits.
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        let self_gcd = gcd(self.num, other.denom).max(1);
+        let other_gcd = gcd(other.num, self.denom).max(1);
+
+        let num = (self.num / self_gcd).checked_mul(other.num / other_gcd)?;
+        let denom =
+            (self.denom / other_gcd).checked_mul(other.denom / self_gcd)?;
+
+        Self::checked_reduced(num, denom)
+    }
+
+    /// Divides the numeric by another one.
+    ///
+    /// Returns `None` if `other` is zero, or if the exact result does not fit
+    /// in 128 bits.
+    pub fn checked_div(self, other: Self) -> Option<Self> {
+        self.checked_mul(other.recip()?)
+    }
+
+    /// Returns the inverse of the numeric, or `None` if it is zero.
+    ///
+    /// The inverse is always exact: dividing by a numeric with a lossy
+    /// quotient is multiplying by its inverse.
+    pub fn recip(self) -> Option<Self> {
+        (!self.is_zero()).then(|| Self::reduced(self.denom, self.num))
+    }
+
+    /// Returns whether the numeric can be represented exactly with the given
+    /// denominator.
+    pub fn fits_fraction(self, fraction: i128) -> bool {
+        // The numeric is reduced, so its denominator must divide the fraction.
+        fraction > 0 && fraction % self.denom == 0
+    }
+
+    /// Rounds the numeric to the closest multiple of `1 / fraction`.
+    ///
+    /// Halves are rounded away from zero.
+    pub fn round_to(self, fraction: i128) -> Self {
+        Self::reduced(self.num_at(fraction), fraction)
+    }
+
+    /// Returns the numerator of the numeric, rounded for the given denominator.
+    ///
+    /// Halves are rounded away from zero.
+    ///
+    /// The result saturates to `±i128::MAX` if it does not fit in 128 bits.
+    pub fn num_at(self, fraction: i128) -> i128 {
+        let integer = self.num / self.denom;
+        let remainder = self.num % self.denom;
+
+        let (quotient, rest) = mul_div(
+            remainder.unsigned_abs(),
+            fraction.unsigned_abs(),
+            self.denom.unsigned_abs(),
+        );
+        let rounded = if rest >= self.denom.unsigned_abs() - rest {
+            quotient + 1
+        } else {
+            quotient
+        };
+
+        // The rounded remainder is at most `fraction`, so it fits.
+        let rounded = i128::try_from(rounded).unwrap_or(i128::MAX);
+        integer
+            .saturating_mul(fraction)
+            .saturating_add(self.num.signum() * rounded)
+            .max(-i128::MAX)
+    }
+
+    /// Formats the numeric with at least the number of decimals of
+    /// `fraction`, adding more decimals when needed to stay exact.
+    pub fn to_exact(self, fraction: i128) -> String {
+        if self.fits_fraction(10_i128.pow(decimals(fraction))) {
+            self.to_fixed(fraction)
+        } else {
+            self.to_string()
+        }
+    }
+
+    /// Formats the numeric with the number of decimals of `fraction`.
+    ///
+    /// `fraction` is expected to be a power of ten, as are the fractions of
+    /// most commodities. The numeric is rounded if needed.
+    pub fn to_fixed(self, fraction: i128) -> String {
+        let decimals = decimals(fraction);
+        let scale = 10_i128.pow(decimals);
+        let scaled = self.num_at(scale);
+
+        let sign = if scaled < 0 { "-" } else { "" };
+        let integer = (scaled / scale).abs();
+        let fractional = (scaled % scale).abs();
+
+        if decimals == 0 {
+            format!("{sign}{integer}")
+        } else {
+            let width = usize::try_from(decimals).unwrap_or_default();
+            format!("{sign}{integer}.{fractional:0width$}")
+        }
+    }
+
+    /// Returns the sum of two numerics, rounded to [`MAX_FRACTION`].
+    fn rounded_add(self, other: Self) -> Self {
+        tracing::warn!(
+            %self,
+            %other,
+            "the exact sum does not fit in a numeric: rounding it"
+        );
+
+        Self::reduced(
+            self.num_at(MAX_FRACTION)
+                .saturating_add(other.num_at(MAX_FRACTION)),
+            MAX_FRACTION,
+        )
+    }
+
+    /// Returns the product of two numerics, rounded to [`MAX_FRACTION`].
+    ///
+    /// The operands are rounded only as much as needed for the product to fit.
+    fn rounded_mul(self, other: Self) -> Self {
+        tracing::warn!(
+            %self,
+            %other,
+            "the exact product does not fit in a numeric: rounding it"
+        );
+
+        let self_rounded = self.round_to(MAX_FRACTION);
+        let other_rounded = other.round_to(MAX_FRACTION);
+
+        self_rounded
+            .checked_mul(other)
+            .or_else(|| self.checked_mul(other_rounded))
+            .or_else(|| self_rounded.checked_mul(other_rounded))
+            .map_or_else(
+                || {
+                    let sign = self.num.signum() * other.num.signum();
+                    Self::reduced(sign * i128::MAX, MAX_FRACTION)
+                },
+                |product| product.round_to(MAX_FRACTION),
+            )
+    }
+
+    /// Builds a reduced numeric.
+    ///
+    /// `denom` must not be zero. The rare values that do not fit once reduced,
+    /// like `i128::MIN / 1`, saturate to `±i128::MAX`.
+    fn reduced(num: i128, denom: i128) -> Self {
+        Self::checked_reduced(num, denom).unwrap_or_else(|| {
+            let (num, denom) = reduce(num, denom);
+            let negative = (num < 0) != (denom < 0);
+            let saturate =
+                |value: u128| i128::try_from(value).unwrap_or(i128::MAX);
+
+            Self {
+                num: if negative {
+                    -saturate(num.unsigned_abs())
+                } else {
+                    saturate(num.unsigned_abs())
+                },
+                denom: saturate(denom.unsigned_abs()).max(1),
+            }
+        })
+    }
+
+    /// Builds a reduced numeric, or returns `None` if it does not fit.
+    ///
+    /// `denom` must not be zero.
+    fn checked_reduced(num: i128, denom: i128) -> Option<Self> {
+        let (num, denom) = reduce(num, denom);
+        let (num, denom) = if denom < 0 {
+            (num.checked_neg()?, denom.checked_neg()?)
+        } else {
+            (num, denom)
+        };
+
+        (num != i128::MIN).then_some(Self { num, denom })
+    }
+}
+
+impl Default for Numeric {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl fmt::Display for Numeric {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let exact = (0..=18)
+            .map(|decimals| 10_i128.pow(decimals))
+            .find(|&fraction| self.fits_fraction(fraction));
+
+        if let Some(fraction) = exact {
+            formatter.write_str(&self.to_fixed(fraction))
+        } else {
+            let fixed = self.to_fixed(10_000_000_000);
+            formatter.write_str(fixed.trim_end_matches('0'))
+        }
+    }
+}
+
+impl FromStr for Numeric {
+    type Err = NumericError;
+
+    /// Parses a decimal number like `-12.34`, or a fraction like `1234/100`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || NumericError::Parse(s.to_owned());
+
+        if let Some((num, denom)) = s.split_once('/') {
+            let num = num.trim().parse().map_err(|_error| error())?;
+            let denom = denom.trim().parse().map_err(|_error| error())?;
+            return Self::new(num, denom);
+        }
+
+        let s = s.trim();
+        let (negative, digits) = match s.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+
+        let (integer, fractional) =
+            digits.split_once('.').unwrap_or((digits, ""));
+
+        let is_valid = !(integer.is_empty() && fractional.is_empty())
+            && integer.chars().all(|c| c.is_ascii_digit())
+            && fractional.chars().all(|c| c.is_ascii_digit())
+            && fractional.len() <= 18;
+
+        if !is_valid {
+            return Err(error());
+        }
+
+        let denom = 10_i128
+            .pow(u32::try_from(fractional.len()).map_err(|_error| error())?);
+        let num: i128 = format!("0{integer}{fractional}")
+            .parse()
+            .map_err(|_error| error())?;
+
+        Self::new(if negative { -num } else { num }, denom)
+    }
+}
+
+impl PartialOrd for Numeric {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Numeric {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (
+            self.num.checked_mul(other.denom),
+            other.num.checked_mul(self.denom),
+        ) {
+            (Some(lhs), Some(rhs)) => lhs.cmp(&rhs),
+            _ => compare_fractions(
+                (self.num, self.denom),
+                (other.num, other.denom),
+            ),
+        }
+    }
+}
+
+impl Add for Numeric {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs)
+            .unwrap_or_else(|| self.rounded_add(rhs))
+    }
+}
+
+impl AddAssign for Numeric {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Numeric {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl SubAssign for Numeric {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul for Numeric {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.checked_mul(rhs)
+            .unwrap_or_else(|| self.rounded_mul(rhs))
+    }
+}
+
+impl Neg for Numeric {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            num: -self.num,
+            denom: self.denom,
+        }
+    }
+}
+
+impl Sum for Numeric {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+impl<'a> Sum<&'a Self> for Numeric {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+/// Returns the greatest common divisor of `lhs` and `rhs`.
+///
+/// It saturates to `i128::MAX` in the only case it does not fit, when both are
+/// `i128::MIN`, or one is `i128::MIN` and the other zero.
+fn gcd(lhs: i128, rhs: i128) -> i128 {
+    i128::try_from(unsigned_gcd(lhs.unsigned_abs(), rhs.unsigned_abs()))
+        .unwrap_or(i128::MAX)
+}
+
+/// Returns the greatest common divisor of `lhs` and `rhs`.
+fn unsigned_gcd(lhs: u128, rhs: u128) -> u128 {
+    let (mut lhs, mut rhs) = (lhs, rhs);
+
+    while rhs != 0 {
+        (lhs, rhs) = (rhs, lhs % rhs);
+    }
+
+    lhs
+}
+
+/// Divides `num` and `denom` by their greatest common divisor, keeping their
+/// signs.
+fn reduce(num: i128, denom: i128) -> (i128, i128) {
+    // The GCD does not fit in an i128 only when it is 2^127: `num` and `denom`
+    // are then `i128::MIN` or zero.
+    match i128::try_from(unsigned_gcd(num.unsigned_abs(), denom.unsigned_abs()))
+    {
+        Ok(0) => (num, denom),
+        Ok(gcd) => (num / gcd, denom / gcd),
+        Err(_) => (num.signum(), denom.signum()),
+    }
+}
+
+/// Compares two fractions with positive denominators without overflowing.
+///
+/// The integer parts are compared first. When they are equal, comparing the
+/// remainders `r1 / d1` and `r2 / d2` is the same as comparing `d2 / r2` and
+/// `d1 / r1`, which have smaller denominators, as in Euclid’s algorithm.
+fn compare_fractions(lhs: (i128, i128), rhs: (i128, i128)) -> Ordering {
+    let (mut lhs, mut rhs) = (lhs, rhs);
+
+    loop {
+        let (lhs_num, lhs_denom) = lhs;
+        let (rhs_num, rhs_denom) = rhs;
+
+        let integers = lhs_num
+            .div_euclid(lhs_denom)
+            .cmp(&rhs_num.div_euclid(rhs_denom));
+        if integers.is_ne() {
+            return integers;
+        }
+
+        let lhs_rem = lhs_num.rem_euclid(lhs_denom);
+        let rhs_rem = rhs_num.rem_euclid(rhs_denom);
+
+        if lhs_rem == 0 || rhs_rem == 0 {
+            return lhs_rem.cmp(&rhs_rem);
+        }
+
+        (lhs, rhs) = ((rhs_denom, rhs_rem), (lhs_denom, lhs_rem));
+    }
+}
+
+/// Returns the quotient and remainder of `lhs * rhs / divisor`.
+///
+/// `lhs` must be less than `divisor`. The product does not need to fit in 128
+/// bits: it is then computed bit by bit, keeping the remainder below
+/// `divisor`.
+fn mul_div(lhs: u128, rhs: u128, divisor: u128) -> (u128, u128) {
+    if let Some(product) = lhs.checked_mul(rhs) {
+        return (product / divisor, product % divisor);
+    }
+
+    // Adds `value` to `remainder`, carrying to `quotient`. Both are less than
+    // `divisor`, so the sum is less than twice the divisor.
+    let add = |quotient: &mut u128, remainder: &mut u128, value: u128| {
+        if *remainder >= divisor - value {
+            *quotient += 1;
+            *remainder -= divisor - value;
+        } else {
+            *remainder += value;
+        }
+    };
+
+    let (mut quotient, mut remainder) = (0, 0);
+
+    for bit in (0..u128::BITS).rev() {
+        quotient *= 2;
+        let doubled = remainder;
+        add(&mut quotient, &mut remainder, doubled);
+
+        if (rhs >> bit) & 1 == 1 {
+            add(&mut quotient, &mut remainder, lhs);
+        }
+    }
+
+    (quotient, remainder)
+}
+
+/// Returns the number of decimals needed to represent `1 / fraction`.
+fn decimals(fraction: i128) -> u32 {
+    let mut decimals = 0;
+    let mut scale = 1_i128;
+
+    while scale < fraction && decimals < 18 {
+        scale *= 10;
+        decimals += 1;
+    }
+
+    decimals
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::pedantic, clippy::restriction)]
+
+    use super::*;
+
+    #[test]
+    fn mul_div_without_overflow() {
+        assert_eq!(mul_div(3, 7, 4), (5, 1), "3 * 7 = 5 * 4 + 1");
+        assert_eq!(mul_div(0, u128::MAX, 5), (0, 0), "0 * x = 0");
+    }
+
+    #[test]
+    fn mul_div_with_an_overflowing_product() {
+        assert_eq!(
+            mul_div(u128::MAX - 1, u128::MAX, u128::MAX),
+            (u128::MAX - 1, 0),
+            "(x - 1) * x / x = x - 1"
+        );
+
+        let divisor = 1_u128 << 127;
+        assert_eq!(
+            mul_div(divisor - 1, 4, divisor),
+            (3, divisor - 4),
+            "(2^127 - 1) * 4 = 3 * 2^127 + 2^127 - 4"
+        );
+    }
+
+    #[test]
+    fn compare_fractions_with_different_integer_parts() {
+        assert_eq!(compare_fractions((7, 2), (5, 2)), Ordering::Greater);
+        assert_eq!(compare_fractions((-1, 3), (1, 3)), Ordering::Less);
+    }
+
+    #[test]
+    fn compare_fractions_with_equal_integer_parts() {
+        assert_eq!(compare_fractions((1, 3), (1, 2)), Ordering::Less);
+        assert_eq!(compare_fractions((2, 4), (1, 2)), Ordering::Equal);
+        assert_eq!(compare_fractions((-1, 3), (-1, 2)), Ordering::Greater);
+    }
+
+    #[test]
+    fn compare_fractions_with_huge_terms() {
+        let max = i128::MAX;
+
+        assert_eq!(
+            compare_fractions((max - 1, max), (max - 2, max - 1)),
+            Ordering::Greater,
+            "1 - 1/x grows with x"
+        );
+        assert_eq!(
+            compare_fractions((max, max - 1), (max, max - 1)),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn num_at_rounds_halves_away_from_zero() {
+        let half = Numeric::new(1, 200).unwrap();
+
+        assert_eq!(half.num_at(100), 1);
+        assert_eq!((-half).num_at(100), -1);
+        assert_eq!(Numeric::new(1, 300).unwrap().num_at(100), 0);
+        assert_eq!(Numeric::new(-5, 3).unwrap().num_at(1), -2);
+    }
+
+    #[test]
+    fn num_at_with_an_overflowing_remainder() {
+        let value = Numeric::new(i128::MAX - 1, i128::MAX).unwrap();
+
+        assert_eq!(value.num_at(MAX_FRACTION), MAX_FRACTION);
+    }
+
+    #[test]
+    fn num_at_saturates() {
+        let value = Numeric::new(i128::MAX, 1).unwrap();
+
+        assert_eq!(value.num_at(100), i128::MAX);
+        assert_eq!((-value).num_at(100), -i128::MAX);
+    }
+
+    #[test]
+    fn reduced_keeps_the_denominator_positive() {
+        let value = Numeric::new(4, -6).unwrap();
+
+        assert_eq!((value.num(), value.denom()), (-2, 3));
+    }
+
+    #[test]
+    fn reduced_saturates_i128_min() {
+        let value = Numeric::new(i128::MIN, 1).unwrap();
+        assert_eq!((value.num(), value.denom()), (-i128::MAX, 1));
+
+        let value = Numeric::new(1, i128::MIN).unwrap();
+        assert_eq!((value.num(), value.denom()), (-1, i128::MAX));
+
+        let value = Numeric::new(i128::MIN, i128::MIN).unwrap();
+        assert_eq!(value, Numeric::ONE);
+
+        let value = Numeric::new(i128::MIN, 2).unwrap();
+        assert_eq!((value.num(), value.denom()), (i128::MIN / 2, 1));
+    }
+
+    #[test]
+    fn abs_and_neg_of_the_smallest_numeric() {
+        let min = Numeric::new(i128::MIN, 1).unwrap();
+
+        assert_eq!(min.abs(), Numeric::new(i128::MAX, 1).unwrap());
+        assert_eq!(-min, Numeric::new(i128::MAX, 1).unwrap());
+        assert_eq!(-(-min), min);
+    }
+
+    #[test]
+    fn checked_operations_return_none_on_overflow() {
+        let max = Numeric::new(i128::MAX, 1).unwrap();
+
+        assert_eq!(max.checked_add(Numeric::ONE), None);
+        assert_eq!(max.checked_mul(max), None);
+        assert_eq!(max.checked_div(Numeric::new(1, 2).unwrap()), None);
+        assert_eq!(Numeric::ONE.checked_div(Numeric::ZERO), None);
+    }
+
+    #[test]
+    fn recip_is_exact() {
+        let value = Numeric::new(-3, i128::MAX).unwrap();
+
+        assert_eq!(value.recip(), Numeric::new(i128::MAX, -3).ok());
+        assert_eq!(Numeric::ZERO.recip(), None);
+    }
+
+    #[test]
+    fn lossy_operators_round_to_the_max_fraction() {
+        let third = Numeric::new(1, 3).unwrap();
+        let tiny = Numeric::new(1, i128::MAX).unwrap();
+
+        assert_eq!(
+            third + tiny,
+            Numeric::new(333_333_333, MAX_FRACTION).unwrap()
+        );
+        assert_eq!(
+            Numeric::new(i128::MAX, 1).unwrap() * Numeric::new(2, 1).unwrap(),
+            Numeric::new(i128::MAX, MAX_FRACTION).unwrap()
+        );
+    }
+}
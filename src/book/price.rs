@@ -0,0 +1,39 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! GnuCash prices.
+
+use chrono::{DateTime, Utc};
+
+use super::{Guid, Numeric};
+
+/// A price from the price database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Price {
+    /// The GUID of the price.
+    pub guid: Guid,
+    /// The commodity being priced.
+    pub commodity: Guid,
+    /// The currency in which the price is expressed.
+    pub currency: Guid,
+    /// The date of the price.
+    pub date: DateTime<Utc>,
+    /// The source of the price, like `user:price-editor`.
+    pub source: Option<String>,
+    /// The type of the price, like `last` or `transaction`.
+    pub kind: Option<String>,
+    /// The value of one unit of the commodity in the currency.
+    pub value: Numeric,
+}
@@ -0,0 +1,339 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! GnuCash recurrences, used by budgets and scheduled transactions.
+
+use std::{fmt, str::FromStr};
+
+use chrono::{Datelike as _, Days, Months, NaiveDate, TimeDelta, Weekday};
+use thiserror::Error;
+
+/// A recurrence, like “every 2 months starting on 2024-01-31”.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recurrence {
+    /// The number of periods between two occurrences.
+    pub multiplier: i64,
+    /// The type of period.
+    pub period: PeriodType,
+    /// The date of the first occurrence.
+    pub start: NaiveDate,
+    /// How to adjust occurrences falling on a weekend.
+    pub weekend_adjust: WeekendAdjust,
+}
+
+/// The type of period of a recurrence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PeriodType {
+    /// Only once.
+    Once,
+    /// Every day.
+    Day,
+    /// Every week.
+    Week,
+    /// Every month, on the day of the start date.
+    Month,
+    /// Every month, on its last day.
+    EndOfMonth,
+    /// Every month, on the same weekday of the same week as the start date.
+    NthWeekday,
+    /// Every month, on the last occurrence of the weekday of the start date.
+    LastWeekday,
+    /// Every year.
+    Year,
+}
+
+/// How to adjust occurrences falling on a weekend.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum WeekendAdjust {
+    /// Do not adjust.
+    #[default]
+    None,
+    /// Move to the previous Friday.
+    Back,
+    /// Move to the next Monday.
+    Forward,
+}
+
+/// An error that can occur when parsing a recurrence field.
+#[derive(Debug, Error)]
+#[error("invalid {field}: {value:?}")]
+pub struct ParseRecurrenceError {
+    /// The field being parsed.
+    field: &'static str,
+    /// The invalid value.
+    value: String,
+}
+
+impl Recurrence {
+    /// Returns the first occurrence strictly after `date`.
+    ///
+    /// As in GnuCash, occurrences are computed from the start date, and then
+    /// moved if they fall on a weekend.
+    pub fn next_after(&self, date: NaiveDate) -> Option<NaiveDate> {
+        (self.first_index_before(date)..)
+            .map_while(|index| self.occurrence(index))
+            .map(|occurrence| self.weekend_adjust.adjust(occurrence))
+            .find(|&occurrence| occurrence > date)
+    }
+
+    /// Returns the occurrence of the given index, before weekend adjustment.
+    fn occurrence(&self, index: u32) -> Option<NaiveDate> {
+        let multiplier = u32::try_from(self.multiplier.max(1)).ok()?;
+        let periods = index.checked_mul(multiplier)?;
+
+        match self.period {
+            PeriodType::Once => (index == 0).then_some(self.start),
+            PeriodType::Day => {
+                self.start.checked_add_days(Days::new(u64::from(periods)))
+            }
+            PeriodType::Week => self
+                .start
+                .checked_add_days(Days::new(u64::from(periods) * 7)),
+            PeriodType::Month => {
+                self.start.checked_add_months(Months::new(periods))
+            }
+            PeriodType::Year => self
+                .start
+                .checked_add_months(Months::new(periods.checked_mul(12)?)),
+            PeriodType::EndOfMonth => {
+                last_day_of_month(self.month_start(periods)?)
+            }
+            PeriodType::NthWeekday => {
+                let month = self.month_start(periods)?;
+                let nth = u8::try_from((self.start.day() - 1) / 7 + 1).ok()?;
+
+                NaiveDate::from_weekday_of_month_opt(
+                    month.year(),
+                    month.month(),
+                    self.start.weekday(),
+                    nth,
+                )
+                .or_else(|| last_weekday_of_month(month, self.start.weekday()))
+            }
+            PeriodType::LastWeekday => last_weekday_of_month(
+                self.month_start(periods)?,
+                self.start.weekday(),
+            ),
+        }
+    }
+
+    /// Returns the first day of the month `months` after the start date.
+    fn month_start(&self, months: u32) -> Option<NaiveDate> {
+        self.start
+            .with_day(1)?
+            .checked_add_months(Months::new(months))
+    }
+
+    /// Returns the index of an occurrence before `date`, to avoid iterating
+    /// from the start date.
+    fn first_index_before(&self, date: NaiveDate) -> u32 {
+        let multiplier = self.multiplier.max(1);
+        let periods = match self.period {
+            PeriodType::Once => 0,
+            PeriodType::Day => (date - self.start).num_days(),
+            PeriodType::Week => (date - self.start).num_weeks(),
+            PeriodType::Month
+            | PeriodType::EndOfMonth
+            | PeriodType::NthWeekday
+            | PeriodType::LastWeekday => months_between(self.start, date),
+            PeriodType::Year => months_between(self.start, date) / 12,
+        };
+
+        // Weekend adjustments move occurrences by at most two days.
+        u32::try_from(periods / multiplier - 1).unwrap_or_default()
+    }
+}
+
+impl PeriodType {
+    /// Returns the name of the period type as stored by GnuCash.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Once => "once",
+            Self::Day => "day",
+            Self::Week => "week",
+            Self::Month => "month",
+            Self::EndOfMonth => "end of month",
+            Self::NthWeekday => "nth weekday",
+            Self::LastWeekday => "last weekday",
+            Self::Year => "year",
+        }
+    }
+}
+
+impl WeekendAdjust {
+    /// Returns the name of the weekend adjustment as stored by GnuCash.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Back => "back",
+            Self::Forward => "forward",
+        }
+    }
+
+    /// Moves a date falling on a weekend.
+    pub fn adjust(self, date: NaiveDate) -> NaiveDate {
+        let days = match (self, date.weekday()) {
+            (Self::Back, Weekday::Sat) => -1,
+            (Self::Back, Weekday::Sun) => -2,
+            (Self::Forward, Weekday::Sat) => 2,
+            (Self::Forward, Weekday::Sun) => 1,
+            _ => 0,
+        };
+
+        date.checked_add_signed(TimeDelta::days(days))
+            .unwrap_or(date)
+    }
+}
+
+impl FromStr for PeriodType {
+    type Err = ParseRecurrenceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "once" => Ok(Self::Once),
+            "day" => Ok(Self::Day),
+            "week" => Ok(Self::Week),
+            "month" => Ok(Self::Month),
+            "end of month" => Ok(Self::EndOfMonth),
+            "nth weekday" => Ok(Self::NthWeekday),
+            "last weekday" => Ok(Self::LastWeekday),
+            "year" => Ok(Self::Year),
+            _ => Err(ParseRecurrenceError {
+                field: "period type",
+                value: s.to_owned(),
+            }),
+        }
+    }
+}
+
+impl FromStr for WeekendAdjust {
+    type Err = ParseRecurrenceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(Self::None),
+            "back" => Ok(Self::Back),
+            "forward" => Ok(Self::Forward),
+            _ => Err(ParseRecurrenceError {
+                field: "weekend adjustment",
+                value: s.to_owned(),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Recurrence {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let unit = match self.period {
+            PeriodType::Once => {
+                return write!(formatter, "once on {}", self.start);
+            }
+            PeriodType::Day => "day",
+            PeriodType::Week => "week",
+            PeriodType::Month
+            | PeriodType::EndOfMonth
+            | PeriodType::NthWeekday
+            | PeriodType::LastWeekday => "month",
+            PeriodType::Year => "year",
+        };
+
+        if self.multiplier > 1 {
+            write!(formatter, "every {} {unit}s", self.multiplier)?;
+        } else {
+            write!(formatter, "every {unit}")?;
+        }
+
+        match self.period {
+            PeriodType::EndOfMonth => {
+                formatter.write_str(" on the last day")?;
+            }
+            PeriodType::NthWeekday => write!(
+                formatter,
+                " on the {} {}",
+                ordinal((self.start.day() - 1) / 7 + 1),
+                self.start.format("%A")
+            )?,
+            PeriodType::LastWeekday => {
+                write!(formatter, " on the last {}", self.start.format("%A"))?;
+            }
+            PeriodType::Once
+            | PeriodType::Day
+            | PeriodType::Week
+            | PeriodType::Month
+            | PeriodType::Year => (),
+        }
+
+        write!(formatter, ", starting on {}", self.start)?;
+
+        match self.weekend_adjust {
+            WeekendAdjust::None => Ok(()),
+            WeekendAdjust::Back => {
+                formatter.write_str(", moved back on weekends")
+            }
+            WeekendAdjust::Forward => {
+                formatter.write_str(", moved forward on weekends")
+            }
+        }
+    }
+}
+
+impl fmt::Display for PeriodType {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl fmt::Display for WeekendAdjust {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+/// Returns the last day of the month of `date`.
+pub(super) fn last_day_of_month(date: NaiveDate) -> Option<NaiveDate> {
+    date.with_day(1)?
+        .checked_add_months(Months::new(1))?
+        .pred_opt()
+}
+
+/// Returns the last given weekday in the month of `date`.
+fn last_weekday_of_month(
+    date: NaiveDate,
+    weekday: Weekday,
+) -> Option<NaiveDate> {
+    let last_day = last_day_of_month(date)?;
+    let offset = (7 + last_day.weekday().num_days_from_monday()
+        - weekday.num_days_from_monday())
+        % 7;
+    last_day.checked_sub_days(Days::new(u64::from(offset)))
+}
+
+/// Returns the number of months from `start` to `end`, ignoring the days.
+fn months_between(start: NaiveDate, end: NaiveDate) -> i64 {
+    i64::from(end.year() - start.year()) * 12 + i64::from(end.month())
+        - i64::from(start.month())
+}
+
+/// Returns an ordinal number, like `2nd`.
+fn ordinal(number: u32) -> String {
+    let suffix = match number {
+        1 => "st",
+        2 => "nd",
+        3 => "rd",
+        _ => "th",
+    };
+
+    format!("{number}{suffix}")
+}
@@ -0,0 +1,140 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! GnuCash scheduled transactions.
+
+use chrono::NaiveDate;
+
+use super::{Book, Frame, Guid, Recurrence, Transaction};
+
+/// A scheduled transaction.
+///
+/// The splits to create are the ones of the template transactions having
+/// splits in the template account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScheduledTransaction {
+    /// The GUID of the scheduled transaction.
+    pub guid: Guid,
+    /// The name of the scheduled transaction.
+    pub name: Option<String>,
+    /// Whether the scheduled transaction is enabled.
+    pub enabled: bool,
+    /// The date of the first occurrence.
+    pub start_date: Option<NaiveDate>,
+    /// The date after which there is no more occurrence.
+    pub end_date: Option<NaiveDate>,
+    /// The date of the last occurrence created.
+    pub last_occur: Option<NaiveDate>,
+    /// The total number of occurrences, or 0 if unlimited.
+    pub num_occur: i64,
+    /// The remaining number of occurrences.
+    pub rem_occur: i64,
+    /// Whether to create the transactions without asking.
+    pub auto_create: bool,
+    /// Whether to notify when transactions are created automatically.
+    pub auto_notify: bool,
+    /// The number of days in advance to create the transactions.
+    pub adv_creation: i64,
+    /// The number of days in advance to remind of the transactions.
+    pub adv_notify: i64,
+    /// The number of occurrences created so far.
+    pub instance_count: i64,
+    /// The template account holding the template splits.
+    pub template_account: Guid,
+    /// The recurrences of the schedule.
+    pub recurrences: Vec<Recurrence>,
+    /// The slots of the scheduled transaction.
+    pub slots: Frame,
+}
+
+impl ScheduledTransaction {
+    /// Returns the next occurrence to create, like GnuCash does.
+    ///
+    /// Returns `None` when the schedule has ended, either at its end date or
+    /// after its number of occurrences.
+    pub fn next_occurrence(&self) -> Option<NaiveDate> {
+        let after = match (self.last_occur, self.start_date) {
+            (Some(last_occur), Some(start_date)) => last_occur.max(start_date),
+            (Some(last_occur), None) => last_occur,
+            (None, Some(start_date)) => start_date.pred_opt()?,
+            (None, None) => NaiveDate::MIN,
+        };
+
+        self.next_after(after, self.rem_occur)
+    }
+
+    /// Returns the occurrences to create up to `until`, included.
+    pub fn occurrences_until(&self, until: NaiveDate) -> Vec<NaiveDate> {
+        let mut occurrences = vec![];
+        let mut remaining = self.rem_occur;
+        let mut next = self.next_occurrence();
+
+        while let Some(date) = next.filter(|&date| date <= until) {
+            occurrences.push(date);
+            remaining -= 1;
+            next = self.next_after(date, remaining);
+        }
+
+        occurrences
+    }
+
+    /// Records the creation of the given occurrences.
+    pub fn record_occurrences(&mut self, occurrences: &[NaiveDate]) {
+        let Some(&last) = occurrences.last() else {
+            return;
+        };
+
+        let count = i64::try_from(occurrences.len()).unwrap_or(i64::MAX);
+
+        self.last_occur = Some(last);
+        self.instance_count += count;
+
+        if self.has_occurrence_count() {
+            self.rem_occur = (self.rem_occur - count).max(0);
+        }
+    }
+
+    /// Returns whether the schedule ends after a number of occurrences.
+    pub const fn has_occurrence_count(&self) -> bool {
+        self.num_occur != 0
+    }
+
+    /// Returns the template transactions of the schedule.
+    pub fn templates<'a>(&self, book: &'a Book) -> Vec<&'a Transaction> {
+        book.transactions
+            .values()
+            .filter(|transaction| {
+                transaction
+                    .splits
+                    .iter()
+                    .any(|split| split.account == self.template_account)
+            })
+            .collect()
+    }
+
+    /// Returns the next occurrence after `date`, given the remaining number of
+    /// occurrences.
+    fn next_after(&self, date: NaiveDate, remaining: i64) -> Option<NaiveDate> {
+        if self.has_occurrence_count() && remaining <= 0 {
+            return None;
+        }
+
+        self.recurrences
+            .iter()
+            .filter_map(|recurrence| recurrence.next_after(date))
+            .min()
+            .filter(|&next| self.end_date.is_none_or(|end| next <= end))
+    }
+}
@@ -0,0 +1,178 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! GnuCash key-value slots.
+//!
+//! Slots store data that has no dedicated column or element, like the notes of
+//! a transaction or the options of a book. They form a tree of frames, in which
+//! each value is given by its path, like `options/Business/Company Name`.
+
+use std::{
+    collections::{btree_map, BTreeMap},
+    fmt,
+};
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use super::{Guid, Numeric};
+
+/// The separator between slot names in a path.
+pub const SLOT_SEPARATOR: char = '/';
+
+/// A frame of slots.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Frame(BTreeMap<String, SlotValue>);
+
+/// The value of a slot.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SlotValue {
+    /// A 64-bit integer.
+    Int64(i64),
+    /// A floating point number.
+    Double(f64),
+    /// A rational number.
+    Numeric(Numeric),
+    /// A string.
+    String(String),
+    /// A GUID.
+    Guid(Guid),
+    /// A timestamp.
+    Timespec(DateTime<Utc>),
+    /// A date.
+    GDate(NaiveDate),
+    /// A list of values.
+    List(Vec<SlotValue>),
+    /// A frame of slots.
+    Frame(Frame),
+}
+
+impl Frame {
+    /// Returns whether the frame is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns an iterator over the slots of the frame.
+    pub fn iter(&self) -> btree_map::Iter<'_, String, SlotValue> {
+        self.0.iter()
+    }
+
+    /// Returns the value at the given path.
+    pub fn get(&self, path: &str) -> Option<&SlotValue> {
+        match path.split_once(SLOT_SEPARATOR) {
+            Some((name, rest)) => match self.0.get(name)? {
+                SlotValue::Frame(frame) => frame.get(rest),
+                _ => None,
+            },
+            None => self.0.get(path),
+        }
+    }
+
+    /// Returns the string at the given path.
+    pub fn string(&self, path: &str) -> Option<&str> {
+        match self.get(path)? {
+            SlotValue::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    /// Inserts a value at the given path, creating the intermediate frames.
+    ///
+    /// Any value in the way of the path is replaced by a frame.
+    pub fn insert(&mut self, path: &str, value: SlotValue) {
+        match path.split_once(SLOT_SEPARATOR) {
+            Some((name, rest)) => {
+                let entry = self
+                    .0
+                    .entry(name.to_owned())
+                    .or_insert_with(|| SlotValue::Frame(Self::default()));
+
+                if !matches!(entry, SlotValue::Frame(_)) {
+                    *entry = SlotValue::Frame(Self::default());
+                }
+
+                if let SlotValue::Frame(frame) = entry {
+                    frame.insert(rest, value);
+                }
+            }
+            None => {
+                self.0.insert(path.to_owned(), value);
+            }
+        }
+    }
+
+    /// Removes the value at the given path.
+    pub fn remove(&mut self, path: &str) -> Option<SlotValue> {
+        match path.split_once(SLOT_SEPARATOR) {
+            Some((name, rest)) => match self.0.get_mut(name)? {
+                SlotValue::Frame(frame) => frame.remove(rest),
+                _ => None,
+            },
+            None => self.0.remove(path),
+        }
+    }
+}
+
+impl SlotValue {
+    /// Returns the name of the type of the value, as used by GnuCash.
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::Int64(_) => "int64",
+            Self::Double(_) => "double",
+            Self::Numeric(_) => "numeric",
+            Self::String(_) => "string",
+            Self::Guid(_) => "guid",
+            Self::Timespec(_) => "timespec",
+            Self::GDate(_) => "gdate",
+            Self::List(_) => "list",
+            Self::Frame(_) => "frame",
+        }
+    }
+}
+
+impl FromIterator<(String, SlotValue)> for Frame {
+    fn from_iter<T: IntoIterator<Item = (String, SlotValue)>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl<'a> IntoIterator for &'a Frame {
+    type Item = (&'a String, &'a SlotValue);
+    type IntoIter = btree_map::Iter<'a, String, SlotValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl fmt::Display for SlotValue {
+    /// Formats the value on one line.
+    ///
+    /// Strings are quoted to show their boundaries, and the content of lists
+    /// and frames is elided.
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Int64(int64) => write!(formatter, "{int64}"),
+            Self::Double(double) => write!(formatter, "{double}"),
+            Self::Numeric(numeric) => write!(formatter, "{numeric}"),
+            Self::String(string) => write!(formatter, "{string:?}"),
+            Self::Guid(guid) => write!(formatter, "{guid}"),
+            Self::Timespec(timespec) => write!(formatter, "{timespec}"),
+            Self::GDate(date) => write!(formatter, "{date}"),
+            Self::List(_) => formatter.write_str("[...]"),
+            Self::Frame(_) => formatter.write_str("{...}"),
+        }
+    }
+}
@@ -0,0 +1,752 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The SQLite backend.
+
+mod load;
+mod save;
+
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use rusqlite::{
+    params,
+    types::{FromSql, FromSqlError, FromSqlResult, ToSqlOutput, ValueRef},
+    Connection, OpenFlags, OptionalExtension, ToSql,
+};
+use thiserror::Error;
+
+use super::{
+    Account, Book, Contact, Entry, Guid, Invoice, Lot, Numeric, OwnerType,
+    ReconcileState, ScheduledTransaction, Split, Transaction,
+};
+
+/// The SQL script creating an empty GnuCash book.
+const SCHEMA: &str = include_str!("sqlite/schema.sql");
+
+/// The format of timestamps in the database.
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// The format of timestamps in databases written by GnuCash < 2.6.
+const LEGACY_TIMESTAMP_FORMAT: &str = "%Y%m%d%H%M%S";
+
+/// The format of dates in the database.
+const DATE_FORMAT: &str = "%Y%m%d";
+
+/// The slot type for 64-bit integers.
+const SLOT_TYPE_INT64: i64 = 1;
+
+/// The slot type for floating point numbers.
+const SLOT_TYPE_DOUBLE: i64 = 2;
+
+/// The slot type for numerics.
+const SLOT_TYPE_NUMERIC: i64 = 3;
+
+/// The slot type for strings.
+const SLOT_TYPE_STRING: i64 = 4;
+
+/// The slot type for GUIDs.
+const SLOT_TYPE_GUID: i64 = 5;
+
+/// The slot type for timestamps.
+const SLOT_TYPE_TIMESPEC: i64 = 6;
+
+/// The slot type for lists.
+const SLOT_TYPE_LIST: i64 = 8;
+
+/// The slot type for frames.
+const SLOT_TYPE_FRAME: i64 = 9;
+
+/// The slot type for dates.
+const SLOT_TYPE_GDATE: i64 = 10;
+
+/// A GnuCash SQLite database.
+#[derive(Debug)]
+pub struct Database {
+    /// The connection to the database.
+    connection: Connection,
+}
+
+/// Errors that can occur when using a GnuCash SQLite database.
+#[derive(Debug, Error)]
+pub enum DatabaseError {
+    /// The database cannot be opened.
+    #[error("failed to open {path}")]
+    Open {
+        /// The path of the database.
+        path: PathBuf,
+        /// The source error.
+        source: rusqlite::Error,
+    },
+    /// The file to create already exists.
+    #[error("{0} already exists")]
+    AlreadyExists(PathBuf),
+    /// The file is not a GnuCash SQLite book.
+    #[error("{0} is not a GnuCash SQLite book")]
+    NotABook(PathBuf),
+    /// The book is currently opened by GnuCash.
+    #[error("the book is locked by GnuCash on {hostname} (PID {pid})")]
+    Locked {
+        /// The host on which GnuCash has opened the book.
+        hostname: String,
+        /// The PID of the GnuCash instance.
+        pid: i64,
+    },
+    /// The book contains invalid data.
+    #[error("invalid data in the {table} table: {message}")]
+    InvalidData {
+        /// The table containing invalid data.
+        table: &'static str,
+        /// A description of the issue.
+        message: String,
+    },
+    /// An error occured in SQLite.
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+}
+
+impl Database {
+    /// Opens a GnuCash SQLite book for reading and writing.
+    ///
+    /// Opening the book fails if it is currently opened in GnuCash.
+    pub fn open(path: &Path) -> Result<Self, DatabaseError> {
+        let database =
+            Self::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_WRITE)?;
+        database.check_unlocked()?;
+        Ok(database)
+    }
+
+    /// Opens a GnuCash SQLite book for reading only.
+    ///
+    /// The book can be opened even if it is currently opened in GnuCash.
+    pub fn open_read_only(path: &Path) -> Result<Self, DatabaseError> {
+        Self::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+    }
+
+    /// Creates a new GnuCash SQLite book with the content of `book`.
+    #[tracing::instrument(level = "trace", skip(book))]
+    pub fn create(path: &Path, book: &Book) -> Result<Self, DatabaseError> {
+        if path.exists() {
+            return Err(DatabaseError::AlreadyExists(path.to_owned()));
+        }
+
+        let mut connection =
+            Connection::open(path).map_err(|source| DatabaseError::Open {
+                path: path.to_owned(),
+                source,
+            })?;
+
+        let db_transaction = connection.transaction()?;
+        db_transaction.execute_batch(SCHEMA)?;
+        save::save_book(&db_transaction, book)?;
+        db_transaction.commit()?;
+
+        Ok(Self { connection })
+    }
+
+    /// Loads the book from the database.
+    #[tracing::instrument(level = "trace", skip_all)]
+    pub fn load(&self) -> Result<Book, DatabaseError> {
+        let book = load::load_book(&self.connection)?;
+
+        tracing::debug!(
+            guid = %book.guid,
+            commodities = book.commodities.len(),
+            accounts = book.accounts.len(),
+            transactions = book.transactions.len(),
+            prices = book.prices.len(),
+            "book loaded"
+        );
+
+        Ok(book)
+    }
+
+    /// Inserts new transactions in the book.
+    ///
+    /// Values are stored with the fraction of the transaction currency, and
+    /// quantities with the SCU of their account, as GnuCash does. Either all
+    /// transactions are inserted, or none of them.
+    #[tracing::instrument(level = "trace", skip_all)]
+    pub fn insert_transactions(
+        &mut self,
+        book: &Book,
+        transactions: &[Transaction],
+    ) -> Result<(), DatabaseError> {
+        let db_transaction = self.connection.transaction()?;
+
+        for transaction in transactions {
+            tracing::debug!(guid = %transaction.guid, "inserting transaction");
+            save::insert_transaction(&db_transaction, book, transaction)?;
+        }
+
+        db_transaction.commit()?;
+        Ok(())
+    }
+
+    /// Inserts splits in existing transactions, with the accounts they need.
+    ///
+    /// Either everything is saved, or nothing is.
+    #[tracing::instrument(level = "trace", skip_all)]
+    pub fn insert_splits(
+        &mut self,
+        book: &Book,
+        accounts: &[Account],
+        splits: &[(Guid, Split)],
+    ) -> Result<(), DatabaseError> {
+        let db_transaction = self.connection.transaction()?;
+
+        for account in accounts {
+            tracing::debug!(guid = %account.guid, "inserting account");
+            save::insert_account(&db_transaction, account)?;
+        }
+
+        for (transaction, split) in splits {
+            // The splits are added to transactions of the book.
+            let Some(transaction) = book.transactions.get(transaction) else {
+                continue;
+            };
+
+            tracing::debug!(guid = %split.guid, "inserting split");
+            save::insert_split(&db_transaction, book, transaction, split)?;
+        }
+
+        db_transaction.commit()?;
+        Ok(())
+    }
+
+    /// Inserts closing transactions with the accounts they need.
+    ///
+    /// The slots of the book are saved too, so that its options stay up to
+    /// date. Either everything is saved, or nothing is.
+    #[tracing::instrument(level = "trace", skip_all)]
+    pub fn insert_closing_entries(
+        &mut self,
+        book: &Book,
+        accounts: &[Account],
+        transactions: &[Transaction],
+    ) -> Result<(), DatabaseError> {
+        let db_transaction = self.connection.transaction()?;
+
+        for account in accounts {
+            tracing::debug!(guid = %account.guid, "inserting account");
+            save::insert_account(&db_transaction, account)?;
+        }
+
+        for transaction in transactions {
+            tracing::debug!(guid = %transaction.guid, "inserting transaction");
+            save::insert_transaction(&db_transaction, book, transaction)?;
+        }
+
+        save::replace_slots(&db_transaction, book.guid, &book.slots)?;
+
+        db_transaction.commit()?;
+        Ok(())
+    }
+
+    /// Replaces archived transactions by their summary.
+    ///
+    /// Either everything is saved, or nothing is.
+    #[tracing::instrument(level = "trace", skip_all)]
+    pub fn replace_transactions(
+        &mut self,
+        book: &Book,
+        archived: &[&Transaction],
+        summaries: &[Transaction],
+    ) -> Result<(), DatabaseError> {
+        let db_transaction = self.connection.transaction()?;
+
+        for transaction in archived {
+            tracing::debug!(guid = %transaction.guid, "deleting transaction");
+            save::delete_transaction(&db_transaction, transaction)?;
+        }
+
+        for transaction in summaries {
+            tracing::debug!(guid = %transaction.guid, "inserting transaction");
+            save::insert_transaction(&db_transaction, book, transaction)?;
+        }
+
+        db_transaction.commit()?;
+        Ok(())
+    }
+
+    /// Saves accounts, inserting the new ones and replacing the others.
+    ///
+    /// Either all accounts are saved, or none of them.
+    #[tracing::instrument(level = "trace", skip_all)]
+    pub fn save_accounts(
+        &mut self,
+        accounts: &[&Account],
+    ) -> Result<(), DatabaseError> {
+        let db_transaction = self.connection.transaction()?;
+
+        for account in accounts {
+            tracing::debug!(guid = %account.guid, "saving account");
+            save::delete_object(&db_transaction, "accounts", account.guid)?;
+            save::insert_account(&db_transaction, account)?;
+        }
+
+        db_transaction.commit()?;
+        Ok(())
+    }
+
+    /// Merges an account into another one, then deletes it.
+    ///
+    /// `splits` are the splits moved to the target account, with the GUID of
+    /// their transaction. The lots, invoices, entries, employees, tax tables
+    /// and budgets referring to the source account are updated too. Either
+    /// everything is saved, or nothing is.
+    #[tracing::instrument(level = "trace", skip_all)]
+    pub fn merge_accounts(
+        &mut self,
+        book: &Book,
+        source: &Account,
+        target: Guid,
+        splits: &[(Guid, Split)],
+    ) -> Result<(), DatabaseError> {
+        let db_transaction = self.connection.transaction()?;
+
+        for (transaction, split) in splits {
+            let Some(transaction) = book.transactions.get(transaction) else {
+                continue;
+            };
+
+            tracing::debug!(guid = %split.guid, "replacing split");
+            save::delete_object(&db_transaction, "splits", split.guid)?;
+            save::insert_split(&db_transaction, book, transaction, split)?;
+        }
+
+        let references = [
+            ("lots", "account_guid"),
+            ("invoices", "post_acc"),
+            ("entries", "i_acct"),
+            ("entries", "b_acct"),
+            ("employees", "ccard_guid"),
+            ("taxtable_entries", "account"),
+            ("budget_amounts", "account_guid"),
+        ];
+
+        for (table, column) in references {
+            db_transaction.execute(
+                &format!(
+                    "UPDATE {table} SET {column} = ?1 WHERE {column} = ?2"
+                ),
+                params![target, source.guid],
+            )?;
+        }
+
+        tracing::debug!(guid = %source.guid, "deleting account");
+        save::delete_object(&db_transaction, "accounts", source.guid)?;
+
+        db_transaction.commit()?;
+        Ok(())
+    }
+
+    /// Inserts the transactions created for scheduled transactions, and
+    /// updates the state of the latter.
+    ///
+    /// Either everything is saved, or nothing is.
+    #[tracing::instrument(level = "trace", skip_all)]
+    pub fn insert_scheduled_transactions(
+        &mut self,
+        book: &Book,
+        transactions: &[Transaction],
+        schedules: &[ScheduledTransaction],
+    ) -> Result<(), DatabaseError> {
+        let db_transaction = self.connection.transaction()?;
+
+        for transaction in transactions {
+            tracing::debug!(guid = %transaction.guid, "inserting transaction");
+            save::insert_transaction(&db_transaction, book, transaction)?;
+        }
+
+        for schedule in schedules {
+            tracing::debug!(guid = %schedule.guid, "updating schedule");
+            save::update_schedule(&db_transaction, schedule)?;
+        }
+
+        db_transaction.commit()?;
+        Ok(())
+    }
+
+    /// Inserts a new invoice with its entries and, once posted, its lot and
+    /// posting transaction.
+    ///
+    /// As in GnuCash, the billing terms and tax tables used by the invoice get
+    /// their reference count incremented. The slots of the book are saved too,
+    /// so that its counters stay up to date. Either everything is saved, or
+    /// nothing is.
+    #[tracing::instrument(level = "trace", skip_all)]
+    pub fn insert_invoice(
+        &mut self,
+        book: &Book,
+        invoice: &Invoice,
+        entries: &[Entry],
+        posting: Option<&(Lot, Transaction)>,
+    ) -> Result<(), DatabaseError> {
+        let db_transaction = self.connection.transaction()?;
+
+        tracing::debug!(guid = %invoice.guid, "inserting invoice");
+        save::insert_invoice(&db_transaction, invoice)?;
+
+        if let Some(terms) = invoice.terms {
+            save::update_refcount(&db_transaction, "billterms", terms, 1)?;
+        }
+
+        for entry in entries {
+            tracing::debug!(guid = %entry.guid, "inserting entry");
+            save::insert_entry(&db_transaction, entry)?;
+
+            for tax_table in [entry.invoice_tax_table, entry.bill_tax_table]
+                .into_iter()
+                .flatten()
+            {
+                save::update_refcount(
+                    &db_transaction,
+                    "taxtables",
+                    tax_table,
+                    1,
+                )?;
+            }
+        }
+
+        if let Some((lot, transaction)) = posting {
+            tracing::debug!(guid = %lot.guid, "inserting lot");
+            save::insert_lot(&db_transaction, lot)?;
+            tracing::debug!(guid = %transaction.guid, "inserting transaction");
+            save::insert_transaction(&db_transaction, book, transaction)?;
+        }
+
+        save::replace_slots(&db_transaction, book.guid, &book.slots)?;
+
+        db_transaction.commit()?;
+        Ok(())
+    }
+
+    /// Inserts a payment transaction with its pre-payment lot, if any, and
+    /// closes the lots it fully pays.
+    ///
+    /// Either everything is saved, or nothing is.
+    #[tracing::instrument(level = "trace", skip_all)]
+    pub fn insert_payment(
+        &mut self,
+        book: &Book,
+        transaction: &Transaction,
+        prepayment: Option<&Lot>,
+        closed_lots: &[Guid],
+    ) -> Result<(), DatabaseError> {
+        let db_transaction = self.connection.transaction()?;
+
+        if let Some(lot) = prepayment {
+            tracing::debug!(guid = %lot.guid, "inserting lot");
+            save::insert_lot(&db_transaction, lot)?;
+        }
+
+        tracing::debug!(guid = %transaction.guid, "inserting transaction");
+        save::insert_transaction(&db_transaction, book, transaction)?;
+
+        for lot in closed_lots {
+            tracing::debug!(guid = %lot, "closing lot");
+            db_transaction.execute(
+                "UPDATE lots SET is_closed = 1 WHERE guid = ?1",
+                params![lot],
+            )?;
+        }
+
+        db_transaction.commit()?;
+        Ok(())
+    }
+
+    /// Saves new or edited customers, vendors and employees, with the slots of
+    /// the book for its counters.
+    ///
+    /// `book` is the book as loaded, so that the reference counts of the
+    /// billing terms and tax tables can be updated like GnuCash does when they
+    /// are changed. Either everything is saved, or nothing is.
+    #[tracing::instrument(level = "trace", skip_all)]
+    pub fn save_contacts(
+        &mut self,
+        book: &Book,
+        contacts: &[Contact],
+    ) -> Result<(), DatabaseError> {
+        let db_transaction = self.connection.transaction()?;
+
+        for contact in contacts {
+            let owner = contact.owner();
+            let previous = book.business.contact(owner);
+
+            if previous.is_some() {
+                tracing::debug!(guid = %owner.guid, "replacing contact");
+                save::delete_object(
+                    &db_transaction,
+                    contact_table(owner.kind),
+                    owner.guid,
+                )?;
+            } else {
+                tracing::debug!(guid = %owner.guid, "inserting contact");
+            }
+
+            match contact {
+                Contact::Customer(customer) => {
+                    save::insert_customer(&db_transaction, customer)?;
+                }
+                Contact::Vendor(vendor) => {
+                    save::insert_vendor(&db_transaction, vendor)?;
+                }
+                Contact::Employee(employee) => {
+                    save::insert_employee(&db_transaction, employee)?;
+                }
+            }
+
+            let previous = previous.as_ref();
+            let references = [
+                (
+                    "billterms",
+                    previous.and_then(Contact::terms),
+                    contact.terms(),
+                ),
+                (
+                    "taxtables",
+                    previous.and_then(Contact::tax_table),
+                    contact.tax_table(),
+                ),
+            ];
+
+            for (table, old, new) in references {
+                if old == new {
+                    continue;
+                }
+
+                if let Some(old) = old {
+                    save::update_refcount(&db_transaction, table, old, -1)?;
+                }
+
+                if let Some(new) = new {
+                    save::update_refcount(&db_transaction, table, new, 1)?;
+                }
+            }
+        }
+
+        save::replace_slots(&db_transaction, book.guid, &book.slots)?;
+
+        db_transaction.commit()?;
+        Ok(())
+    }
+
+    /// Marks splits as reconciled and saves the slots of their account.
+    ///
+    /// Either everything is saved, or nothing is.
+    #[tracing::instrument(level = "trace", skip_all)]
+    pub fn reconcile(
+        &mut self,
+        account: &Account,
+        splits: &[Guid],
+        reconcile_date: DateTime<Utc>,
+    ) -> Result<(), DatabaseError> {
+        let db_transaction = self.connection.transaction()?;
+
+        for split in splits {
+            tracing::debug!(guid = %split, "reconciling split");
+            db_transaction.execute(
+                "UPDATE splits SET reconcile_state = ?1, reconcile_date = ?2
+                WHERE guid = ?3",
+                params![
+                    ReconcileState::Reconciled.to_string(),
+                    Timestamp(reconcile_date),
+                    split,
+                ],
+            )?;
+        }
+
+        save::replace_slots(&db_transaction, account.guid, &account.slots)?;
+
+        db_transaction.commit()?;
+        Ok(())
+    }
+
+    /// Opens a GnuCash SQLite book with the given flags.
+    fn open_with_flags(
+        path: &Path,
+        flags: OpenFlags,
+    ) -> Result<Self, DatabaseError> {
+        let connection = Connection::open_with_flags(
+            path,
+            flags | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )
+        .map_err(|source| DatabaseError::Open {
+            path: path.to_owned(),
+            source,
+        })?;
+
+        let is_gnucash = connection
+            .query_row(
+                "SELECT 1 FROM versions WHERE table_name = 'Gnucash'",
+                [],
+                |_row| Ok(()),
+            )
+            .optional()
+            .ok()
+            .flatten()
+            .is_some();
+
+        if is_gnucash {
+            Ok(Self { connection })
+        } else {
+            Err(DatabaseError::NotABook(path.to_owned()))
+        }
+    }
+
+    /// Checks the book is not currently opened in GnuCash.
+    fn check_unlocked(&self) -> Result<(), DatabaseError> {
+        let lock = self
+            .connection
+            .query_row("SELECT Hostname, PID FROM gnclock", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .optional()?;
+
+        match lock {
+            Some((hostname, pid)) => {
+                Err(DatabaseError::Locked { hostname, pid })
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Returns the table storing the owners of the given type.
+const fn contact_table(kind: OwnerType) -> &'static str {
+    match kind {
+        OwnerType::Customer => "customers",
+        OwnerType::Job => "jobs",
+        OwnerType::Vendor => "vendors",
+        OwnerType::Employee => "employees",
+    }
+}
+
+/// Builds a numeric from its stored parts.
+fn numeric(
+    num: i64,
+    denom: i64,
+    table: &'static str,
+) -> Result<Numeric, DatabaseError> {
+    Numeric::new(i128::from(num), i128::from(denom)).map_err(|error| {
+        DatabaseError::InvalidData {
+            table,
+            message: error.to_string(),
+        }
+    })
+}
+
+/// Builds an optional numeric from its stored parts.
+fn optional_numeric(
+    num: Option<i64>,
+    denom: Option<i64>,
+    table: &'static str,
+) -> Result<Option<Numeric>, DatabaseError> {
+    match (num, denom) {
+        (Some(num), Some(denom)) => numeric(num, denom, table).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Returns the parts of a numeric to store, using `fraction` as denominator
+/// when the numeric can be represented exactly with it, and the smallest
+/// possible denominator otherwise.
+fn numeric_parts(
+    numeric: Numeric,
+    fraction: i64,
+    table: &'static str,
+) -> Result<(i64, i64), DatabaseError> {
+    let overflow = || DatabaseError::InvalidData {
+        table,
+        message: format!("{numeric} does not fit in 64 bits"),
+    };
+
+    let (num, denom) = if numeric.fits_fraction(i128::from(fraction)) {
+        (numeric.num_at(i128::from(fraction)), i128::from(fraction))
+    } else {
+        (numeric.num(), numeric.denom())
+    };
+
+    Ok((
+        i64::try_from(num).map_err(|_error| overflow())?,
+        i64::try_from(denom).map_err(|_error| overflow())?,
+    ))
+}
+
+/// A timestamp as stored in the database.
+struct Timestamp(DateTime<Utc>);
+
+/// A date as stored in the database.
+struct Date(NaiveDate);
+
+impl FromSql for Timestamp {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let text = value.as_str()?;
+
+        NaiveDateTime::parse_from_str(text, TIMESTAMP_FORMAT)
+            .or_else(|_error| {
+                NaiveDateTime::parse_from_str(text, LEGACY_TIMESTAMP_FORMAT)
+            })
+            .or_else(|error| {
+                NaiveDate::parse_from_str(text, DATE_FORMAT)
+                    .map(|date| date.and_time(chrono::NaiveTime::MIN))
+                    .map_err(|_error| error)
+            })
+            .map(|timestamp| Self(timestamp.and_utc()))
+            .map_err(|error| FromSqlError::Other(Box::new(error)))
+    }
+}
+
+impl ToSql for Timestamp {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(self.0.format(TIMESTAMP_FORMAT).to_string().into())
+    }
+}
+
+impl FromSql for Date {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        // Some tools store dates as integers.
+        let text = match value {
+            ValueRef::Integer(integer) => integer.to_string(),
+            _ => value.as_str()?.to_owned(),
+        };
+
+        NaiveDate::parse_from_str(&text, DATE_FORMAT)
+            .map(Self)
+            .map_err(|error| FromSqlError::Other(Box::new(error)))
+    }
+}
+
+impl ToSql for Date {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(self.0.format(DATE_FORMAT).to_string().into())
+    }
+}
+
+impl FromSql for Guid {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        value
+            .as_str()?
+            .parse()
+            .map_err(|error| FromSqlError::Other(Box::new(error)))
+    }
+}
+
+impl ToSql for Guid {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(self.to_string().into())
+    }
+}
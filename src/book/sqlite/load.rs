@@ -0,0 +1,981 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Loading of books from the database.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    str::FromStr,
+};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rusqlite::{Connection, Row};
+
+use super::{
+    numeric, optional_numeric, DatabaseError, Date, Timestamp,
+    SLOT_TYPE_DOUBLE, SLOT_TYPE_FRAME, SLOT_TYPE_GDATE, SLOT_TYPE_GUID,
+    SLOT_TYPE_INT64, SLOT_TYPE_LIST, SLOT_TYPE_NUMERIC, SLOT_TYPE_STRING,
+    SLOT_TYPE_TIMESPEC,
+};
+use crate::book::{
+    Account, Address, AmountType, BillTerm, Book, Budget, BudgetAmount,
+    Business, Commodity, Customer, Employee, Entry, Frame, Guid, Invoice, Job,
+    Lot, Order, Owner, PaymentType, Price, Recurrence, ScheduledTransaction,
+    SlotValue, Split, TaxIncluded, TaxTable, TaxTableEntry, Transaction,
+    Vendor, SLOT_SEPARATOR,
+};
+
+/// The slots of the book, by GUID of the object or container they belong to.
+struct Slots(HashMap<Guid, Vec<SlotRow>>);
+
+/// A row of the `slots` table.
+struct SlotRow {
+    /// The full name of the slot.
+    name: String,
+    /// The type of the slot.
+    kind: i64,
+    /// The integer value.
+    int64: Option<i64>,
+    /// The string value.
+    string: Option<String>,
+    /// The floating point value.
+    double: Option<f64>,
+    /// The timestamp value.
+    timespec: Option<DateTime<Utc>>,
+    /// The GUID value, also used to link frames and lists to their content.
+    guid: Option<Guid>,
+    /// The numerator of the numeric value.
+    num: Option<i64>,
+    /// The denominator of the numeric value.
+    denom: Option<i64>,
+    /// The date value.
+    gdate: Option<NaiveDate>,
+}
+
+/// Loads the whole book from the database.
+pub(super) fn load_book(
+    connection: &Connection,
+) -> Result<Book, DatabaseError> {
+    let (guid, root_account, root_template) = connection.query_row(
+        "SELECT guid, root_account_guid, root_template_guid FROM books",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+
+    let slots = Slots::load(connection)?;
+    let mut recurrences = load_recurrences(connection)?;
+
+    Ok(Book {
+        guid,
+        root_account,
+        root_template,
+        commodities: load_commodities(connection, &slots)?,
+        accounts: load_accounts(connection, &slots)?,
+        transactions: load_transactions(connection, &slots)?,
+        prices: load_prices(connection)?,
+        lots: load_lots(connection, &slots)?,
+        budgets: load_budgets(connection, &slots, &mut recurrences)?,
+        schedules: load_schedules(connection, &slots, &mut recurrences)?,
+        business: load_business(connection, &slots)?,
+        slots: slots.frame(guid)?,
+    })
+}
+
+impl Slots {
+    /// Loads all the slots of the book.
+    fn load(connection: &Connection) -> Result<Self, DatabaseError> {
+        let mut statement = connection.prepare(
+            "SELECT obj_guid, name, slot_type, int64_val, string_val,
+                double_val, timespec_val, guid_val, numeric_val_num,
+                numeric_val_denom, gdate_val
+            FROM slots
+            ORDER BY id",
+        )?;
+
+        let mut slots: HashMap<Guid, Vec<SlotRow>> = HashMap::new();
+        let mut rows = statement.query([])?;
+        while let Some(row) = rows.next()? {
+            slots.entry(row.get(0)?).or_default().push(SlotRow {
+                name: row.get(1)?,
+                kind: row.get(2)?,
+                int64: row.get(3)?,
+                string: row.get(4)?,
+                double: row.get(5)?,
+                timespec: optional_timestamp(row, 6)?,
+                guid: row.get(7)?,
+                num: row.get(8)?,
+                denom: row.get(9)?,
+                gdate: row.get::<_, Option<Date>>(10)?.map(|date| date.0),
+            });
+        }
+
+        Ok(Self(slots))
+    }
+
+    /// Returns the frame of slots belonging to the given GUID.
+    fn frame(&self, guid: Guid) -> Result<Frame, DatabaseError> {
+        self.0.get(&guid).map_or_else(
+            || Ok(Frame::default()),
+            |rows| {
+                rows.iter()
+                    .map(|row| {
+                        let key = row
+                            .name
+                            .rsplit_once(SLOT_SEPARATOR)
+                            .map_or(row.name.as_str(), |(_, key)| key);
+                        Ok((key.to_owned(), self.value(guid, row)?))
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Returns the value of a slot belonging to `guid`.
+    fn value(
+        &self,
+        guid: Guid,
+        row: &SlotRow,
+    ) -> Result<SlotValue, DatabaseError> {
+        let missing = || {
+            invalid("slots", guid, &format!("slot {} has no value", row.name))
+        };
+
+        let value = match row.kind {
+            SLOT_TYPE_INT64 => SlotValue::Int64(row.int64.unwrap_or_default()),
+            SLOT_TYPE_DOUBLE => {
+                SlotValue::Double(row.double.unwrap_or_default())
+            }
+            SLOT_TYPE_NUMERIC => SlotValue::Numeric(numeric(
+                row.num.unwrap_or_default(),
+                row.denom.unwrap_or(1),
+                "slots",
+            )?),
+            SLOT_TYPE_STRING => {
+                SlotValue::String(row.string.clone().unwrap_or_default())
+            }
+            SLOT_TYPE_GUID => SlotValue::Guid(row.guid.ok_or_else(missing)?),
+            SLOT_TYPE_TIMESPEC => {
+                SlotValue::Timespec(row.timespec.ok_or_else(missing)?)
+            }
+            SLOT_TYPE_GDATE => SlotValue::GDate(row.gdate.ok_or_else(missing)?),
+            SLOT_TYPE_LIST => {
+                let container = row.guid.ok_or_else(missing)?;
+                SlotValue::List(
+                    self.0
+                        .get(&container)
+                        .map(|rows| {
+                            rows.iter()
+                                .map(|element| self.value(container, element))
+                                .collect::<Result<_, _>>()
+                        })
+                        .transpose()?
+                        .unwrap_or_default(),
+                )
+            }
+            SLOT_TYPE_FRAME => {
+                SlotValue::Frame(self.frame(row.guid.ok_or_else(missing)?)?)
+            }
+            kind => {
+                return Err(invalid(
+                    "slots",
+                    guid,
+                    &format!(
+                        "slot {} has the unsupported type {kind}",
+                        row.name
+                    ),
+                ))
+            }
+        };
+
+        Ok(value)
+    }
+}
+
+/// Loads the rows of a table, by GUID.
+///
+/// The GUID must be the first column returned by the query.
+fn load_table<T>(
+    connection: &Connection,
+    query: &str,
+    mut build: impl FnMut(Guid, &Row<'_>) -> Result<T, DatabaseError>,
+) -> Result<BTreeMap<Guid, T>, DatabaseError> {
+    let mut statement = connection.prepare(query)?;
+
+    let mut objects = BTreeMap::new();
+    let mut rows = statement.query([])?;
+    while let Some(row) = rows.next()? {
+        let guid = row.get(0)?;
+        objects.insert(guid, build(guid, row)?);
+    }
+
+    Ok(objects)
+}
+
+/// Loads the commodities.
+fn load_commodities(
+    connection: &Connection,
+    slots: &Slots,
+) -> Result<BTreeMap<Guid, Commodity>, DatabaseError> {
+    load_table(
+        connection,
+        "SELECT guid, namespace, mnemonic, fullname, cusip, fraction,
+            quote_flag, quote_source, quote_tz
+        FROM commodities",
+        |guid, row| {
+            Ok(Commodity {
+                guid,
+                namespace: row.get(1)?,
+                mnemonic: row.get(2)?,
+                fullname: row.get(3)?,
+                cusip: row.get(4)?,
+                fraction: row.get(5)?,
+                quote_flag: row.get(6)?,
+                quote_source: row.get(7)?,
+                quote_tz: row.get(8)?,
+                slots: slots.frame(guid)?,
+            })
+        },
+    )
+}
+
+/// Loads the accounts.
+fn load_accounts(
+    connection: &Connection,
+    slots: &Slots,
+) -> Result<BTreeMap<Guid, Account>, DatabaseError> {
+    load_table(
+        connection,
+        "SELECT guid, name, account_type, commodity_guid, commodity_scu,
+            non_std_scu, parent_guid, code, description, hidden, placeholder
+        FROM accounts",
+        |guid, row| {
+            Ok(Account {
+                guid,
+                name: row.get(1)?,
+                kind: parse("accounts", guid, &row.get::<_, String>(2)?)?,
+                commodity: row.get(3)?,
+                commodity_scu: row.get(4)?,
+                non_std_scu: row.get(5)?,
+                parent: row.get(6)?,
+                code: row.get(7)?,
+                description: row.get(8)?,
+                hidden: flag(row, 9)?,
+                placeholder: flag(row, 10)?,
+                slots: slots.frame(guid)?,
+            })
+        },
+    )
+}
+
+/// Loads the transactions with their splits.
+fn load_transactions(
+    connection: &Connection,
+    slots: &Slots,
+) -> Result<BTreeMap<Guid, Transaction>, DatabaseError> {
+    let mut transactions = load_table(
+        connection,
+        "SELECT guid, currency_guid, num, post_date, enter_date, description
+        FROM transactions",
+        |guid, row| {
+            Ok(Transaction {
+                guid,
+                currency: row.get(1)?,
+                num: row.get(2)?,
+                post_date: row.get::<_, Timestamp>(3)?.0,
+                enter_date: row.get::<_, Timestamp>(4)?.0,
+                description: row
+                    .get::<_, Option<String>>(5)?
+                    .unwrap_or_default(),
+                splits: vec![],
+                slots: slots.frame(guid)?,
+            })
+        },
+    )?;
+
+    let mut statement = connection.prepare(
+        "SELECT guid, tx_guid, account_guid, memo, action, reconcile_state,
+            reconcile_date, value_num, value_denom, quantity_num,
+            quantity_denom, lot_guid
+        FROM splits",
+    )?;
+
+    let mut rows = statement.query([])?;
+    while let Some(row) = rows.next()? {
+        let guid = row.get(0)?;
+        let tx_guid: Guid = row.get(1)?;
+        let reconcile_state: String = row.get(5)?;
+
+        let split = Split {
+            guid,
+            account: row.get(2)?,
+            memo: row.get(3)?,
+            action: row.get(4)?,
+            reconcile_state: reconcile_state
+                .as_str()
+                .try_into()
+                .map_err(|error| invalid("splits", guid, &error))?,
+            reconcile_date: optional_timestamp(row, 6)?,
+            value: numeric(row.get(7)?, row.get(8)?, "splits")?,
+            quantity: numeric(row.get(9)?, row.get(10)?, "splits")?,
+            lot: row.get(11)?,
+            slots: slots.frame(guid)?,
+        };
+
+        transactions
+            .get_mut(&tx_guid)
+            .ok_or_else(|| {
+                invalid(
+                    "splits",
+                    guid,
+                    &format!("unknown transaction {tx_guid}"),
+                )
+            })?
+            .splits
+            .push(split);
+    }
+
+    Ok(transactions)
+}
+
+/// Loads the prices.
+fn load_prices(
+    connection: &Connection,
+) -> Result<BTreeMap<Guid, Price>, DatabaseError> {
+    load_table(
+        connection,
+        "SELECT guid, commodity_guid, currency_guid, date, source, type,
+            value_num, value_denom
+        FROM prices",
+        |guid, row| {
+            Ok(Price {
+                guid,
+                commodity: row.get(1)?,
+                currency: row.get(2)?,
+                date: row.get::<_, Timestamp>(3)?.0,
+                source: row.get(4)?,
+                kind: row.get(5)?,
+                value: numeric(row.get(6)?, row.get(7)?, "prices")?,
+            })
+        },
+    )
+}
+
+/// Loads the lots.
+fn load_lots(
+    connection: &Connection,
+    slots: &Slots,
+) -> Result<BTreeMap<Guid, Lot>, DatabaseError> {
+    load_table(
+        connection,
+        "SELECT guid, account_guid, is_closed FROM lots",
+        |guid, row| {
+            Ok(Lot {
+                guid,
+                account: row.get(1)?,
+                is_closed: row.get(2)?,
+                slots: slots.frame(guid)?,
+            })
+        },
+    )
+}
+
+/// Loads the recurrences, by GUID of the object they belong to.
+fn load_recurrences(
+    connection: &Connection,
+) -> Result<HashMap<Guid, Vec<Recurrence>>, DatabaseError> {
+    let mut statement = connection.prepare(
+        "SELECT obj_guid, recurrence_mult, recurrence_period_type,
+            recurrence_period_start, recurrence_weekend_adjust
+        FROM recurrences
+        ORDER BY id",
+    )?;
+
+    let mut recurrences: HashMap<Guid, Vec<Recurrence>> = HashMap::new();
+    let mut rows = statement.query([])?;
+    while let Some(row) = rows.next()? {
+        let guid = row.get(0)?;
+
+        recurrences.entry(guid).or_default().push(Recurrence {
+            multiplier: row.get(1)?,
+            period: parse("recurrences", guid, &row.get::<_, String>(2)?)?,
+            start: row.get::<_, Date>(3)?.0,
+            weekend_adjust: parse(
+                "recurrences",
+                guid,
+                &row.get::<_, String>(4)?,
+            )?,
+        });
+    }
+
+    Ok(recurrences)
+}
+
+/// Loads the budgets with their amounts.
+fn load_budgets(
+    connection: &Connection,
+    slots: &Slots,
+    recurrences: &mut HashMap<Guid, Vec<Recurrence>>,
+) -> Result<BTreeMap<Guid, Budget>, DatabaseError> {
+    let mut budgets = load_table(
+        connection,
+        "SELECT guid, name, description, num_periods FROM budgets",
+        |guid, row| {
+            Ok(Budget {
+                guid,
+                name: row.get(1)?,
+                description: row.get(2)?,
+                num_periods: row.get(3)?,
+                recurrence: recurrences
+                    .remove(&guid)
+                    .and_then(|recurrences| recurrences.into_iter().next())
+                    .ok_or_else(|| {
+                        invalid(
+                            "budgets",
+                            guid,
+                            &"the budget has no recurrence",
+                        )
+                    })?,
+                amounts: vec![],
+                slots: slots.frame(guid)?,
+            })
+        },
+    )?;
+
+    let mut statement = connection.prepare(
+        "SELECT budget_guid, account_guid, period_num, amount_num, amount_denom
+        FROM budget_amounts
+        ORDER BY account_guid, period_num",
+    )?;
+
+    let mut rows = statement.query([])?;
+    while let Some(row) = rows.next()? {
+        let guid: Guid = row.get(0)?;
+
+        budgets
+            .get_mut(&guid)
+            .ok_or_else(|| invalid("budget_amounts", guid, &"unknown budget"))?
+            .amounts
+            .push(BudgetAmount {
+                account: row.get(1)?,
+                period: row.get(2)?,
+                amount: numeric(row.get(3)?, row.get(4)?, "budget_amounts")?,
+            });
+    }
+
+    Ok(budgets)
+}
+
+/// Loads the scheduled transactions.
+fn load_schedules(
+    connection: &Connection,
+    slots: &Slots,
+    recurrences: &mut HashMap<Guid, Vec<Recurrence>>,
+) -> Result<BTreeMap<Guid, ScheduledTransaction>, DatabaseError> {
+    load_table(
+        connection,
+        "SELECT guid, name, enabled, start_date, end_date, last_occur,
+            num_occur, rem_occur, auto_create, auto_notify, adv_creation,
+            adv_notify, instance_count, template_act_guid
+        FROM schedxactions",
+        |guid, row| {
+            Ok(ScheduledTransaction {
+                guid,
+                name: row.get(1)?,
+                enabled: row.get(2)?,
+                start_date: optional_date(row, 3)?,
+                end_date: optional_date(row, 4)?,
+                last_occur: optional_date(row, 5)?,
+                num_occur: row.get(6)?,
+                rem_occur: row.get(7)?,
+                auto_create: row.get(8)?,
+                auto_notify: row.get(9)?,
+                adv_creation: row.get(10)?,
+                adv_notify: row.get(11)?,
+                instance_count: row.get(12)?,
+                template_account: row.get(13)?,
+                recurrences: recurrences.remove(&guid).unwrap_or_default(),
+                slots: slots.frame(guid)?,
+            })
+        },
+    )
+}
+
+/// Loads the business objects.
+fn load_business(
+    connection: &Connection,
+    slots: &Slots,
+) -> Result<Business, DatabaseError> {
+    Ok(Business {
+        bill_terms: load_bill_terms(connection, slots)?,
+        tax_tables: load_tax_tables(connection, slots)?,
+        customers: load_customers(connection, slots)?,
+        vendors: load_vendors(connection, slots)?,
+        employees: load_employees(connection, slots)?,
+        jobs: load_jobs(connection, slots)?,
+        invoices: load_invoices(connection, slots)?,
+        entries: load_entries(connection, slots)?,
+        orders: load_orders(connection, slots)?,
+    })
+}
+
+/// Loads the billing terms.
+fn load_bill_terms(
+    connection: &Connection,
+    slots: &Slots,
+) -> Result<BTreeMap<Guid, BillTerm>, DatabaseError> {
+    load_table(
+        connection,
+        "SELECT guid, name, description, refcount, invisible, parent, type,
+            duedays, discountdays, discount_num, discount_denom, cutoff
+        FROM billterms",
+        |guid, row| {
+            Ok(BillTerm {
+                guid,
+                name: row.get(1)?,
+                description: row.get(2)?,
+                refcount: row.get(3)?,
+                invisible: row.get(4)?,
+                parent: row.get(5)?,
+                kind: parse("billterms", guid, &row.get::<_, String>(6)?)?,
+                due_days: row.get::<_, Option<i64>>(7)?.unwrap_or_default(),
+                discount_days: row
+                    .get::<_, Option<i64>>(8)?
+                    .unwrap_or_default(),
+                discount: optional_numeric(
+                    row.get(9)?,
+                    row.get(10)?,
+                    "billterms",
+                )?,
+                cutoff: row.get::<_, Option<i64>>(11)?.unwrap_or_default(),
+                slots: slots.frame(guid)?,
+            })
+        },
+    )
+}
+
+/// Loads the tax tables with their entries.
+fn load_tax_tables(
+    connection: &Connection,
+    slots: &Slots,
+) -> Result<BTreeMap<Guid, TaxTable>, DatabaseError> {
+    let mut tax_tables = load_table(
+        connection,
+        "SELECT guid, name, refcount, invisible, parent FROM taxtables",
+        |guid, row| {
+            Ok(TaxTable {
+                guid,
+                name: row.get(1)?,
+                refcount: row.get(2)?,
+                invisible: row.get(3)?,
+                parent: row.get(4)?,
+                entries: vec![],
+                slots: slots.frame(guid)?,
+            })
+        },
+    )?;
+
+    let mut statement = connection.prepare(
+        "SELECT taxtable, account, amount_num, amount_denom, type
+        FROM taxtable_entries
+        ORDER BY id",
+    )?;
+
+    let mut rows = statement.query([])?;
+    while let Some(row) = rows.next()? {
+        let guid: Guid = row.get(0)?;
+
+        tax_tables
+            .get_mut(&guid)
+            .ok_or_else(|| {
+                invalid("taxtable_entries", guid, &"unknown tax table")
+            })?
+            .entries
+            .push(TaxTableEntry {
+                account: row.get(1)?,
+                amount: numeric(row.get(2)?, row.get(3)?, "taxtable_entries")?,
+                kind: code(
+                    "taxtable_entries",
+                    guid,
+                    row.get(4)?,
+                    AmountType::from_code,
+                )?
+                .ok_or_else(|| {
+                    invalid("taxtable_entries", guid, &"missing type")
+                })?,
+            });
+    }
+
+    Ok(tax_tables)
+}
+
+/// Loads the customers.
+fn load_customers(
+    connection: &Connection,
+    slots: &Slots,
+) -> Result<BTreeMap<Guid, Customer>, DatabaseError> {
+    load_table(
+        connection,
+        "SELECT guid, name, id, notes, active, discount_num, discount_denom,
+            credit_num, credit_denom, currency, tax_override, addr_name,
+            addr_addr1, addr_addr2, addr_addr3, addr_addr4, addr_phone,
+            addr_fax, addr_email, shipaddr_name, shipaddr_addr1,
+            shipaddr_addr2, shipaddr_addr3, shipaddr_addr4, shipaddr_phone,
+            shipaddr_fax, shipaddr_email, terms, tax_included, taxtable
+        FROM customers",
+        |guid, row| {
+            Ok(Customer {
+                guid,
+                name: row.get(1)?,
+                id: row.get(2)?,
+                notes: row.get(3)?,
+                active: row.get(4)?,
+                discount: numeric(row.get(5)?, row.get(6)?, "customers")?,
+                credit: numeric(row.get(7)?, row.get(8)?, "customers")?,
+                currency: row.get(9)?,
+                tax_override: row.get(10)?,
+                address: address(row, 11)?,
+                ship_address: address(row, 19)?,
+                terms: row.get(27)?,
+                tax_included: code(
+                    "customers",
+                    guid,
+                    row.get(28)?,
+                    TaxIncluded::from_code,
+                )?,
+                tax_table: row.get(29)?,
+                slots: slots.frame(guid)?,
+            })
+        },
+    )
+}
+
+/// Loads the vendors.
+fn load_vendors(
+    connection: &Connection,
+    slots: &Slots,
+) -> Result<BTreeMap<Guid, Vendor>, DatabaseError> {
+    load_table(
+        connection,
+        "SELECT guid, name, id, notes, currency, active, tax_override,
+            addr_name, addr_addr1, addr_addr2, addr_addr3, addr_addr4,
+            addr_phone, addr_fax, addr_email, terms, tax_inc, tax_table
+        FROM vendors",
+        |guid, row| {
+            Ok(Vendor {
+                guid,
+                name: row.get(1)?,
+                id: row.get(2)?,
+                notes: row.get(3)?,
+                currency: row.get(4)?,
+                active: row.get(5)?,
+                tax_override: row.get(6)?,
+                address: address(row, 7)?,
+                terms: row.get(15)?,
+                tax_included: parse_optional("vendors", guid, row.get(16)?)?,
+                tax_table: row.get(17)?,
+                slots: slots.frame(guid)?,
+            })
+        },
+    )
+}
+
+/// Loads the employees.
+fn load_employees(
+    connection: &Connection,
+    slots: &Slots,
+) -> Result<BTreeMap<Guid, Employee>, DatabaseError> {
+    load_table(
+        connection,
+        "SELECT guid, username, id, language, acl, active, currency,
+            ccard_guid, workday_num, workday_denom, rate_num, rate_denom,
+            addr_name, addr_addr1, addr_addr2, addr_addr3, addr_addr4,
+            addr_phone, addr_fax, addr_email
+        FROM employees",
+        |guid, row| {
+            Ok(Employee {
+                guid,
+                username: row.get(1)?,
+                id: row.get(2)?,
+                language: row.get(3)?,
+                acl: row.get(4)?,
+                active: row.get(5)?,
+                currency: row.get(6)?,
+                credit_card: row.get(7)?,
+                workday: numeric(row.get(8)?, row.get(9)?, "employees")?,
+                rate: numeric(row.get(10)?, row.get(11)?, "employees")?,
+                address: address(row, 12)?,
+                slots: slots.frame(guid)?,
+            })
+        },
+    )
+}
+
+/// Loads the jobs.
+fn load_jobs(
+    connection: &Connection,
+    slots: &Slots,
+) -> Result<BTreeMap<Guid, Job>, DatabaseError> {
+    load_table(
+        connection,
+        "SELECT guid, id, name, reference, active, owner_type, owner_guid
+        FROM jobs",
+        |guid, row| {
+            Ok(Job {
+                guid,
+                id: row.get(1)?,
+                name: row.get(2)?,
+                reference: row.get(3)?,
+                active: row.get(4)?,
+                owner: Owner::from_code(row.get(5)?, row.get(6)?),
+                slots: slots.frame(guid)?,
+            })
+        },
+    )
+}
+
+/// Loads the invoices.
+fn load_invoices(
+    connection: &Connection,
+    slots: &Slots,
+) -> Result<BTreeMap<Guid, Invoice>, DatabaseError> {
+    load_table(
+        connection,
+        "SELECT guid, id, date_opened, date_posted, notes, active, currency,
+            owner_type, owner_guid, terms, billing_id, post_txn, post_lot,
+            post_acc, billto_type, billto_guid, charge_amt_num,
+            charge_amt_denom
+        FROM invoices",
+        |guid, row| {
+            Ok(Invoice {
+                guid,
+                id: row.get(1)?,
+                date_opened: optional_timestamp(row, 2)?,
+                date_posted: optional_timestamp(row, 3)?,
+                notes: row.get(4)?,
+                active: row.get(5)?,
+                currency: row.get(6)?,
+                owner: Owner::from_code(row.get(7)?, row.get(8)?),
+                terms: row.get(9)?,
+                billing_id: row.get(10)?,
+                post_txn: row.get(11)?,
+                post_lot: row.get(12)?,
+                post_account: row.get(13)?,
+                bill_to: Owner::from_code(row.get(14)?, row.get(15)?),
+                charge_amount: optional_numeric(
+                    row.get(16)?,
+                    row.get(17)?,
+                    "invoices",
+                )?,
+                slots: slots.frame(guid)?,
+            })
+        },
+    )
+}
+
+/// Loads the entries of invoices and orders.
+fn load_entries(
+    connection: &Connection,
+    slots: &Slots,
+) -> Result<BTreeMap<Guid, Entry>, DatabaseError> {
+    load_table(
+        connection,
+        "SELECT guid, date, date_entered, description, action, notes,
+            quantity_num, quantity_denom, i_acct, i_price_num, i_price_denom,
+            i_discount_num, i_discount_denom, invoice, i_disc_type,
+            i_disc_how, i_taxable, i_taxincluded, i_taxtable, b_acct,
+            b_price_num, b_price_denom, bill, b_taxable, b_taxincluded,
+            b_taxtable, b_paytype, billable, billto_type, billto_guid,
+            order_guid
+        FROM entries",
+        |guid, row| {
+            Ok(Entry {
+                guid,
+                date: row.get::<_, Timestamp>(1)?.0,
+                date_entered: optional_timestamp(row, 2)?,
+                description: row.get(3)?,
+                action: row.get(4)?,
+                notes: row.get(5)?,
+                quantity: optional_numeric(
+                    row.get(6)?,
+                    row.get(7)?,
+                    "entries",
+                )?,
+                invoice_account: row.get(8)?,
+                invoice_price: optional_numeric(
+                    row.get(9)?,
+                    row.get(10)?,
+                    "entries",
+                )?,
+                invoice_discount: optional_numeric(
+                    row.get(11)?,
+                    row.get(12)?,
+                    "entries",
+                )?,
+                invoice: row.get(13)?,
+                invoice_discount_type: parse_optional(
+                    "entries",
+                    guid,
+                    row.get(14)?,
+                )?,
+                invoice_discount_how: parse_optional(
+                    "entries",
+                    guid,
+                    row.get(15)?,
+                )?,
+                invoice_taxable: flag(row, 16)?,
+                invoice_tax_included: flag(row, 17)?,
+                invoice_tax_table: row.get(18)?,
+                bill_account: row.get(19)?,
+                bill_price: optional_numeric(
+                    row.get(20)?,
+                    row.get(21)?,
+                    "entries",
+                )?,
+                bill: row.get(22)?,
+                bill_taxable: flag(row, 23)?,
+                bill_tax_included: flag(row, 24)?,
+                bill_tax_table: row.get(25)?,
+                bill_payment: code(
+                    "entries",
+                    guid,
+                    row.get(26)?,
+                    PaymentType::from_code,
+                )?,
+                billable: flag(row, 27)?,
+                bill_to: Owner::from_code(row.get(28)?, row.get(29)?),
+                order: row.get(30)?,
+                slots: slots.frame(guid)?,
+            })
+        },
+    )
+}
+
+/// Loads the orders.
+fn load_orders(
+    connection: &Connection,
+    slots: &Slots,
+) -> Result<BTreeMap<Guid, Order>, DatabaseError> {
+    load_table(
+        connection,
+        "SELECT guid, id, notes, reference, active, date_opened, date_closed,
+            owner_type, owner_guid
+        FROM orders",
+        |guid, row| {
+            Ok(Order {
+                guid,
+                id: row.get(1)?,
+                notes: row.get(2)?,
+                reference: row.get(3)?,
+                active: row.get(4)?,
+                date_opened: row.get::<_, Timestamp>(5)?.0,
+                date_closed: row.get::<_, Timestamp>(6)?.0,
+                owner: Owner::from_code(row.get(7)?, row.get(8)?)
+                    .ok_or_else(|| invalid("orders", guid, &"invalid owner"))?,
+                slots: slots.frame(guid)?,
+            })
+        },
+    )
+}
+
+/// Reads an address stored in the 8 columns starting at `first`.
+fn address(row: &Row<'_>, first: usize) -> rusqlite::Result<Address> {
+    Ok(Address {
+        name: row.get(first)?,
+        addr1: row.get(first + 1)?,
+        addr2: row.get(first + 2)?,
+        addr3: row.get(first + 3)?,
+        addr4: row.get(first + 4)?,
+        phone: row.get(first + 5)?,
+        fax: row.get(first + 6)?,
+        email: row.get(first + 7)?,
+    })
+}
+
+/// Reads a nullable boolean column, defaulting to `false`.
+fn flag(row: &Row<'_>, index: usize) -> rusqlite::Result<bool> {
+    Ok(row.get::<_, Option<bool>>(index)?.unwrap_or_default())
+}
+
+/// Reads a nullable timestamp column.
+fn optional_timestamp(
+    row: &Row<'_>,
+    index: usize,
+) -> rusqlite::Result<Option<DateTime<Utc>>> {
+    Ok(row
+        .get::<_, Option<Timestamp>>(index)?
+        .map(|timestamp| timestamp.0))
+}
+
+/// Reads a nullable date column.
+fn optional_date(
+    row: &Row<'_>,
+    index: usize,
+) -> rusqlite::Result<Option<NaiveDate>> {
+    Ok(row.get::<_, Option<Date>>(index)?.map(|date| date.0))
+}
+
+/// Parses a value stored as text.
+fn parse<T>(
+    table: &'static str,
+    guid: Guid,
+    value: &str,
+) -> Result<T, DatabaseError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    value.parse().map_err(|error| invalid(table, guid, &error))
+}
+
+/// Parses an optional value stored as text.
+fn parse_optional<T>(
+    table: &'static str,
+    guid: Guid,
+    value: Option<String>,
+) -> Result<Option<T>, DatabaseError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    value.map(|value| parse(table, guid, &value)).transpose()
+}
+
+/// Decodes an optional value stored as an integer code.
+fn code<T>(
+    table: &'static str,
+    guid: Guid,
+    code: Option<i64>,
+    from_code: fn(i64) -> Option<T>,
+) -> Result<Option<T>, DatabaseError> {
+    code.map(|code| {
+        from_code(code).ok_or_else(|| {
+            invalid(table, guid, &format!("invalid code {code}"))
+        })
+    })
+    .transpose()
+}
+
+/// Builds an error for invalid data in a row of `table`.
+fn invalid(
+    table: &'static str,
+    guid: Guid,
+    error: &dyn fmt::Display,
+) -> DatabaseError {
+    DatabaseError::InvalidData {
+        table,
+        message: format!("{guid}: {error}"),
+    }
+}
@@ -0,0 +1,997 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Saving of books to the database.
+
+use rusqlite::{params, Connection};
+
+use super::{
+    numeric_parts, DatabaseError, Date, Timestamp, SLOT_TYPE_DOUBLE,
+    SLOT_TYPE_FRAME, SLOT_TYPE_GDATE, SLOT_TYPE_GUID, SLOT_TYPE_INT64,
+    SLOT_TYPE_LIST, SLOT_TYPE_NUMERIC, SLOT_TYPE_STRING, SLOT_TYPE_TIMESPEC,
+};
+use crate::book::{
+    Account, Address, AmountType, Book, Business, Customer, DiscountHow,
+    Employee, Entry, Frame, Guid, Invoice, Lot, Numeric, Owner, PaymentType,
+    Recurrence, ScheduledTransaction, SlotValue, Split, TaxIncluded,
+    Transaction, Vendor, SLOT_SEPARATOR,
+};
+
+/// The value columns of a row of the `slots` table.
+struct SlotColumns<'a> {
+    /// The type of the slot.
+    kind: i64,
+    /// The integer value.
+    int64: i64,
+    /// The string value.
+    string: Option<&'a str>,
+    /// The floating point value.
+    double: f64,
+    /// The timestamp value.
+    timespec: Option<Timestamp>,
+    /// The GUID value, also used to link frames and lists to their content.
+    guid: Option<Guid>,
+    /// The numerator of the numeric value.
+    num: i64,
+    /// The denominator of the numeric value.
+    denom: i64,
+    /// The date value.
+    gdate: Option<Date>,
+}
+
+/// Saves a whole book in an empty database.
+pub(super) fn save_book(
+    connection: &Connection,
+    book: &Book,
+) -> Result<(), DatabaseError> {
+    connection.execute(
+        "INSERT INTO books (guid, root_account_guid, root_template_guid)
+        VALUES (?1, ?2, ?3)",
+        params![book.guid, book.root_account, book.root_template],
+    )?;
+    insert_slots(connection, book.guid, &book.slots)?;
+
+    for commodity in book.commodities.values() {
+        connection.execute(
+            "INSERT INTO commodities
+                (guid, namespace, mnemonic, fullname, cusip, fraction,
+                quote_flag, quote_source, quote_tz)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                commodity.guid,
+                commodity.namespace,
+                commodity.mnemonic,
+                commodity.fullname,
+                commodity.cusip,
+                commodity.fraction,
+                commodity.quote_flag,
+                commodity.quote_source,
+                commodity.quote_tz,
+            ],
+        )?;
+        insert_slots(connection, commodity.guid, &commodity.slots)?;
+    }
+
+    for account in book.accounts.values() {
+        insert_account(connection, account)?;
+    }
+
+    for price in book.prices.values() {
+        let fraction = book
+            .commodity(price.currency)
+            .map_or(1, |currency| currency.fraction);
+        let (value_num, value_denom) =
+            numeric_parts(price.value, fraction, "prices")?;
+
+        connection.execute(
+            "INSERT INTO prices
+                (guid, commodity_guid, currency_guid, date, source, type,
+                value_num, value_denom)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                price.guid,
+                price.commodity,
+                price.currency,
+                Timestamp(price.date),
+                price.source,
+                price.kind,
+                value_num,
+                value_denom,
+            ],
+        )?;
+    }
+
+    for lot in book.lots.values() {
+        insert_lot(connection, lot)?;
+    }
+
+    for transaction in book.transactions.values() {
+        insert_transaction(connection, book, transaction)?;
+    }
+
+    save_budgets(connection, book)?;
+    save_schedules(connection, book)?;
+    save_business(connection, &book.business)?;
+
+    Ok(())
+}
+
+/// Inserts an account with its slots.
+pub(super) fn insert_account(
+    connection: &Connection,
+    account: &Account,
+) -> Result<(), DatabaseError> {
+    connection.execute(
+        "INSERT INTO accounts
+            (guid, name, account_type, commodity_guid, commodity_scu,
+            non_std_scu, parent_guid, code, description, hidden, placeholder)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            account.guid,
+            account.name,
+            account.kind.as_str(),
+            account.commodity,
+            account.commodity_scu,
+            account.non_std_scu,
+            account.parent,
+            account.code,
+            account.description,
+            account.hidden,
+            account.placeholder,
+        ],
+    )?;
+
+    insert_slots(connection, account.guid, &account.slots)
+}
+
+/// Inserts a lot with its slots.
+pub(super) fn insert_lot(
+    connection: &Connection,
+    lot: &Lot,
+) -> Result<(), DatabaseError> {
+    connection.execute(
+        "INSERT INTO lots (guid, account_guid, is_closed)
+        VALUES (?1, ?2, ?3)",
+        params![lot.guid, lot.account, lot.is_closed],
+    )?;
+
+    insert_slots(connection, lot.guid, &lot.slots)
+}
+
+/// Inserts a transaction with its splits and slots.
+pub(super) fn insert_transaction(
+    connection: &Connection,
+    book: &Book,
+    transaction: &Transaction,
+) -> Result<(), DatabaseError> {
+    connection.execute(
+        "INSERT INTO transactions
+            (guid, currency_guid, num, post_date, enter_date, description)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            transaction.guid,
+            transaction.currency,
+            transaction.num,
+            Timestamp(transaction.post_date),
+            Timestamp(transaction.enter_date),
+            transaction.description,
+        ],
+    )?;
+
+    for split in &transaction.splits {
+        insert_split(connection, book, transaction, split)?;
+    }
+
+    insert_slots(connection, transaction.guid, &transaction.slots)
+}
+
+/// Inserts a split of a transaction with its slots.
+pub(super) fn insert_split(
+    connection: &Connection,
+    book: &Book,
+    transaction: &Transaction,
+    split: &Split,
+) -> Result<(), DatabaseError> {
+    let currency_fraction = book
+        .commodity(transaction.currency)
+        .map_or(1, |currency| currency.fraction);
+    let scu = book
+        .account(split.account)
+        .map_or(1, |account| account.commodity_scu);
+
+    let (value_num, value_denom) =
+        numeric_parts(split.value, currency_fraction, "splits")?;
+    let (quantity_num, quantity_denom) =
+        numeric_parts(split.quantity, scu, "splits")?;
+
+    connection.execute(
+        "INSERT INTO splits
+            (guid, tx_guid, account_guid, memo, action, reconcile_state,
+            reconcile_date, value_num, value_denom, quantity_num,
+            quantity_denom, lot_guid)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        params![
+            split.guid,
+            transaction.guid,
+            split.account,
+            split.memo,
+            split.action,
+            split.reconcile_state.to_string(),
+            split.reconcile_date.map(Timestamp),
+            value_num,
+            value_denom,
+            quantity_num,
+            quantity_denom,
+            split.lot,
+        ],
+    )?;
+
+    insert_slots(connection, split.guid, &split.slots)
+}
+
+/// Deletes a transaction with its splits and slots.
+pub(super) fn delete_transaction(
+    connection: &Connection,
+    transaction: &Transaction,
+) -> Result<(), DatabaseError> {
+    for split in &transaction.splits {
+        delete_object(connection, "splits", split.guid)?;
+    }
+
+    delete_object(connection, "transactions", transaction.guid)
+}
+
+/// Inserts the budgets with their recurrence and amounts.
+fn save_budgets(
+    connection: &Connection,
+    book: &Book,
+) -> Result<(), DatabaseError> {
+    for budget in book.budgets.values() {
+        connection.execute(
+            "INSERT INTO budgets (guid, name, description, num_periods)
+            VALUES (?1, ?2, ?3, ?4)",
+            params![
+                budget.guid,
+                budget.name,
+                budget.description,
+                budget.num_periods,
+            ],
+        )?;
+        insert_recurrence(connection, budget.guid, &budget.recurrence)?;
+        insert_slots(connection, budget.guid, &budget.slots)?;
+
+        for amount in &budget.amounts {
+            let fraction = book
+                .account_commodity(amount.account)
+                .map_or(1, |commodity| commodity.fraction);
+            let (amount_num, amount_denom) =
+                numeric_parts(amount.amount, fraction, "budget_amounts")?;
+
+            connection.execute(
+                "INSERT INTO budget_amounts
+                    (budget_guid, account_guid, period_num, amount_num,
+                    amount_denom)
+                VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    budget.guid,
+                    amount.account,
+                    amount.period,
+                    amount_num,
+                    amount_denom,
+                ],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Inserts the scheduled transactions with their recurrences.
+fn save_schedules(
+    connection: &Connection,
+    book: &Book,
+) -> Result<(), DatabaseError> {
+    for schedule in book.schedules.values() {
+        connection.execute(
+            "INSERT INTO schedxactions
+                (guid, name, enabled, start_date, end_date, last_occur,
+                num_occur, rem_occur, auto_create, auto_notify, adv_creation,
+                adv_notify, instance_count, template_act_guid)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13,
+                ?14)",
+            params![
+                schedule.guid,
+                schedule.name,
+                schedule.enabled,
+                schedule.start_date.map(Date),
+                schedule.end_date.map(Date),
+                schedule.last_occur.map(Date),
+                schedule.num_occur,
+                schedule.rem_occur,
+                schedule.auto_create,
+                schedule.auto_notify,
+                schedule.adv_creation,
+                schedule.adv_notify,
+                schedule.instance_count,
+                schedule.template_account,
+            ],
+        )?;
+
+        for recurrence in &schedule.recurrences {
+            insert_recurrence(connection, schedule.guid, recurrence)?;
+        }
+
+        insert_slots(connection, schedule.guid, &schedule.slots)?;
+    }
+
+    Ok(())
+}
+
+/// Updates the state of a scheduled transaction after creating occurrences.
+pub(super) fn update_schedule(
+    connection: &Connection,
+    schedule: &ScheduledTransaction,
+) -> Result<(), DatabaseError> {
+    connection.execute(
+        "UPDATE schedxactions
+        SET last_occur = ?1, rem_occur = ?2, instance_count = ?3
+        WHERE guid = ?4",
+        params![
+            schedule.last_occur.map(Date),
+            schedule.rem_occur,
+            schedule.instance_count,
+            schedule.guid,
+        ],
+    )?;
+
+    Ok(())
+}
+
+/// Inserts the business objects.
+#[expect(
+    clippy::too_many_lines,
+    reason = "Each business table needs its own query."
+)]
+fn save_business(
+    connection: &Connection,
+    business: &Business,
+) -> Result<(), DatabaseError> {
+    for term in business.bill_terms.values() {
+        let (discount_num, discount_denom) =
+            optional_parts(term.discount, "billterms")?;
+
+        connection.execute(
+            "INSERT INTO billterms
+                (guid, name, description, refcount, invisible, parent, type,
+                duedays, discountdays, discount_num, discount_denom, cutoff)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+            params![
+                term.guid,
+                term.name,
+                term.description,
+                term.refcount,
+                term.invisible,
+                term.parent,
+                term.kind.as_str(),
+                term.due_days,
+                term.discount_days,
+                discount_num,
+                discount_denom,
+                term.cutoff,
+            ],
+        )?;
+        insert_slots(connection, term.guid, &term.slots)?;
+    }
+
+    for table in business.tax_tables.values() {
+        connection.execute(
+            "INSERT INTO taxtables (guid, name, refcount, invisible, parent)
+            VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                table.guid,
+                table.name,
+                table.refcount,
+                table.invisible,
+                table.parent,
+            ],
+        )?;
+
+        for entry in &table.entries {
+            let (amount_num, amount_denom) =
+                numeric_parts(entry.amount, 1, "taxtable_entries")?;
+
+            connection.execute(
+                "INSERT INTO taxtable_entries
+                    (taxtable, account, amount_num, amount_denom, type)
+                VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    table.guid,
+                    entry.account,
+                    amount_num,
+                    amount_denom,
+                    entry.kind.code(),
+                ],
+            )?;
+        }
+
+        insert_slots(connection, table.guid, &table.slots)?;
+    }
+
+    for customer in business.customers.values() {
+        insert_customer(connection, customer)?;
+    }
+
+    for vendor in business.vendors.values() {
+        insert_vendor(connection, vendor)?;
+    }
+
+    for employee in business.employees.values() {
+        insert_employee(connection, employee)?;
+    }
+
+    for job in business.jobs.values() {
+        let (owner_type, owner_guid) = owner_columns(job.owner);
+
+        connection.execute(
+            "INSERT INTO jobs
+                (guid, id, name, reference, active, owner_type, owner_guid)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                job.guid,
+                job.id,
+                job.name,
+                job.reference,
+                job.active,
+                owner_type,
+                owner_guid,
+            ],
+        )?;
+        insert_slots(connection, job.guid, &job.slots)?;
+    }
+
+    for invoice in business.invoices.values() {
+        insert_invoice(connection, invoice)?;
+    }
+
+    for entry in business.entries.values() {
+        insert_entry(connection, entry)?;
+    }
+
+    for order in business.orders.values() {
+        connection.execute(
+            "INSERT INTO orders
+                (guid, id, notes, reference, active, date_opened, date_closed,
+                owner_type, owner_guid)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                order.guid,
+                order.id,
+                order.notes,
+                order.reference,
+                order.active,
+                Timestamp(order.date_opened),
+                Timestamp(order.date_closed),
+                order.owner.kind.code(),
+                order.owner.guid,
+            ],
+        )?;
+        insert_slots(connection, order.guid, &order.slots)?;
+    }
+
+    Ok(())
+}
+
+/// Inserts a customer with its slots.
+pub(super) fn insert_customer(
+    connection: &Connection,
+    customer: &Customer,
+) -> Result<(), DatabaseError> {
+    let (discount_num, discount_denom) =
+        numeric_parts(customer.discount, 1, "customers")?;
+    let (credit_num, credit_denom) =
+        numeric_parts(customer.credit, 1, "customers")?;
+    let address = address_columns(&customer.address);
+    let ship_address = address_columns(&customer.ship_address);
+
+    connection.execute(
+        "INSERT INTO customers
+            (guid, name, id, notes, active, discount_num, discount_denom,
+            credit_num, credit_denom, currency, tax_override, addr_name,
+            addr_addr1, addr_addr2, addr_addr3, addr_addr4, addr_phone,
+            addr_fax, addr_email, shipaddr_name, shipaddr_addr1,
+            shipaddr_addr2, shipaddr_addr3, shipaddr_addr4,
+            shipaddr_phone, shipaddr_fax, shipaddr_email, terms,
+            tax_included, taxtable)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13,
+            ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25,
+            ?26, ?27, ?28, ?29, ?30)",
+        params![
+            customer.guid,
+            customer.name,
+            customer.id,
+            customer.notes,
+            customer.active,
+            discount_num,
+            discount_denom,
+            credit_num,
+            credit_denom,
+            customer.currency,
+            customer.tax_override,
+            address[0],
+            address[1],
+            address[2],
+            address[3],
+            address[4],
+            address[5],
+            address[6],
+            address[7],
+            ship_address[0],
+            ship_address[1],
+            ship_address[2],
+            ship_address[3],
+            ship_address[4],
+            ship_address[5],
+            ship_address[6],
+            ship_address[7],
+            customer.terms,
+            customer.tax_included.map(TaxIncluded::code),
+            customer.tax_table,
+        ],
+    )?;
+    insert_slots(connection, customer.guid, &customer.slots)
+}
+
+/// Inserts a vendor with its slots.
+pub(super) fn insert_vendor(
+    connection: &Connection,
+    vendor: &Vendor,
+) -> Result<(), DatabaseError> {
+    let address = address_columns(&vendor.address);
+
+    connection.execute(
+        "INSERT INTO vendors
+            (guid, name, id, notes, currency, active, tax_override,
+            addr_name, addr_addr1, addr_addr2, addr_addr3, addr_addr4,
+            addr_phone, addr_fax, addr_email, terms, tax_inc, tax_table)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13,
+            ?14, ?15, ?16, ?17, ?18)",
+        params![
+            vendor.guid,
+            vendor.name,
+            vendor.id,
+            vendor.notes,
+            vendor.currency,
+            vendor.active,
+            vendor.tax_override,
+            address[0],
+            address[1],
+            address[2],
+            address[3],
+            address[4],
+            address[5],
+            address[6],
+            address[7],
+            vendor.terms,
+            vendor.tax_included.map(TaxIncluded::as_str),
+            vendor.tax_table,
+        ],
+    )?;
+    insert_slots(connection, vendor.guid, &vendor.slots)
+}
+
+/// Inserts a employee with its slots.
+pub(super) fn insert_employee(
+    connection: &Connection,
+    employee: &Employee,
+) -> Result<(), DatabaseError> {
+    let (workday_num, workday_denom) =
+        numeric_parts(employee.workday, 1, "employees")?;
+    let (rate_num, rate_denom) = numeric_parts(employee.rate, 1, "employees")?;
+    let address = address_columns(&employee.address);
+
+    connection.execute(
+        "INSERT INTO employees
+            (guid, username, id, language, acl, active, currency,
+            ccard_guid, workday_num, workday_denom, rate_num, rate_denom,
+            addr_name, addr_addr1, addr_addr2, addr_addr3, addr_addr4,
+            addr_phone, addr_fax, addr_email)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13,
+            ?14, ?15, ?16, ?17, ?18, ?19, ?20)",
+        params![
+            employee.guid,
+            employee.username,
+            employee.id,
+            employee.language,
+            employee.acl,
+            employee.active,
+            employee.currency,
+            employee.credit_card,
+            workday_num,
+            workday_denom,
+            rate_num,
+            rate_denom,
+            address[0],
+            address[1],
+            address[2],
+            address[3],
+            address[4],
+            address[5],
+            address[6],
+            address[7],
+        ],
+    )?;
+    insert_slots(connection, employee.guid, &employee.slots)
+}
+
+/// Inserts an invoice with its slots.
+pub(super) fn insert_invoice(
+    connection: &Connection,
+    invoice: &Invoice,
+) -> Result<(), DatabaseError> {
+    let (owner_type, owner_guid) = owner_columns(invoice.owner);
+    let (billto_type, billto_guid) = owner_columns(invoice.bill_to);
+    let (charge_num, charge_denom) =
+        optional_parts(invoice.charge_amount, "invoices")?;
+
+    connection.execute(
+        "INSERT INTO invoices
+            (guid, id, date_opened, date_posted, notes, active, currency,
+            owner_type, owner_guid, terms, billing_id, post_txn, post_lot,
+            post_acc, billto_type, billto_guid, charge_amt_num,
+            charge_amt_denom)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13,
+            ?14, ?15, ?16, ?17, ?18)",
+        params![
+            invoice.guid,
+            invoice.id,
+            invoice.date_opened.map(Timestamp),
+            invoice.date_posted.map(Timestamp),
+            invoice.notes,
+            invoice.active,
+            invoice.currency,
+            owner_type,
+            owner_guid,
+            invoice.terms,
+            invoice.billing_id,
+            invoice.post_txn,
+            invoice.post_lot,
+            invoice.post_account,
+            billto_type,
+            billto_guid,
+            charge_num,
+            charge_denom,
+        ],
+    )?;
+
+    insert_slots(connection, invoice.guid, &invoice.slots)
+}
+
+/// Inserts an entry with its slots.
+pub(super) fn insert_entry(
+    connection: &Connection,
+    entry: &Entry,
+) -> Result<(), DatabaseError> {
+    let (quantity_num, quantity_denom) =
+        optional_parts(entry.quantity, "entries")?;
+    let (i_price_num, i_price_denom) =
+        optional_parts(entry.invoice_price, "entries")?;
+    let (i_discount_num, i_discount_denom) =
+        optional_parts(entry.invoice_discount, "entries")?;
+    let (b_price_num, b_price_denom) =
+        optional_parts(entry.bill_price, "entries")?;
+    let (billto_type, billto_guid) = owner_columns(entry.bill_to);
+
+    connection.execute(
+        "INSERT INTO entries
+            (guid, date, date_entered, description, action, notes,
+            quantity_num, quantity_denom, i_acct, i_price_num,
+            i_price_denom, i_discount_num, i_discount_denom, invoice,
+            i_disc_type, i_disc_how, i_taxable, i_taxincluded, i_taxtable,
+            b_acct, b_price_num, b_price_denom, bill, b_taxable,
+            b_taxincluded, b_taxtable, b_paytype, billable, billto_type,
+            billto_guid, order_guid)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13,
+            ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25,
+            ?26, ?27, ?28, ?29, ?30, ?31)",
+        params![
+            entry.guid,
+            Timestamp(entry.date),
+            entry.date_entered.map(Timestamp),
+            entry.description,
+            entry.action,
+            entry.notes,
+            quantity_num,
+            quantity_denom,
+            entry.invoice_account,
+            i_price_num,
+            i_price_denom,
+            i_discount_num,
+            i_discount_denom,
+            entry.invoice,
+            entry.invoice_discount_type.map(AmountType::as_str),
+            entry.invoice_discount_how.map(DiscountHow::as_str),
+            entry.invoice_taxable,
+            entry.invoice_tax_included,
+            entry.invoice_tax_table,
+            entry.bill_account,
+            b_price_num,
+            b_price_denom,
+            entry.bill,
+            entry.bill_taxable,
+            entry.bill_tax_included,
+            entry.bill_tax_table,
+            entry.bill_payment.map(PaymentType::code),
+            entry.billable,
+            billto_type,
+            billto_guid,
+            entry.order,
+        ],
+    )?;
+
+    insert_slots(connection, entry.guid, &entry.slots)
+}
+
+/// Updates the reference count of a billing term or tax table by `delta`.
+///
+/// As in GnuCash, only parent objects are counted.
+pub(super) fn update_refcount(
+    connection: &Connection,
+    table: &'static str,
+    guid: Guid,
+    delta: i64,
+) -> Result<(), DatabaseError> {
+    connection.execute(
+        &format!(
+            "UPDATE {table} SET refcount = MAX(refcount + ?2, 0)
+            WHERE guid = ?1 AND parent IS NULL AND invisible = 0"
+        ),
+        params![guid, delta],
+    )?;
+
+    Ok(())
+}
+
+/// Deletes the row of an object from a table, with its slots.
+pub(super) fn delete_object(
+    connection: &Connection,
+    table: &'static str,
+    guid: Guid,
+) -> Result<(), DatabaseError> {
+    connection
+        .execute(&format!("DELETE FROM {table} WHERE guid = ?1"), [guid])?;
+    delete_slots(connection, guid)
+}
+
+/// Inserts a recurrence belonging to the object with the given GUID.
+fn insert_recurrence(
+    connection: &Connection,
+    guid: Guid,
+    recurrence: &Recurrence,
+) -> Result<(), DatabaseError> {
+    connection.execute(
+        "INSERT INTO recurrences
+            (obj_guid, recurrence_mult, recurrence_period_type,
+            recurrence_period_start, recurrence_weekend_adjust)
+        VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            guid,
+            recurrence.multiplier,
+            recurrence.period.as_str(),
+            Date(recurrence.start),
+            recurrence.weekend_adjust.as_str(),
+        ],
+    )?;
+
+    Ok(())
+}
+
+/// Inserts the slots of the object with the given GUID.
+fn insert_slots(
+    connection: &Connection,
+    guid: Guid,
+    frame: &Frame,
+) -> Result<(), DatabaseError> {
+    for (name, value) in frame {
+        insert_slot(connection, guid, name, value)?;
+    }
+
+    Ok(())
+}
+
+/// Replaces the slots of the object with the given GUID.
+pub(super) fn replace_slots(
+    connection: &Connection,
+    guid: Guid,
+    frame: &Frame,
+) -> Result<(), DatabaseError> {
+    delete_slots(connection, guid)?;
+    insert_slots(connection, guid, frame)
+}
+
+/// Deletes the slots of the object with the given GUID, with the content of
+/// its frames and lists.
+fn delete_slots(
+    connection: &Connection,
+    guid: Guid,
+) -> Result<(), DatabaseError> {
+    let containers = connection
+        .prepare(
+            "SELECT guid_val FROM slots
+            WHERE obj_guid = ?1 AND slot_type IN (?2, ?3)",
+        )?
+        .query_map(params![guid, SLOT_TYPE_LIST, SLOT_TYPE_FRAME], |row| {
+            row.get::<_, Option<Guid>>(0)
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for container in containers.into_iter().flatten() {
+        delete_slots(connection, container)?;
+    }
+
+    connection.execute("DELETE FROM slots WHERE obj_guid = ?1", [guid])?;
+    Ok(())
+}
+
+/// Inserts a slot, with the content of frames and lists.
+///
+/// As GnuCash does, the content of a frame or a list is stored under a new
+/// GUID referenced by the slot, and the names of the slots in a frame are
+/// their full path.
+fn insert_slot(
+    connection: &Connection,
+    guid: Guid,
+    name: &str,
+    value: &SlotValue,
+) -> Result<(), DatabaseError> {
+    let container = matches!(value, SlotValue::List(_) | SlotValue::Frame(_))
+        .then(Guid::new);
+
+    let columns = match value {
+        SlotValue::Int64(int64) => SlotColumns {
+            kind: SLOT_TYPE_INT64,
+            int64: *int64,
+            ..SlotColumns::default()
+        },
+        SlotValue::Double(double) => SlotColumns {
+            kind: SLOT_TYPE_DOUBLE,
+            double: *double,
+            ..SlotColumns::default()
+        },
+        SlotValue::Numeric(numeric) => {
+            let (num, denom) = numeric_parts(*numeric, 1, "slots")?;
+            SlotColumns {
+                kind: SLOT_TYPE_NUMERIC,
+                num,
+                denom,
+                ..SlotColumns::default()
+            }
+        }
+        SlotValue::String(string) => SlotColumns {
+            kind: SLOT_TYPE_STRING,
+            string: Some(string),
+            ..SlotColumns::default()
+        },
+        SlotValue::Guid(value) => SlotColumns {
+            kind: SLOT_TYPE_GUID,
+            guid: Some(*value),
+            ..SlotColumns::default()
+        },
+        SlotValue::Timespec(timestamp) => SlotColumns {
+            kind: SLOT_TYPE_TIMESPEC,
+            timespec: Some(Timestamp(*timestamp)),
+            ..SlotColumns::default()
+        },
+        SlotValue::GDate(date) => SlotColumns {
+            kind: SLOT_TYPE_GDATE,
+            gdate: Some(Date(*date)),
+            ..SlotColumns::default()
+        },
+        SlotValue::List(_) => SlotColumns {
+            kind: SLOT_TYPE_LIST,
+            guid: container,
+            ..SlotColumns::default()
+        },
+        SlotValue::Frame(_) => SlotColumns {
+            kind: SLOT_TYPE_FRAME,
+            guid: container,
+            ..SlotColumns::default()
+        },
+    };
+
+    connection.execute(
+        "INSERT INTO slots
+            (obj_guid, name, slot_type, int64_val, string_val, double_val,
+            timespec_val, guid_val, numeric_val_num, numeric_val_denom,
+            gdate_val)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        params![
+            guid,
+            name,
+            columns.kind,
+            columns.int64,
+            columns.string,
+            columns.double,
+            columns.timespec,
+            columns.guid,
+            columns.num,
+            columns.denom,
+            columns.gdate,
+        ],
+    )?;
+
+    match (value, container) {
+        (SlotValue::List(elements), Some(container)) => {
+            for element in elements {
+                insert_slot(connection, container, name, element)?;
+            }
+        }
+        (SlotValue::Frame(frame), Some(container)) => {
+            for (key, child) in frame {
+                let path = format!("{name}{SLOT_SEPARATOR}{key}");
+                insert_slot(connection, container, &path, child)?;
+            }
+        }
+        _ => (),
+    }
+
+    Ok(())
+}
+
+/// Returns the parts of an optional numeric to store.
+fn optional_parts(
+    numeric: Option<Numeric>,
+    table: &'static str,
+) -> Result<(Option<i64>, Option<i64>), DatabaseError> {
+    Ok(numeric
+        .map(|numeric| numeric_parts(numeric, 1, table))
+        .transpose()?
+        .unzip())
+}
+
+/// Returns the columns storing an address.
+fn address_columns(address: &Address) -> [Option<&str>; 8] {
+    [
+        &address.name,
+        &address.addr1,
+        &address.addr2,
+        &address.addr3,
+        &address.addr4,
+        &address.phone,
+        &address.fax,
+        &address.email,
+    ]
+    .map(Option::as_deref)
+}
+
+/// Returns the columns storing an owner.
+fn owner_columns(owner: Option<Owner>) -> (Option<i64>, Option<Guid>) {
+    owner.map(|owner| (owner.kind.code(), owner.guid)).unzip()
+}
+
+impl Default for SlotColumns<'_> {
+    fn default() -> Self {
+        Self {
+            kind: SLOT_TYPE_STRING,
+            int64: 0,
+            string: None,
+            double: 0.0,
+            timespec: None,
+            guid: None,
+            num: 0,
+            denom: 1,
+            gdate: None,
+        }
+    }
+}
@@ -0,0 +1,151 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! GnuCash transactions and splits.
+
+use std::fmt;
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use thiserror::Error;
+
+use super::{Frame, Guid, Numeric};
+
+/// A transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transaction {
+    /// The GUID of the transaction.
+    pub guid: Guid,
+    /// The currency of the transaction.
+    pub currency: Guid,
+    /// The number of the transaction, like a check number.
+    pub num: String,
+    /// The date at which the transaction is posted.
+    pub post_date: DateTime<Utc>,
+    /// The date at which the transaction has been entered.
+    pub enter_date: DateTime<Utc>,
+    /// The description of the transaction.
+    pub description: String,
+    /// The splits of the transaction.
+    pub splits: Vec<Split>,
+    /// The slots of the transaction.
+    pub slots: Frame,
+}
+
+/// A split of a transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Split {
+    /// The GUID of the split.
+    pub guid: Guid,
+    /// The account of the split.
+    pub account: Guid,
+    /// The memo of the split.
+    pub memo: String,
+    /// The action of the split.
+    pub action: String,
+    /// The reconcile state of the split.
+    pub reconcile_state: ReconcileState,
+    /// The date at which the split has been reconciled.
+    pub reconcile_date: Option<DateTime<Utc>>,
+    /// The value of the split, in the currency of the transaction.
+    pub value: Numeric,
+    /// The quantity of the split, in the commodity of the account.
+    pub quantity: Numeric,
+    /// The lot of the split.
+    pub lot: Option<Guid>,
+    /// The slots of the split.
+    pub slots: Frame,
+}
+
+/// The reconcile state of a split.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ReconcileState {
+    /// The split is neither cleared nor reconciled.
+    #[default]
+    NotReconciled,
+    /// The split is cleared.
+    Cleared,
+    /// The split is reconciled.
+    Reconciled,
+    /// The split is frozen.
+    Frozen,
+    /// The split is voided.
+    Voided,
+}
+
+/// An error that can occur when parsing a reconcile state.
+#[derive(Debug, Error)]
+#[error("invalid reconcile state: {0:?}")]
+pub struct ParseReconcileStateError(String);
+
+impl Transaction {
+    /// Returns the date at which the transaction is posted.
+    pub fn date(&self) -> NaiveDate {
+        self.post_date.date_naive()
+    }
+
+    /// Returns the notes of the transaction.
+    pub fn notes(&self) -> Option<&str> {
+        self.slots.string("notes")
+    }
+
+    /// Returns the ID of the journal entry the transaction has been created
+    /// from.
+    pub fn journal_id(&self) -> Option<&str> {
+        self.slots.string("journal-id")
+    }
+}
+
+impl ReconcileState {
+    /// Returns the flag of the reconcile state as stored by GnuCash.
+    pub const fn as_char(self) -> char {
+        match self {
+            Self::NotReconciled => 'n',
+            Self::Cleared => 'c',
+            Self::Reconciled => 'y',
+            Self::Frozen => 'f',
+            Self::Voided => 'v',
+        }
+    }
+}
+
+impl TryFrom<&str> for ReconcileState {
+    type Error = ParseReconcileStateError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "n" => Ok(Self::NotReconciled),
+            "c" => Ok(Self::Cleared),
+            "y" => Ok(Self::Reconciled),
+            "f" => Ok(Self::Frozen),
+            "v" => Ok(Self::Voided),
+            _ => Err(ParseReconcileStateError(s.to_owned())),
+        }
+    }
+}
+
+impl fmt::Display for ReconcileState {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}", self.as_char())
+    }
+}
+
+/// Returns the post date GnuCash uses for a given day.
+///
+/// GnuCash posts transactions at 10:59 UTC, so that the date stays the same in
+/// most timezones.
+pub fn post_date(date: NaiveDate) -> DateTime<Utc> {
+    date.and_time(NaiveTime::from_hms_opt(10, 59, 0).unwrap_or_default())
+        .and_utc()
+}
@@ -0,0 +1,239 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The XML backend.
+//!
+//! GnuCash XML books are usually compressed with gzip. Books are read
+//! whether they are compressed or not, and always written compressed, as
+//! GnuCash does by default.
+//!
+//! The account templates shipped with GnuCash, the `acctchrt_*.gnucash-xea`
+//! files, use the same XML format for their accounts, without compression.
+
+mod dom;
+mod read;
+mod write;
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use flate2::{read::MultiGzDecoder, Compression, GzBuilder};
+use thiserror::Error;
+
+use self::dom::Element;
+use super::Book;
+
+/// The format of timestamps in XML books.
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S %z";
+
+/// The format of dates in XML books.
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// The magic number at the start of gzip files.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// The namespaces declared by GnuCash in XML books.
+const NAMESPACES: &[&str] = &[
+    "gnc",
+    "act",
+    "book",
+    "cd",
+    "cmdty",
+    "price",
+    "slot",
+    "split",
+    "sx",
+    "trn",
+    "ts",
+    "fs",
+    "bgt",
+    "recurrence",
+    "lot",
+    "addr",
+    "billterm",
+    "bt-days",
+    "bt-prox",
+    "cust",
+    "employee",
+    "entry",
+    "invoice",
+    "job",
+    "order",
+    "owner",
+    "taxtable",
+    "tte",
+    "vendor",
+];
+
+/// An account template, like the `acctchrt_*.gnucash-xea` files shipped with
+/// GnuCash.
+#[derive(Debug)]
+pub struct AccountTemplate {
+    /// The title of the template.
+    pub title: String,
+    /// A short description of the template.
+    pub short_description: String,
+    /// A long description of the template.
+    pub long_description: String,
+    /// A book holding the accounts of the template and their commodities.
+    pub book: Book,
+}
+
+/// Errors that can occur when using a GnuCash XML book.
+#[derive(Debug, Error)]
+pub enum XmlError {
+    /// The book cannot be read.
+    #[error("failed to read {path}")]
+    Read {
+        /// The path of the book.
+        path: PathBuf,
+        /// The source error.
+        source: io::Error,
+    },
+    /// The book cannot be written.
+    #[error("failed to write {path}")]
+    Write {
+        /// The path of the book.
+        path: PathBuf,
+        /// The source error.
+        source: io::Error,
+    },
+    /// The file is not valid XML.
+    #[error("invalid XML: {0}")]
+    Xml(#[from] quick_xml::Error),
+    /// The XML document is not well-formed.
+    #[error("the XML document is not well-formed")]
+    Malformed,
+    /// The file is not a GnuCash XML book.
+    #[error("the file is not a GnuCash XML book")]
+    NotABook,
+    /// The file is not a GnuCash account template.
+    #[error("the file is not a GnuCash account template")]
+    NotATemplate,
+    /// The XML document cannot be written.
+    #[error("failed to write the XML document")]
+    Output(#[source] io::Error),
+    /// The book contains an invalid element.
+    #[error("invalid {element}: {message}")]
+    Invalid {
+        /// The name of the invalid element.
+        element: String,
+        /// A description of the issue.
+        message: String,
+    },
+}
+
+/// Reads a GnuCash XML book, compressed or not.
+#[tracing::instrument(level = "trace")]
+pub fn read_xml(path: &Path) -> Result<Book, XmlError> {
+    let book = read::book(&Element::parse(&read_content(path)?)?)?;
+
+    tracing::debug!(
+        guid = %book.guid,
+        commodities = book.commodities.len(),
+        accounts = book.accounts.len(),
+        transactions = book.transactions.len(),
+        prices = book.prices.len(),
+        "book loaded"
+    );
+
+    Ok(book)
+}
+
+/// Writes a book as a compressed GnuCash XML book.
+///
+/// Any existing file at `path` is overwritten.
+#[tracing::instrument(level = "trace", skip(book))]
+pub fn write_xml(book: &Book, path: &Path) -> Result<(), XmlError> {
+    let document = write::document(book)?;
+
+    let write_error = |source| XmlError::Write {
+        path: path.to_owned(),
+        source,
+    };
+
+    let file = File::create(path).map_err(write_error)?;
+
+    // The modification time is left to zero so that the output only depends
+    // on the content of the book.
+    let mut output = BufWriter::new(
+        GzBuilder::new()
+            .mtime(0)
+            .write(file, Compression::default()),
+    );
+
+    writeln!(output, "<?xml version=\"1.0\" encoding=\"utf-8\" ?>")
+        .and_then(|()| document.write(&mut output, 0))
+        .and_then(|()| {
+            output.into_inner().map_err(io::IntoInnerError::into_error)
+        })
+        .and_then(flate2::write::GzEncoder::finish)
+        .map_err(write_error)?;
+
+    Ok(())
+}
+
+/// Reads an account template, compressed or not.
+#[tracing::instrument(level = "trace")]
+pub fn read_account_template(path: &Path) -> Result<AccountTemplate, XmlError> {
+    let template =
+        read::account_template(&Element::parse(&read_content(path)?)?)?;
+
+    tracing::debug!(
+        title = %template.title,
+        accounts = template.book.accounts.len(),
+        "account template loaded"
+    );
+
+    Ok(template)
+}
+
+/// Writes an account template, without compression like the ones of GnuCash.
+#[tracing::instrument(level = "trace", skip_all)]
+pub fn write_account_template(
+    template: &AccountTemplate,
+    output: &mut impl Write,
+) -> Result<(), XmlError> {
+    let document = write::account_template(template)?;
+
+    writeln!(output, "<?xml version=\"1.0\" encoding=\"utf-8\"?>")
+        .and_then(|()| document.write(output, 0))
+        .map_err(XmlError::Output)
+}
+
+/// Reads the content of an XML file, compressed or not.
+fn read_content(path: &Path) -> Result<String, XmlError> {
+    let read_error = |source| XmlError::Read {
+        path: path.to_owned(),
+        source,
+    };
+
+    let bytes = std::fs::read(path).map_err(read_error)?;
+    let mut content = String::new();
+    if bytes.starts_with(&GZIP_MAGIC) {
+        MultiGzDecoder::new(bytes.as_slice())
+            .read_to_string(&mut content)
+            .map_err(read_error)?;
+    } else {
+        content = String::from_utf8(bytes).map_err(|error| {
+            read_error(io::Error::new(io::ErrorKind::InvalidData, error))
+        })?;
+    }
+
+    Ok(content)
+}
@@ -0,0 +1,241 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! A minimal XML tree, enough for GnuCash books.
+//!
+//! GnuCash books never mix text and elements in the same element, so an
+//! element has either a text or children.
+
+use std::{
+    borrow::Cow,
+    io::{self, Write},
+};
+
+use quick_xml::{
+    encoding::Decoder,
+    escape::escape,
+    events::{BytesStart, Event},
+    Reader,
+};
+
+use super::XmlError;
+use crate::book::Guid;
+
+/// An XML element.
+#[derive(Debug, Default)]
+pub struct Element {
+    /// The qualified name of the element.
+    pub name: String,
+    /// The attributes of the element.
+    pub attributes: Vec<(String, String)>,
+    /// The text content of the element.
+    pub text: String,
+    /// The child elements.
+    pub children: Vec<Element>,
+}
+
+impl Element {
+    /// Builds an empty element.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            ..Self::default()
+        }
+    }
+
+    /// Builds an element containing some text.
+    pub fn text(name: &str, text: impl Into<String>) -> Self {
+        Self {
+            name: name.to_owned(),
+            text: text.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Builds an element containing a GUID, like GnuCash does.
+    pub fn guid(name: &str, guid: Guid) -> Self {
+        Self::text(name, guid.to_string()).with_attribute("type", "guid")
+    }
+
+    /// Adds an attribute to the element.
+    #[must_use]
+    pub fn with_attribute(mut self, name: &str, value: &str) -> Self {
+        self.attributes.push((name.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Adds a version attribute to the element.
+    #[must_use]
+    pub fn with_version(self, version: &str) -> Self {
+        self.with_attribute("version", version)
+    }
+
+    /// Adds a child to the element.
+    #[must_use]
+    pub fn with(mut self, child: Self) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Adds a child to the element if there is one.
+    #[must_use]
+    pub fn with_some(mut self, child: Option<Self>) -> Self {
+        self.children.extend(child);
+        self
+    }
+
+    /// Adds several children to the element.
+    #[must_use]
+    pub fn with_all(
+        mut self,
+        children: impl IntoIterator<Item = Self>,
+    ) -> Self {
+        self.children.extend(children);
+        self
+    }
+
+    /// Returns the first child with the given name.
+    pub fn child(&self, name: &str) -> Option<&Self> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    /// Returns the first child with the given name, or an error.
+    pub fn required(&self, name: &str) -> Result<&Self, XmlError> {
+        self.child(name).ok_or_else(|| XmlError::Invalid {
+            element: self.name.clone(),
+            message: format!("missing {name}"),
+        })
+    }
+
+    /// Returns the children with the given name.
+    pub fn children_named<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a Self> + 'a {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    /// Returns the value of an attribute.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Parses an XML document and returns its root element.
+    pub fn parse(input: &str) -> Result<Self, XmlError> {
+        let mut reader = Reader::from_str(input);
+        let decoder = reader.decoder();
+        let mut stack = vec![Self::default()];
+
+        loop {
+            match reader.read_event()? {
+                Event::Start(start) => {
+                    stack.push(Self::from_start(decoder, &start)?);
+                }
+                Event::Empty(start) => {
+                    let element = Self::from_start(decoder, &start)?;
+                    if let Some(parent) = stack.last_mut() {
+                        parent.children.push(element);
+                    }
+                }
+                Event::End(_) => {
+                    let mut element = stack.pop().ok_or(XmlError::Malformed)?;
+                    if !element.children.is_empty() {
+                        element.text.clear();
+                    }
+                    stack
+                        .last_mut()
+                        .ok_or(XmlError::Malformed)?
+                        .children
+                        .push(element);
+                }
+                Event::Text(text) => {
+                    if let Some(element) = stack.last_mut() {
+                        element.text.push_str(&text.unescape()?);
+                    }
+                }
+                Event::CData(data) => {
+                    if let Some(element) = stack.last_mut() {
+                        element.text.push_str(
+                            &data.decode().map_err(quick_xml::Error::from)?,
+                        );
+                    }
+                }
+                Event::Eof => break,
+                Event::Decl(_)
+                | Event::PI(_)
+                | Event::DocType(_)
+                | Event::Comment(_) => (),
+            }
+        }
+
+        stack
+            .pop()
+            .filter(|_| stack.is_empty())
+            .and_then(|document| document.children.into_iter().next())
+            .ok_or(XmlError::Malformed)
+    }
+
+    /// Builds an element from its start tag.
+    fn from_start(
+        decoder: Decoder,
+        start: &BytesStart<'_>,
+    ) -> Result<Self, XmlError> {
+        let decode = |bytes: &[u8]| {
+            decoder
+                .decode(bytes)
+                .map(Cow::into_owned)
+                .map_err(quick_xml::Error::from)
+        };
+
+        let mut element = Self::new(&decode(start.name().as_ref())?);
+        for attribute in start.attributes() {
+            let attribute = attribute.map_err(quick_xml::Error::from)?;
+            element.attributes.push((
+                decode(attribute.key.as_ref())?,
+                attribute.unescape_value()?.into_owned(),
+            ));
+        }
+
+        Ok(element)
+    }
+
+    /// Writes the element, indented at the given depth.
+    pub fn write(
+        &self,
+        output: &mut impl Write,
+        depth: usize,
+    ) -> io::Result<()> {
+        let indent = "  ".repeat(depth);
+        write!(output, "{indent}<{}", self.name)?;
+        for (name, value) in &self.attributes {
+            write!(output, " {name}=\"{}\"", escape(value.as_str()))?;
+        }
+
+        if !self.children.is_empty() {
+            writeln!(output, ">")?;
+            for child in &self.children {
+                child.write(output, depth + 1)?;
+            }
+            writeln!(output, "{indent}</{}>", self.name)
+        } else if self.text.is_empty() {
+            writeln!(output, "/>")
+        } else {
+            writeln!(output, ">{}</{}>", escape(self.text.as_str()), self.name)
+        }
+    }
+}
@@ -0,0 +1,966 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Conversion of an XML tree to a book.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    str::FromStr,
+};
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use super::{
+    dom::Element, AccountTemplate, XmlError, DATE_FORMAT, TIMESTAMP_FORMAT,
+};
+use crate::book::{
+    commodity::CURRENCY_NAMESPACE, Account, AccountType, Address, BillTerm,
+    BillTermType, Book, Budget, BudgetAmount, Business, Commodity, Customer,
+    Employee, Entry, Frame, Guid, Invoice, Job, Lot, Numeric, Order, Owner,
+    Price, Recurrence, ScheduledTransaction, SlotValue, Split, TaxTable,
+    TaxTableEntry, Transaction, Vendor,
+};
+
+/// The former name of the namespace of currencies, still used in the account
+/// templates of GnuCash.
+const LEGACY_CURRENCY_NAMESPACE: &str = "ISO4217";
+
+/// A reader building a book from XML elements.
+#[derive(Default)]
+struct BookReader {
+    /// The commodities, by GUID.
+    commodities: BTreeMap<Guid, Commodity>,
+    /// The GUIDs of the commodities, by namespace and mnemonic.
+    ///
+    /// Commodities have no GUID in XML books, so they are given a new one.
+    commodity_guids: HashMap<(String, String), Guid>,
+}
+
+/// Builds a book from its XML document.
+pub(super) fn book(document: &Element) -> Result<Book, XmlError> {
+    let book = (document.name == "gnc-v2")
+        .then(|| document.child("gnc:book"))
+        .flatten()
+        .ok_or(XmlError::NotABook)?;
+
+    let mut reader = BookReader::default();
+
+    for commodity in book.children_named("gnc:commodity") {
+        reader.commodity_definition(commodity)?;
+    }
+
+    let templates = book.child("gnc:template-transactions");
+    let template_children = templates
+        .into_iter()
+        .flat_map(|templates| templates.children.iter());
+
+    let mut accounts = BTreeMap::new();
+    let mut lots = BTreeMap::new();
+    let mut root_account = None;
+    let mut root_template = None;
+
+    for (element, is_template) in book
+        .children
+        .iter()
+        .map(|element| (element, false))
+        .chain(template_children.clone().map(|element| (element, true)))
+        .filter(|(element, _)| element.name == "gnc:account")
+    {
+        let (account, account_lots) = reader.account(element)?;
+
+        if account.kind == AccountType::Root && account.parent.is_none() {
+            if is_template {
+                root_template = Some(account.guid);
+            } else {
+                root_account = Some(account.guid);
+            }
+        }
+
+        lots.extend(account_lots.into_iter().map(|lot| (lot.guid, lot)));
+        accounts.insert(account.guid, account);
+    }
+
+    let root_account = root_account
+        .ok_or_else(|| invalid(book, &"there is no root account"))?;
+
+    let root_template = root_template.unwrap_or_else(|| {
+        let account = root("Template Root");
+        let guid = account.guid;
+        accounts.insert(guid, account);
+        guid
+    });
+
+    let transactions = book
+        .children
+        .iter()
+        .chain(template_children)
+        .filter(|element| element.name == "gnc:transaction")
+        .map(|element| {
+            reader
+                .transaction(element)
+                .map(|transaction| (transaction.guid, transaction))
+        })
+        .collect::<Result<BTreeMap<_, _>, _>>()?;
+
+    close_lots(&mut lots, &transactions);
+
+    let prices = book
+        .children_named("gnc:pricedb")
+        .flat_map(|pricedb| pricedb.children_named("price"))
+        .map(|element| reader.price(element).map(|price| (price.guid, price)))
+        .collect::<Result<_, _>>()?;
+
+    let budgets = book
+        .children_named("gnc:budget")
+        .map(|element| budget(element).map(|budget| (budget.guid, budget)))
+        .collect::<Result<_, _>>()?;
+
+    let schedules = book
+        .children_named("gnc:schedxaction")
+        .map(|element| {
+            schedule(element).map(|schedule| (schedule.guid, schedule))
+        })
+        .collect::<Result<_, _>>()?;
+
+    let business = reader.business(book)?;
+
+    Ok(Book {
+        guid: guid(book.required("book:id")?)?,
+        root_account,
+        root_template,
+        commodities: reader.commodities,
+        accounts,
+        transactions,
+        prices,
+        lots,
+        budgets,
+        schedules,
+        business,
+        slots: slots(book.child("book:slots"))?,
+    })
+}
+
+/// Builds an account template from its XML document.
+///
+/// Templates without a root account get one, with the top-level accounts as
+/// children.
+pub(super) fn account_template(
+    document: &Element,
+) -> Result<AccountTemplate, XmlError> {
+    if document.name != "gnc-account-example" {
+        return Err(XmlError::NotATemplate);
+    }
+
+    let mut reader = BookReader::default();
+    let mut accounts = BTreeMap::new();
+    let mut root_account = None;
+
+    for element in document.children_named("gnc:account") {
+        let (account, _) = reader.account(element)?;
+
+        if account.kind == AccountType::Root && account.parent.is_none() {
+            root_account = Some(account.guid);
+        }
+
+        accounts.insert(account.guid, account);
+    }
+
+    let root_account = root_account.unwrap_or_else(|| {
+        let account = root("Root Account");
+        let guid = account.guid;
+
+        for account in accounts.values_mut() {
+            account.parent.get_or_insert(guid);
+        }
+
+        accounts.insert(guid, account);
+        guid
+    });
+
+    let description = |name| {
+        optional_text(document, name)
+            .map(|text| text.trim().to_owned())
+            .unwrap_or_default()
+    };
+
+    Ok(AccountTemplate {
+        title: description("gnc-act:title"),
+        short_description: description("gnc-act:short-description"),
+        long_description: description("gnc-act:long-description"),
+        book: Book {
+            guid: Guid::new(),
+            root_account,
+            root_template: Guid::new(),
+            commodities: reader.commodities,
+            accounts,
+            transactions: BTreeMap::new(),
+            prices: BTreeMap::new(),
+            lots: BTreeMap::new(),
+            budgets: BTreeMap::new(),
+            schedules: BTreeMap::new(),
+            business: Business::default(),
+            slots: Frame::default(),
+        },
+    })
+}
+
+impl BookReader {
+    /// Reads a `gnc:commodity` element.
+    fn commodity_definition(
+        &mut self,
+        element: &Element,
+    ) -> Result<(), XmlError> {
+        let guid = self.commodity(element)?;
+
+        if let Some(commodity) = self.commodities.get_mut(&guid) {
+            commodity.fullname = optional_text(element, "cmdty:name");
+            commodity.cusip = optional_text(element, "cmdty:xcode");
+            commodity.fraction = element
+                .child("cmdty:fraction")
+                .map(parse)
+                .transpose()?
+                .unwrap_or(1);
+            commodity.quote_flag = element.child("cmdty:get_quotes").is_some();
+            commodity.quote_source =
+                optional_text(element, "cmdty:quote_source");
+            commodity.quote_tz = optional_text(element, "cmdty:quote_tz");
+            commodity.slots = slots(element.child("cmdty:slots"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the GUID of the commodity referenced by an element.
+    ///
+    /// Commodities which are referenced without being defined, like the
+    /// `template` commodity, are created on the fly.
+    fn commodity(&mut self, element: &Element) -> Result<Guid, XmlError> {
+        let namespace = match text(element.required("cmdty:space")?) {
+            LEGACY_CURRENCY_NAMESPACE => CURRENCY_NAMESPACE,
+            namespace => namespace,
+        }
+        .to_owned();
+        let mnemonic = text(element.required("cmdty:id")?).to_owned();
+
+        let guid = *self
+            .commodity_guids
+            .entry((namespace.clone(), mnemonic.clone()))
+            .or_default();
+
+        self.commodities.entry(guid).or_insert_with(|| Commodity {
+            guid,
+            namespace,
+            mnemonic,
+            fullname: None,
+            cusip: None,
+            fraction: 1,
+            quote_flag: false,
+            quote_source: None,
+            quote_tz: None,
+            slots: Frame::default(),
+        });
+
+        Ok(guid)
+    }
+
+    /// Reads a `gnc:account` element with its lots.
+    fn account(
+        &mut self,
+        element: &Element,
+    ) -> Result<(Account, Vec<Lot>), XmlError> {
+        let account_guid = guid(element.required("act:id")?)?;
+        let mut account_slots = slots(element.child("act:slots"))?;
+        let mut take_flag = |name| {
+            account_slots.remove(name)
+                == Some(SlotValue::String("true".to_owned()))
+        };
+        let hidden = take_flag("hidden");
+        let placeholder = take_flag("placeholder");
+
+        let lots = element
+            .children_named("act:lots")
+            .flat_map(|lots| lots.children_named("gnc:lot"))
+            .map(|lot| {
+                Ok(Lot {
+                    guid: guid(lot.required("lot:id")?)?,
+                    account: Some(account_guid),
+                    is_closed: false,
+                    slots: slots(lot.child("lot:slots"))?,
+                })
+            })
+            .collect::<Result<_, XmlError>>()?;
+
+        let account = Account {
+            guid: account_guid,
+            name: text(element.required("act:name")?).to_owned(),
+            kind: parse(element.required("act:type")?)?,
+            commodity: element
+                .child("act:commodity")
+                .map(|commodity| self.commodity(commodity))
+                .transpose()?,
+            commodity_scu: element
+                .child("act:commodity-scu")
+                .map(parse)
+                .transpose()?
+                .unwrap_or_default(),
+            non_std_scu: element.child("act:non-standard-scu").is_some(),
+            parent: optional_guid(element, "act:parent")?,
+            code: optional_text(element, "act:code"),
+            description: optional_text(element, "act:description"),
+            hidden,
+            placeholder,
+            slots: account_slots,
+        };
+
+        Ok((account, lots))
+    }
+
+    /// Reads a `gnc:transaction` element.
+    fn transaction(
+        &mut self,
+        element: &Element,
+    ) -> Result<Transaction, XmlError> {
+        let splits = element
+            .required("trn:splits")?
+            .children_named("trn:split")
+            .map(|split| {
+                Ok(Split {
+                    guid: guid(split.required("split:id")?)?,
+                    account: guid(split.required("split:account")?)?,
+                    memo: optional_text(split, "split:memo")
+                        .unwrap_or_default(),
+                    action: optional_text(split, "split:action")
+                        .unwrap_or_default(),
+                    reconcile_state: text(
+                        split.required("split:reconciled-state")?,
+                    )
+                    .try_into()
+                    .map_err(|error| invalid(split, &error))?,
+                    reconcile_date: split
+                        .child("split:reconcile-date")
+                        .map(timestamp)
+                        .transpose()?,
+                    value: numeric(split.required("split:value")?)?,
+                    quantity: numeric(split.required("split:quantity")?)?,
+                    lot: optional_guid(split, "split:lot")?,
+                    slots: slots(split.child("split:slots"))?,
+                })
+            })
+            .collect::<Result<_, XmlError>>()?;
+
+        Ok(Transaction {
+            guid: guid(element.required("trn:id")?)?,
+            currency: self.commodity(element.required("trn:currency")?)?,
+            num: optional_text(element, "trn:num").unwrap_or_default(),
+            post_date: timestamp(element.required("trn:date-posted")?)?,
+            enter_date: timestamp(element.required("trn:date-entered")?)?,
+            description: optional_text(element, "trn:description")
+                .unwrap_or_default(),
+            splits,
+            slots: slots(element.child("trn:slots"))?,
+        })
+    }
+
+    /// Reads a `price` element.
+    fn price(&mut self, element: &Element) -> Result<Price, XmlError> {
+        Ok(Price {
+            guid: guid(element.required("price:id")?)?,
+            commodity: self.commodity(element.required("price:commodity")?)?,
+            currency: self.commodity(element.required("price:currency")?)?,
+            date: timestamp(element.required("price:time")?)?,
+            source: optional_text(element, "price:source"),
+            kind: optional_text(element, "price:type"),
+            value: numeric(element.required("price:value")?)?,
+        })
+    }
+
+    /// Reads the business objects.
+    fn business(&mut self, book: &Element) -> Result<Business, XmlError> {
+        Ok(Business {
+            bill_terms: collect(book, "gnc:GncBillTerm", |element| {
+                bill_term(element).map(|term| (term.guid, term))
+            })?,
+            tax_tables: collect(book, "gnc:GncTaxTable", |element| {
+                tax_table(element).map(|table| (table.guid, table))
+            })?,
+            customers: collect(book, "gnc:GncCustomer", |element| {
+                self.customer(element)
+                    .map(|customer| (customer.guid, customer))
+            })?,
+            vendors: collect(book, "gnc:GncVendor", |element| {
+                self.vendor(element).map(|vendor| (vendor.guid, vendor))
+            })?,
+            employees: collect(book, "gnc:GncEmployee", |element| {
+                self.employee(element)
+                    .map(|employee| (employee.guid, employee))
+            })?,
+            jobs: collect(book, "gnc:GncJob", |element| {
+                job(element).map(|job| (job.guid, job))
+            })?,
+            invoices: collect(book, "gnc:GncInvoice", |element| {
+                self.invoice(element).map(|invoice| (invoice.guid, invoice))
+            })?,
+            entries: collect(book, "gnc:GncEntry", |element| {
+                entry(element).map(|entry| (entry.guid, entry))
+            })?,
+            orders: collect(book, "gnc:GncOrder", |element| {
+                order(element).map(|order| (order.guid, order))
+            })?,
+        })
+    }
+
+    /// Reads a `gnc:GncCustomer` element.
+    fn customer(&mut self, element: &Element) -> Result<Customer, XmlError> {
+        Ok(Customer {
+            guid: guid(element.required("cust:guid")?)?,
+            name: required_text(element, "cust:name")?,
+            id: required_text(element, "cust:id")?,
+            notes: optional_text(element, "cust:notes").unwrap_or_default(),
+            active: flag(element, "cust:active")?,
+            discount: optional_numeric(element, "cust:discount")?
+                .unwrap_or(Numeric::ZERO),
+            credit: optional_numeric(element, "cust:credit")?
+                .unwrap_or(Numeric::ZERO),
+            currency: self.commodity(element.required("cust:currency")?)?,
+            tax_override: flag(element, "cust:use-tt")?,
+            address: address(element.child("cust:addr")),
+            ship_address: address(element.child("cust:shipaddr")),
+            terms: optional_guid(element, "cust:terms")?,
+            tax_included: optional_parse(element, "cust:taxincluded")?,
+            tax_table: optional_guid(element, "cust:taxtable")?,
+            slots: slots(element.child("cust:slots"))?,
+        })
+    }
+
+    /// Reads a `gnc:GncVendor` element.
+    fn vendor(&mut self, element: &Element) -> Result<Vendor, XmlError> {
+        Ok(Vendor {
+            guid: guid(element.required("vendor:guid")?)?,
+            name: required_text(element, "vendor:name")?,
+            id: required_text(element, "vendor:id")?,
+            notes: optional_text(element, "vendor:notes").unwrap_or_default(),
+            currency: self.commodity(element.required("vendor:currency")?)?,
+            active: flag(element, "vendor:active")?,
+            tax_override: flag(element, "vendor:use-tt")?,
+            address: address(element.child("vendor:addr")),
+            terms: optional_guid(element, "vendor:terms")?,
+            tax_included: optional_parse(element, "vendor:taxincluded")?,
+            tax_table: optional_guid(element, "vendor:taxtable")?,
+            slots: slots(element.child("vendor:slots"))?,
+        })
+    }
+
+    /// Reads a `gnc:GncEmployee` element.
+    fn employee(&mut self, element: &Element) -> Result<Employee, XmlError> {
+        Ok(Employee {
+            guid: guid(element.required("employee:guid")?)?,
+            username: required_text(element, "employee:username")?,
+            id: required_text(element, "employee:id")?,
+            language: optional_text(element, "employee:language")
+                .unwrap_or_default(),
+            acl: optional_text(element, "employee:acl").unwrap_or_default(),
+            active: flag(element, "employee:active")?,
+            currency: self.commodity(element.required("employee:currency")?)?,
+            credit_card: optional_guid(element, "employee:ccard")?,
+            workday: optional_numeric(element, "employee:workday")?
+                .unwrap_or(Numeric::ZERO),
+            rate: optional_numeric(element, "employee:rate")?
+                .unwrap_or(Numeric::ZERO),
+            address: address(element.child("employee:addr")),
+            slots: slots(element.child("employee:slots"))?,
+        })
+    }
+
+    /// Reads a `gnc:GncInvoice` element.
+    fn invoice(&mut self, element: &Element) -> Result<Invoice, XmlError> {
+        Ok(Invoice {
+            guid: guid(element.required("invoice:guid")?)?,
+            id: required_text(element, "invoice:id")?,
+            date_opened: element
+                .child("invoice:opened")
+                .map(timestamp)
+                .transpose()?,
+            date_posted: element
+                .child("invoice:posted")
+                .map(timestamp)
+                .transpose()?,
+            notes: optional_text(element, "invoice:notes").unwrap_or_default(),
+            active: flag(element, "invoice:active")?,
+            currency: self.commodity(element.required("invoice:currency")?)?,
+            owner: element.child("invoice:owner").map(owner).transpose()?,
+            terms: optional_guid(element, "invoice:terms")?,
+            billing_id: optional_text(element, "invoice:billing_id"),
+            post_txn: optional_guid(element, "invoice:posttxn")?,
+            post_lot: optional_guid(element, "invoice:postlot")?,
+            post_account: optional_guid(element, "invoice:postacc")?,
+            bill_to: element.child("invoice:billto").map(owner).transpose()?,
+            charge_amount: optional_numeric(element, "invoice:charge-amt")?,
+            slots: slots(element.child("invoice:slots"))?,
+        })
+    }
+}
+
+/// Reads a `gnc:budget` element.
+///
+/// The amounts, stored in the slots by account GUID and period number, are
+/// extracted from them.
+fn budget(element: &Element) -> Result<Budget, XmlError> {
+    let mut budget_slots = Frame::default();
+    let mut amounts = vec![];
+
+    for (key, value) in &slots(element.child("bgt:slots"))? {
+        match (key.parse::<Guid>(), value) {
+            (Ok(account), SlotValue::Frame(periods)) => {
+                for (period, amount) in periods {
+                    match (period.parse(), amount) {
+                        (Ok(period), SlotValue::Numeric(amount)) => {
+                            amounts.push(BudgetAmount {
+                                account,
+                                period,
+                                amount: *amount,
+                            });
+                        }
+                        _ => budget_slots
+                            .insert(&format!("{key}/{period}"), amount.clone()),
+                    }
+                }
+            }
+            _ => budget_slots.insert(key, value.clone()),
+        }
+    }
+
+    amounts.sort_by_key(|amount| (amount.account, amount.period));
+
+    Ok(Budget {
+        guid: guid(element.required("bgt:id")?)?,
+        name: required_text(element, "bgt:name")?,
+        description: optional_text(element, "bgt:description"),
+        num_periods: parse(element.required("bgt:num-periods")?)?,
+        recurrence: recurrence(element.required("bgt:recurrence")?)?,
+        amounts,
+        slots: budget_slots,
+    })
+}
+
+/// Reads a `gnc:schedxaction` element.
+fn schedule(element: &Element) -> Result<ScheduledTransaction, XmlError> {
+    let integer = |name| -> Result<i64, XmlError> {
+        Ok(element
+            .child(name)
+            .map(parse)
+            .transpose()?
+            .unwrap_or_default())
+    };
+
+    Ok(ScheduledTransaction {
+        guid: guid(element.required("sx:id")?)?,
+        name: optional_text(element, "sx:name"),
+        enabled: yes_no(element, "sx:enabled"),
+        start_date: element.child("sx:start").map(gdate).transpose()?,
+        end_date: element.child("sx:end").map(gdate).transpose()?,
+        last_occur: element.child("sx:last").map(gdate).transpose()?,
+        num_occur: integer("sx:num-occur")?,
+        rem_occur: integer("sx:rem-occur")?,
+        auto_create: yes_no(element, "sx:autoCreate"),
+        auto_notify: yes_no(element, "sx:autoCreateNotify"),
+        adv_creation: integer("sx:advanceCreateDays")?,
+        adv_notify: integer("sx:advanceRemindDays")?,
+        instance_count: integer("sx:instanceCount")?,
+        template_account: guid(element.required("sx:templ-acct")?)?,
+        recurrences: element
+            .children_named("sx:schedule")
+            .flat_map(|schedule| schedule.children_named("gnc:recurrence"))
+            .map(recurrence)
+            .collect::<Result<_, _>>()?,
+        slots: slots(element.child("sx:slots"))?,
+    })
+}
+
+/// Reads a `gnc:GncBillTerm` element.
+fn bill_term(element: &Element) -> Result<BillTerm, XmlError> {
+    let (kind, definition, prefix) = match (
+        element.child("billterm:days"),
+        element.child("billterm:proximo"),
+    ) {
+        (Some(days), _) => (BillTermType::Days, days, "bt-days"),
+        (None, Some(proximo)) => (BillTermType::Proximo, proximo, "bt-prox"),
+        (None, None) => {
+            return Err(invalid(
+                element,
+                &"missing billterm:days or billterm:proximo",
+            ))
+        }
+    };
+
+    let (due, discount_days) = match kind {
+        BillTermType::Days => ("due-days", "disc-days"),
+        BillTermType::Proximo => ("due-day", "disc-day"),
+    };
+
+    let integer = |name: &str| -> Result<i64, XmlError> {
+        Ok(definition
+            .child(&format!("{prefix}:{name}"))
+            .map(parse)
+            .transpose()?
+            .unwrap_or_default())
+    };
+
+    Ok(BillTerm {
+        guid: guid(element.required("billterm:guid")?)?,
+        name: required_text(element, "billterm:name")?,
+        description: optional_text(element, "billterm:desc")
+            .unwrap_or_default(),
+        refcount: parse(element.required("billterm:refcount")?)?,
+        invisible: flag(element, "billterm:invisible")?,
+        parent: optional_guid(element, "billterm:parent")?,
+        kind,
+        due_days: integer(due)?,
+        discount_days: integer(discount_days)?,
+        discount: optional_numeric(definition, &format!("{prefix}:discount"))?,
+        cutoff: integer("cutoff-day")?,
+        slots: slots(element.child("billterm:slots"))?,
+    })
+}
+
+/// Reads a `gnc:GncTaxTable` element.
+fn tax_table(element: &Element) -> Result<TaxTable, XmlError> {
+    Ok(TaxTable {
+        guid: guid(element.required("taxtable:guid")?)?,
+        name: required_text(element, "taxtable:name")?,
+        refcount: parse(element.required("taxtable:refcount")?)?,
+        invisible: flag(element, "taxtable:invisible")?,
+        parent: optional_guid(element, "taxtable:parent")?,
+        entries: element
+            .children_named("taxtable:entries")
+            .flat_map(|entries| entries.children_named("gnc:GncTaxTableEntry"))
+            .map(|entry| {
+                Ok(TaxTableEntry {
+                    account: guid(entry.required("tte:acct")?)?,
+                    amount: numeric(entry.required("tte:amount")?)?,
+                    kind: parse(entry.required("tte:type")?)?,
+                })
+            })
+            .collect::<Result<_, XmlError>>()?,
+        slots: slots(element.child("taxtable:slots"))?,
+    })
+}
+
+/// Reads a `gnc:GncJob` element.
+fn job(element: &Element) -> Result<Job, XmlError> {
+    Ok(Job {
+        guid: guid(element.required("job:guid")?)?,
+        id: required_text(element, "job:id")?,
+        name: required_text(element, "job:name")?,
+        reference: optional_text(element, "job:reference").unwrap_or_default(),
+        active: flag(element, "job:active")?,
+        owner: element.child("job:owner").map(owner).transpose()?,
+        slots: slots(element.child("job:slots"))?,
+    })
+}
+
+/// Reads a `gnc:GncEntry` element.
+fn entry(element: &Element) -> Result<Entry, XmlError> {
+    Ok(Entry {
+        guid: guid(element.required("entry:guid")?)?,
+        date: timestamp(element.required("entry:date")?)?,
+        date_entered: element
+            .child("entry:entered")
+            .map(timestamp)
+            .transpose()?,
+        description: optional_text(element, "entry:description"),
+        action: optional_text(element, "entry:action"),
+        notes: optional_text(element, "entry:notes"),
+        quantity: optional_numeric(element, "entry:qty")?,
+        invoice_account: optional_guid(element, "entry:i-acct")?,
+        invoice_price: optional_numeric(element, "entry:i-price")?,
+        invoice_discount: optional_numeric(element, "entry:i-discount")?,
+        invoice: optional_guid(element, "entry:invoice")?,
+        invoice_discount_type: optional_parse(element, "entry:i-disc-type")?,
+        invoice_discount_how: optional_parse(element, "entry:i-disc-how")?,
+        invoice_taxable: flag(element, "entry:i-taxable")?,
+        invoice_tax_included: flag(element, "entry:i-taxincluded")?,
+        invoice_tax_table: optional_guid(element, "entry:i-taxtable")?,
+        bill_account: optional_guid(element, "entry:b-acct")?,
+        bill_price: optional_numeric(element, "entry:b-price")?,
+        bill: optional_guid(element, "entry:bill")?,
+        bill_taxable: flag(element, "entry:b-taxable")?,
+        bill_tax_included: flag(element, "entry:b-taxincluded")?,
+        bill_tax_table: optional_guid(element, "entry:b-taxtable")?,
+        bill_payment: optional_parse(element, "entry:b-pay")?,
+        billable: flag(element, "entry:billable")?,
+        bill_to: element.child("entry:billto").map(owner).transpose()?,
+        order: optional_guid(element, "entry:order")?,
+        slots: slots(element.child("entry:slots"))?,
+    })
+}
+
+/// Reads a `gnc:GncOrder` element.
+fn order(element: &Element) -> Result<Order, XmlError> {
+    Ok(Order {
+        guid: guid(element.required("order:guid")?)?,
+        id: required_text(element, "order:id")?,
+        notes: optional_text(element, "order:notes").unwrap_or_default(),
+        reference: optional_text(element, "order:reference")
+            .unwrap_or_default(),
+        active: flag(element, "order:active")?,
+        date_opened: timestamp(element.required("order:opened")?)?,
+        date_closed: timestamp(element.required("order:closed")?)?,
+        owner: owner(element.required("order:owner")?)?,
+        slots: slots(element.child("order:slots"))?,
+    })
+}
+
+/// Reads an owner element.
+fn owner(element: &Element) -> Result<Owner, XmlError> {
+    Ok(Owner {
+        kind: parse(element.required("owner:type")?)?,
+        guid: guid(element.required("owner:id")?)?,
+    })
+}
+
+/// Reads an optional address element.
+fn address(element: Option<&Element>) -> Address {
+    let field = |name| element.and_then(|element| optional_text(element, name));
+
+    Address {
+        name: field("addr:name"),
+        addr1: field("addr:addr1"),
+        addr2: field("addr:addr2"),
+        addr3: field("addr:addr3"),
+        addr4: field("addr:addr4"),
+        phone: field("addr:phone"),
+        fax: field("addr:fax"),
+        email: field("addr:email"),
+    }
+}
+
+/// Reads a recurrence element.
+fn recurrence(element: &Element) -> Result<Recurrence, XmlError> {
+    Ok(Recurrence {
+        multiplier: parse(element.required("recurrence:mult")?)?,
+        period: parse(element.required("recurrence:period_type")?)?,
+        start: gdate(element.required("recurrence:start")?)?,
+        weekend_adjust: element
+            .child("recurrence:weekend_adj")
+            .map(parse)
+            .transpose()?
+            .unwrap_or_default(),
+    })
+}
+
+/// Builds a root account.
+fn root(name: &str) -> Account {
+    Account {
+        guid: Guid::new(),
+        name: name.to_owned(),
+        kind: AccountType::Root,
+        commodity: None,
+        commodity_scu: 0,
+        non_std_scu: false,
+        parent: None,
+        code: None,
+        description: None,
+        hidden: false,
+        placeholder: false,
+        slots: Frame::default(),
+    }
+}
+
+/// Reads an optional slots element.
+fn slots(element: Option<&Element>) -> Result<Frame, XmlError> {
+    element.map_or_else(|| Ok(Frame::default()), slot_frame)
+}
+
+/// Reads the `slot` children of an element as a frame.
+fn slot_frame(element: &Element) -> Result<Frame, XmlError> {
+    element
+        .children_named("slot")
+        .map(|slot| {
+            Ok((
+                required_text(slot, "slot:key")?,
+                slot_value(slot.required("slot:value")?)?,
+            ))
+        })
+        .collect()
+}
+
+/// Reads a `slot:value` element.
+fn slot_value(element: &Element) -> Result<SlotValue, XmlError> {
+    let value = match element.attribute("type").unwrap_or_default() {
+        "integer" => SlotValue::Int64(parse(element)?),
+        "double" => SlotValue::Double(parse(element)?),
+        "numeric" => SlotValue::Numeric(numeric(element)?),
+        "string" => SlotValue::String(element.text.clone()),
+        "guid" => SlotValue::Guid(guid(element)?),
+        "timespec" => SlotValue::Timespec(timestamp(element)?),
+        "gdate" => SlotValue::GDate(gdate(element)?),
+        "list" => SlotValue::List(
+            element
+                .children_named("slot:value")
+                .map(slot_value)
+                .collect::<Result<_, _>>()?,
+        ),
+        "frame" => SlotValue::Frame(slot_frame(element)?),
+        kind => {
+            return Err(invalid(
+                element,
+                &format!("unsupported slot type {kind:?}"),
+            ))
+        }
+    };
+
+    Ok(value)
+}
+
+/// Marks as closed the lots whose balance is zero, as GnuCash does.
+fn close_lots(
+    lots: &mut BTreeMap<Guid, Lot>,
+    transactions: &BTreeMap<Guid, Transaction>,
+) {
+    let mut balances: BTreeMap<Guid, Numeric> = BTreeMap::new();
+
+    for split in transactions
+        .values()
+        .flat_map(|transaction| &transaction.splits)
+    {
+        if let Some(lot) = split.lot {
+            let balance = balances.entry(lot).or_insert(Numeric::ZERO);
+            *balance += split.quantity;
+        }
+    }
+
+    for (guid, balance) in balances {
+        if let Some(lot) = lots.get_mut(&guid) {
+            lot.is_closed = balance.is_zero();
+        }
+    }
+}
+
+/// Reads the children with the given name, by GUID.
+fn collect<T>(
+    parent: &Element,
+    name: &str,
+    read: impl FnMut(&Element) -> Result<(Guid, T), XmlError>,
+) -> Result<BTreeMap<Guid, T>, XmlError> {
+    parent.children_named(name).map(read).collect()
+}
+
+/// Returns the text of an element.
+fn text(element: &Element) -> &str {
+    &element.text
+}
+
+/// Returns the text of a required child element.
+fn required_text(element: &Element, name: &str) -> Result<String, XmlError> {
+    Ok(text(element.required(name)?).to_owned())
+}
+
+/// Returns the text of an optional child element.
+fn optional_text(element: &Element, name: &str) -> Option<String> {
+    element.child(name).map(|child| text(child).to_owned())
+}
+
+/// Parses the text of an element.
+fn parse<T>(element: &Element) -> Result<T, XmlError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    text(element)
+        .trim()
+        .parse()
+        .map_err(|error| invalid(element, &error))
+}
+
+/// Parses the text of an optional child element.
+fn optional_parse<T>(
+    element: &Element,
+    name: &str,
+) -> Result<Option<T>, XmlError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    element.child(name).map(parse).transpose()
+}
+
+/// Reads a GUID element.
+fn guid(element: &Element) -> Result<Guid, XmlError> {
+    parse(element)
+}
+
+/// Reads an optional GUID child element.
+fn optional_guid(
+    element: &Element,
+    name: &str,
+) -> Result<Option<Guid>, XmlError> {
+    optional_parse(element, name)
+}
+
+/// Reads a boolean stored as an integer, defaulting to `false`.
+fn flag(element: &Element, name: &str) -> Result<bool, XmlError> {
+    Ok(optional_parse::<i64>(element, name)?.is_some_and(|flag| flag != 0))
+}
+
+/// Reads a boolean stored as `y` or `n`.
+fn yes_no(element: &Element, name: &str) -> bool {
+    optional_text(element, name).is_some_and(|flag| flag == "y")
+}
+
+/// Reads a numeric stored as `num/denom`.
+fn numeric(element: &Element) -> Result<Numeric, XmlError> {
+    let value = text(element).trim();
+    let (num, denom) = value.split_once('/').unwrap_or((value, "1"));
+
+    num.parse()
+        .ok()
+        .zip(denom.parse().ok())
+        .and_then(|(num, denom)| Numeric::new(num, denom).ok())
+        .ok_or_else(|| invalid(element, &format!("invalid numeric {value:?}")))
+}
+
+/// Reads an optional numeric child element.
+fn optional_numeric(
+    element: &Element,
+    name: &str,
+) -> Result<Option<Numeric>, XmlError> {
+    element.child(name).map(numeric).transpose()
+}
+
+/// Reads a timestamp stored in a `ts:date` child element.
+fn timestamp(element: &Element) -> Result<DateTime<Utc>, XmlError> {
+    let date = element.required("ts:date")?;
+
+    DateTime::parse_from_str(text(date).trim(), TIMESTAMP_FORMAT)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .map_err(|error| invalid(date, &error))
+}
+
+/// Reads a date stored in a `gdate` child element.
+fn gdate(element: &Element) -> Result<NaiveDate, XmlError> {
+    let date = element.required("gdate")?;
+
+    NaiveDate::parse_from_str(text(date).trim(), DATE_FORMAT)
+        .map_err(|error| invalid(date, &error))
+}
+
+/// Builds an error for an invalid element.
+fn invalid(element: &Element, error: &dyn fmt::Display) -> XmlError {
+    XmlError::Invalid {
+        element: element.name.clone(),
+        message: error.to_string(),
+    }
+}
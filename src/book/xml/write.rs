@@ -0,0 +1,1029 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Conversion of a book to an XML tree.
+
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, NaiveDate, Utc};
+
+use super::{
+    dom::Element, AccountTemplate, XmlError, DATE_FORMAT, NAMESPACES,
+    TIMESTAMP_FORMAT,
+};
+use crate::book::{
+    Account, Address, BillTerm, BillTermType, Book, Budget, Commodity,
+    Customer, Employee, Entry, Frame, Guid, Invoice, Job, Lot, Numeric, Order,
+    Owner, Price, Recurrence, ScheduledTransaction, SlotValue, TaxTable,
+    Transaction, Vendor, WeekendAdjust,
+};
+
+/// Builds the XML document of a book.
+pub(super) fn document(book: &Book) -> Result<Element, XmlError> {
+    Ok(with_namespaces(Element::new("gnc-v2"), NAMESPACES)
+        .with(count("book", 1))
+        .with(BookWriter { book }.book()?))
+}
+
+/// Builds the XML document of an account template.
+pub(super) fn account_template(
+    template: &AccountTemplate,
+) -> Result<Element, XmlError> {
+    let writer = BookWriter {
+        book: &template.book,
+    };
+
+    let mut accounts = vec![];
+    writer.account_tree(
+        template.book.root_account,
+        &mut BTreeSet::new(),
+        &mut accounts,
+    );
+
+    let root = with_namespaces(Element::new("gnc-account-example"), NAMESPACES);
+
+    Ok(with_namespaces(root, &["gnc-act"])
+        .with(Element::text("gnc-act:title", &template.title))
+        .with(Element::text(
+            "gnc-act:short-description",
+            &template.short_description,
+        ))
+        .with(Element::text(
+            "gnc-act:long-description",
+            &template.long_description,
+        ))
+        .with_all(
+            accounts
+                .into_iter()
+                .map(|account| writer.account(account))
+                .collect::<Result<Vec<_>, _>>()?,
+        ))
+}
+
+/// A writer converting a book to XML elements.
+struct BookWriter<'a> {
+    /// The book to convert.
+    book: &'a Book,
+}
+
+impl<'a> BookWriter<'a> {
+    /// Builds the `gnc:book` element.
+    fn book(&self) -> Result<Element, XmlError> {
+        let book = self.book;
+        let business = &book.business;
+
+        let mut visited = BTreeSet::new();
+        let mut templates = vec![];
+        self.account_tree(book.root_template, &mut visited, &mut templates);
+        let mut accounts = vec![];
+        self.account_tree(book.root_account, &mut visited, &mut accounts);
+        accounts.extend(
+            book.accounts
+                .values()
+                .filter(|account| !visited.contains(&account.guid)),
+        );
+
+        // Commodities have no GUID in XML books, so they are sorted by name to
+        // keep the output stable.
+        let mut commodities = book.commodities.values().collect::<Vec<_>>();
+        commodities.sort_by_key(|commodity| {
+            (&commodity.namespace, &commodity.mnemonic)
+        });
+
+        let (template_transactions, transactions): (Vec<_>, Vec<_>) = book
+            .transactions
+            .values()
+            .partition(|transaction| book.is_template_transaction(transaction));
+
+        let counts = [
+            ("commodity", book.commodities.len()),
+            ("account", accounts.len()),
+            ("transaction", transactions.len()),
+            ("schedxaction", book.schedules.len()),
+            ("budget", book.budgets.len()),
+            ("price", book.prices.len()),
+            ("gnc:GncBillTerm", business.bill_terms.len()),
+            ("gnc:GncCustomer", business.customers.len()),
+            ("gnc:GncEmployee", business.employees.len()),
+            ("gnc:GncEntry", business.entries.len()),
+            ("gnc:GncInvoice", business.invoices.len()),
+            ("gnc:GncJob", business.jobs.len()),
+            ("gnc:GncOrder", business.orders.len()),
+            ("gnc:GncTaxTable", business.tax_tables.len()),
+            ("gnc:GncVendor", business.vendors.len()),
+        ];
+
+        let mut element = Element::new("gnc:book")
+            .with_version("2.0.0")
+            .with(Element::guid("book:id", book.guid))
+            .with_some(slots("book:slots", &book.slots))
+            .with_all(
+                counts
+                    .into_iter()
+                    .filter(|(_, count)| *count > 0)
+                    .map(|(kind, number)| count(kind, number)),
+            )
+            .with_all(commodities.into_iter().map(commodity_definition));
+
+        if !book.prices.is_empty() {
+            element = element.with(
+                Element::new("gnc:pricedb").with_version("1").with_all(
+                    book.sorted_prices()
+                        .into_iter()
+                        .map(|price| self.price(price))
+                        .collect::<Result<Vec<_>, _>>()?,
+                ),
+            );
+        }
+
+        element = element
+            .with_all(
+                accounts
+                    .into_iter()
+                    .map(|account| self.account(account))
+                    .collect::<Result<Vec<_>, _>>()?,
+            )
+            .with_all(
+                transactions
+                    .into_iter()
+                    .map(|transaction| self.transaction(transaction))
+                    .collect::<Result<Vec<_>, _>>()?,
+            );
+
+        if !templates.is_empty() || !template_transactions.is_empty() {
+            element = element.with(
+                Element::new("gnc:template-transactions")
+                    .with_all(
+                        templates
+                            .into_iter()
+                            .map(|account| self.account(account))
+                            .collect::<Result<Vec<_>, _>>()?,
+                    )
+                    .with_all(
+                        template_transactions
+                            .into_iter()
+                            .map(|transaction| self.transaction(transaction))
+                            .collect::<Result<Vec<_>, _>>()?,
+                    ),
+            );
+        }
+
+        self.business(
+            element
+                .with_all(book.schedules.values().map(schedule))
+                .with_all(book.budgets.values().map(budget)),
+        )
+    }
+
+    /// Adds the business objects to the `gnc:book` element.
+    fn business(&self, element: Element) -> Result<Element, XmlError> {
+        let business = &self.book.business;
+
+        Ok(element
+            .with_all(business.bill_terms.values().map(bill_term))
+            .with_all(
+                business
+                    .customers
+                    .values()
+                    .map(|customer| self.customer(customer))
+                    .collect::<Result<Vec<_>, _>>()?,
+            )
+            .with_all(
+                business
+                    .employees
+                    .values()
+                    .map(|employee| self.employee(employee))
+                    .collect::<Result<Vec<_>, _>>()?,
+            )
+            .with_all(business.entries.values().map(entry))
+            .with_all(
+                business
+                    .invoices
+                    .values()
+                    .map(|invoice| self.invoice(invoice))
+                    .collect::<Result<Vec<_>, _>>()?,
+            )
+            .with_all(business.jobs.values().map(job))
+            .with_all(business.orders.values().map(order))
+            .with_all(business.tax_tables.values().map(tax_table))
+            .with_all(
+                business
+                    .vendors
+                    .values()
+                    .map(|vendor| self.vendor(vendor))
+                    .collect::<Result<Vec<_>, _>>()?,
+            ))
+    }
+
+    /// Collects an account and its descendants, parents first.
+    fn account_tree(
+        &self,
+        guid: Guid,
+        visited: &mut BTreeSet<Guid>,
+        accounts: &mut Vec<&'a Account>,
+    ) {
+        if let Some(account) = self.book.account(guid) {
+            if visited.insert(guid) {
+                accounts.push(account);
+
+                for child in self.book.children(guid) {
+                    self.account_tree(child.guid, visited, accounts);
+                }
+            }
+        }
+    }
+
+    /// Builds a `gnc:account` element.
+    fn account(&self, account: &Account) -> Result<Element, XmlError> {
+        let mut account_slots = account.slots.clone();
+        if account.hidden {
+            account_slots
+                .insert("hidden", SlotValue::String("true".to_owned()));
+        }
+        if account.placeholder {
+            account_slots
+                .insert("placeholder", SlotValue::String("true".to_owned()));
+        }
+
+        let lots: Vec<_> = self
+            .book
+            .lots
+            .values()
+            .filter(|lot| lot.account == Some(account.guid))
+            .map(lot)
+            .collect();
+
+        Ok(Element::new("gnc:account")
+            .with_version("2.0.0")
+            .with(Element::text("act:name", &account.name))
+            .with(Element::guid("act:id", account.guid))
+            .with(Element::text("act:type", account.kind.as_str()))
+            .with_some(
+                account
+                    .commodity
+                    .map(|commodity| self.commodity("act:commodity", commodity))
+                    .transpose()?,
+            )
+            .with(Element::text(
+                "act:commodity-scu",
+                account.commodity_scu.to_string(),
+            ))
+            .with_some(
+                account
+                    .non_std_scu
+                    .then(|| Element::new("act:non-standard-scu")),
+            )
+            .with_some(
+                account
+                    .code
+                    .as_ref()
+                    .map(|code| Element::text("act:code", code)),
+            )
+            .with_some(account.description.as_ref().map(|description| {
+                Element::text("act:description", description)
+            }))
+            .with_some(slots("act:slots", &account_slots))
+            .with_some(
+                account
+                    .parent
+                    .map(|parent| Element::guid("act:parent", parent)),
+            )
+            .with_some(
+                (!lots.is_empty())
+                    .then(|| Element::new("act:lots").with_all(lots)),
+            ))
+    }
+
+    /// Builds a `gnc:transaction` element.
+    fn transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<Element, XmlError> {
+        let book = self.book;
+        let currency_fraction = book
+            .commodity(transaction.currency)
+            .map_or(1, |currency| currency.fraction);
+
+        let splits = transaction.splits.iter().map(|split| {
+            let scu = book
+                .account(split.account)
+                .map_or(1, |account| account.commodity_scu);
+
+            Element::new("trn:split")
+                .with(Element::guid("split:id", split.guid))
+                .with_some(
+                    (!split.memo.is_empty())
+                        .then(|| Element::text("split:memo", &split.memo)),
+                )
+                .with_some(
+                    (!split.action.is_empty())
+                        .then(|| Element::text("split:action", &split.action)),
+                )
+                .with(Element::text(
+                    "split:reconciled-state",
+                    split.reconcile_state.to_string(),
+                ))
+                .with_some(
+                    split
+                        .reconcile_date
+                        .map(|date| timestamp("split:reconcile-date", date)),
+                )
+                .with(Element::text(
+                    "split:value",
+                    numeric(split.value, currency_fraction),
+                ))
+                .with(Element::text(
+                    "split:quantity",
+                    numeric(split.quantity, scu),
+                ))
+                .with(Element::guid("split:account", split.account))
+                .with_some(split.lot.map(|lot| Element::guid("split:lot", lot)))
+                .with_some(slots("split:slots", &split.slots))
+        });
+
+        Ok(Element::new("gnc:transaction")
+            .with_version("2.0.0")
+            .with(Element::guid("trn:id", transaction.guid))
+            .with(self.commodity("trn:currency", transaction.currency)?)
+            .with_some(
+                (!transaction.num.is_empty())
+                    .then(|| Element::text("trn:num", &transaction.num)),
+            )
+            .with(timestamp("trn:date-posted", transaction.post_date))
+            .with(timestamp("trn:date-entered", transaction.enter_date))
+            .with(Element::text("trn:description", &transaction.description))
+            .with_some(slots("trn:slots", &transaction.slots))
+            .with(Element::new("trn:splits").with_all(splits)))
+    }
+
+    /// Builds a `price` element.
+    fn price(&self, price: &Price) -> Result<Element, XmlError> {
+        Ok(Element::new("price")
+            .with(Element::guid("price:id", price.guid))
+            .with(self.commodity("price:commodity", price.commodity)?)
+            .with(self.commodity("price:currency", price.currency)?)
+            .with(timestamp("price:time", price.date))
+            .with_some(
+                price
+                    .source
+                    .as_ref()
+                    .map(|source| Element::text("price:source", source)),
+            )
+            .with_some(
+                price
+                    .kind
+                    .as_ref()
+                    .map(|kind| Element::text("price:type", kind)),
+            )
+            .with(Element::text("price:value", numeric(price.value, 1))))
+    }
+
+    /// Builds a `gnc:GncCustomer` element.
+    fn customer(&self, customer: &Customer) -> Result<Element, XmlError> {
+        Ok(Element::new("gnc:GncCustomer")
+            .with_version("2.0.0")
+            .with(Element::guid("cust:guid", customer.guid))
+            .with(Element::text("cust:name", &customer.name))
+            .with(Element::text("cust:id", &customer.id))
+            .with(address("cust:addr", &customer.address))
+            .with(address("cust:shipaddr", &customer.ship_address))
+            .with(Element::text("cust:notes", &customer.notes))
+            .with_some(
+                customer
+                    .terms
+                    .map(|terms| Element::guid("cust:terms", terms)),
+            )
+            .with_some(customer.tax_included.map(|included| {
+                Element::text("cust:taxincluded", included.as_str())
+            }))
+            .with(Element::text("cust:active", flag(customer.active)))
+            .with(Element::text(
+                "cust:discount",
+                numeric(customer.discount, 1),
+            ))
+            .with(Element::text("cust:credit", numeric(customer.credit, 1)))
+            .with(self.commodity("cust:currency", customer.currency)?)
+            .with(Element::text("cust:use-tt", flag(customer.tax_override)))
+            .with_some(
+                customer
+                    .tax_table
+                    .map(|table| Element::guid("cust:taxtable", table)),
+            )
+            .with_some(slots("cust:slots", &customer.slots)))
+    }
+
+    /// Builds a `gnc:GncVendor` element.
+    fn vendor(&self, vendor: &Vendor) -> Result<Element, XmlError> {
+        Ok(Element::new("gnc:GncVendor")
+            .with_version("2.0.0")
+            .with(Element::guid("vendor:guid", vendor.guid))
+            .with(Element::text("vendor:name", &vendor.name))
+            .with(Element::text("vendor:id", &vendor.id))
+            .with(address("vendor:addr", &vendor.address))
+            .with(Element::text("vendor:notes", &vendor.notes))
+            .with_some(
+                vendor
+                    .terms
+                    .map(|terms| Element::guid("vendor:terms", terms)),
+            )
+            .with_some(vendor.tax_included.map(|included| {
+                Element::text("vendor:taxincluded", included.as_str())
+            }))
+            .with(Element::text("vendor:active", flag(vendor.active)))
+            .with(self.commodity("vendor:currency", vendor.currency)?)
+            .with(Element::text("vendor:use-tt", flag(vendor.tax_override)))
+            .with_some(
+                vendor
+                    .tax_table
+                    .map(|table| Element::guid("vendor:taxtable", table)),
+            )
+            .with_some(slots("vendor:slots", &vendor.slots)))
+    }
+
+    /// Builds a `gnc:GncEmployee` element.
+    fn employee(&self, employee: &Employee) -> Result<Element, XmlError> {
+        Ok(Element::new("gnc:GncEmployee")
+            .with_version("2.0.0")
+            .with(Element::guid("employee:guid", employee.guid))
+            .with(Element::text("employee:username", &employee.username))
+            .with(Element::text("employee:id", &employee.id))
+            .with(address("employee:addr", &employee.address))
+            .with(Element::text("employee:language", &employee.language))
+            .with(Element::text("employee:acl", &employee.acl))
+            .with(Element::text("employee:active", flag(employee.active)))
+            .with(Element::text(
+                "employee:workday",
+                numeric(employee.workday, 1),
+            ))
+            .with(Element::text("employee:rate", numeric(employee.rate, 1)))
+            .with(self.commodity("employee:currency", employee.currency)?)
+            .with_some(
+                employee
+                    .credit_card
+                    .map(|account| Element::guid("employee:ccard", account)),
+            )
+            .with_some(slots("employee:slots", &employee.slots)))
+    }
+
+    /// Builds a `gnc:GncInvoice` element.
+    fn invoice(&self, invoice: &Invoice) -> Result<Element, XmlError> {
+        Ok(Element::new("gnc:GncInvoice")
+            .with_version("2.0.0")
+            .with(Element::guid("invoice:guid", invoice.guid))
+            .with(Element::text("invoice:id", &invoice.id))
+            .with_some(
+                invoice
+                    .owner
+                    .map(|owner| owner_element("invoice:owner", owner)),
+            )
+            .with_some(
+                invoice
+                    .date_opened
+                    .map(|date| timestamp("invoice:opened", date)),
+            )
+            .with_some(
+                invoice
+                    .date_posted
+                    .map(|date| timestamp("invoice:posted", date)),
+            )
+            .with_some(
+                invoice
+                    .terms
+                    .map(|terms| Element::guid("invoice:terms", terms)),
+            )
+            .with_some(invoice.billing_id.as_ref().map(|billing_id| {
+                Element::text("invoice:billing_id", billing_id)
+            }))
+            .with(Element::text("invoice:notes", &invoice.notes))
+            .with(Element::text("invoice:active", flag(invoice.active)))
+            .with_some(
+                invoice
+                    .post_txn
+                    .map(|txn| Element::guid("invoice:posttxn", txn)),
+            )
+            .with_some(
+                invoice
+                    .post_lot
+                    .map(|lot| Element::guid("invoice:postlot", lot)),
+            )
+            .with_some(
+                invoice
+                    .post_account
+                    .map(|account| Element::guid("invoice:postacc", account)),
+            )
+            .with(self.commodity("invoice:currency", invoice.currency)?)
+            .with_some(
+                invoice
+                    .bill_to
+                    .map(|owner| owner_element("invoice:billto", owner)),
+            )
+            .with_some(invoice.charge_amount.map(|amount| {
+                Element::text("invoice:charge-amt", numeric(amount, 1))
+            }))
+            .with_some(slots("invoice:slots", &invoice.slots)))
+    }
+
+    /// Builds a commodity reference, made of its namespace and mnemonic.
+    fn commodity(&self, name: &str, guid: Guid) -> Result<Element, XmlError> {
+        let commodity =
+            self.book.commodity(guid).ok_or_else(|| XmlError::Invalid {
+                element: name.to_owned(),
+                message: format!("unknown commodity {guid}"),
+            })?;
+
+        Ok(Element::new(name)
+            .with(Element::text("cmdty:space", &commodity.namespace))
+            .with(Element::text("cmdty:id", &commodity.mnemonic)))
+    }
+}
+
+/// Declares the GnuCash namespaces with the given prefixes on an element.
+fn with_namespaces(element: Element, prefixes: &[&str]) -> Element {
+    prefixes.iter().fold(element, |element, prefix| {
+        element.with_attribute(
+            &format!("xmlns:{prefix}"),
+            &format!("http://www.gnucash.org/XML/{prefix}"),
+        )
+    })
+}
+
+/// Builds a `gnc:commodity` element defining a commodity.
+fn commodity_definition(commodity: &Commodity) -> Element {
+    Element::new("gnc:commodity")
+        .with_version("2.0.0")
+        .with(Element::text("cmdty:space", &commodity.namespace))
+        .with(Element::text("cmdty:id", &commodity.mnemonic))
+        .with_some(
+            commodity
+                .fullname
+                .as_ref()
+                .map(|fullname| Element::text("cmdty:name", fullname)),
+        )
+        .with_some(
+            commodity
+                .cusip
+                .as_ref()
+                .map(|cusip| Element::text("cmdty:xcode", cusip)),
+        )
+        .with(Element::text(
+            "cmdty:fraction",
+            commodity.fraction.to_string(),
+        ))
+        .with_some(
+            commodity
+                .quote_flag
+                .then(|| Element::new("cmdty:get_quotes")),
+        )
+        .with_some(
+            commodity
+                .quote_source
+                .as_ref()
+                .map(|source| Element::text("cmdty:quote_source", source)),
+        )
+        .with_some(
+            commodity
+                .quote_tz
+                .as_ref()
+                .map(|timezone| Element::text("cmdty:quote_tz", timezone)),
+        )
+        .with_some(slots("cmdty:slots", &commodity.slots))
+}
+
+/// Builds a `gnc:count-data` element.
+fn count(kind: &str, number: usize) -> Element {
+    Element::text("gnc:count-data", number.to_string())
+        .with_attribute("cd:type", kind)
+}
+
+/// Builds a `gnc:lot` element.
+fn lot(lot: &Lot) -> Element {
+    Element::new("gnc:lot")
+        .with_version("2.0.0")
+        .with(Element::guid("lot:id", lot.guid))
+        .with_some(slots("lot:slots", &lot.slots))
+}
+
+/// Builds a `gnc:schedxaction` element.
+fn schedule(schedule: &ScheduledTransaction) -> Element {
+    Element::new("gnc:schedxaction")
+        .with_version("2.0.0")
+        .with(Element::guid("sx:id", schedule.guid))
+        .with_some(
+            schedule
+                .name
+                .as_ref()
+                .map(|name| Element::text("sx:name", name)),
+        )
+        .with(Element::text("sx:enabled", yes_no(schedule.enabled)))
+        .with(Element::text("sx:autoCreate", yes_no(schedule.auto_create)))
+        .with(Element::text(
+            "sx:autoCreateNotify",
+            yes_no(schedule.auto_notify),
+        ))
+        .with(Element::text(
+            "sx:advanceCreateDays",
+            schedule.adv_creation.to_string(),
+        ))
+        .with(Element::text(
+            "sx:advanceRemindDays",
+            schedule.adv_notify.to_string(),
+        ))
+        .with(Element::text(
+            "sx:instanceCount",
+            schedule.instance_count.to_string(),
+        ))
+        .with_some(schedule.start_date.map(|date| gdate("sx:start", date)))
+        .with_some(schedule.last_occur.map(|date| gdate("sx:last", date)))
+        .with_some(schedule.end_date.map(|date| gdate("sx:end", date)))
+        .with(Element::text(
+            "sx:num-occur",
+            schedule.num_occur.to_string(),
+        ))
+        .with(Element::text(
+            "sx:rem-occur",
+            schedule.rem_occur.to_string(),
+        ))
+        .with(Element::guid("sx:templ-acct", schedule.template_account))
+        .with(Element::new("sx:schedule").with_all(
+            schedule.recurrences.iter().map(|schedule_recurrence| {
+                recurrence("gnc:recurrence", schedule_recurrence)
+            }),
+        ))
+        .with_some(slots("sx:slots", &schedule.slots))
+}
+
+/// Builds a `gnc:budget` element.
+///
+/// As in GnuCash, the amounts are stored in the slots of the budget, by
+/// account GUID and period number.
+fn budget(budget: &Budget) -> Element {
+    let mut budget_slots = budget.slots.clone();
+    for amount in &budget.amounts {
+        budget_slots.insert(
+            &format!("{}/{}", amount.account, amount.period),
+            SlotValue::Numeric(amount.amount),
+        );
+    }
+
+    Element::new("gnc:budget")
+        .with_version("2.0.0")
+        .with(Element::guid("bgt:id", budget.guid))
+        .with(Element::text("bgt:name", &budget.name))
+        .with_some(
+            budget.description.as_ref().map(|description| {
+                Element::text("bgt:description", description)
+            }),
+        )
+        .with(Element::text(
+            "bgt:num-periods",
+            budget.num_periods.to_string(),
+        ))
+        .with(recurrence("bgt:recurrence", &budget.recurrence))
+        .with_some(slots("bgt:slots", &budget_slots))
+}
+
+/// Builds a `gnc:GncBillTerm` element.
+fn bill_term(term: &BillTerm) -> Element {
+    let definition = match term.kind {
+        BillTermType::Days => Element::new("billterm:days")
+            .with(Element::text("bt-days:due-days", term.due_days.to_string()))
+            .with(Element::text(
+                "bt-days:disc-days",
+                term.discount_days.to_string(),
+            ))
+            .with_some(term.discount.map(|discount| {
+                Element::text("bt-days:discount", numeric(discount, 1))
+            })),
+        BillTermType::Proximo => Element::new("billterm:proximo")
+            .with(Element::text("bt-prox:due-day", term.due_days.to_string()))
+            .with(Element::text(
+                "bt-prox:disc-day",
+                term.discount_days.to_string(),
+            ))
+            .with_some(term.discount.map(|discount| {
+                Element::text("bt-prox:discount", numeric(discount, 1))
+            }))
+            .with(Element::text("bt-prox:cutoff-day", term.cutoff.to_string())),
+    };
+
+    Element::new("gnc:GncBillTerm")
+        .with_version("2.0.0")
+        .with(Element::guid("billterm:guid", term.guid))
+        .with(Element::text("billterm:name", &term.name))
+        .with(Element::text("billterm:desc", &term.description))
+        .with(Element::text(
+            "billterm:refcount",
+            term.refcount.to_string(),
+        ))
+        .with(Element::text("billterm:invisible", flag(term.invisible)))
+        .with_some(
+            term.parent
+                .map(|parent| Element::guid("billterm:parent", parent)),
+        )
+        .with_some(slots("billterm:slots", &term.slots))
+        .with(definition)
+}
+
+/// Builds a `gnc:GncTaxTable` element.
+fn tax_table(table: &TaxTable) -> Element {
+    let entries = table.entries.iter().map(|entry| {
+        Element::new("gnc:GncTaxTableEntry")
+            .with(Element::guid("tte:acct", entry.account))
+            .with(Element::text("tte:amount", numeric(entry.amount, 1)))
+            .with(Element::text("tte:type", entry.kind.as_str()))
+    });
+
+    Element::new("gnc:GncTaxTable")
+        .with_version("2.0.0")
+        .with(Element::guid("taxtable:guid", table.guid))
+        .with(Element::text("taxtable:name", &table.name))
+        .with(Element::text(
+            "taxtable:refcount",
+            table.refcount.to_string(),
+        ))
+        .with(Element::text("taxtable:invisible", flag(table.invisible)))
+        .with_some(
+            table
+                .parent
+                .map(|parent| Element::guid("taxtable:parent", parent)),
+        )
+        .with(Element::new("taxtable:entries").with_all(entries))
+        .with_some(slots("taxtable:slots", &table.slots))
+}
+
+/// Builds a `gnc:GncJob` element.
+fn job(job: &Job) -> Element {
+    Element::new("gnc:GncJob")
+        .with_version("2.0.0")
+        .with(Element::guid("job:guid", job.guid))
+        .with(Element::text("job:id", &job.id))
+        .with(Element::text("job:name", &job.name))
+        .with(Element::text("job:reference", &job.reference))
+        .with_some(job.owner.map(|owner| owner_element("job:owner", owner)))
+        .with(Element::text("job:active", flag(job.active)))
+        .with_some(slots("job:slots", &job.slots))
+}
+
+/// Builds a `gnc:GncEntry` element.
+fn entry(entry: &Entry) -> Element {
+    /// Builds an optional element containing a numeric.
+    fn optional_numeric(name: &str, value: Option<Numeric>) -> Option<Element> {
+        value.map(|value| Element::text(name, numeric(value, 1)))
+    }
+
+    /// Builds an optional element containing a string.
+    fn optional_text(name: &str, value: Option<&String>) -> Option<Element> {
+        value.map(|value| Element::text(name, value))
+    }
+
+    /// Builds an optional element containing a GUID.
+    fn optional_guid(name: &str, value: Option<Guid>) -> Option<Element> {
+        value.map(|value| Element::guid(name, value))
+    }
+
+    Element::new("gnc:GncEntry")
+        .with_version("2.0.0")
+        .with(Element::guid("entry:guid", entry.guid))
+        .with(timestamp("entry:date", entry.date))
+        .with_some(
+            entry
+                .date_entered
+                .map(|date| timestamp("entry:entered", date)),
+        )
+        .with_some(optional_text(
+            "entry:description",
+            entry.description.as_ref(),
+        ))
+        .with_some(optional_text("entry:action", entry.action.as_ref()))
+        .with_some(optional_text("entry:notes", entry.notes.as_ref()))
+        .with_some(optional_numeric("entry:qty", entry.quantity))
+        .with_some(optional_guid("entry:i-acct", entry.invoice_account))
+        .with_some(optional_numeric("entry:i-price", entry.invoice_price))
+        .with_some(optional_numeric("entry:i-discount", entry.invoice_discount))
+        .with_some(optional_guid("entry:invoice", entry.invoice))
+        .with_some(
+            entry
+                .invoice_discount_type
+                .map(|kind| Element::text("entry:i-disc-type", kind.as_str())),
+        )
+        .with_some(
+            entry
+                .invoice_discount_how
+                .map(|how| Element::text("entry:i-disc-how", how.as_str())),
+        )
+        .with(Element::text(
+            "entry:i-taxable",
+            flag(entry.invoice_taxable),
+        ))
+        .with(Element::text(
+            "entry:i-taxincluded",
+            flag(entry.invoice_tax_included),
+        ))
+        .with_some(optional_guid("entry:i-taxtable", entry.invoice_tax_table))
+        .with_some(optional_guid("entry:b-acct", entry.bill_account))
+        .with_some(optional_numeric("entry:b-price", entry.bill_price))
+        .with_some(optional_guid("entry:bill", entry.bill))
+        .with(Element::text("entry:billable", flag(entry.billable)))
+        .with_some(
+            entry
+                .bill_to
+                .map(|owner| owner_element("entry:billto", owner)),
+        )
+        .with(Element::text("entry:b-taxable", flag(entry.bill_taxable)))
+        .with(Element::text(
+            "entry:b-taxincluded",
+            flag(entry.bill_tax_included),
+        ))
+        .with_some(optional_guid("entry:b-taxtable", entry.bill_tax_table))
+        .with_some(
+            entry
+                .bill_payment
+                .map(|payment| Element::text("entry:b-pay", payment.as_str())),
+        )
+        .with_some(optional_guid("entry:order", entry.order))
+        .with_some(slots("entry:slots", &entry.slots))
+}
+
+/// Builds a `gnc:GncOrder` element.
+fn order(order: &Order) -> Element {
+    Element::new("gnc:GncOrder")
+        .with_version("2.0.0")
+        .with(Element::guid("order:guid", order.guid))
+        .with(Element::text("order:id", &order.id))
+        .with(owner_element("order:owner", order.owner))
+        .with(timestamp("order:opened", order.date_opened))
+        .with(timestamp("order:closed", order.date_closed))
+        .with(Element::text("order:notes", &order.notes))
+        .with(Element::text("order:reference", &order.reference))
+        .with(Element::text("order:active", flag(order.active)))
+        .with_some(slots("order:slots", &order.slots))
+}
+
+/// Builds an owner element.
+fn owner_element(name: &str, owner: Owner) -> Element {
+    Element::new(name)
+        .with_version("2.0.0")
+        .with(Element::text("owner:type", owner.kind.as_str()))
+        .with(Element::guid("owner:id", owner.guid))
+}
+
+/// Builds an address element.
+fn address(name: &str, address: &Address) -> Element {
+    let fields = [
+        ("addr:name", &address.name),
+        ("addr:addr1", &address.addr1),
+        ("addr:addr2", &address.addr2),
+        ("addr:addr3", &address.addr3),
+        ("addr:addr4", &address.addr4),
+        ("addr:phone", &address.phone),
+        ("addr:fax", &address.fax),
+        ("addr:email", &address.email),
+    ];
+
+    Element::new(name).with_version("2.0.0").with_all(
+        fields.into_iter().filter_map(|(field, value)| {
+            value.as_ref().map(|value| Element::text(field, value))
+        }),
+    )
+}
+
+/// Builds a recurrence element.
+fn recurrence(name: &str, recurrence: &Recurrence) -> Element {
+    Element::new(name)
+        .with_version("1.0.0")
+        .with(Element::text(
+            "recurrence:mult",
+            recurrence.multiplier.to_string(),
+        ))
+        .with(Element::text(
+            "recurrence:period_type",
+            recurrence.period.as_str(),
+        ))
+        .with(gdate("recurrence:start", recurrence.start))
+        .with_some((recurrence.weekend_adjust != WeekendAdjust::None).then(
+            || {
+                Element::text(
+                    "recurrence:weekend_adj",
+                    recurrence.weekend_adjust.as_str(),
+                )
+            },
+        ))
+}
+
+/// Builds a slots element, or nothing if there are no slots.
+fn slots(name: &str, frame: &Frame) -> Option<Element> {
+    (!frame.is_empty()).then(|| Element::new(name).with_all(slot_list(frame)))
+}
+
+/// Builds the `slot` elements of a frame.
+fn slot_list(frame: &Frame) -> Vec<Element> {
+    frame
+        .iter()
+        .map(|(key, value)| {
+            Element::new("slot")
+                .with(Element::text("slot:key", key))
+                .with(slot_value(value))
+        })
+        .collect()
+}
+
+/// Builds a `slot:value` element.
+fn slot_value(value: &SlotValue) -> Element {
+    let (kind, element) = match value {
+        SlotValue::Int64(int64) => {
+            ("integer", Element::text("slot:value", int64.to_string()))
+        }
+        SlotValue::Double(double) => {
+            ("double", Element::text("slot:value", double.to_string()))
+        }
+        SlotValue::Numeric(value) => {
+            ("numeric", Element::text("slot:value", numeric(*value, 1)))
+        }
+        SlotValue::String(string) => {
+            ("string", Element::text("slot:value", string))
+        }
+        SlotValue::Guid(guid) => {
+            ("guid", Element::text("slot:value", guid.to_string()))
+        }
+        SlotValue::Timespec(date) => (
+            "timespec",
+            Element::new("slot:value").with(Element::text(
+                "ts:date",
+                date.format(TIMESTAMP_FORMAT).to_string(),
+            )),
+        ),
+        SlotValue::GDate(date) => (
+            "gdate",
+            Element::new("slot:value").with(Element::text(
+                "gdate",
+                date.format(DATE_FORMAT).to_string(),
+            )),
+        ),
+        SlotValue::List(values) => (
+            "list",
+            Element::new("slot:value").with_all(values.iter().map(slot_value)),
+        ),
+        SlotValue::Frame(frame) => (
+            "frame",
+            Element::new("slot:value").with_all(slot_list(frame)),
+        ),
+    };
+
+    element.with_attribute("type", kind)
+}
+
+/// Builds an element containing a timestamp.
+fn timestamp(name: &str, date: DateTime<Utc>) -> Element {
+    Element::new(name).with(Element::text(
+        "ts:date",
+        date.format(TIMESTAMP_FORMAT).to_string(),
+    ))
+}
+
+/// Builds an element containing a date.
+fn gdate(name: &str, date: NaiveDate) -> Element {
+    Element::new(name)
+        .with(Element::text("gdate", date.format(DATE_FORMAT).to_string()))
+}
+
+/// Formats a numeric, using `fraction` as denominator when the numeric can be
+/// represented exactly with it.
+fn numeric(value: Numeric, fraction: i64) -> String {
+    let fraction = i128::from(fraction);
+
+    if value.fits_fraction(fraction) {
+        format!("{}/{fraction}", value.num_at(fraction))
+    } else {
+        format!("{}/{}", value.num(), value.denom())
+    }
+}
+
+/// Formats a boolean as business objects do.
+const fn flag(value: bool) -> &'static str {
+    if value {
+        "1"
+    } else {
+        "0"
+    }
+}
+
+/// Formats a boolean as scheduled transactions do.
+const fn yes_no(value: bool) -> &'static str {
+    if value {
+        "y"
+    } else {
+        "n"
+    }
+}
@@ -0,0 +1,680 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Changes to the chart of accounts, and its templates.
+//!
+//! Accounts are validated against the type rules of GnuCash: an income account
+//! cannot be under an asset account, and the splits of an account can only be
+//! moved to an account of a compatible type.
+//!
+//! A chart of accounts can be exported as an account template, like the ones
+//! shipped with GnuCash, and the missing accounts of a template imported in
+//! another book.
+
+use std::{collections::BTreeMap, io};
+
+use thiserror::Error;
+
+use crate::{
+    book::{
+        Account, AccountTemplate, AccountType, Book, Business, Commodity,
+        Frame, Guid, SlotValue, Split, ACCOUNT_SEPARATOR,
+    },
+    conversion,
+    since_last_run::ACCOUNT_SLOT,
+};
+
+/// The account slots kept in templates.
+///
+/// The other slots, like the reconciliation info, only make sense in the book.
+const TEMPLATE_SLOTS: &[&str] = &["color", "notes", "tax-related"];
+
+/// The columns of the CSV export, as in the account export of GnuCash.
+const CSV_COLUMNS: [&str; 12] = [
+    "type",
+    "full_name",
+    "name",
+    "code",
+    "description",
+    "color",
+    "notes",
+    "symbol",
+    "namespace",
+    "hidden",
+    "tax",
+    "place_holder",
+];
+
+/// An account merged into another one.
+#[derive(Debug)]
+pub struct Merge {
+    /// The merged account, removed from the book.
+    pub source: Account,
+    /// The GUID of the account receiving the splits.
+    pub target: Guid,
+    /// The splits moved to the target account, with the GUID of their
+    /// transaction.
+    ///
+    /// This includes the template splits of scheduled transactions referring
+    /// to the merged account.
+    pub splits: Vec<(Guid, Split)>,
+}
+
+/// The accounts of a template imported in a book.
+#[derive(Debug, Default)]
+pub struct TemplateImport {
+    /// The accounts added to the book, parents first.
+    pub accounts: Vec<Account>,
+    /// The number of accounts of the template already in the book.
+    pub existing: usize,
+}
+
+/// Errors that can occur when changing the chart of accounts.
+#[derive(Debug, Error)]
+pub enum ChartError {
+    /// An account does not exist.
+    #[error("unknown account: {0}")]
+    UnknownAccount(String),
+    /// An account already exists.
+    #[error("the account {0} already exists")]
+    AlreadyExists(String),
+    /// An account name is invalid.
+    #[error("invalid account name: {0:?}")]
+    InvalidName(String),
+    /// No commodity can be inferred for a top-level account.
+    #[error("no commodity can be inferred for the account {0}")]
+    MissingCommodity(String),
+    /// An account cannot have a parent of this type.
+    #[error(
+        "an account of type {kind} cannot be under {parent}, of type \
+        {parent_kind}"
+    )]
+    IncompatibleParent {
+        /// The type of the account.
+        kind: AccountType,
+        /// The full path of the parent.
+        parent: String,
+        /// The type of the parent.
+        parent_kind: AccountType,
+    },
+    /// An account is moved under itself.
+    #[error("cannot move the account {0} under itself")]
+    UnderItself(String),
+    /// An account is merged into itself.
+    #[error("cannot merge the account {0} into itself")]
+    SameAccount(String),
+    /// A merged account has sub-accounts.
+    #[error("the account {0} has sub-accounts")]
+    HasChildren(String),
+    /// The splits of an account cannot be moved to an account of this type.
+    #[error(
+        "the accounts {merged} ({merged_kind}) and {target} ({target_kind}) \
+        have incompatible types"
+    )]
+    IncompatibleTypes {
+        /// The full path of the merged account.
+        merged: String,
+        /// The type of the merged account.
+        merged_kind: AccountType,
+        /// The full path of the account receiving the splits.
+        target: String,
+        /// The type of the account receiving the splits.
+        target_kind: AccountType,
+    },
+    /// Two accounts have different commodities.
+    #[error("the accounts {merged} and {target} have different commodities")]
+    DifferentCommodities {
+        /// The full path of the merged account.
+        merged: String,
+        /// The full path of the account receiving the splits.
+        target: String,
+    },
+    /// An account is a placeholder.
+    #[error("the account {0} is a placeholder")]
+    PlaceholderAccount(String),
+    /// A commodity of a template is not in the book.
+    #[error("unknown commodity: {0}")]
+    UnknownCommodity(String),
+}
+
+/// Adds an account to the book, given its full path.
+///
+/// The parent must exist. When no commodity is given, the account uses the
+/// one of its parent, or the main currency of the book for top-level accounts.
+pub fn add<'a>(
+    book: &'a mut Book,
+    path: &str,
+    kind: AccountType,
+    commodity: Option<Guid>,
+    force: bool,
+) -> Result<&'a mut Account, ChartError> {
+    if book.find_account(path).is_some() {
+        return Err(ChartError::AlreadyExists(path.to_owned()));
+    }
+
+    let (parent, name) = split_path(book, path)?;
+    check_parent(book, kind, parent, force)?;
+
+    let commodity = commodity
+        .or_else(|| book.account(parent).and_then(|parent| parent.commodity))
+        .or_else(|| conversion::main_currency(book))
+        .ok_or_else(|| ChartError::MissingCommodity(path.to_owned()))?;
+
+    let account = Account {
+        guid: Guid::new(),
+        name,
+        kind,
+        commodity: Some(commodity),
+        commodity_scu: book
+            .commodity(commodity)
+            .map_or(1, |commodity| commodity.fraction),
+        non_std_scu: false,
+        parent: Some(parent),
+        code: None,
+        description: None,
+        hidden: false,
+        placeholder: false,
+        slots: Frame::default(),
+    };
+
+    Ok(book.accounts.entry(account.guid).or_insert(account))
+}
+
+/// Moves an account with its sub-accounts.
+///
+/// As with `mv`, when the destination is an existing account, the account is
+/// moved into it. Otherwise, the destination is the new full path of the
+/// account.
+pub fn move_account<'a>(
+    book: &'a mut Book,
+    source: &str,
+    destination: &str,
+    force: bool,
+) -> Result<&'a Account, ChartError> {
+    let account = find(book, source)?;
+
+    let (parent, name) = match book.find_account(destination) {
+        Some(parent) => (parent.guid, account.name.clone()),
+        None => split_path(book, destination)?,
+    };
+
+    if book.is_under(parent, account.guid) {
+        return Err(ChartError::UnderItself(book.account_path(account.guid)));
+    }
+
+    if account.parent != Some(parent) {
+        check_parent(book, account.kind, parent, force)?;
+    }
+
+    reparent(book, account.guid, parent, name)
+}
+
+/// Renames an account, keeping it under the same parent.
+pub fn rename<'a>(
+    book: &'a mut Book,
+    path: &str,
+    name: &str,
+) -> Result<&'a Account, ChartError> {
+    let account = find(book, path)?;
+    check_name(name)?;
+
+    let parent = account.parent.unwrap_or(book.root_account);
+    reparent(book, account.guid, parent, name.to_owned())
+}
+
+/// Merges an account into another one.
+///
+/// All the splits of the source account are moved to the target one, and the
+/// source account is removed from the book. The accounts must have the same
+/// commodity, and the source account no sub-account.
+pub fn merge(
+    book: &mut Book,
+    source: &str,
+    target: &str,
+    force: bool,
+) -> Result<Merge, ChartError> {
+    let source = find(book, source)?;
+    let target = find(book, target)?;
+    let source_path = book.account_path(source.guid);
+    let target_path = book.account_path(target.guid);
+
+    if source.guid == target.guid {
+        return Err(ChartError::SameAccount(source_path));
+    }
+
+    if book.children(source.guid).next().is_some() {
+        return Err(ChartError::HasChildren(source_path));
+    }
+
+    if target.placeholder {
+        return Err(ChartError::PlaceholderAccount(target_path));
+    }
+
+    if source.commodity != target.commodity {
+        return Err(ChartError::DifferentCommodities {
+            merged: source_path,
+            target: target_path,
+        });
+    }
+
+    if !force && !are_compatible(source.kind, target.kind) {
+        return Err(ChartError::IncompatibleTypes {
+            merged: source_path,
+            merged_kind: source.kind,
+            target: target_path,
+            target_kind: target.kind,
+        });
+    }
+
+    let (source, target) = (source.guid, target.guid);
+    let mut splits = vec![];
+
+    for transaction in book.transactions.values_mut() {
+        for split in &mut transaction.splits {
+            let is_template_split = matches!(
+                split.slots.get(ACCOUNT_SLOT),
+                Some(SlotValue::Guid(account)) if *account == source
+            );
+
+            if split.account == source {
+                split.account = target;
+            } else if is_template_split {
+                split.slots.insert(ACCOUNT_SLOT, SlotValue::Guid(target));
+            } else {
+                continue;
+            }
+
+            splits.push((transaction.guid, split.clone()));
+        }
+    }
+
+    for lot in book.lots.values_mut() {
+        if lot.account == Some(source) {
+            lot.account = Some(target);
+        }
+    }
+
+    let source = book
+        .accounts
+        .remove(&source)
+        .ok_or(ChartError::UnknownAccount(source_path))?;
+
+    Ok(Merge {
+        source,
+        target,
+        splits,
+    })
+}
+
+/// Finds an account for changing it, given its full path.
+pub fn find_mut<'a>(
+    book: &'a mut Book,
+    path: &str,
+) -> Result<&'a mut Account, ChartError> {
+    let guid = find(book, path)?.guid;
+
+    book.accounts
+        .get_mut(&guid)
+        .ok_or_else(|| ChartError::UnknownAccount(path.to_owned()))
+}
+
+/// Builds an account template from the chart of accounts of a book.
+///
+/// Only the color, notes and tax-related slots are kept, and the accounts of
+/// scheduled transactions are left out.
+pub fn template(
+    book: &Book,
+    title: &str,
+    description: &str,
+) -> AccountTemplate {
+    let accounts: BTreeMap<_, _> = book
+        .accounts
+        .values()
+        .filter(|account| !book.is_template_account(account.guid))
+        .map(|account| {
+            let slots = account
+                .slots
+                .iter()
+                .filter(|(name, _)| TEMPLATE_SLOTS.contains(&name.as_str()))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect();
+
+            (
+                account.guid,
+                Account {
+                    slots,
+                    ..account.clone()
+                },
+            )
+        })
+        .collect();
+
+    let commodities = accounts
+        .values()
+        .filter_map(|account| account.commodity)
+        .filter_map(|commodity| book.commodity(commodity))
+        .map(|commodity| (commodity.guid, commodity.clone()))
+        .collect();
+
+    AccountTemplate {
+        title: title.to_owned(),
+        short_description: description.to_owned(),
+        long_description: description.to_owned(),
+        book: Book {
+            guid: Guid::new(),
+            root_account: book.root_account,
+            root_template: book.root_template,
+            commodities,
+            accounts,
+            transactions: BTreeMap::new(),
+            prices: BTreeMap::new(),
+            lots: BTreeMap::new(),
+            budgets: BTreeMap::new(),
+            schedules: BTreeMap::new(),
+            business: Business::default(),
+            slots: Frame::default(),
+        },
+    }
+}
+
+/// Adds the accounts of a template missing from a book.
+///
+/// Accounts are matched by full path. The new accounts in a currency use
+/// `currency` when given, else the currency of the template when the book has
+/// it, else the main currency of the book. The ones in another commodity need
+/// it to be in the book.
+pub fn import_template(
+    book: &mut Book,
+    template: &Book,
+    currency: Option<Guid>,
+    force: bool,
+) -> Result<TemplateImport, ChartError> {
+    let mut import = TemplateImport::default();
+    let mut pending = sorted_children(template, template.root_account);
+
+    while let Some(account) = pending.pop() {
+        if account.kind == AccountType::Root {
+            continue;
+        }
+
+        let path = template.account_path(account.guid);
+
+        if book.find_account(&path).is_some() {
+            import.existing += 1;
+        } else {
+            let commodity = account
+                .commodity
+                .and_then(|commodity| template.commodity(commodity))
+                .map(|commodity| template_commodity(book, commodity, currency))
+                .transpose()?;
+
+            let created = add(book, &path, account.kind, commodity, force)?;
+            created.code.clone_from(&account.code);
+            created.description.clone_from(&account.description);
+            created.hidden = account.hidden;
+            created.placeholder = account.placeholder;
+            created.slots = account.slots.clone();
+            import.accounts.push(created.clone());
+        }
+
+        pending.extend(sorted_children(template, account.guid));
+    }
+
+    Ok(import)
+}
+
+/// Exports the chart of accounts to CSV, like the account export of GnuCash.
+pub fn export_csv(
+    book: &Book,
+    output: impl io::Write,
+) -> Result<(), csv::Error> {
+    let mut writer = csv::WriterBuilder::new()
+        .quote_style(csv::QuoteStyle::Always)
+        .from_writer(output);
+
+    writer.write_record(CSV_COLUMNS)?;
+
+    let mut accounts: Vec<_> = book
+        .accounts
+        .values()
+        .filter(|account| account.kind != AccountType::Root)
+        .filter(|account| !book.is_template_account(account.guid))
+        .map(|account| (book.account_path(account.guid), account))
+        .collect();
+    accounts.sort_by(|(left, _), (right, _)| left.cmp(right));
+
+    for (path, account) in accounts {
+        let commodity = account.commodity.and_then(|guid| book.commodity(guid));
+        let flag = |value| if value { "T" } else { "F" };
+
+        writer.write_record([
+            account.kind.as_str(),
+            &path,
+            &account.name,
+            account.code.as_deref().unwrap_or_default(),
+            account.description.as_deref().unwrap_or_default(),
+            account.slots.string("color").unwrap_or_default(),
+            account.slots.string("notes").unwrap_or_default(),
+            commodity.map_or("", |commodity| &commodity.mnemonic),
+            commodity.map_or("", |commodity| &commodity.namespace),
+            flag(account.hidden),
+            flag(matches!(
+                account.slots.get("tax-related"),
+                Some(SlotValue::Int64(1))
+            )),
+            flag(account.placeholder),
+        ])?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Returns whether an account of type `kind` can have a parent of type
+/// `parent`, with the rules of GnuCash.
+const fn is_valid_parent(kind: AccountType, parent: AccountType) -> bool {
+    match kind {
+        AccountType::Bank
+        | AccountType::Cash
+        | AccountType::Asset
+        | AccountType::Credit
+        | AccountType::Liability
+        | AccountType::Stock
+        | AccountType::Mutual
+        | AccountType::Currency
+        | AccountType::Receivable
+        | AccountType::Payable
+        | AccountType::Checking
+        | AccountType::Savings
+        | AccountType::MoneyMarket
+        | AccountType::CreditLine => !matches!(
+            parent,
+            AccountType::Income
+                | AccountType::Expense
+                | AccountType::Equity
+                | AccountType::Trading
+        ),
+        AccountType::Income | AccountType::Expense => matches!(
+            parent,
+            AccountType::Root | AccountType::Income | AccountType::Expense
+        ),
+        AccountType::Equity => {
+            matches!(parent, AccountType::Root | AccountType::Equity)
+        }
+        AccountType::Trading => {
+            matches!(parent, AccountType::Root | AccountType::Trading)
+        }
+        AccountType::Root => false,
+    }
+}
+
+/// Returns whether the splits of an account of type `source` can be moved to
+/// an account of type `target`, with the rules of GnuCash.
+const fn are_compatible(source: AccountType, target: AccountType) -> bool {
+    match source {
+        AccountType::Bank
+        | AccountType::Cash
+        | AccountType::Asset
+        | AccountType::Credit
+        | AccountType::Liability
+        | AccountType::Checking
+        | AccountType::Savings
+        | AccountType::MoneyMarket
+        | AccountType::CreditLine => matches!(
+            target,
+            AccountType::Bank
+                | AccountType::Cash
+                | AccountType::Asset
+                | AccountType::Credit
+                | AccountType::Liability
+                | AccountType::Checking
+                | AccountType::Savings
+                | AccountType::MoneyMarket
+                | AccountType::CreditLine
+        ),
+        AccountType::Stock | AccountType::Mutual | AccountType::Currency => {
+            matches!(
+                target,
+                AccountType::Stock
+                    | AccountType::Mutual
+                    | AccountType::Currency
+            )
+        }
+        AccountType::Income | AccountType::Expense => {
+            matches!(target, AccountType::Income | AccountType::Expense)
+        }
+        AccountType::Equity => matches!(target, AccountType::Equity),
+        AccountType::Receivable => matches!(target, AccountType::Receivable),
+        AccountType::Payable => matches!(target, AccountType::Payable),
+        AccountType::Trading => matches!(target, AccountType::Trading),
+        AccountType::Root => false,
+    }
+}
+
+/// Returns the children of an account, sorted by reverse name to be popped
+/// from a stack in order.
+fn sorted_children(book: &Book, parent: Guid) -> Vec<&Account> {
+    let mut children: Vec<_> = book.children(parent).collect();
+    children.sort_by(|left, right| right.name.cmp(&left.name));
+    children
+}
+
+/// Returns the commodity of the book to use for a commodity of a template.
+fn template_commodity(
+    book: &Book,
+    commodity: &Commodity,
+    currency: Option<Guid>,
+) -> Result<Guid, ChartError> {
+    let guid = if commodity.is_currency() {
+        currency
+            .or_else(|| {
+                book.currency(&commodity.mnemonic)
+                    .map(|currency| currency.guid)
+            })
+            .or_else(|| conversion::main_currency(book))
+    } else {
+        book.commodities
+            .values()
+            .find(|candidate| {
+                candidate.namespace == commodity.namespace
+                    && candidate.mnemonic == commodity.mnemonic
+            })
+            .map(|commodity| commodity.guid)
+    };
+
+    guid.ok_or_else(|| ChartError::UnknownCommodity(commodity.mnemonic.clone()))
+}
+
+/// Finds an account given its full path.
+fn find<'a>(book: &'a Book, path: &str) -> Result<&'a Account, ChartError> {
+    book.find_account(path)
+        .ok_or_else(|| ChartError::UnknownAccount(path.to_owned()))
+}
+
+/// Splits a full path into the GUID of the existing parent and the name of
+/// the account.
+fn split_path(book: &Book, path: &str) -> Result<(Guid, String), ChartError> {
+    let (parent, name) = match path.rsplit_once(ACCOUNT_SEPARATOR) {
+        Some((parent, name)) => (Some(parent), name),
+        None => (None, path),
+    };
+
+    check_name(name)?;
+
+    let parent = match parent {
+        Some(parent) => find(book, parent)?.guid,
+        None => book.root_account,
+    };
+
+    Ok((parent, name.to_owned()))
+}
+
+/// Checks an account name is valid.
+fn check_name(name: &str) -> Result<(), ChartError> {
+    if name.is_empty() || name.contains(ACCOUNT_SEPARATOR) {
+        Err(ChartError::InvalidName(name.to_owned()))
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks an account of type `kind` can be under `parent`, unless forced.
+fn check_parent(
+    book: &Book,
+    kind: AccountType,
+    parent: Guid,
+    force: bool,
+) -> Result<(), ChartError> {
+    let Some(parent) = book.account(parent) else {
+        return Ok(());
+    };
+
+    if force || is_valid_parent(kind, parent.kind) {
+        Ok(())
+    } else {
+        Err(ChartError::IncompatibleParent {
+            kind,
+            parent: book.account_path(parent.guid),
+            parent_kind: parent.kind,
+        })
+    }
+}
+
+/// Moves an account under a parent with a new name.
+///
+/// There must be no other account with the same name under the parent.
+fn reparent(
+    book: &mut Book,
+    guid: Guid,
+    parent: Guid,
+    name: String,
+) -> Result<&Account, ChartError> {
+    let sibling = book
+        .children(parent)
+        .find(|sibling| sibling.name == name && sibling.guid != guid);
+
+    if let Some(sibling) = sibling {
+        return Err(ChartError::AlreadyExists(book.account_path(sibling.guid)));
+    }
+
+    let account = book
+        .accounts
+        .get_mut(&guid)
+        .ok_or_else(|| ChartError::UnknownAccount(name.clone()))?;
+
+    account.parent = Some(parent);
+    account.name = name;
+    Ok(account)
+}
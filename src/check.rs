@@ -0,0 +1,83 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Integrity checks on a book.
+
+use crate::{
+    book::{Book, Numeric, Transaction},
+    trading::{self, Imbalance},
+};
+
+/// An integrity issue in a book.
+#[derive(Debug)]
+pub enum Issue<'a> {
+    /// The values of the splits of a transaction do not sum to zero.
+    Unbalanced {
+        /// The transaction.
+        transaction: &'a Transaction,
+        /// The sum of the values, in the currency of the transaction.
+        imbalance: Numeric,
+    },
+    /// A multi-commodity transaction lacks trading splits in a commodity.
+    MissingTradingSplits {
+        /// The transaction.
+        transaction: &'a Transaction,
+        /// The imbalance in the commodity.
+        imbalance: Imbalance<'a>,
+    },
+}
+
+impl Issue<'_> {
+    /// Returns the transaction having the issue.
+    pub const fn transaction(&self) -> &Transaction {
+        match self {
+            Self::Unbalanced { transaction, .. }
+            | Self::MissingTradingSplits { transaction, .. } => transaction,
+        }
+    }
+}
+
+/// Checks the integrity of a book.
+///
+/// Trading splits are only checked when the book uses trading accounts.
+pub fn check(book: &Book) -> Vec<Issue<'_>> {
+    let uses_trading_accounts = trading::is_enabled(book);
+    let mut issues = vec![];
+
+    for transaction in book.regular_transactions() {
+        let imbalance: Numeric =
+            transaction.splits.iter().map(|split| split.value).sum();
+
+        if !imbalance.is_zero() {
+            issues.push(Issue::Unbalanced {
+                transaction,
+                imbalance,
+            });
+        }
+
+        if uses_trading_accounts {
+            issues.extend(
+                trading::imbalances(book, transaction).into_iter().map(
+                    |imbalance| Issue::MissingTradingSplits {
+                        transaction,
+                        imbalance,
+                    },
+                ),
+            );
+        }
+    }
+
+    issues
+}
@@ -0,0 +1,652 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Closing entries, like the Close Book assistant of GnuCash, and opening
+//! balances of new books.
+//!
+//! At the end of a period, the balances of the income and expense accounts are
+//! transferred to an equity account, with one transaction per currency. A new
+//! book can then start from the balances of the other accounts, and old
+//! transactions can be moved to an archive book.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use thiserror::Error;
+
+use crate::{
+    book::{
+        post_date, Account, AccountType, Book, Business, Frame, Guid, Numeric,
+        ReconcileState, SlotValue, Split, Transaction,
+    },
+    trading,
+};
+
+/// The slot flagging closing transactions.
+const BOOK_CLOSING_SLOT: &str = "book_closing";
+
+/// The book option setting the age after which transactions are read-only.
+const READ_ONLY_DAYS_SLOT: &str =
+    "options/Accounts/Day Threshold for Read-Only Transactions";
+
+/// The closing entries of a book.
+#[derive(Debug, Default)]
+pub struct ClosingEntries {
+    /// The equity accounts created for currencies other than the one of the
+    /// closing account.
+    pub accounts: Vec<Account>,
+    /// The closing transactions, one per currency.
+    pub transactions: Vec<Transaction>,
+}
+
+/// A new book starting with the balances of another one.
+#[derive(Debug)]
+pub struct OpeningBook {
+    /// The new book.
+    pub book: Book,
+    /// The equity accounts created for currencies other than the one of the
+    /// opening balances account.
+    pub accounts: Vec<Account>,
+    /// The number of opening balance transactions.
+    pub openings: usize,
+    /// The number of unreconciled splits carried over.
+    pub unreconciled: usize,
+}
+
+/// The balance of an account carried over to a new book.
+#[derive(Debug, Default)]
+struct Balance {
+    /// The quantity, in the commodity of the account.
+    quantity: Numeric,
+    /// The value, in the currency of the opening transaction.
+    value: Numeric,
+    /// The date of the last reconciliation, if any split has been reconciled.
+    reconcile_date: Option<DateTime<Utc>>,
+}
+
+/// Old transactions moved to an archive book.
+#[derive(Debug)]
+pub struct Archive {
+    /// The archive book, with the archived transactions.
+    pub book: Book,
+    /// The summary transactions replacing the archived ones.
+    pub summaries: Vec<Transaction>,
+}
+
+/// An unreconciled split carried over to a new book.
+#[derive(Debug)]
+struct CarriedSplit<'a> {
+    /// The transaction of the split.
+    transaction: &'a Transaction,
+    /// The split.
+    split: &'a Split,
+    /// The currency in which the split is carried over.
+    currency: Guid,
+    /// The value of the split in that currency.
+    value: Numeric,
+}
+
+/// Errors that can occur when closing a book.
+#[derive(Debug, Error)]
+pub enum ClosingError {
+    /// An account does not exist.
+    #[error("unknown account: {0}")]
+    UnknownAccount(String),
+    /// The closing account is not an equity account.
+    #[error("the account {0} is not an equity account")]
+    NotEquity(String),
+    /// An account is a placeholder.
+    #[error("the account {0} is a placeholder")]
+    PlaceholderAccount(String),
+}
+
+impl CarriedSplit<'_> {
+    /// Returns a transaction carrying the split over to a new book, balanced by
+    /// an equity account.
+    fn to_transaction(&self, equity: Guid) -> Transaction {
+        let transaction = self.transaction;
+
+        let mut slots = Frame::default();
+        slots.insert("date-posted", SlotValue::GDate(transaction.date()));
+
+        if let Some(notes) = transaction.notes() {
+            slots.insert("notes", SlotValue::String(notes.to_owned()));
+        }
+
+        Transaction {
+            guid: Guid::new(),
+            currency: self.currency,
+            num: transaction.num.clone(),
+            post_date: transaction.post_date,
+            enter_date: Utc::now(),
+            description: transaction.description.clone(),
+            splits: vec![
+                Split {
+                    guid: Guid::new(),
+                    value: self.value,
+                    lot: None,
+                    ..self.split.clone()
+                },
+                closing_split(equity, -self.value),
+            ],
+            slots,
+        }
+    }
+}
+
+/// Builds the closing entries of the income and expense accounts on a date.
+///
+/// The balances include all the splits posted up to the end of the day. When a
+/// currency is not the one of the equity account, its balances go to a child
+/// of the equity account named after the currency, which is created if needed.
+/// The accounts created are added to the book.
+pub fn closing_entries(
+    book: &mut Book,
+    date: NaiveDate,
+    equity: &str,
+    description: &str,
+) -> Result<ClosingEntries, ClosingError> {
+    let equity = find_equity(book, equity)?;
+    let mut entries = ClosingEntries::default();
+
+    for (currency, balances) in balances_by_currency(book, date) {
+        let account =
+            equity_account(book, equity, currency, &mut entries.accounts)?;
+        let total: Numeric = balances.iter().map(|(_, balance)| *balance).sum();
+
+        let mut splits: Vec<_> = balances
+            .into_iter()
+            .map(|(account, balance)| closing_split(account, -balance))
+            .collect();
+        splits.push(closing_split(account, total));
+
+        let mut slots = Frame::default();
+        slots.insert("date-posted", SlotValue::GDate(date));
+        slots.insert(BOOK_CLOSING_SLOT, SlotValue::Int64(1));
+
+        entries.transactions.push(Transaction {
+            guid: Guid::new(),
+            currency,
+            num: String::new(),
+            post_date: post_date(date),
+            enter_date: Utc::now(),
+            description: description.to_owned(),
+            splits,
+            slots,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Builds a new book starting on a date with the balances of another one.
+///
+/// The new book has the same accounts, commodities, prices, scheduled
+/// transactions and options. The reconciled balance of each balance sheet
+/// account before the date is carried over by one opening transaction per
+/// currency, and its unreconciled splits are carried over one by one so that
+/// they can still be reconciled. Both are balanced by the equity account, or
+/// by a child of it named after the currency, created if needed.
+///
+/// Balances in commodities which are not currencies are valued at their cost,
+/// in the currency of their transactions. When the book uses trading accounts,
+/// the trading splits of the new transactions are recomputed.
+pub fn opening_book(
+    book: &Book,
+    date: NaiveDate,
+    equity: &str,
+    description: &str,
+) -> Result<OpeningBook, ClosingError> {
+    let equity = find_equity(book, equity)?;
+    let uses_trading_accounts = trading::is_enabled(book);
+
+    let templates = book
+        .transactions
+        .values()
+        .filter(|transaction| book.is_template_transaction(transaction))
+        .map(|transaction| (transaction.guid, transaction.clone()))
+        .collect();
+
+    let mut new = Book {
+        transactions: templates,
+        schedules: book.schedules.clone(),
+        ..empty_copy(book)
+    };
+
+    let (balances, carried) =
+        balances_at_opening(book, date, uses_trading_accounts);
+
+    let mut accounts = vec![];
+    let mut transactions = vec![];
+
+    for (currency, balances) in balances {
+        let mut splits = opening_splits(book, balances);
+
+        if splits.is_empty() {
+            continue;
+        }
+
+        let account =
+            equity_account(&mut new, equity, currency, &mut accounts)?;
+        let total: Numeric = splits.iter().map(|split| split.value).sum();
+
+        // The equity account may have a balance of its own.
+        if let Some(split) =
+            splits.iter_mut().find(|split| split.account == account)
+        {
+            split.quantity -= total;
+            split.value -= total;
+        } else {
+            splits.push(closing_split(account, -total));
+        }
+
+        let mut slots = Frame::default();
+        slots.insert("date-posted", SlotValue::GDate(date));
+
+        transactions.push(Transaction {
+            guid: Guid::new(),
+            currency,
+            num: String::new(),
+            post_date: post_date(date),
+            enter_date: Utc::now(),
+            description: description.to_owned(),
+            splits,
+            slots,
+        });
+    }
+
+    let openings = transactions.len();
+    let unreconciled = carried.len();
+
+    for split in carried {
+        let account =
+            equity_account(&mut new, equity, split.currency, &mut accounts)?;
+        transactions.push(split.to_transaction(account));
+    }
+
+    new.transactions.extend(
+        transactions
+            .into_iter()
+            .map(|transaction| (transaction.guid, transaction)),
+    );
+
+    if uses_trading_accounts {
+        let added = trading::add_trading_splits(&mut new);
+        accounts.extend(added.accounts);
+    }
+
+    Ok(OpeningBook {
+        book: new,
+        accounts,
+        openings,
+        unreconciled,
+    })
+}
+
+/// Moves the transactions posted before a date to an archive book.
+///
+/// The archive book has the same accounts, commodities, prices and options. In
+/// the book, the archived transactions are replaced by summary transactions
+/// posted the day before, one per transaction currency with one split per
+/// account, so that the balances are kept.
+///
+/// Transactions with splits in lots, like the ones of invoices and payments, or
+/// with unreconciled splits in balance sheet accounts are not archived, so that
+/// lots stay complete and reconciliation can continue.
+pub fn archive(book: &Book, before: NaiveDate, description: &str) -> Archive {
+    let mut archive = empty_copy(book);
+    let mut balances: BTreeMap<Guid, BTreeMap<Guid, Balance>> = BTreeMap::new();
+
+    for transaction in book.regular_transactions() {
+        if transaction.date() >= before {
+            break;
+        }
+
+        if !is_archivable(book, transaction) {
+            continue;
+        }
+
+        for split in &transaction.splits {
+            let balance = balances
+                .entry(transaction.currency)
+                .or_default()
+                .entry(split.account)
+                .or_default();
+            balance.quantity += split.quantity;
+            balance.value += split.value;
+            balance.reconcile_date =
+                balance.reconcile_date.max(split.reconcile_date);
+        }
+
+        archive
+            .transactions
+            .insert(transaction.guid, transaction.clone());
+    }
+
+    let date = before.pred_opt().unwrap_or(before);
+
+    let summaries = balances
+        .into_iter()
+        .map(|(currency, balances)| (currency, opening_splits(book, balances)))
+        .filter(|(_, splits)| !splits.is_empty())
+        .map(|(currency, splits)| {
+            let mut slots = Frame::default();
+            slots.insert("date-posted", SlotValue::GDate(date));
+
+            Transaction {
+                guid: Guid::new(),
+                currency,
+                num: String::new(),
+                post_date: post_date(date),
+                enter_date: Utc::now(),
+                description: description.to_owned(),
+                splits,
+                slots,
+            }
+        })
+        .collect();
+
+    Archive {
+        book: archive,
+        summaries,
+    }
+}
+
+/// Sets the number of days after which transactions are read-only.
+pub fn set_read_only_days(book: &mut Book, days: u32) {
+    book.slots
+        .insert(READ_ONLY_DAYS_SLOT, SlotValue::Double(f64::from(days)));
+}
+
+/// Returns the non-zero balances of the income and expense accounts on a date,
+/// by currency, sorted by account path.
+fn balances_by_currency(
+    book: &Book,
+    date: NaiveDate,
+) -> BTreeMap<Guid, Vec<(Guid, Numeric)>> {
+    let mut balances: BTreeMap<Guid, Numeric> = BTreeMap::new();
+
+    for transaction in book.regular_transactions() {
+        if transaction.date() > date {
+            break;
+        }
+
+        for split in &transaction.splits {
+            let is_closed =
+                book.account(split.account).is_some_and(|account| {
+                    matches!(
+                        account.kind,
+                        AccountType::Income | AccountType::Expense
+                    )
+                });
+
+            if is_closed {
+                *balances.entry(split.account).or_default() += split.quantity;
+            }
+        }
+    }
+
+    let mut by_path: Vec<_> = balances
+        .into_iter()
+        .filter(|(_, balance)| !balance.is_zero())
+        .map(|(account, balance)| {
+            (book.account_path(account), account, balance)
+        })
+        .collect();
+    by_path.sort();
+
+    let mut by_currency: BTreeMap<Guid, Vec<(Guid, Numeric)>> = BTreeMap::new();
+
+    for (_, account, balance) in by_path {
+        if let Some(currency) = book.account_commodity(account) {
+            by_currency
+                .entry(currency.guid)
+                .or_default()
+                .push((account, balance));
+        }
+    }
+
+    by_currency
+}
+
+/// Finds the equity account receiving the balances.
+fn find_equity(book: &Book, path: &str) -> Result<Guid, ClosingError> {
+    let equity = book
+        .find_account(path)
+        .ok_or_else(|| ClosingError::UnknownAccount(path.to_owned()))?;
+
+    if equity.kind == AccountType::Equity {
+        Ok(equity.guid)
+    } else {
+        Err(ClosingError::NotEquity(book.account_path(equity.guid)))
+    }
+}
+
+/// Returns the equity account receiving the balances in a currency.
+///
+/// The accounts created are added to the book and to `created`.
+fn equity_account(
+    book: &mut Book,
+    equity: Guid,
+    currency: Guid,
+    created: &mut Vec<Account>,
+) -> Result<Guid, ClosingError> {
+    let account = book
+        .account(equity)
+        .filter(|account| account.commodity == Some(currency))
+        .or_else(|| {
+            let mnemonic = &book.commodity(currency)?.mnemonic;
+            book.children(equity)
+                .find(|account| &account.name == mnemonic)
+        });
+
+    if let Some(account) = account {
+        return if account.placeholder {
+            Err(ClosingError::PlaceholderAccount(
+                book.account_path(account.guid),
+            ))
+        } else {
+            Ok(account.guid)
+        };
+    }
+
+    let (mnemonic, fraction) = book
+        .commodity(currency)
+        .map(|currency| (currency.mnemonic.clone(), currency.fraction))
+        .unwrap_or_default();
+
+    let account = Account {
+        guid: Guid::new(),
+        name: mnemonic,
+        kind: AccountType::Equity,
+        commodity: Some(currency),
+        commodity_scu: fraction,
+        non_std_scu: false,
+        parent: Some(equity),
+        code: None,
+        description: None,
+        hidden: false,
+        placeholder: false,
+        slots: Frame::default(),
+    };
+
+    let guid = account.guid;
+    book.accounts.insert(guid, account.clone());
+    created.push(account);
+    Ok(guid)
+}
+
+/// Returns the balances carried over to a new book starting on a date, by
+/// currency and account, and the unreconciled splits carried over one by one
+/// with their currency and value.
+fn balances_at_opening(
+    book: &Book,
+    date: NaiveDate,
+    uses_trading_accounts: bool,
+) -> (
+    BTreeMap<Guid, BTreeMap<Guid, Balance>>,
+    Vec<CarriedSplit<'_>>,
+) {
+    let mut balances: BTreeMap<Guid, BTreeMap<Guid, Balance>> = BTreeMap::new();
+    let mut carried = vec![];
+
+    for transaction in book.regular_transactions() {
+        if transaction.date() >= date {
+            break;
+        }
+
+        for split in &transaction.splits {
+            let Some(account) = book.account(split.account) else {
+                continue;
+            };
+
+            let is_carried = match account.kind {
+                AccountType::Income | AccountType::Expense => continue,
+                AccountType::Trading if uses_trading_accounts => continue,
+                kind => kind.is_balance_sheet() && !is_reconciled(split),
+            };
+
+            let (currency, value) = opening_value(book, transaction, split);
+
+            if is_carried {
+                carried.push(CarriedSplit {
+                    transaction,
+                    split,
+                    currency,
+                    value,
+                });
+            } else {
+                let balance = balances
+                    .entry(currency)
+                    .or_default()
+                    .entry(split.account)
+                    .or_default();
+                balance.quantity += split.quantity;
+                balance.value += value;
+                balance.reconcile_date =
+                    balance.reconcile_date.max(split.reconcile_date);
+            }
+        }
+    }
+
+    (balances, carried)
+}
+
+/// Returns the splits of an opening or summary transaction, sorted by account
+/// path.
+///
+/// The splits of accounts with reconciled splits are reconciled, so that the
+/// reconciled balance of the accounts is kept.
+fn opening_splits(
+    book: &Book,
+    balances: BTreeMap<Guid, Balance>,
+) -> Vec<Split> {
+    let mut balances: Vec<_> = balances
+        .into_iter()
+        .filter(|(_, balance)| {
+            !balance.quantity.is_zero() || !balance.value.is_zero()
+        })
+        .map(|(account, balance)| {
+            (book.account_path(account), account, balance)
+        })
+        .collect();
+
+    balances.sort_by(|(path, ..), (other, ..)| path.cmp(other));
+
+    balances
+        .into_iter()
+        .map(|(_, account, balance)| Split {
+            reconcile_state: if balance.reconcile_date.is_some() {
+                ReconcileState::Reconciled
+            } else {
+                ReconcileState::NotReconciled
+            },
+            reconcile_date: balance.reconcile_date,
+            value: balance.value,
+            ..closing_split(account, balance.quantity)
+        })
+        .collect()
+}
+
+/// Returns a copy of a book with the same accounts, commodities, prices and
+/// options, but no transactions.
+fn empty_copy(book: &Book) -> Book {
+    Book {
+        guid: Guid::new(),
+        root_account: book.root_account,
+        root_template: book.root_template,
+        commodities: book.commodities.clone(),
+        accounts: book.accounts.clone(),
+        transactions: BTreeMap::new(),
+        prices: book.prices.clone(),
+        lots: BTreeMap::new(),
+        budgets: BTreeMap::new(),
+        schedules: BTreeMap::new(),
+        business: Business::default(),
+        slots: book.slots.clone(),
+    }
+}
+
+/// Returns whether a transaction can be archived.
+fn is_archivable(book: &Book, transaction: &Transaction) -> bool {
+    transaction.splits.iter().all(|split| {
+        split.lot.is_none()
+            && (is_reconciled(split)
+                || book
+                    .account(split.account)
+                    .map_or(true, |account| !account.kind.is_balance_sheet()))
+    })
+}
+
+/// Returns whether a split has been reconciled.
+const fn is_reconciled(split: &Split) -> bool {
+    matches!(
+        split.reconcile_state,
+        ReconcileState::Reconciled | ReconcileState::Frozen
+    )
+}
+
+/// Returns the currency in which a split is carried over, with its value.
+///
+/// Splits in a currency account are carried over in that currency, and the
+/// others in the currency of their transaction.
+fn opening_value(
+    book: &Book,
+    transaction: &Transaction,
+    split: &Split,
+) -> (Guid, Numeric) {
+    match book.account_commodity(split.account) {
+        Some(commodity) if commodity.is_currency() => {
+            (commodity.guid, split.quantity)
+        }
+        _ => (transaction.currency, split.value),
+    }
+}
+
+/// Returns a split of a closing transaction.
+fn closing_split(account: Guid, amount: Numeric) -> Split {
+    Split {
+        guid: Guid::new(),
+        account,
+        memo: String::new(),
+        action: String::new(),
+        reconcile_state: ReconcileState::NotReconciled,
+        reconcile_date: None,
+        value: amount,
+        quantity: amount,
+        lot: None,
+        slots: Frame::default(),
+    }
+}
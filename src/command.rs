@@ -15,14 +15,64 @@
 
 //! The Command Line Interface for gnucash-toolbox.
 
+mod account;
+mod archive;
+mod assert;
+mod check;
+mod close_book;
+mod convert;
+mod directory;
+mod export;
 mod hello;
 mod helpers;
+mod invoice;
+mod migrate;
+mod price;
+mod reconcile;
+mod report;
+mod slots;
+mod split_book;
+mod sx;
+mod tx;
 
 use clap::{ArgAction, Parser, Subcommand};
 use eyre::{Report, Result};
 use tracing_subscriber::fmt::format::FmtSpan;
 
-use self::hello::Hello;
+use self::{
+    account::Account,
+    archive::{Archive, ArchiveExists},
+    assert::{Assert, AssertionsFailed},
+    check::{Check, IssuesFound},
+    close_book::CloseBook,
+    convert::Convert,
+    directory::{Customer, Employee, Vendor},
+    export::Export,
+    hello::Hello,
+    invoice::{Invoice, InvoiceError},
+    migrate::{Migrate, TradingAccountsDisabled},
+    price::Price,
+    reconcile::{Reconcile, ReconcileError},
+    slots::{Slots, UnknownGuid},
+    split_book::SplitBook,
+    sx::Sx,
+    tx::Tx,
+};
+use crate::{
+    assertion::AssertionError,
+    book::{DatabaseError, DraftError, XmlError},
+    chart::ChartError,
+    closing::ClosingError,
+    conversion::ConversionError,
+    directory::DirectoryError,
+    error,
+    formula::FormulaError,
+    hint,
+    invoice::{draft::InvoiceDraftError, payment::PaymentError},
+    journal::{JournalError, JournalErrorKind},
+    report::tax::TaxError,
+    since_last_run::{SinceLastRunError, SinceLastRunErrorKind},
+};
 
 /// The long version information.
 const LONG_VERSION: &str = concat!(
@@ -58,8 +108,46 @@ pub struct GnucashToolbox {
 /// The subcommands of `gnucash-toolbox`.
 #[derive(Debug, Subcommand)]
 pub enum GnucashToolboxCommand {
+    /// Edit the chart of accounts.
+    Account(Account),
+    /// Move old transactions to an archive book.
+    Archive(Archive),
+    /// Check the balances of accounts against bank statements.
+    Assert(Assert),
+    /// Check the integrity of a book.
+    Check(Check),
+    /// Transfer the income and expenses to equity at the end of a period.
+    CloseBook(CloseBook),
+    /// Convert a book between the SQLite and XML formats.
+    Convert(Convert),
+    /// Manage the customers.
+    Customer(Customer),
+    /// Manage the employees.
+    Employee(Employee),
+    /// Export a book to another format.
+    Export(Export),
     /// Say hello.
     Hello(Hello),
+    /// Manage invoices, bills and expense vouchers.
+    Invoice(Invoice),
+    /// Migrate a book after changing its options.
+    Migrate(Migrate),
+    /// Work with the price database.
+    Price(Price),
+    /// Reconcile an account against a bank statement.
+    Reconcile(Reconcile),
+    /// Show reports on a book.
+    Report(report::Report),
+    /// Dump the slots of an object, for debugging.
+    Slots(Slots),
+    /// Start a new book with the balances of an existing one.
+    SplitBook(SplitBook),
+    /// Manage scheduled transactions.
+    Sx(Sx),
+    /// Manage transactions.
+    Tx(Tx),
+    /// Manage the vendors.
+    Vendor(Vendor),
 }
 
 /// A command.
@@ -85,7 +173,26 @@ impl GnucashToolboxCommand {
     /// Runs the given command.
     pub fn run(&self) -> Result<()> {
         match self {
+            Self::Account(account) => account.run(),
+            Self::Archive(archive) => archive.run(),
+            Self::Assert(assert) => assert.run(),
+            Self::Check(check) => check.run(),
+            Self::CloseBook(close_book) => close_book.run(),
+            Self::Convert(convert) => convert.run(),
+            Self::Customer(customer) => customer.run(),
+            Self::Employee(employee) => employee.run(),
+            Self::Export(export) => export.run(),
             Self::Hello(hello) => hello.run(),
+            Self::Invoice(invoice) => invoice.run(),
+            Self::Migrate(migrate) => migrate.run(),
+            Self::Price(price) => price.run(),
+            Self::Reconcile(reconcile) => reconcile.run(),
+            Self::Report(report) => report.run(),
+            Self::Slots(slots) => slots.run(),
+            Self::SplitBook(split_book) => split_book.run(),
+            Self::Sx(sx) => sx.run(),
+            Self::Tx(tx) => tx.run(),
+            Self::Vendor(vendor) => vendor.run(),
         }
     }
 }
@@ -116,43 +223,422 @@ fn span_events(verbosity: u8) -> FmtSpan {
     }
 }
 
-// /// How to handle the error.
-// enum ErrorHandling {
-//     /// Return the report.
-//     Return(Report),
-//     /// Exit the program with the given status code.
-//     Exit(i32),
-// }
+/// How to handle the error.
+enum ErrorHandling {
+    /// Return the report.
+    Return(Report),
+    /// Exit the program with the given status code.
+    Exit(i32),
+}
 
 /// Handles typical usage errors to enhance their output.
 fn handle_errors(error: Report) -> Result<()> {
-    // let handling = if let Some(error) = error.downcast_ref::<ErrorType>() {
-    //     handle_error_type(error)
-    // } else {
-    //     ErrorHandling::Return(error)
-    // };
-
-    // match handling {
-    //     ErrorHandling::Return(error) => Err(error),
-    //     ErrorHandling::Exit(code) => {
-    //         #[expect(
-    //             clippy::exit,
-    //             reason = "this function is purposefully written to handle \
-    //                 errors, write a useful message and exit with an error code"
-    //         )]
-    //         std::process::exit(code);
-    //     }
-    // }
-    Err(error)
-}
-
-// fn handle_error_type(error: &ErrorType) -> ErrorHandling {
-//     match error {
-//         ErrorType::ErrorKind => {
-//             error!("{error}");
-//             hint!("Some help message.");
-//         }
-//     }
-
-//     ErrorHandling::Exit(exitcode::USAGE)
-// }
+    let handling = if let Some(error) = error.downcast_ref::<DatabaseError>() {
+        handle_database_error(error)
+    } else if let Some(error) = error.downcast_ref::<XmlError>() {
+        handle_xml_error(error)
+    } else if let Some(error) = error.downcast_ref::<AssertionError>() {
+        handle_assertion_error(error)
+    } else if let Some(error) = error.downcast_ref::<AssertionsFailed>() {
+        error!("{error}.");
+        ErrorHandling::Exit(exitcode::DATAERR)
+    } else if let Some(error) = error.downcast_ref::<IssuesFound>() {
+        error!("{error}.");
+        ErrorHandling::Exit(exitcode::DATAERR)
+    } else if let Some(error) = error.downcast_ref::<TradingAccountsDisabled>()
+    {
+        error!("{error}.");
+        hint!("Enable them in GnuCash, in File > Properties > Accounts.");
+        ErrorHandling::Exit(exitcode::DATAERR)
+    } else if let Some(error) = error.downcast_ref::<ArchiveExists>() {
+        error!("{error}.");
+        hint!("Archive to a new book: the transactions of an existing archive are not in the book anymore.");
+        ErrorHandling::Exit(exitcode::CANTCREAT)
+    } else if let Some(error) = error.downcast_ref::<UnknownGuid>() {
+        error!("{error}.");
+        ErrorHandling::Exit(exitcode::DATAERR)
+    } else if let Some(error) = error.downcast_ref::<InvoiceError>() {
+        handle_invoice_error(error)
+    } else if let Some(error) = error.downcast_ref::<InvoiceDraftError>() {
+        handle_invoice_draft_error(error)
+    } else if let Some(error) = error.downcast_ref::<DirectoryError>() {
+        handle_directory_error(error)
+    } else if let Some(error) = error.downcast_ref::<PaymentError>() {
+        handle_payment_error(error)
+    } else if let Some(error) = error.downcast_ref::<ReconcileError>() {
+        handle_reconcile_error(error)
+    } else if let Some(error) = error.downcast_ref::<DraftError>() {
+        handle_draft_error(error)
+    } else if let Some(error) = error.downcast_ref::<JournalError>() {
+        handle_journal_error(error)
+    } else if let Some(error) = error.downcast_ref::<SinceLastRunError>() {
+        handle_since_last_run_error(error)
+    } else if let Some(error) = error.downcast_ref::<ConversionError>() {
+        handle_conversion_error(error)
+    } else if let Some(error) = error.downcast_ref::<ClosingError>() {
+        handle_closing_error(error)
+    } else if let Some(error) = error.downcast_ref::<ChartError>() {
+        handle_chart_error(error)
+    } else if let Some(error) = error.downcast_ref::<TaxError>() {
+        handle_tax_error(error)
+    } else {
+        ErrorHandling::Return(error)
+    };
+
+    match handling {
+        ErrorHandling::Return(error) => Err(error),
+        ErrorHandling::Exit(code) => {
+            #[expect(
+                clippy::exit,
+                reason = "this function is purposefully written to handle \
+                    errors, write a useful message and exit with an error code"
+            )]
+            std::process::exit(code);
+        }
+    }
+}
+
+/// Handles errors from the database.
+fn handle_database_error(error: &DatabaseError) -> ErrorHandling {
+    match error {
+        DatabaseError::Locked { .. } => {
+            error!("{error}.");
+            hint!("Please close GnuCash before modifying the book.");
+            ErrorHandling::Exit(exitcode::TEMPFAIL)
+        }
+        DatabaseError::NotABook(_) => {
+            error!("{error}.");
+            hint!("To use an XML book, convert it with `gnc convert --to sqlite`.");
+            ErrorHandling::Exit(exitcode::DATAERR)
+        }
+        DatabaseError::Open { .. } => {
+            error!("{error}.");
+            ErrorHandling::Exit(exitcode::NOINPUT)
+        }
+        DatabaseError::AlreadyExists(_) => {
+            error!("{error}.");
+            hint!("Use --force to overwrite it.");
+            ErrorHandling::Exit(exitcode::CANTCREAT)
+        }
+        DatabaseError::InvalidData { .. } | DatabaseError::Sqlite(_) => {
+            error!("{error}.");
+            ErrorHandling::Exit(exitcode::DATAERR)
+        }
+    }
+}
+
+/// Handles errors from XML books.
+fn handle_xml_error(error: &XmlError) -> ErrorHandling {
+    error!("{error}.");
+
+    match error {
+        XmlError::Read { .. } => ErrorHandling::Exit(exitcode::NOINPUT),
+        XmlError::Write { .. } => ErrorHandling::Exit(exitcode::CANTCREAT),
+        XmlError::Output(_) => ErrorHandling::Exit(exitcode::IOERR),
+        XmlError::Xml(_)
+        | XmlError::Malformed
+        | XmlError::NotABook
+        | XmlError::NotATemplate
+        | XmlError::Invalid { .. } => ErrorHandling::Exit(exitcode::DATAERR),
+    }
+}
+
+/// Handles errors in an assertion file.
+fn handle_assertion_error(error: &AssertionError) -> ErrorHandling {
+    error!("{error}.");
+
+    match error {
+        AssertionError::UnsupportedFormat(_) => {
+            hint!("Assertion files must end with .yaml, .yml or .toml.");
+        }
+        AssertionError::UnknownAccount(_) => {
+            hint!("Accounts are given by their full path, like Assets:Bank.");
+        }
+        AssertionError::Yaml(_) | AssertionError::Toml(_) => (),
+    }
+
+    ErrorHandling::Exit(exitcode::DATAERR)
+}
+
+/// Handles errors when building a transaction.
+fn handle_draft_error(error: &DraftError) -> ErrorHandling {
+    error!("{error}.");
+
+    match error {
+        DraftError::UnknownAccount(_) => {
+            hint!("Accounts are given by their full path, like Assets:Bank.");
+        }
+        DraftError::AmbiguousCurrency => {
+            hint!("Please set the currency of the transaction explicitly.");
+        }
+        DraftError::MissingPrice { .. } => {
+            hint!("Give a price as ACCOUNT=AMOUNT@PRICE or ACCOUNT=AMOUNT@@TOTAL.");
+        }
+        DraftError::NotEnoughSplits
+        | DraftError::PlaceholderAccount(_)
+        | DraftError::UnknownCurrency(_)
+        | DraftError::SeveralElidedAmounts
+        | DraftError::TooPrecise { .. }
+        | DraftError::UnneededPrice { .. }
+        | DraftError::Unbalanced { .. } => (),
+    }
+
+    ErrorHandling::Exit(exitcode::DATAERR)
+}
+
+/// Handles errors when working with invoices.
+fn handle_invoice_error(error: &InvoiceError) -> ErrorHandling {
+    error!("{error}.");
+
+    match error {
+        InvoiceError::UnknownOwner { .. } => (),
+        InvoiceError::UnknownId { .. } => {
+            hint!("Use `gnc invoice list` to see the IDs of the documents.");
+        }
+        InvoiceError::Template { source, .. } => {
+            if source.kind() == minijinja::ErrorKind::UndefinedError {
+                hint!(
+                    "See `gnc invoice render --help` for the available values."
+                );
+            }
+        }
+    }
+
+    ErrorHandling::Exit(exitcode::DATAERR)
+}
+
+/// Handles errors when creating an invoice.
+fn handle_invoice_draft_error(error: &InvoiceDraftError) -> ErrorHandling {
+    error!("{error}.");
+
+    match error {
+        InvoiceDraftError::UnknownAccount(_) => {
+            hint!("Accounts are given by their full path, like Assets:Bank.");
+        }
+        InvoiceDraftError::DuplicateId(_) => {
+            hint!("Omit --id to use the next ID of the book.");
+        }
+        InvoiceDraftError::MissingCustomer | InvoiceDraftError::NoEntries => {
+            hint!("Give them on the command line or in the invoice file.");
+        }
+        InvoiceDraftError::Toml(_)
+        | InvoiceDraftError::UnknownCustomer(_)
+        | InvoiceDraftError::UnknownCurrency(_)
+        | InvoiceDraftError::UnknownTerms(_)
+        | InvoiceDraftError::UnknownTaxTable(_)
+        | InvoiceDraftError::PlaceholderAccount(_)
+        | InvoiceDraftError::WrongCurrency { .. }
+        | InvoiceDraftError::NotReceivable(_) => (),
+    }
+
+    ErrorHandling::Exit(exitcode::DATAERR)
+}
+
+/// Handles errors when changing the directory.
+fn handle_directory_error(error: &DirectoryError) -> ErrorHandling {
+    error!("{error}.");
+
+    let error = match error {
+        DirectoryError::Line { source, .. } => source,
+        error => error,
+    };
+
+    match error {
+        DirectoryError::DuplicateId { .. } => {
+            hint!("Omit the ID to use the next ID of the book.");
+        }
+        DirectoryError::MissingCurrency(_) => {
+            hint!("Give it with --currency, or in a currency column.");
+        }
+        DirectoryError::UnknownColumn(_) => {
+            hint!("Use `export` to see the columns of the directory.");
+        }
+        DirectoryError::UnknownContact { .. }
+        | DirectoryError::MissingName(_)
+        | DirectoryError::UnknownCurrency(_)
+        | DirectoryError::CurrencyInUse(_)
+        | DirectoryError::UnknownTerms(_)
+        | DirectoryError::UnknownTaxTable(_)
+        | DirectoryError::InvalidValue { .. }
+        | DirectoryError::NotApplicable { .. }
+        | DirectoryError::Csv(_)
+        | DirectoryError::Line { .. } => (),
+    }
+
+    ErrorHandling::Exit(exitcode::DATAERR)
+}
+
+/// Handles errors when recording a payment.
+fn handle_payment_error(error: &PaymentError) -> ErrorHandling {
+    error!("{error}.");
+
+    match error {
+        PaymentError::UnknownAccount(_) => {
+            hint!("Accounts are given by their full path, like Assets:Bank.");
+        }
+        PaymentError::UnknownDocument { .. } => {
+            hint!("Use `gnc invoice list` to see the IDs of the documents.");
+        }
+        PaymentError::DifferentPostAccounts => {
+            hint!("Pay them separately, or choose one with --post-account.");
+        }
+        PaymentError::MissingPostAccount => {
+            hint!("Use --post-account to record a pre-payment.");
+        }
+        PaymentError::NotPositive
+        | PaymentError::UnknownCurrency(_)
+        | PaymentError::TooPrecise { .. }
+        | PaymentError::PlaceholderAccount(_)
+        | PaymentError::WrongCurrency { .. }
+        | PaymentError::WrongPostAccount { .. }
+        | PaymentError::NotOpen { .. }
+        | PaymentError::ExceedsBalance { .. }
+        | PaymentError::ExceedsPayment(_) => (),
+    }
+
+    ErrorHandling::Exit(exitcode::DATAERR)
+}
+
+/// Handles errors when reconciling an account.
+fn handle_reconcile_error(error: &ReconcileError) -> ErrorHandling {
+    error!("{error}.");
+
+    match error {
+        ReconcileError::UnknownAccount(_) => {
+            hint!("Accounts are given by their full path, like Assets:Bank.");
+        }
+        ReconcileError::NoMatch => {
+            hint!("Run without --auto to select the splits interactively.");
+        }
+    }
+
+    ErrorHandling::Exit(exitcode::DATAERR)
+}
+
+/// Handles errors in a journal.
+fn handle_journal_error(error: &JournalError) -> ErrorHandling {
+    error!("{error}.");
+
+    match &error.kind {
+        JournalErrorKind::Draft(DraftError::UnknownAccount(_)) => {
+            hint!("Accounts are given by their full path, like Assets:Bank.");
+        }
+        JournalErrorKind::Draft(DraftError::MissingPrice { .. }) => {
+            hint!("Give a price as AMOUNT @ PRICE or AMOUNT @@ TOTAL.");
+        }
+        JournalErrorKind::InvalidAmount(_) => {
+            hint!("Amounts are written like -1234.56 EUR, without separators.");
+        }
+        JournalErrorKind::DuplicateId(_) => {
+            hint!("Each transaction must have a unique id tag.");
+        }
+        JournalErrorKind::InvalidDate(_)
+        | JournalErrorKind::OrphanLine
+        | JournalErrorKind::VirtualPosting
+        | JournalErrorKind::BalanceAssertion
+        | JournalErrorKind::UnsupportedDirective(_)
+        | JournalErrorKind::SeveralPriceCurrencies
+        | JournalErrorKind::CommodityMismatch { .. }
+        | JournalErrorKind::Draft(_) => (),
+    }
+
+    ErrorHandling::Exit(exitcode::DATAERR)
+}
+
+/// Handles errors when creating scheduled transactions.
+fn handle_since_last_run_error(error: &SinceLastRunError) -> ErrorHandling {
+    error!("{error}.");
+
+    if let SinceLastRunErrorKind::Formula {
+        source: FormulaError::UnknownVariable(name),
+        ..
+    } = &error.kind
+    {
+        hint!("Give the value of the variable with --var {name}=VALUE.");
+    }
+
+    ErrorHandling::Exit(exitcode::DATAERR)
+}
+
+/// Handles errors when exporting the tax report.
+fn handle_tax_error(error: &TaxError) -> ErrorHandling {
+    match error {
+        TaxError::NotTxfCode { .. } => {
+            error!("{error}.");
+            hint!("Use --format csv to export codes of your own.");
+            ErrorHandling::Exit(exitcode::DATAERR)
+        }
+        TaxError::Io(_) | TaxError::Csv(_) => {
+            error!("{error}.");
+            ErrorHandling::Exit(exitcode::IOERR)
+        }
+    }
+}
+
+/// Handles errors when converting amounts.
+fn handle_conversion_error(error: &ConversionError) -> ErrorHandling {
+    error!("{error}.");
+
+    match error {
+        ConversionError::MissingRate { .. } => {
+            hint!("Add a price to the book, or use another rate method.");
+        }
+        ConversionError::UnknownCommodity(_) => {
+            hint!("Commodities are given by their symbol, like EUR or AAPL.");
+        }
+        ConversionError::NoCurrency => {
+            hint!("Give the reporting currency with --currency.");
+        }
+    }
+
+    ErrorHandling::Exit(exitcode::DATAERR)
+}
+
+/// Handles errors when closing a book.
+fn handle_closing_error(error: &ClosingError) -> ErrorHandling {
+    error!("{error}.");
+
+    match error {
+        ClosingError::UnknownAccount(_) => {
+            hint!("Accounts are given by their full path, like Equity:Retained Earnings.");
+        }
+        ClosingError::NotEquity(_) => {
+            hint!("The balances can only be closed into an equity account.");
+        }
+        ClosingError::PlaceholderAccount(_) => (),
+    }
+
+    ErrorHandling::Exit(exitcode::DATAERR)
+}
+
+/// Handles errors when changing the chart of accounts.
+fn handle_chart_error(error: &ChartError) -> ErrorHandling {
+    error!("{error}.");
+
+    match error {
+        ChartError::UnknownAccount(_) => {
+            hint!("Accounts are given by their full path, like Assets:Bank.");
+        }
+        ChartError::MissingCommodity(_) => {
+            hint!("Give it with --commodity.");
+        }
+        ChartError::IncompatibleParent { .. }
+        | ChartError::IncompatibleTypes { .. } => {
+            hint!("Use --force to do it anyway.");
+        }
+        ChartError::HasChildren(_) => {
+            hint!("Move or merge its sub-accounts first.");
+        }
+        ChartError::UnknownCommodity(_) => {
+            hint!("Add it to the book in GnuCash, in Tools > Security Editor.");
+        }
+        ChartError::AlreadyExists(_)
+        | ChartError::InvalidName(_)
+        | ChartError::UnderItself(_)
+        | ChartError::SameAccount(_)
+        | ChartError::DifferentCommodities { .. }
+        | ChartError::PlaceholderAccount(_) => (),
+    }
+
+    ErrorHandling::Exit(exitcode::DATAERR)
+}
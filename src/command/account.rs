@@ -0,0 +1,79 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `account` subcommand.
+
+mod add;
+mod export;
+mod hide;
+mod import_template;
+mod merge;
+mod mv;
+mod placeholder;
+mod rename;
+
+use clap::{Parser, Subcommand};
+use eyre::Result;
+
+use self::{
+    add::Add, export::Export, hide::Hide, import_template::ImportTemplate,
+    merge::Merge, mv::Mv, placeholder::Placeholder, rename::Rename,
+};
+
+/// Arguments for `gnucash-toolbox account`.
+#[derive(Debug, Parser)]
+pub struct Account {
+    /// The account command to run.
+    #[command(subcommand)]
+    command: AccountCommand,
+}
+
+/// The subcommands of `gnucash-toolbox account`.
+#[derive(Debug, Subcommand)]
+pub enum AccountCommand {
+    /// Add an account to the book.
+    Add(Add),
+    /// Move an account with its sub-accounts.
+    Mv(Mv),
+    /// Rename an account.
+    Rename(Rename),
+    /// Move all the splits of an account to another one, then delete it.
+    Merge(Merge),
+    /// Hide or show an account.
+    Hide(Hide),
+    /// Mark an account as a placeholder, or unmark it.
+    Placeholder(Placeholder),
+    /// Export the chart of accounts to CSV, or as an account template.
+    Export(Export),
+    /// Add the missing accounts of an account template.
+    ImportTemplate(ImportTemplate),
+}
+
+impl super::Command for Account {
+    fn run(&self) -> Result<()> {
+        match &self.command {
+            AccountCommand::Add(add) => add.run(),
+            AccountCommand::Mv(mv) => mv.run(),
+            AccountCommand::Rename(rename) => rename.run(),
+            AccountCommand::Merge(merge) => merge.run(),
+            AccountCommand::Hide(hide) => hide.run(),
+            AccountCommand::Placeholder(placeholder) => placeholder.run(),
+            AccountCommand::Export(export) => export.run(),
+            AccountCommand::ImportTemplate(import_template) => {
+                import_template.run()
+            }
+        }
+    }
+}
@@ -0,0 +1,146 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `account add` subcommand.
+
+use std::path::PathBuf;
+
+use clap::{Parser, ValueEnum};
+use eyre::Result;
+
+use crate::{
+    book::{AccountType, Database},
+    chart, conversion, success,
+};
+
+/// Arguments for `gnucash-toolbox account add`.
+#[derive(Debug, Parser)]
+pub struct Add {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// The type of the account.
+    #[arg(short = 't', long = "type")]
+    kind: Kind,
+    /// The commodity of the account.
+    ///
+    /// Defaults to the commodity of the parent account.
+    #[arg(short, long)]
+    commodity: Option<String>,
+    /// The account code.
+    #[arg(long)]
+    code: Option<String>,
+    /// The description of the account.
+    #[arg(short = 'D', long)]
+    description: Option<String>,
+    /// Mark the account as a placeholder.
+    #[arg(long)]
+    placeholder: bool,
+    /// Hide the account.
+    #[arg(long)]
+    hidden: bool,
+    /// Add the account even if its type is not compatible with its parent.
+    #[arg(short, long)]
+    force: bool,
+    /// The full path of the account, like `Expenses:Travel`.
+    ///
+    /// The parent account must exist.
+    path: String,
+}
+
+/// The type of an account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Kind {
+    /// A bank account.
+    Bank,
+    /// A cash account.
+    Cash,
+    /// A generic asset account.
+    Asset,
+    /// A credit card account.
+    Credit,
+    /// A generic liability account.
+    Liability,
+    /// A stock account.
+    Stock,
+    /// A mutual fund account.
+    Mutual,
+    /// An income account.
+    Income,
+    /// An expense account.
+    Expense,
+    /// An equity account.
+    Equity,
+    /// An accounts receivable account.
+    Receivable,
+    /// An accounts payable account.
+    Payable,
+    /// A trading account.
+    Trading,
+}
+
+impl crate::command::Command for Add {
+    #[tracing::instrument(name = "account_add", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running account add");
+
+        let mut database = Database::open(&self.book)?;
+        let mut book = database.load()?;
+
+        let commodity = self
+            .commodity
+            .as_deref()
+            .map(|commodity| conversion::find_commodity(&book, commodity))
+            .transpose()?;
+
+        let account = chart::add(
+            &mut book,
+            &self.path,
+            self.kind.into(),
+            commodity,
+            self.force,
+        )?;
+
+        account.code.clone_from(&self.code);
+        account.description.clone_from(&self.description);
+        account.placeholder = self.placeholder;
+        account.hidden = self.hidden;
+
+        database.save_accounts(&[account])?;
+        success!("The account {} has been added.", self.path);
+
+        Ok(())
+    }
+}
+
+impl From<Kind> for AccountType {
+    fn from(kind: Kind) -> Self {
+        match kind {
+            Kind::Bank => Self::Bank,
+            Kind::Cash => Self::Cash,
+            Kind::Asset => Self::Asset,
+            Kind::Credit => Self::Credit,
+            Kind::Liability => Self::Liability,
+            Kind::Stock => Self::Stock,
+            Kind::Mutual => Self::Mutual,
+            Kind::Income => Self::Income,
+            Kind::Expense => Self::Expense,
+            Kind::Equity => Self::Equity,
+            Kind::Receivable => Self::Receivable,
+            Kind::Payable => Self::Payable,
+            Kind::Trading => Self::Trading,
+        }
+    }
+}
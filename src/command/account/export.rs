@@ -0,0 +1,86 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `account export` subcommand.
+
+use std::{
+    fs,
+    io::{self, Write as _},
+    path::PathBuf,
+};
+
+use clap::Parser;
+use eyre::{Context as _, Result};
+
+use crate::{
+    book::{self, Database},
+    chart, success,
+};
+
+/// Arguments for `gnucash-toolbox account export`.
+#[derive(Debug, Parser)]
+pub struct Export {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// Export an account template, like the `.gnucash-xea` files of GnuCash.
+    ///
+    /// The template can be imported in another book with
+    /// `gnc account import-template`.
+    #[arg(long)]
+    template: bool,
+    /// The title of the template.
+    #[arg(long, requires = "template", default_value = "Chart of accounts")]
+    title: String,
+    /// The description of the template.
+    #[arg(short = 'D', long, requires = "template", default_value = "")]
+    description: String,
+    /// The output file, instead of the standard output.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+impl crate::command::Command for Export {
+    #[tracing::instrument(name = "account_export", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running account export");
+
+        let book = Database::open_read_only(&self.book)?.load()?;
+
+        let mut content = Vec::new();
+        if self.template {
+            let template =
+                chart::template(&book, &self.title, &self.description);
+            book::write_account_template(&template, &mut content)?;
+        } else {
+            chart::export_csv(&book, &mut content)?;
+        }
+
+        if let Some(output) = &self.output {
+            fs::write(output, content).wrap_err_with(|| {
+                format!("failed to write {}", output.display())
+            })?;
+
+            success!(
+                "The accounts have been exported to {}.",
+                output.display()
+            );
+        } else {
+            io::stdout().write_all(&content)?;
+        }
+
+        Ok(())
+    }
+}
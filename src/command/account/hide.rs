@@ -0,0 +1,58 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `account hide` subcommand.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use eyre::Result;
+
+use crate::{book::Database, chart, success};
+
+/// Arguments for `gnucash-toolbox account hide`.
+#[derive(Debug, Parser)]
+pub struct Hide {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// Show the account again.
+    #[arg(long)]
+    unset: bool,
+    /// The full path of the account.
+    account: String,
+}
+
+impl crate::command::Command for Hide {
+    #[tracing::instrument(name = "account_hide", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running account hide");
+
+        let mut database = Database::open(&self.book)?;
+        let mut book = database.load()?;
+
+        let account = chart::find_mut(&mut book, &self.account)?;
+        account.hidden = !self.unset;
+        database.save_accounts(&[account])?;
+
+        if self.unset {
+            success!("The account {} is now visible.", self.account);
+        } else {
+            success!("The account {} is now hidden.", self.account);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `account import-template` subcommand.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use eyre::Result;
+
+use crate::{
+    book::{self, Database},
+    chart, conversion, success,
+};
+
+/// Arguments for `gnucash-toolbox account import-template`.
+#[derive(Debug, Parser)]
+pub struct ImportTemplate {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// The currency of the new accounts in a currency.
+    ///
+    /// By default, they keep the currency of the template when the book has
+    /// it, or else use the currency used by most accounts of the book.
+    #[arg(short, long)]
+    currency: Option<String>,
+    /// Add the accounts even if their type is not compatible with their
+    /// parent in the book.
+    #[arg(short, long)]
+    force: bool,
+    /// Show the accounts to add without modifying the book.
+    #[arg(long)]
+    dry_run: bool,
+    /// The account template, like the `.gnucash-xea` files of GnuCash.
+    ///
+    /// Accounts are matched by full path: only the ones missing from the book
+    /// are added.
+    template: PathBuf,
+}
+
+impl crate::command::Command for ImportTemplate {
+    #[tracing::instrument(
+        name = "account_import_template",
+        level = "trace",
+        skip_all
+    )]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running account import-template");
+
+        let mut database = Database::open(&self.book)?;
+        let mut book = database.load()?;
+        let template = book::read_account_template(&self.template)?;
+
+        let currency = self
+            .currency
+            .as_deref()
+            .map(|currency| conversion::find_commodity(&book, currency))
+            .transpose()?;
+
+        let import = chart::import_template(
+            &mut book,
+            &template.book,
+            currency,
+            self.force,
+        )?;
+
+        for account in &import.accounts {
+            println!("New account: {}", book.account_path(account.guid));
+        }
+
+        if self.dry_run {
+            success!(
+                "{} accounts to add, {} already in the book.",
+                import.accounts.len(),
+                import.existing
+            );
+        } else {
+            let accounts: Vec<_> = import.accounts.iter().collect();
+            database.save_accounts(&accounts)?;
+
+            success!(
+                "{} accounts added, {} already in the book.",
+                import.accounts.len(),
+                import.existing
+            );
+        }
+
+        Ok(())
+    }
+}
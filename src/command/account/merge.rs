@@ -0,0 +1,68 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `account merge` subcommand.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use eyre::Result;
+
+use crate::{book::Database, chart, success};
+
+/// Arguments for `gnucash-toolbox account merge`.
+#[derive(Debug, Parser)]
+pub struct Merge {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// Merge the accounts even if their types are not compatible.
+    #[arg(short, long)]
+    force: bool,
+    /// The full path of the account to merge, which is deleted.
+    source: String,
+    /// The full path of the account receiving the splits.
+    target: String,
+}
+
+impl crate::command::Command for Merge {
+    #[tracing::instrument(name = "account_merge", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running account merge");
+
+        let mut database = Database::open(&self.book)?;
+        let mut book = database.load()?;
+
+        let merge =
+            chart::merge(&mut book, &self.source, &self.target, self.force)?;
+
+        database.merge_accounts(
+            &book,
+            &merge.source,
+            merge.target,
+            &merge.splits,
+        )?;
+
+        success!(
+            "{} splits moved from {} to {}.",
+            merge.splits.len(),
+            self.source,
+            book.account_path(merge.target)
+        );
+        success!("The account {} has been deleted.", self.source);
+
+        Ok(())
+    }
+}
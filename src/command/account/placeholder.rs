@@ -0,0 +1,65 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `account placeholder` subcommand.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use eyre::Result;
+
+use crate::{book::Database, chart, success};
+
+/// Arguments for `gnucash-toolbox account placeholder`.
+#[derive(Debug, Parser)]
+pub struct Placeholder {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// Unmark the account as a placeholder.
+    #[arg(long)]
+    unset: bool,
+    /// The full path of the account.
+    account: String,
+}
+
+impl crate::command::Command for Placeholder {
+    #[tracing::instrument(
+        name = "account_placeholder",
+        level = "trace",
+        skip_all
+    )]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running account placeholder");
+
+        let mut database = Database::open(&self.book)?;
+        let mut book = database.load()?;
+
+        let account = chart::find_mut(&mut book, &self.account)?;
+        account.placeholder = !self.unset;
+        database.save_accounts(&[account])?;
+
+        if self.unset {
+            success!(
+                "The account {} is no longer a placeholder.",
+                self.account
+            );
+        } else {
+            success!("The account {} is now a placeholder.", self.account);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `account rename` subcommand.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use eyre::Result;
+
+use crate::{book::Database, chart, success};
+
+/// Arguments for `gnucash-toolbox account rename`.
+#[derive(Debug, Parser)]
+pub struct Rename {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// The full path of the account to rename.
+    account: String,
+    /// The new name of the account.
+    name: String,
+}
+
+impl crate::command::Command for Rename {
+    #[tracing::instrument(name = "account_rename", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running account rename");
+
+        let mut database = Database::open(&self.book)?;
+        let mut book = database.load()?;
+
+        let account =
+            chart::rename(&mut book, &self.account, &self.name)?.clone();
+        database.save_accounts(&[&account])?;
+
+        success!(
+            "The account {} has been renamed to {}.",
+            self.account,
+            book.account_path(account.guid)
+        );
+
+        Ok(())
+    }
+}
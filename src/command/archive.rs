@@ -0,0 +1,103 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `archive` subcommand.
+
+use std::{fs, path::PathBuf};
+
+use chrono::NaiveDate;
+use clap::Parser;
+use eyre::Result;
+use thiserror::Error;
+
+use crate::{book::Database, closing, success};
+
+/// Arguments for `gnucash-toolbox archive`.
+#[derive(Debug, Parser)]
+pub struct Archive {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// Archive the transactions posted before this date, excluded.
+    #[arg(long)]
+    before: NaiveDate,
+    /// The description of the summary transactions.
+    #[arg(short = 'D', long, default_value = "Archived transactions")]
+    description: String,
+    /// The path of the archive book, which must not exist.
+    output: PathBuf,
+}
+
+/// An error when the archive book already exists.
+///
+/// An existing archive is never overwritten, as its transactions are not in the
+/// book anymore.
+#[derive(Debug, Error)]
+#[error("{} already exists", .0.display())]
+pub struct ArchiveExists(PathBuf);
+
+impl super::Command for Archive {
+    #[tracing::instrument(name = "archive", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running archive");
+
+        if self.output.exists() {
+            return Err(ArchiveExists(self.output.clone()).into());
+        }
+
+        let mut database = Database::open(&self.book)?;
+        let book = database.load()?;
+
+        let archive = closing::archive(&book, self.before, &self.description);
+
+        if archive.book.transactions.is_empty() {
+            success!(
+                "There is no transaction to archive before {}.",
+                self.before
+            );
+            return Ok(());
+        }
+
+        Database::create(&self.output, &archive.book)?;
+
+        let archived: Vec<_> = archive.book.transactions.values().collect();
+        let replaced =
+            database.replace_transactions(&book, &archived, &archive.summaries);
+
+        if let Err(error) = replaced {
+            // The transactions are still in the book: remove the archive so
+            // that the command can be run again.
+            if let Err(error) = fs::remove_file(&self.output) {
+                tracing::warn!(
+                    %error,
+                    path = %self.output.display(),
+                    "failed to remove the archive"
+                );
+            }
+
+            return Err(error.into());
+        }
+
+        success!(
+            "{} transactions have been archived in {}, replaced by {} summary \
+            transactions.",
+            archived.len(),
+            self.output.display(),
+            archive.summaries.len()
+        );
+
+        Ok(())
+    }
+}
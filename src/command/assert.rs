@@ -0,0 +1,138 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `assert` subcommand.
+
+use std::{fs, path::PathBuf};
+
+use clap::Parser;
+use eyre::{Context as _, Result};
+use thiserror::Error;
+
+use crate::{
+    assertion::{self, Outcome},
+    book::{Book, Commodity, Database, Numeric},
+    success, warning,
+};
+
+/// Arguments for `gnucash-toolbox assert`.
+#[derive(Debug, Parser)]
+pub struct Assert {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// How many days around a failing date to search for culprits.
+    #[arg(long, default_value_t = 7)]
+    days: i64,
+    /// The assertion file, in YAML or TOML.
+    ///
+    /// It contains a list of `assertions` with an `account`, a `date` and the
+    /// expected `balance` of the account at the end of that day.
+    file: PathBuf,
+}
+
+/// Some assertions do not hold.
+#[derive(Debug, Error)]
+#[error("{failed} of {total} balance assertions failed")]
+pub struct AssertionsFailed {
+    /// The number of failed assertions.
+    failed: usize,
+    /// The total number of assertions.
+    total: usize,
+}
+
+impl super::Command for Assert {
+    #[tracing::instrument(name = "assert", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running assert");
+
+        let input = fs::read_to_string(&self.file).wrap_err_with(|| {
+            format!("failed to read {}", self.file.display())
+        })?;
+        let assertions = assertion::parse(&self.file, &input)?;
+
+        let book = Database::open_read_only(&self.book)?.load()?;
+
+        let outcomes = assertions
+            .iter()
+            .map(|assertion| assertion.check(&book, self.days))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for outcome in &outcomes {
+            print_outcome(&book, outcome);
+        }
+
+        let failed = outcomes.iter().filter(|outcome| !outcome.is_ok()).count();
+
+        if failed == 0 {
+            success!("All {} balance assertions hold.", outcomes.len());
+            Ok(())
+        } else {
+            Err(AssertionsFailed {
+                failed,
+                total: outcomes.len(),
+            }
+            .into())
+        }
+    }
+}
+
+/// Prints the outcome of an assertion, with the likely culprits on failure.
+fn print_outcome(book: &Book, outcome: &Outcome<'_>) {
+    let commodity = book.account_commodity(outcome.account.guid);
+    let fraction = commodity.map_or(100, Commodity::fraction);
+    let symbol = commodity.map(|commodity| commodity.mnemonic.as_str());
+    let amount = |amount: Numeric| match symbol {
+        Some(symbol) => format!("{} {symbol}", amount.to_fixed(fraction)),
+        None => amount.to_fixed(fraction),
+    };
+
+    let assertion = outcome.assertion;
+
+    if outcome.is_ok() {
+        println!(
+            "{} on {}: {}, as expected.",
+            assertion.account,
+            assertion.date,
+            amount(outcome.actual)
+        );
+        return;
+    }
+
+    warning!(
+        "{} on {}: {} instead of {} (difference: {}).",
+        assertion.account,
+        assertion.date,
+        amount(outcome.actual),
+        amount(assertion.balance),
+        amount(outcome.difference()),
+    );
+
+    if outcome.culprits.is_empty() {
+        println!("  No transaction near this date.");
+    } else {
+        println!("  Likely culprits:");
+    }
+
+    for culprit in &outcome.culprits {
+        println!(
+            "    {}  {:<32}  {:>14}  ({})",
+            culprit.transaction.date(),
+            culprit.transaction.description,
+            amount(culprit.split.quantity),
+            culprit.reason,
+        );
+    }
+}
@@ -0,0 +1,87 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `check` subcommand.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use eyre::Result;
+use thiserror::Error;
+
+use crate::{
+    book::{Book, Commodity, Database, Numeric},
+    check::{self, Issue},
+    success, warning,
+};
+
+/// Arguments for `gnucash-toolbox check`.
+#[derive(Debug, Parser)]
+pub struct Check {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+}
+
+/// The book has integrity issues.
+#[derive(Debug, Error)]
+#[error("the book has {0} integrity issues")]
+pub struct IssuesFound(usize);
+
+impl super::Command for Check {
+    #[tracing::instrument(name = "check", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running check");
+
+        let book = Database::open_read_only(&self.book)?.load()?;
+        let issues = check::check(&book);
+
+        for issue in &issues {
+            print_issue(&book, issue);
+        }
+
+        if issues.is_empty() {
+            success!("The book is consistent.");
+            Ok(())
+        } else {
+            Err(IssuesFound(issues.len()).into())
+        }
+    }
+}
+
+/// Prints an issue.
+fn print_issue(book: &Book, issue: &Issue<'_>) {
+    let transaction = issue.transaction();
+    let amount = |amount: Numeric, commodity: Option<&Commodity>| {
+        let fraction = commodity.map_or(100, Commodity::fraction);
+        let symbol = commodity.map_or("", |commodity| &commodity.mnemonic);
+        format!("{} {symbol}", amount.to_fixed(fraction))
+    };
+
+    match issue {
+        Issue::Unbalanced { imbalance, .. } => warning!(
+            "{} {}: unbalanced by {}.",
+            transaction.date(),
+            transaction.description,
+            amount(*imbalance, book.commodity(transaction.currency)),
+        ),
+        Issue::MissingTradingSplits { imbalance, .. } => warning!(
+            "{} {}: missing trading splits for {}.",
+            transaction.date(),
+            transaction.description,
+            amount(imbalance.amount, Some(imbalance.commodity)),
+        ),
+    }
+}
@@ -0,0 +1,128 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `close-book` subcommand.
+
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use clap::Parser;
+use eyre::Result;
+
+use crate::{
+    book::{Book, Commodity, Database, Transaction},
+    closing,
+    command::helpers::print_table,
+    success,
+};
+
+/// Arguments for `gnucash-toolbox close-book`.
+#[derive(Debug, Parser)]
+pub struct CloseBook {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// The closing date, included.
+    #[arg(short, long)]
+    date: NaiveDate,
+    /// The equity account receiving the balances, like `Equity:Retained
+    /// Earnings`.
+    ///
+    /// Balances in other currencies than the one of this account go to a child
+    /// account named after their currency, which is created if needed.
+    #[arg(short, long)]
+    equity: String,
+    /// The description of the closing transactions.
+    #[arg(short = 'D', long, default_value = "Closing Entries")]
+    description: String,
+    /// Make the transactions older than this number of days read-only.
+    #[arg(long, value_name = "DAYS")]
+    read_only_days: Option<u32>,
+    /// Show the closing transactions without modifying the book.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+impl super::Command for CloseBook {
+    #[tracing::instrument(name = "close_book", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running close-book");
+
+        let mut database = Database::open(&self.book)?;
+        let mut book = database.load()?;
+
+        let entries = closing::closing_entries(
+            &mut book,
+            self.date,
+            &self.equity,
+            &self.description,
+        )?;
+
+        for account in &entries.accounts {
+            println!("New account: {}", book.account_path(account.guid));
+        }
+
+        for transaction in &entries.transactions {
+            print_transaction(&book, transaction);
+        }
+
+        if let Some(days) = self.read_only_days {
+            closing::set_read_only_days(&mut book, days);
+            println!("Transactions older than {days} days will be read-only.");
+        }
+
+        let count = entries.transactions.len();
+
+        if self.dry_run {
+            success!("{count} closing transactions to create.");
+        } else {
+            database.insert_closing_entries(
+                &book,
+                &entries.accounts,
+                &entries.transactions,
+            )?;
+            success!("{count} closing transactions created.");
+        }
+
+        Ok(())
+    }
+}
+
+/// Prints a closing transaction with its splits.
+fn print_transaction(book: &Book, transaction: &Transaction) {
+    let currency = book.commodity(transaction.currency);
+    let fraction = currency.map_or(100, Commodity::fraction);
+    let symbol = currency.map_or("", |currency| &currency.mnemonic);
+
+    println!(
+        "{} {} ({symbol}):",
+        transaction.date(),
+        transaction.description
+    );
+
+    let rows: Vec<_> = transaction
+        .splits
+        .iter()
+        .map(|split| {
+            [
+                book.account_path(split.account),
+                split.quantity.to_fixed(fraction),
+            ]
+        })
+        .collect();
+
+    print_table(["Account", "Amount"], &rows, &[1]);
+    println!();
+}
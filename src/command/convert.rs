@@ -0,0 +1,117 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `convert` subcommand.
+
+use std::{
+    fs::File,
+    io::{self, Read as _},
+    path::{Path, PathBuf},
+};
+
+use clap::{Parser, ValueEnum};
+use eyre::{Context as _, Result};
+
+use crate::{
+    book::{read_xml, write_xml, Book, Database, DatabaseError},
+    command::helpers::write_replacing,
+    success,
+};
+
+/// The header of SQLite files.
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Arguments for `gnucash-toolbox convert`.
+#[derive(Debug, Parser)]
+pub struct Convert {
+    /// The book to convert, in SQLite or XML format.
+    input: PathBuf,
+    /// The path of the converted book.
+    output: PathBuf,
+    /// The format of the converted book.
+    #[arg(long)]
+    to: Format,
+    /// Overwrite the output if it already exists.
+    #[arg(short, long)]
+    force: bool,
+}
+
+/// A GnuCash book format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// A SQLite database.
+    Sqlite,
+    /// A compressed XML file.
+    Xml,
+}
+
+impl super::Command for Convert {
+    #[tracing::instrument(name = "convert", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running convert");
+
+        let book = match Format::detect(&self.input)? {
+            Format::Sqlite => Database::open_read_only(&self.input)?.load()?,
+            Format::Xml => read_xml(&self.input)?,
+        };
+
+        write_replacing(&self.output, self.force, |path| {
+            write_book(&book, path, self.to)
+        })?;
+
+        success!("The book has been converted to {}.", self.output.display());
+        Ok(())
+    }
+}
+
+impl Format {
+    /// Detects the format of a book from its content.
+    fn detect(path: &Path) -> Result<Self> {
+        let read_error = || format!("failed to read {}", path.display());
+
+        let mut header = [0; SQLITE_HEADER.len()];
+        let mut file = File::open(path).wrap_err_with(read_error)?;
+        match file.read_exact(&mut header) {
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => (),
+            result => result.wrap_err_with(read_error)?,
+        }
+
+        if &header == SQLITE_HEADER {
+            Ok(Self::Sqlite)
+        } else {
+            Ok(Self::Xml)
+        }
+    }
+}
+
+/// Writes a book in the given format.
+fn write_book(book: &Book, path: &Path, format: Format) -> Result<()> {
+    match format {
+        Format::Sqlite => {
+            Database::create(path, book)?;
+        }
+        Format::Xml => {
+            if path.exists() {
+                return Err(
+                    DatabaseError::AlreadyExists(path.to_owned()).into()
+                );
+            }
+
+            write_xml(book, path)?;
+        }
+    }
+
+    Ok(())
+}
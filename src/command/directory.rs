@@ -0,0 +1,207 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `customer`, `vendor` and `employee` subcommands.
+
+mod add;
+mod edit;
+mod export;
+mod import;
+mod list;
+mod show;
+
+use clap::{Args, Parser, Subcommand};
+use eyre::Result;
+
+use self::{
+    add::Add, edit::Edit, export::Export, import::Import, list::List,
+    show::Show,
+};
+use crate::{
+    book::{Book, Database},
+    directory::{Changes, ContactDraft, ContactKind, Field},
+};
+
+/// Arguments for `gnucash-toolbox customer`.
+#[derive(Debug, Parser)]
+pub struct Customer {
+    /// The customer command to run.
+    #[command(subcommand)]
+    command: DirectoryCommand,
+}
+
+/// Arguments for `gnucash-toolbox vendor`.
+#[derive(Debug, Parser)]
+pub struct Vendor {
+    /// The vendor command to run.
+    #[command(subcommand)]
+    command: DirectoryCommand,
+}
+
+/// Arguments for `gnucash-toolbox employee`.
+#[derive(Debug, Parser)]
+pub struct Employee {
+    /// The employee command to run.
+    #[command(subcommand)]
+    command: DirectoryCommand,
+}
+
+/// The subcommands of `gnucash-toolbox customer|vendor|employee`.
+#[derive(Debug, Subcommand)]
+pub enum DirectoryCommand {
+    /// List the contacts.
+    List(List),
+    /// Show all the fields of a contact.
+    Show(Show),
+    /// Add a contact.
+    Add(Add),
+    /// Edit a contact.
+    Edit(Edit),
+    /// Add or update contacts from a CSV file.
+    Import(Import),
+    /// Export the contacts to CSV.
+    Export(Export),
+}
+
+/// The fields of a contact that can be set from the command line.
+///
+/// An empty value clears an optional field.
+#[derive(Debug, Args)]
+pub struct ContactArgs {
+    /// The ID [default: the next ID of the book].
+    #[arg(long)]
+    id: Option<String>,
+    /// Whether the contact is active.
+    #[arg(long)]
+    active: Option<bool>,
+    /// The currency, as an ISO code like EUR.
+    ///
+    /// New contacts default to the main currency of the book.
+    #[arg(long)]
+    currency: Option<String>,
+    /// The billing terms, by name.
+    #[arg(long)]
+    terms: Option<String>,
+    /// The tax table, by name.
+    #[arg(long)]
+    tax_table: Option<String>,
+    /// The credit limit, for customers.
+    #[arg(long)]
+    credit_limit: Option<String>,
+    /// The notes.
+    #[arg(long)]
+    notes: Option<String>,
+    /// The name of the addressee.
+    #[arg(long)]
+    address_name: Option<String>,
+    /// The first line of the address.
+    #[arg(long)]
+    address1: Option<String>,
+    /// The second line of the address.
+    #[arg(long)]
+    address2: Option<String>,
+    /// The third line of the address.
+    #[arg(long)]
+    address3: Option<String>,
+    /// The fourth line of the address.
+    #[arg(long)]
+    address4: Option<String>,
+    /// The phone number.
+    #[arg(long)]
+    phone: Option<String>,
+    /// The fax number.
+    #[arg(long)]
+    fax: Option<String>,
+    /// The email address.
+    #[arg(long)]
+    email: Option<String>,
+}
+
+impl crate::command::Command for Customer {
+    fn run(&self) -> Result<()> {
+        self.command.run(ContactKind::Customer)
+    }
+}
+
+impl crate::command::Command for Vendor {
+    fn run(&self) -> Result<()> {
+        self.command.run(ContactKind::Vendor)
+    }
+}
+
+impl crate::command::Command for Employee {
+    fn run(&self) -> Result<()> {
+        self.command.run(ContactKind::Employee)
+    }
+}
+
+impl DirectoryCommand {
+    /// Runs the command on the contacts of the given kind.
+    fn run(&self, kind: ContactKind) -> Result<()> {
+        match self {
+            Self::List(list) => list.run(kind),
+            Self::Show(show) => show.run(kind),
+            Self::Add(add) => add.run(kind),
+            Self::Edit(edit) => edit.run(kind),
+            Self::Import(import) => import.run(kind),
+            Self::Export(export) => export.run(kind),
+        }
+    }
+}
+
+impl ContactArgs {
+    /// Returns the changes given on the command line.
+    fn draft(&self) -> ContactDraft {
+        let fields = [
+            (Field::Id, self.id.clone()),
+            (Field::Active, self.active.map(|active| active.to_string())),
+            (Field::Currency, self.currency.clone()),
+            (Field::Terms, self.terms.clone()),
+            (Field::TaxTable, self.tax_table.clone()),
+            (Field::CreditLimit, self.credit_limit.clone()),
+            (Field::Notes, self.notes.clone()),
+            (Field::AddressName, self.address_name.clone()),
+            (Field::Address1, self.address1.clone()),
+            (Field::Address2, self.address2.clone()),
+            (Field::Address3, self.address3.clone()),
+            (Field::Address4, self.address4.clone()),
+            (Field::Phone, self.phone.clone()),
+            (Field::Fax, self.fax.clone()),
+            (Field::Email, self.email.clone()),
+        ];
+
+        ContactDraft {
+            fields: fields
+                .into_iter()
+                .filter_map(|(field, value)| Some((field, value?)))
+                .collect(),
+        }
+    }
+}
+
+/// Saves the changes made to the directory, with the counter of the book.
+fn save(
+    database: &mut Database,
+    book: &mut Book,
+    kind: ContactKind,
+    changes: &Changes,
+) -> Result<()> {
+    if let Some(counter) = changes.counter {
+        book.set_counter(kind.counter(), counter);
+    }
+
+    database.save_contacts(book, &changes.contacts)?;
+    Ok(())
+}
@@ -0,0 +1,66 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `customer|vendor|employee add` subcommand.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use eyre::Result;
+
+use super::ContactArgs;
+use crate::{
+    book::Database,
+    directory::{ContactKind, Directory, Field},
+    success,
+};
+
+/// Arguments for `gnucash-toolbox customer|vendor|employee add`.
+#[derive(Debug, Parser)]
+pub struct Add {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// The fields of the contact.
+    #[command(flatten)]
+    fields: ContactArgs,
+    /// The name of the contact, or the username of an employee.
+    name: String,
+}
+
+impl Add {
+    /// Adds a contact of the given kind.
+    #[tracing::instrument(name = "directory_add", level = "trace", skip_all)]
+    pub fn run(&self, kind: ContactKind) -> Result<()> {
+        tracing::info!(params = ?self, %kind, "running directory add");
+
+        let mut database = Database::open(&self.book)?;
+        let mut book = database.load()?;
+
+        let mut draft = self.fields.draft();
+        draft.fields.insert(Field::Name, self.name.clone());
+
+        let mut directory = Directory::new(&book, kind);
+        let contact = directory.add(&draft)?;
+        let id = directory.value(&contact, Field::Id);
+        let changes = directory.into_changes();
+
+        super::save(&mut database, &mut book, kind, &changes)?;
+
+        success!("The {kind} {} has been added with the ID {id}.", self.name);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,78 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `customer|vendor|employee edit` subcommand.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use eyre::Result;
+
+use super::ContactArgs;
+use crate::{
+    book::Database,
+    directory::{self, ContactKind, Directory, Field},
+    success,
+};
+
+/// Arguments for `gnucash-toolbox customer|vendor|employee edit`.
+#[derive(Debug, Parser)]
+pub struct Edit {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// The new name, or the new username of an employee.
+    #[arg(long)]
+    name: Option<String>,
+    /// The fields to change.
+    #[command(flatten)]
+    fields: ContactArgs,
+    /// The contact, given by ID or name.
+    contact: String,
+}
+
+impl Edit {
+    /// Edits a contact of the given kind.
+    #[tracing::instrument(name = "directory_edit", level = "trace", skip_all)]
+    pub fn run(&self, kind: ContactKind) -> Result<()> {
+        tracing::info!(params = ?self, %kind, "running directory edit");
+
+        let mut database = Database::open(&self.book)?;
+        let mut book = database.load()?;
+
+        let mut draft = self.fields.draft();
+        if let Some(name) = &self.name {
+            draft.fields.insert(Field::Name, name.clone());
+        }
+
+        let mut directory = Directory::new(&book, kind);
+
+        if draft.fields.is_empty() {
+            directory.find(&self.contact)?;
+            println!("There is nothing to change.");
+            return Ok(());
+        }
+
+        let contact = directory.edit(&self.contact, &draft)?;
+        let name = directory::name(&contact).to_owned();
+        let changes = directory.into_changes();
+
+        super::save(&mut database, &mut book, kind, &changes)?;
+
+        success!("The {kind} {name} has been updated.");
+
+        Ok(())
+    }
+}
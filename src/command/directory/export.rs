@@ -0,0 +1,68 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `customer|vendor|employee export` subcommand.
+
+use std::{
+    fs,
+    io::{self, Write as _},
+    path::PathBuf,
+};
+
+use clap::Parser;
+use eyre::{Context as _, Result};
+
+use crate::{
+    book::Database,
+    directory::{ContactKind, Directory},
+    success,
+};
+
+/// Arguments for `gnucash-toolbox customer|vendor|employee export`.
+#[derive(Debug, Parser)]
+pub struct Export {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// The output file, instead of the standard output.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+impl Export {
+    /// Exports the contacts of the given kind.
+    #[tracing::instrument(name = "directory_export", level = "trace", skip_all)]
+    pub fn run(&self, kind: ContactKind) -> Result<()> {
+        tracing::info!(params = ?self, %kind, "running directory export");
+
+        let book = Database::open_read_only(&self.book)?.load()?;
+        let directory = Directory::new(&book, kind);
+
+        let mut csv = Vec::new();
+        directory.export(&mut csv)?;
+
+        if let Some(output) = &self.output {
+            fs::write(output, csv).wrap_err_with(|| {
+                format!("failed to write {}", output.display())
+            })?;
+
+            success!("The {kind}s have been exported to {}.", output.display());
+        } else {
+            io::stdout().write_all(&csv)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `customer|vendor|employee import` subcommand.
+
+use std::{fs::File, path::PathBuf};
+
+use clap::Parser;
+use eyre::{Context as _, Result};
+
+use crate::{
+    book::Database,
+    directory::{ContactKind, Directory},
+    success,
+};
+
+/// Arguments for `gnucash-toolbox customer|vendor|employee import`.
+#[derive(Debug, Parser)]
+pub struct Import {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// Check the file without modifying the book.
+    #[arg(long)]
+    dry_run: bool,
+    /// The CSV file.
+    ///
+    /// Its first row names the fields given in each column, as in the files
+    /// written by `export`. Contacts are matched by ID: the existing ones are
+    /// updated, and the others are added. The fields without a column keep
+    /// their value.
+    file: PathBuf,
+}
+
+impl Import {
+    /// Imports contacts of the given kind.
+    #[tracing::instrument(name = "directory_import", level = "trace", skip_all)]
+    pub fn run(&self, kind: ContactKind) -> Result<()> {
+        tracing::info!(params = ?self, %kind, "running directory import");
+
+        let mut database = Database::open(&self.book)?;
+        let mut book = database.load()?;
+
+        let file = File::open(&self.file).wrap_err_with(|| {
+            format!("failed to open {}", self.file.display())
+        })?;
+
+        let mut directory = Directory::new(&book, kind);
+        directory.import(file)?;
+        let changes = directory.into_changes();
+
+        let added = changes.added;
+        let updated = changes.contacts.len() - added;
+
+        if self.dry_run {
+            success!("{added} {kind}s to add, {updated} to update.");
+        } else {
+            super::save(&mut database, &mut book, kind, &changes)?;
+            success!("{added} {kind}s added, {updated} updated.");
+        }
+
+        Ok(())
+    }
+}
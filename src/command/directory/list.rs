@@ -0,0 +1,81 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `customer|vendor|employee list` subcommand.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use eyre::Result;
+
+use crate::{
+    book::Database,
+    command::helpers::print_table,
+    directory::{self, ContactKind, Directory, Field},
+};
+
+/// The headers of the columns.
+const HEADERS: [&str; 5] = ["ID", "Name", "Currency", "Email", "Active"];
+
+/// The fields shown in the columns.
+const FIELDS: [Field; 4] =
+    [Field::Id, Field::Name, Field::Currency, Field::Email];
+
+/// Arguments for `gnucash-toolbox customer|vendor|employee list`.
+#[derive(Debug, Parser)]
+pub struct List {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// List the inactive contacts too.
+    #[arg(short, long)]
+    all: bool,
+}
+
+impl List {
+    /// Lists the contacts of the given kind.
+    #[tracing::instrument(name = "directory_list", level = "trace", skip_all)]
+    pub fn run(&self, kind: ContactKind) -> Result<()> {
+        tracing::info!(params = ?self, %kind, "running directory list");
+
+        let book = Database::open_read_only(&self.book)?.load()?;
+        let directory = Directory::new(&book, kind);
+
+        let rows: Vec<_> = directory
+            .contacts()
+            .iter()
+            .filter(|contact| self.all || directory::active(contact))
+            .map(|contact| {
+                let [id, name, currency, email] =
+                    FIELDS.map(|field| directory.value(contact, field));
+                let active = if directory::active(contact) {
+                    "yes"
+                } else {
+                    "no"
+                };
+
+                [id, name, currency, email, active.to_owned()]
+            })
+            .collect();
+
+        if rows.is_empty() {
+            println!("There is no {kind} in the book.");
+        } else {
+            print_table(HEADERS, &rows, &[]);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `customer|vendor|employee show` subcommand.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use eyre::Result;
+
+use crate::{
+    book::Database,
+    command::helpers::print_table,
+    directory::{ContactKind, Directory},
+};
+
+/// The headers of the columns.
+const HEADERS: [&str; 2] = ["Field", "Value"];
+
+/// Arguments for `gnucash-toolbox customer|vendor|employee show`.
+#[derive(Debug, Parser)]
+pub struct Show {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// The contact, given by ID or name.
+    contact: String,
+}
+
+impl Show {
+    /// Shows a contact of the given kind.
+    #[tracing::instrument(name = "directory_show", level = "trace", skip_all)]
+    pub fn run(&self, kind: ContactKind) -> Result<()> {
+        tracing::info!(params = ?self, %kind, "running directory show");
+
+        let book = Database::open_read_only(&self.book)?.load()?;
+        let directory = Directory::new(&book, kind);
+        let contact = directory.find(&self.contact)?;
+
+        let rows: Vec<_> = kind
+            .fields()
+            .iter()
+            .map(|field| {
+                [field.label().to_owned(), directory.value(contact, *field)]
+            })
+            .collect();
+
+        print_table(HEADERS, &rows, &[]);
+
+        Ok(())
+    }
+}
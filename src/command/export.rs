@@ -0,0 +1,70 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `export` subcommand.
+
+mod beancount;
+mod ledger;
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+use clap::{Parser, Subcommand};
+use eyre::{Context as _, Result};
+
+use self::{beancount::Beancount, ledger::Ledger};
+
+/// Arguments for `gnucash-toolbox export`.
+#[derive(Debug, Parser)]
+pub struct Export {
+    /// The export command to run.
+    #[command(subcommand)]
+    command: ExportCommand,
+}
+
+/// The subcommands of `gnucash-toolbox export`.
+#[derive(Debug, Subcommand)]
+pub enum ExportCommand {
+    /// Export a book to the Beancount format.
+    Beancount(Beancount),
+    /// Export a book to a ledger / hledger journal.
+    Ledger(Ledger),
+}
+
+impl super::Command for Export {
+    fn run(&self) -> Result<()> {
+        match &self.command {
+            ExportCommand::Beancount(beancount) => beancount.run(),
+            ExportCommand::Ledger(ledger) => ledger.run(),
+        }
+    }
+}
+
+/// Opens the output of an export, defaulting to the standard output.
+fn open_output(path: Option<&Path>) -> Result<Box<dyn Write>> {
+    match path {
+        Some(path) => {
+            let file = File::create(path).wrap_err_with(|| {
+                format!("failed to create {}", path.display())
+            })?;
+
+            Ok(Box::new(BufWriter::new(file)))
+        }
+        None => Ok(Box::new(BufWriter::new(io::stdout().lock()))),
+    }
+}
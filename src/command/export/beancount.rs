@@ -0,0 +1,61 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `export beancount` subcommand.
+
+use std::{io::Write as _, path::PathBuf};
+
+use clap::Parser;
+use eyre::Result;
+
+use crate::{book::Database, export::beancount, warning};
+
+/// Arguments for `gnucash-toolbox export beancount`.
+#[derive(Debug, Parser)]
+pub struct Beancount {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// The output file.
+    ///
+    /// By default, the ledger is written to the standard output.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+impl crate::command::Command for Beancount {
+    #[tracing::instrument(name = "export_beancount", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running export beancount");
+
+        let book = Database::open_read_only(&self.book)?.load()?;
+
+        let mut output = super::open_output(self.output.as_deref())?;
+        let renames = beancount::write(&book, &mut output)?;
+        output.flush()?;
+
+        if !renames.is_empty() {
+            warning!(
+                "Some names have been changed to follow the Beancount rules:"
+            );
+
+            for rename in renames {
+                eprintln!("  {} -> {}", rename.original, rename.renamed);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -15,6 +15,11 @@
 
 //! Helpers for writing CLIs.
 
+use std::{fs, path::Path};
+
+use eyre::{Context as _, Result};
+use uuid::Uuid;
+
 /// Prints a success.
 #[macro_export]
 macro_rules! success {
@@ -61,3 +66,85 @@ macro_rules! hint {
         eprintln!("{message}");
     }};
 }
+
+/// Prints rows in a table with aligned columns.
+///
+/// The columns whose index is in `right_aligned` are aligned to the right, like
+/// amounts.
+pub fn print_table<const N: usize>(
+    headers: [&str; N],
+    rows: &[[String; N]],
+    right_aligned: &[usize],
+) {
+    let mut widths = headers.map(|header| header.chars().count());
+
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    print_row(&headers.map(str::to_owned), &widths, right_aligned);
+
+    for row in rows {
+        print_row(row, &widths, right_aligned);
+    }
+}
+
+/// Prints a row of a table.
+fn print_row<const N: usize>(
+    row: &[String; N],
+    widths: &[usize; N],
+    right_aligned: &[usize],
+) {
+    let cells: Vec<_> = row
+        .iter()
+        .zip(widths)
+        .enumerate()
+        .map(|(index, (cell, &width))| {
+            if right_aligned.contains(&index) {
+                format!("{cell:>width$}")
+            } else {
+                format!("{cell:<width$}")
+            }
+        })
+        .collect();
+
+    println!("{}", cells.join("  ").trim_end());
+}
+
+/// Writes a new file at `path` with `write`.
+///
+/// When `force` is set, the file is first written next to `path` under a
+/// temporary name, then renamed over the existing file once complete, so that a
+/// failure never loses the existing file.
+pub fn write_replacing(
+    path: &Path,
+    force: bool,
+    write: impl FnOnce(&Path) -> Result<()>,
+) -> Result<()> {
+    if !force || !path.exists() {
+        return write(path);
+    }
+
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temporary =
+        path.with_file_name(format!(".{name}.{}.tmp", Uuid::new_v4().simple()));
+
+    if let Err(error) = write(&temporary) {
+        if temporary.exists() {
+            if let Err(error) = fs::remove_file(&temporary) {
+                tracing::warn!(
+                    %error,
+                    path = %temporary.display(),
+                    "failed to remove the temporary file"
+                );
+            }
+        }
+
+        return Err(error);
+    }
+
+    fs::rename(&temporary, path)
+        .wrap_err_with(|| format!("failed to replace {}", path.display()))
+}
@@ -0,0 +1,111 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `invoice` subcommand.
+
+mod list;
+mod new;
+mod pay;
+mod render;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use eyre::Result;
+use thiserror::Error;
+
+use self::{list::List, new::New, pay::Pay, render::Render};
+use crate::invoice::DocumentKind;
+
+/// Arguments for `gnucash-toolbox invoice`.
+#[derive(Debug, Parser)]
+pub struct Invoice {
+    /// The invoice command to run.
+    #[command(subcommand)]
+    command: InvoiceCommand,
+}
+
+/// The subcommands of `gnucash-toolbox invoice`.
+#[derive(Debug, Subcommand)]
+pub enum InvoiceCommand {
+    /// List the invoices, bills and expense vouchers.
+    List(List),
+    /// Create an invoice, and optionally post it.
+    New(New),
+    /// Record a payment against open documents of a customer, vendor or
+    /// employee.
+    Pay(Pay),
+    /// Render a document from a template, to HTML or Typst for instance.
+    Render(Render),
+}
+
+/// A kind of document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Kind {
+    /// An invoice to a customer.
+    Invoice,
+    /// A bill from a vendor.
+    Bill,
+    /// An expense voucher from an employee.
+    Voucher,
+}
+
+/// Errors that can occur when looking up a document.
+#[derive(Debug, Error)]
+pub enum InvoiceError {
+    /// There is no owner with the given name or ID.
+    #[error("unknown {kind}: {name}")]
+    UnknownOwner {
+        /// The type of owner.
+        kind: &'static str,
+        /// The name or ID.
+        name: String,
+    },
+    /// There is no document with the given ID.
+    #[error("there is no {kind} with the ID {id}")]
+    UnknownId {
+        /// The kind of document.
+        kind: DocumentKind,
+        /// The ID.
+        id: String,
+    },
+    /// The template cannot be rendered.
+    #[error("cannot render {template}: {source}")]
+    Template {
+        /// The path of the template.
+        template: String,
+        /// The error from the template engine.
+        source: minijinja::Error,
+    },
+}
+
+impl super::Command for Invoice {
+    fn run(&self) -> Result<()> {
+        match &self.command {
+            InvoiceCommand::List(list) => list.run(),
+            InvoiceCommand::New(new) => new.run(),
+            InvoiceCommand::Pay(pay) => pay.run(),
+            InvoiceCommand::Render(render) => render.run(),
+        }
+    }
+}
+
+impl From<Kind> for DocumentKind {
+    fn from(kind: Kind) -> Self {
+        match kind {
+            Kind::Invoice => Self::Invoice,
+            Kind::Bill => Self::Bill,
+            Kind::Voucher => Self::ExpenseVoucher,
+        }
+    }
+}
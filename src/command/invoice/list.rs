@@ -0,0 +1,180 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `invoice list` subcommand.
+
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use clap::{Parser, ValueEnum};
+use eyre::Result;
+
+use crate::{
+    book::{Book, Database},
+    command::helpers::print_table,
+    invoice::{Document, Status},
+};
+
+/// The headers of the columns.
+const HEADERS: [&str; 9] = [
+    "ID", "Type", "Owner", "Opened", "Posted", "Due", "Account", "Total",
+    "Status",
+];
+
+/// The index of the total column, which is aligned to the right.
+const TOTAL_COLUMN: usize = 7;
+
+/// Arguments for `gnucash-toolbox invoice list`.
+#[derive(Debug, Parser)]
+pub struct List {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// Only list the documents of this customer, vendor or employee, given by
+    /// name or ID.
+    #[arg(short, long)]
+    owner: Option<String>,
+    /// Only list the documents with this status.
+    #[arg(short, long)]
+    status: Option<StatusFilter>,
+    /// Only list the documents due strictly before this date.
+    #[arg(long)]
+    due_before: Option<NaiveDate>,
+    /// Only list the documents due strictly after this date.
+    #[arg(long)]
+    due_after: Option<NaiveDate>,
+}
+
+/// A filter on the status of documents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StatusFilter {
+    /// Documents not posted yet.
+    Unposted,
+    /// Posted documents without any payment.
+    Unpaid,
+    /// Posted documents partially paid.
+    Partial,
+    /// Posted documents fully paid.
+    Paid,
+    /// Posted documents not fully paid yet.
+    Open,
+}
+
+impl crate::command::Command for List {
+    #[tracing::instrument(name = "invoice_list", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running invoice list");
+
+        let book = Database::open_read_only(&self.book)?.load()?;
+
+        let rows: Vec<_> = Document::all(&book)
+            .iter()
+            .filter(|document| self.matches(&book, document))
+            .map(|document| row(&book, document))
+            .collect();
+
+        if rows.is_empty() {
+            println!("There is no matching invoice in the book.");
+        } else {
+            print_table(HEADERS, &rows, &[TOTAL_COLUMN]);
+        }
+
+        Ok(())
+    }
+}
+
+impl List {
+    /// Returns whether a document matches the filters.
+    fn matches(&self, book: &Book, document: &Document<'_>) -> bool {
+        let owner_matches = self.owner.as_ref().map_or(true, |owner| {
+            document.owner.is_some_and(|document_owner| {
+                book.business.owner_name(document_owner) == Some(owner)
+                    || book.business.owner_id(document_owner) == Some(owner)
+            })
+        });
+
+        let status_matches = self
+            .status
+            .map_or(true, |status| status.matches(document.status()));
+
+        let due_before_matches = self.due_before.map_or(true, |date| {
+            document.due_date.is_some_and(|due_date| due_date < date)
+        });
+
+        let due_after_matches = self.due_after.map_or(true, |date| {
+            document.due_date.is_some_and(|due_date| due_date > date)
+        });
+
+        owner_matches
+            && status_matches
+            && due_before_matches
+            && due_after_matches
+    }
+}
+
+impl StatusFilter {
+    /// Returns whether the status matches the filter.
+    const fn matches(self, status: Status) -> bool {
+        match self {
+            Self::Unposted => matches!(status, Status::Unposted),
+            Self::Unpaid => matches!(status, Status::Unpaid),
+            Self::Partial => matches!(status, Status::PartiallyPaid),
+            Self::Paid => matches!(status, Status::Paid),
+            Self::Open => {
+                matches!(status, Status::Unpaid | Status::PartiallyPaid)
+            }
+        }
+    }
+}
+
+/// Builds the row of the table for a document.
+fn row(book: &Book, document: &Document<'_>) -> [String; 9] {
+    let date = |date: Option<NaiveDate>| {
+        date.map_or_else(|| "-".to_owned(), |date| date.to_string())
+    };
+
+    let owner = document
+        .owner
+        .and_then(|owner| book.business.owner_name(owner))
+        .unwrap_or("-");
+
+    let account = document
+        .invoice
+        .post_account
+        .map_or_else(|| "-".to_owned(), |account| book.account_path(account));
+
+    let total = document.currency.map_or_else(
+        || document.total().to_string(),
+        |currency| {
+            format!(
+                "{} {}",
+                document.total().to_fixed(currency.fraction()),
+                currency.mnemonic
+            )
+        },
+    );
+
+    [
+        document.invoice.id.clone(),
+        document.kind.to_string(),
+        owner.to_owned(),
+        date(document.opened()),
+        date(document.posted()),
+        date(document.due_date),
+        account,
+        total,
+        document.status().to_string(),
+    ]
+}
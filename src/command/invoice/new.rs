@@ -0,0 +1,190 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `invoice new` subcommand.
+
+use std::{fs, path::PathBuf};
+
+use chrono::{Local, NaiveDate};
+use clap::Parser;
+use eyre::{Context as _, Result};
+
+use crate::{
+    book::Database,
+    invoice::draft::{EntryDraft, InvoiceDraft, PostDraft},
+    success,
+};
+
+/// Arguments for `gnucash-toolbox invoice new`.
+#[derive(Debug, Parser)]
+pub struct New {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// A TOML file describing the invoice.
+    ///
+    /// The options given on the command line take precedence over the file,
+    /// and their entries are added to the ones of the file.
+    #[arg(short, long)]
+    file: Option<PathBuf>,
+    /// The customer, given by name or ID.
+    #[arg(short, long, required_unless_present = "file")]
+    customer: Option<String>,
+    /// The ID of the invoice.
+    ///
+    /// By default, the next ID of the book counter is used, as in GnuCash.
+    #[arg(long)]
+    id: Option<String>,
+    /// The date at which the invoice is opened [default: today].
+    #[arg(short, long)]
+    date: Option<NaiveDate>,
+    /// The billing ID, like the reference of a purchase order.
+    #[arg(long)]
+    billing_id: Option<String>,
+    /// The notes of the invoice.
+    #[arg(long)]
+    notes: Option<String>,
+    /// The billing terms, instead of those of the customer.
+    #[arg(long)]
+    terms: Option<String>,
+    /// The tax table of the entries, instead of the one of the customer.
+    #[arg(long)]
+    tax_table: Option<String>,
+    /// The entries, as `ACCOUNT=QUANTITY@PRICE:DESCRIPTION`.
+    ///
+    /// The account is the income account of the entry.
+    #[arg(
+        short,
+        long = "entry",
+        required_unless_present = "file",
+        value_parser = parse_entry
+    )]
+    entries: Vec<EntryDraft>,
+    /// Post the invoice to this accounts receivable account.
+    #[arg(short, long)]
+    post: Option<String>,
+    /// The date at which the invoice is posted [default: the opening date].
+    #[arg(long, requires = "post")]
+    post_date: Option<NaiveDate>,
+    /// The date at which the invoice is due [default: from the billing terms].
+    #[arg(long, requires = "post")]
+    due: Option<NaiveDate>,
+}
+
+impl crate::command::Command for New {
+    #[tracing::instrument(name = "invoice_new", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running invoice new");
+
+        let mut draft = match &self.file {
+            Some(file) => {
+                let input = fs::read_to_string(file).wrap_err_with(|| {
+                    format!("failed to read {}", file.display())
+                })?;
+                InvoiceDraft::from_toml(&input)?
+            }
+            None => InvoiceDraft::default(),
+        };
+
+        self.merge_into(&mut draft);
+
+        let mut database = Database::open(&self.book)?;
+        let mut book = database.load()?;
+
+        let new_invoice = draft.build(&book, Local::now().date_naive())?;
+        new_invoice.update_counter(&mut book);
+
+        database.insert_invoice(
+            &book,
+            &new_invoice.invoice,
+            &new_invoice.entries,
+            new_invoice.posting.as_ref(),
+        )?;
+
+        let invoice = &new_invoice.invoice;
+        let total = book.commodity(invoice.currency).map_or_else(
+            || new_invoice.total.to_string(),
+            |currency| {
+                format!(
+                    "{} {}",
+                    new_invoice.total.to_fixed(currency.fraction()),
+                    currency.mnemonic
+                )
+            },
+        );
+
+        match invoice.post_account {
+            Some(account) => success!(
+                "The invoice {} for {total} has been created and posted to \
+                {}.",
+                invoice.id,
+                book.account_path(account)
+            ),
+            None => {
+                success!(
+                    "The invoice {} for {total} has been created.",
+                    invoice.id
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl New {
+    /// Merges the options given on the command line into the draft.
+    fn merge_into(&self, draft: &mut InvoiceDraft) {
+        draft.customer = self.customer.clone().or(draft.customer.take());
+        draft.id = self.id.clone().or(draft.id.take());
+        draft.date = self.date.or(draft.date);
+        draft.billing_id = self.billing_id.clone().or(draft.billing_id.take());
+        draft.notes = self.notes.clone().or(draft.notes.take());
+        draft.terms = self.terms.clone().or(draft.terms.take());
+        draft.tax_table = self.tax_table.clone().or(draft.tax_table.take());
+
+        draft.entries.extend(self.entries.iter().cloned());
+
+        if let Some(account) = &self.post {
+            let post = draft.post.get_or_insert_with(PostDraft::default);
+            post.account.clone_from(account);
+            post.date = self.post_date.or(post.date);
+            post.due = self.due.or(post.due);
+        }
+    }
+}
+
+/// Parses an entry given as `ACCOUNT=QUANTITY@PRICE[:DESCRIPTION]`.
+fn parse_entry(s: &str) -> Result<EntryDraft, String> {
+    let error =
+        || format!("expected ACCOUNT=QUANTITY@PRICE:DESCRIPTION, got {s:?}");
+
+    let (account, rest) = s.split_once('=').ok_or_else(error)?;
+    let (amounts, description) = rest.split_once(':').unwrap_or((rest, ""));
+    let (quantity, price) = amounts.split_once('@').ok_or_else(error)?;
+
+    Ok(EntryDraft {
+        description: description.trim().to_owned(),
+        quantity: parse_amount(quantity)?,
+        price: parse_amount(price)?,
+        account: account.trim().to_owned(),
+        ..EntryDraft::default()
+    })
+}
+
+/// Parses an amount.
+fn parse_amount(s: &str) -> Result<crate::book::Numeric, String> {
+    s.trim().parse().map_err(|error| format!("{error}"))
+}
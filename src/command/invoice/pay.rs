@@ -0,0 +1,209 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `invoice pay` subcommand.
+
+use std::path::PathBuf;
+
+use chrono::{Local, NaiveDate};
+use clap::{ArgGroup, Parser};
+use eyre::Result;
+
+use super::InvoiceError;
+use crate::{
+    book::{Commodity, Database, Numeric, OwnerType},
+    command::helpers::print_table,
+    invoice::payment::{Allocation, PaymentDraft},
+    success,
+};
+
+/// The headers of the columns.
+const HEADERS: [&str; 3] = ["ID", "Paid", "Left to pay"];
+
+/// Arguments for `gnucash-toolbox invoice pay`.
+#[derive(Debug, Parser)]
+#[command(group(
+    ArgGroup::new("owner")
+        .required(true)
+        .args(["customer", "vendor", "employee"]),
+))]
+pub struct Pay {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// The customer paying its invoices, given by name or ID.
+    #[arg(short, long)]
+    customer: Option<String>,
+    /// The vendor whose bills are paid, given by name or ID.
+    #[arg(long)]
+    vendor: Option<String>,
+    /// The employee whose expense vouchers are paid, given by name or ID.
+    #[arg(long)]
+    employee: Option<String>,
+    /// The transfer account, like a bank account.
+    #[arg(short, long)]
+    account: String,
+    /// The date of the payment.
+    #[arg(short, long, default_value_t = Local::now().date_naive())]
+    date: NaiveDate,
+    /// The number of the payment, like a check number.
+    #[arg(short, long, default_value = "")]
+    num: String,
+    /// The memo of the payment.
+    #[arg(short, long, default_value = "")]
+    memo: String,
+    /// The documents to pay, in order, as `ID` or `ID=AMOUNT`.
+    ///
+    /// By default, the open documents of the owner are paid by due date, oldest
+    /// first. Whatever remains of the payment is kept as a pre-payment.
+    #[arg(short = 'i', long = "invoice", value_parser = parse_allocation)]
+    allocations: Vec<Allocation>,
+    /// The accounts receivable or payable account of the payment.
+    ///
+    /// By default, it is the posting account of the documents paid. It is
+    /// required for a pre-payment when there is no document to pay.
+    #[arg(long)]
+    post_account: Option<String>,
+    /// The amount of the payment.
+    #[arg(value_parser = parse_amount)]
+    amount: Numeric,
+}
+
+impl crate::command::Command for Pay {
+    #[tracing::instrument(name = "invoice_pay", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running invoice pay");
+
+        let mut database = Database::open(&self.book)?;
+        let book = database.load()?;
+
+        let (kind, name) = self.owner();
+        let owner = book.business.find_owner(kind, name).ok_or_else(|| {
+            InvoiceError::UnknownOwner {
+                kind: owner_kind_name(kind),
+                name: name.to_owned(),
+            }
+        })?;
+
+        let draft = PaymentDraft {
+            owner,
+            account: self.account.clone(),
+            amount: self.amount,
+            date: self.date,
+            num: self.num.clone(),
+            memo: self.memo.clone(),
+            allocations: self.allocations.clone(),
+            post_account: self.post_account.clone(),
+        };
+
+        let payment = draft.build(&book)?;
+
+        database.insert_payment(
+            &book,
+            &payment.transaction,
+            payment.prepayment.as_ref(),
+            &payment.closed_lots,
+        )?;
+
+        let currency = book.commodity(payment.transaction.currency);
+        let amount = |amount: Numeric| format_amount(amount, currency);
+
+        let mut rows: Vec<_> = payment
+            .applied
+            .iter()
+            .map(|(document, paid)| {
+                [
+                    document.invoice.id.clone(),
+                    amount(*paid),
+                    amount(document.balance - *paid),
+                ]
+            })
+            .collect();
+
+        if payment.prepayment.is_some() {
+            let paid = payment.applied.iter().map(|(_, paid)| *paid).sum();
+            rows.push([
+                "pre-payment".to_owned(),
+                amount(self.amount - paid),
+                "-".to_owned(),
+            ]);
+        }
+
+        print_table(HEADERS, &rows, &[1, 2]);
+        println!();
+        success!(
+            "The payment of {} for {} has been recorded.",
+            amount(self.amount),
+            payment.transaction.description
+        );
+
+        Ok(())
+    }
+}
+
+impl Pay {
+    /// Returns the type and name of the owner.
+    fn owner(&self) -> (OwnerType, &str) {
+        match (&self.customer, &self.vendor, &self.employee) {
+            (Some(customer), _, _) => (OwnerType::Customer, customer),
+            (None, Some(vendor), _) => (OwnerType::Vendor, vendor),
+            (None, None, employee) => {
+                (OwnerType::Employee, employee.as_deref().unwrap_or_default())
+            }
+        }
+    }
+}
+
+/// Returns the name of a type of owner.
+const fn owner_kind_name(kind: OwnerType) -> &'static str {
+    match kind {
+        OwnerType::Customer => "customer",
+        OwnerType::Job => "job",
+        OwnerType::Vendor => "vendor",
+        OwnerType::Employee => "employee",
+    }
+}
+
+/// Formats an amount with its currency.
+fn format_amount(amount: Numeric, currency: Option<&Commodity>) -> String {
+    currency.map_or_else(
+        || amount.to_string(),
+        |currency| {
+            format!(
+                "{} {}",
+                amount.to_fixed(currency.fraction()),
+                currency.mnemonic
+            )
+        },
+    )
+}
+
+/// Parses a document to pay given as `ID[=AMOUNT]`.
+fn parse_allocation(s: &str) -> Result<Allocation, String> {
+    let (id, amount) = match s.split_once('=') {
+        Some((id, amount)) => (id, Some(parse_amount(amount)?)),
+        None => (s, None),
+    };
+
+    Ok(Allocation {
+        id: id.trim().to_owned(),
+        amount,
+    })
+}
+
+/// Parses an amount.
+fn parse_amount(s: &str) -> Result<Numeric, String> {
+    s.trim().parse().map_err(|error| format!("{error}"))
+}
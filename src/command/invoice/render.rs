@@ -0,0 +1,117 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `invoice render` subcommand.
+
+use std::{fs, path::PathBuf};
+
+use clap::Parser;
+use eyre::{Context as _, Result};
+
+use super::{InvoiceError, Kind};
+use crate::{
+    book::Database,
+    invoice::{render::render, Document},
+    success,
+};
+
+/// Arguments for `gnucash-toolbox invoice render`.
+#[derive(Debug, Parser)]
+pub struct Render {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// The kind of document.
+    #[arg(short, long, default_value = "invoice")]
+    kind: Kind,
+    /// The template, using the Jinja syntax.
+    ///
+    /// Values are escaped for HTML in `.html` templates and for Typst markup
+    /// in `.typ` templates. Templates are given:
+    ///
+    /// - `company`: `name`, `address`, `id`, `contact`, `phone`, `fax`,
+    ///   `email` and `website`, from the book options;
+    ///
+    /// - `invoice`: `id`, `kind`, `credit_note`, `billing_id`, `notes`,
+    ///   `opened`, `posted`, `due`, `terms`, `currency` and `status`;
+    ///
+    /// - `owner`: `kind`, `id`, `name`, `address` and `shipping_address`, each
+    ///   address having a `name`, `lines`, `phone`, `fax` and `email`;
+    ///
+    /// - `job`: `id`, `name` and `reference`;
+    ///
+    /// - `entries`: `date`, `description`, `action`, `notes`, `quantity`,
+    ///   `price`, `discount`, `value`, `taxes`, `tax` and `total`;
+    ///
+    /// - `taxes`, the taxes by account and rate: `name`, `rate` and `amount`;
+    ///
+    /// - `totals`: `subtotal`, `tax`, `total`, `paid` and `due`.
+    #[arg(short, long)]
+    template: PathBuf,
+    /// The output file, instead of the standard output.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// The ID of the document.
+    id: String,
+}
+
+impl crate::command::Command for Render {
+    #[tracing::instrument(name = "invoice_render", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running invoice render");
+
+        let book = Database::open_read_only(&self.book)?.load()?;
+
+        let document = Document::find(&book, self.kind.into(), &self.id)
+            .ok_or_else(|| InvoiceError::UnknownId {
+                kind: self.kind.into(),
+                id: self.id.clone(),
+            })?;
+
+        let template =
+            fs::read_to_string(&self.template).wrap_err_with(|| {
+                format!("failed to read {}", self.template.display())
+            })?;
+
+        let name = self.template.file_name().map_or_else(String::new, |name| {
+            name.to_string_lossy().into_owned()
+        });
+
+        let rendered =
+            render(&book, &document, &name, &template).map_err(|source| {
+                InvoiceError::Template {
+                    template: self.template.display().to_string(),
+                    source,
+                }
+            })?;
+
+        if let Some(output) = &self.output {
+            fs::write(output, rendered).wrap_err_with(|| {
+                format!("failed to write {}", output.display())
+            })?;
+
+            success!(
+                "The {} {} has been rendered to {}.",
+                document.kind,
+                self.id,
+                output.display()
+            );
+        } else {
+            print!("{rendered}");
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `migrate` subcommand.
+
+mod trading_accounts;
+
+use clap::{Parser, Subcommand};
+use eyre::Result;
+
+use self::trading_accounts::TradingAccounts;
+pub use self::trading_accounts::TradingAccountsDisabled;
+
+/// Arguments for `gnucash-toolbox migrate`.
+#[derive(Debug, Parser)]
+pub struct Migrate {
+    /// The migration to run.
+    #[command(subcommand)]
+    command: MigrateCommand,
+}
+
+/// The subcommands of `gnucash-toolbox migrate`.
+#[derive(Debug, Subcommand)]
+pub enum MigrateCommand {
+    /// Add the missing trading splits after enabling trading accounts.
+    TradingAccounts(TradingAccounts),
+}
+
+impl super::Command for Migrate {
+    fn run(&self) -> Result<()> {
+        match &self.command {
+            MigrateCommand::TradingAccounts(trading_accounts) => {
+                trading_accounts.run()
+            }
+        }
+    }
+}
@@ -0,0 +1,81 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `migrate trading-accounts` subcommand.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use eyre::Result;
+use thiserror::Error;
+
+use crate::{book::Database, success, trading};
+
+/// Arguments for `gnucash-toolbox migrate trading-accounts`.
+#[derive(Debug, Parser)]
+pub struct TradingAccounts {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// Show the splits to add without modifying the book.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+/// The book does not use trading accounts.
+#[derive(Debug, Error)]
+#[error("the book does not use trading accounts")]
+pub struct TradingAccountsDisabled;
+
+impl crate::command::Command for TradingAccounts {
+    #[tracing::instrument(
+        name = "migrate_trading_accounts",
+        level = "trace",
+        skip_all
+    )]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running migrate trading-accounts");
+
+        let mut database = Database::open(&self.book)?;
+        let mut book = database.load()?;
+
+        if !trading::is_enabled(&book) {
+            return Err(TradingAccountsDisabled.into());
+        }
+
+        let added = trading::add_trading_splits(&mut book);
+
+        for account in &added.accounts {
+            println!("New account: {}", book.account_path(account.guid));
+        }
+
+        if self.dry_run {
+            success!(
+                "{} trading splits to add to {} transactions.",
+                added.splits.len(),
+                added.transactions
+            );
+        } else {
+            database.insert_splits(&book, &added.accounts, &added.splits)?;
+            success!(
+                "{} trading splits added to {} transactions.",
+                added.splits.len(),
+                added.transactions
+            );
+        }
+
+        Ok(())
+    }
+}
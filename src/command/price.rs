@@ -0,0 +1,46 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `price` subcommand.
+
+mod convert;
+
+use clap::{Parser, Subcommand};
+use eyre::Result;
+
+use self::convert::Convert;
+
+/// Arguments for `gnucash-toolbox price`.
+#[derive(Debug, Parser)]
+pub struct Price {
+    /// The price command to run.
+    #[command(subcommand)]
+    command: PriceCommand,
+}
+
+/// The subcommands of `gnucash-toolbox price`.
+#[derive(Debug, Subcommand)]
+pub enum PriceCommand {
+    /// Convert an amount using the price database.
+    Convert(Convert),
+}
+
+impl super::Command for Price {
+    fn run(&self) -> Result<()> {
+        match &self.command {
+            PriceCommand::Convert(convert) => convert.run(),
+        }
+    }
+}
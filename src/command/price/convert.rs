@@ -0,0 +1,112 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `price convert` subcommand.
+
+use std::path::PathBuf;
+
+use chrono::{Local, NaiveDate};
+use clap::{Parser, ValueEnum};
+use eyre::Result;
+
+use crate::{
+    book::{Commodity, Database, Numeric},
+    conversion::{self, Converter, RateMethod},
+    report::period::Period,
+};
+
+/// Arguments for `gnucash-toolbox price convert`.
+#[derive(Debug, Parser)]
+pub struct Convert {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// The date of the rate.
+    #[arg(short, long, default_value_t = Local::now().date_naive())]
+    date: NaiveDate,
+    /// The period of the rate, instead of a date.
+    ///
+    /// It can be a year like 2024, a quarter like 2024-Q1, a month like
+    /// 2024-03, or a range of dates like 2024-01-01..2024-06-30.
+    #[arg(short, long, conflicts_with = "date")]
+    period: Option<Period>,
+    /// How to choose the rate among the prices.
+    #[arg(short, long, default_value = "last-before")]
+    method: Method,
+    /// The currency through which to triangulate rates.
+    ///
+    /// Defaults to the currency used by most accounts.
+    #[arg(long)]
+    via: Option<String>,
+    /// The amount to convert.
+    amount: Numeric,
+    /// The commodity of the amount.
+    from: String,
+    /// The commodity to convert to.
+    to: String,
+}
+
+/// How to choose a rate among the prices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Method {
+    /// The price nearest to the date, before or after it.
+    Nearest,
+    /// The last price on or before the date.
+    LastBefore,
+    /// The average of the prices during the period.
+    Average,
+}
+
+impl crate::command::Command for Convert {
+    #[tracing::instrument(name = "price_convert", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running price convert");
+
+        let book = Database::open_read_only(&self.book)?.load()?;
+
+        let method = match self.method {
+            Method::Nearest => RateMethod::Nearest,
+            Method::LastBefore => RateMethod::LastBefore,
+            Method::Average => RateMethod::Average,
+        };
+
+        let base = self
+            .via
+            .as_deref()
+            .map(|via| conversion::find_commodity(&book, via))
+            .transpose()?;
+
+        let from = conversion::find_commodity(&book, &self.from)?;
+        let to = conversion::find_commodity(&book, &self.to)?;
+
+        let converter = Converter::new(&book, method, base);
+        let period = self.period.unwrap_or(Period::day(self.date));
+
+        let rate = converter.rate(from, to, period)?;
+        let amount = self.amount * rate;
+        let fraction = book.commodity(to).map_or(100, Commodity::fraction);
+
+        println!(
+            "{} {} = {} {} (rate: {})",
+            self.amount,
+            self.from,
+            amount.to_fixed(fraction),
+            self.to,
+            rate.round_to(1_000_000),
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,226 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `reconcile` subcommand.
+
+use std::{
+    io::{self, BufRead as _, Write as _},
+    path::PathBuf,
+};
+
+use chrono::NaiveDate;
+use clap::Parser;
+use eyre::Result;
+use thiserror::Error;
+
+use crate::{
+    book::{Book, Commodity, Database, Numeric},
+    reconcile::Reconciliation,
+    success, warning,
+};
+
+/// Arguments for `gnucash-toolbox reconcile`.
+#[derive(Debug, Parser)]
+pub struct Reconcile {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// The statement date.
+    #[arg(short, long)]
+    date: NaiveDate,
+    /// The statement ending balance.
+    #[arg(short = 'B', long, allow_hyphen_values = true)]
+    balance: Numeric,
+    /// Select the splits matching the statement automatically.
+    #[arg(short, long)]
+    auto: bool,
+    /// The account to reconcile, like `Assets:Bank:Checking`.
+    account: String,
+}
+
+/// Errors when reconciling an account.
+#[derive(Debug, Error)]
+pub enum ReconcileError {
+    /// The account does not exist.
+    #[error("unknown account {0}")]
+    UnknownAccount(String),
+    /// No combination of splits matches the statement.
+    #[error("no combination of splits matches the statement balance")]
+    NoMatch,
+}
+
+/// What to do after a prompt.
+enum Action {
+    /// Keep on toggling splits.
+    Continue,
+    /// Save the reconciliation.
+    Finish,
+    /// Quit without saving.
+    Quit,
+}
+
+impl super::Command for Reconcile {
+    #[tracing::instrument(name = "reconcile", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running reconcile");
+
+        let mut database = Database::open(&self.book)?;
+        let book = database.load()?;
+
+        let account = book.find_account(&self.account).ok_or_else(|| {
+            ReconcileError::UnknownAccount(self.account.clone())
+        })?;
+
+        let mut reconciliation =
+            Reconciliation::new(&book, account, self.date, self.balance);
+
+        let confirmed = if self.auto {
+            if !reconciliation.auto_match() {
+                return Err(ReconcileError::NoMatch.into());
+            }
+
+            true
+        } else {
+            select_interactively(&book, &mut reconciliation)?
+        };
+
+        if !confirmed {
+            warning!("Reconciliation cancelled, nothing has been saved.");
+            return Ok(());
+        }
+
+        database.reconcile(
+            &reconciliation.reconciled_account(),
+            &reconciliation.selected_splits(),
+            reconciliation.reconcile_date(),
+        )?;
+
+        success!("{} has been reconciled on {}.", self.account, self.date);
+
+        Ok(())
+    }
+}
+
+/// Lets the user toggle splits until the difference is zero.
+///
+/// Returns whether the reconciliation has to be saved.
+fn select_interactively(
+    book: &Book,
+    reconciliation: &mut Reconciliation<'_>,
+) -> Result<bool> {
+    let fraction = book
+        .account_commodity(reconciliation.account.guid)
+        .map_or(100, Commodity::fraction);
+
+    let mut lines = io::stdin().lock().lines();
+
+    loop {
+        print_status(book, reconciliation, fraction);
+        print!(
+            "Toggle splits by number, or: a (auto match), d (done), q (quit): "
+        );
+        io::stdout().flush()?;
+
+        let Some(line) = lines.next().transpose()? else {
+            println!();
+            return Ok(false);
+        };
+
+        match handle_input(reconciliation, line.trim(), fraction) {
+            Action::Continue => (),
+            Action::Finish => return Ok(true),
+            Action::Quit => return Ok(false),
+        }
+    }
+}
+
+/// Handles a line of input from the user.
+fn handle_input(
+    reconciliation: &mut Reconciliation<'_>,
+    input: &str,
+    fraction: i128,
+) -> Action {
+    match input {
+        "d" | "done" => {
+            if reconciliation.is_balanced() {
+                return Action::Finish;
+            }
+
+            warning!(
+                "The difference is {}: select the splits matching the \
+                statement first.",
+                reconciliation.difference().to_fixed(fraction)
+            );
+        }
+        "q" | "quit" => return Action::Quit,
+        "a" | "auto" => {
+            if !reconciliation.auto_match() {
+                warning!("No combination of splits matches the statement.");
+            }
+        }
+        _ => {
+            for number in input.split([' ', ',']).filter(|s| !s.is_empty()) {
+                let toggled = number
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|number| number.checked_sub(1))
+                    .is_some_and(|index| reconciliation.toggle(index));
+
+                if !toggled {
+                    warning!("There is no split number {number}.");
+                }
+            }
+        }
+    }
+
+    Action::Continue
+}
+
+/// Prints the candidates and balances of the reconciliation.
+fn print_status(
+    book: &Book,
+    reconciliation: &Reconciliation<'_>,
+    fraction: i128,
+) {
+    println!(
+        "\nReconciling {} up to {}:\n",
+        book.account_path(reconciliation.account.guid),
+        reconciliation.date
+    );
+
+    let width = reconciliation.candidates.len().to_string().len();
+
+    for (index, candidate) in reconciliation.candidates.iter().enumerate() {
+        println!(
+            "[{}] {:>width$}  {}  {:<32}  {:>14}",
+            if candidate.selected { 'x' } else { ' ' },
+            index + 1,
+            candidate.transaction.date(),
+            candidate.transaction.description,
+            candidate.split.quantity.to_fixed(fraction),
+        );
+    }
+
+    if reconciliation.candidates.is_empty() {
+        println!("There is no split to reconcile.");
+    }
+
+    println!(
+        "\nStatement balance: {:>14}\nCleared balance:   {:>14}\nDifference:        {:>14}\n",
+        reconciliation.balance.to_fixed(fraction),
+        reconciliation.cleared_balance().to_fixed(fraction),
+        reconciliation.difference().to_fixed(fraction),
+    );
+}
@@ -0,0 +1,58 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `report` subcommand.
+
+mod aging;
+mod fx_gains;
+mod tax;
+mod vat;
+
+use clap::{Parser, Subcommand};
+use eyre::Result;
+
+use self::{aging::Aging, fx_gains::FxGains, tax::Tax, vat::Vat};
+
+/// Arguments for `gnucash-toolbox report`.
+#[derive(Debug, Parser)]
+pub struct Report {
+    /// The report to show.
+    #[command(subcommand)]
+    command: ReportCommand,
+}
+
+/// The subcommands of `gnucash-toolbox report`.
+#[derive(Debug, Subcommand)]
+pub enum ReportCommand {
+    /// Show the open balances of customers or vendors by days past due.
+    Aging(Aging),
+    /// Show the gains and losses on balances in foreign currencies.
+    FxGains(FxGains),
+    /// Show the totals of the tax-related accounts for a year.
+    Tax(Tax),
+    /// Show the VAT collected and paid on the documents of a period.
+    Vat(Vat),
+}
+
+impl super::Command for Report {
+    fn run(&self) -> Result<()> {
+        match &self.command {
+            ReportCommand::Aging(aging) => aging.run(),
+            ReportCommand::FxGains(fx_gains) => fx_gains.run(),
+            ReportCommand::Tax(tax) => tax.run(),
+            ReportCommand::Vat(vat) => vat.run(),
+        }
+    }
+}
@@ -0,0 +1,138 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `report aging` subcommand.
+
+use std::{collections::BTreeMap, iter, path::PathBuf};
+
+use chrono::{Local, NaiveDate};
+use clap::{ArgGroup, Parser};
+use eyre::Result;
+
+use crate::{
+    book::{Commodity, Database, Numeric},
+    command::helpers::print_table,
+    report::aging::{self, AgingRow, Side, BUCKETS},
+};
+
+/// The number of columns of the report.
+const COLUMNS: usize = BUCKETS.len() + 3;
+
+/// Arguments for `gnucash-toolbox report aging`.
+#[derive(Debug, Parser)]
+#[command(group(
+    ArgGroup::new("side").required(true).args(["receivable", "payable"]),
+))]
+pub struct Aging {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// Show the invoices due by customers.
+    #[arg(short, long)]
+    receivable: bool,
+    /// Show the bills due to vendors and employees.
+    #[arg(short, long)]
+    payable: bool,
+    /// The date of the report.
+    #[arg(short, long, default_value_t = Local::now().date_naive())]
+    date: NaiveDate,
+}
+
+impl crate::command::Command for Aging {
+    #[tracing::instrument(name = "report_aging", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running report aging");
+
+        let book = Database::open_read_only(&self.book)?.load()?;
+
+        let (side, owner_header, documents) = if self.payable {
+            // Payables are due to vendors and employees alike.
+            (Side::Payable, "Owner", "bill")
+        } else {
+            (Side::Receivable, "Customer", "invoice")
+        };
+
+        let rows = aging::aging(&book, side, self.date);
+
+        if rows.is_empty() {
+            println!("There is no open {documents} on {}.", self.date);
+            return Ok(());
+        }
+
+        let mut table: Vec<_> = rows.iter().map(row).collect();
+        table.extend(totals(&rows));
+
+        let mut headers = [""; COLUMNS];
+        headers[0] = owner_header;
+        headers[1] = "Currency";
+        headers[2..COLUMNS - 1].copy_from_slice(&BUCKETS);
+        headers[COLUMNS - 1] = "Total";
+
+        let amounts: Vec<_> = (2..COLUMNS).collect();
+        print_table(headers, &table, &amounts);
+
+        Ok(())
+    }
+}
+
+/// Builds the row of the table for the balances of an owner.
+fn row(row: &AgingRow<'_>) -> [String; COLUMNS] {
+    cells(row.owner, row.currency, &row.buckets)
+}
+
+/// Builds the total rows, one per currency.
+fn totals(rows: &[AgingRow<'_>]) -> Vec<[String; COLUMNS]> {
+    let mut totals: BTreeMap<_, (_, [Numeric; BUCKETS.len()])> =
+        BTreeMap::new();
+
+    for row in rows {
+        let key = row.currency.map(|currency| currency.guid);
+        let (_, buckets) = totals
+            .entry(key)
+            .or_insert((row.currency, [Numeric::ZERO; BUCKETS.len()]));
+
+        for (total, balance) in buckets.iter_mut().zip(row.buckets) {
+            *total += balance;
+        }
+    }
+
+    totals
+        .values()
+        .map(|(currency, buckets)| cells("Total", *currency, buckets))
+        .collect()
+}
+
+/// Builds the cells of a row.
+fn cells(
+    name: &str,
+    currency: Option<&Commodity>,
+    buckets: &[Numeric; BUCKETS.len()],
+) -> [String; COLUMNS] {
+    let fraction = currency.map_or(100, Commodity::fraction);
+    let total: Numeric = buckets.iter().sum();
+
+    let mut cells = iter::once(name.to_owned())
+        .chain(iter::once(currency.map_or_else(String::new, |currency| {
+            currency.mnemonic.clone()
+        })))
+        .chain(
+            buckets
+                .iter()
+                .chain(iter::once(&total))
+                .map(|amount| amount.to_fixed(fraction)),
+        );
+
+    [(); COLUMNS].map(|()| cells.next().unwrap_or_default())
+}
@@ -0,0 +1,134 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `report fx-gains` subcommand.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use eyre::Result;
+
+use crate::{
+    book::{Commodity, Database, Numeric},
+    command::helpers::print_table,
+    conversion::{self, ConversionError, Converter, RateMethod},
+    report::{
+        fx_gains::{self, FxGain},
+        period::Period,
+    },
+};
+
+/// The headers of the columns.
+const HEADERS: [&str; 7] = [
+    "Account",
+    "Currency",
+    "Balance",
+    "Cost",
+    "Value",
+    "Unrealised",
+    "Realised",
+];
+
+/// Arguments for `gnucash-toolbox report fx-gains`.
+#[derive(Debug, Parser)]
+pub struct FxGains {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// The period of the report.
+    ///
+    /// It can be a year like 2024, a quarter like 2024-Q1, a month like
+    /// 2024-03, or a range of dates like 2024-01-01..2024-06-30. The
+    /// unrealised gains are computed at its end.
+    #[arg(short, long)]
+    period: Period,
+    /// The reporting currency.
+    ///
+    /// Defaults to the currency used by most accounts.
+    #[arg(short, long)]
+    currency: Option<String>,
+}
+
+impl crate::command::Command for FxGains {
+    #[tracing::instrument(name = "report_fx_gains", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running report fx-gains");
+
+        let book = Database::open_read_only(&self.book)?.load()?;
+
+        let currency = match &self.currency {
+            Some(currency) => conversion::find_commodity(&book, currency)?,
+            None => conversion::main_currency(&book)
+                .ok_or(ConversionError::NoCurrency)?,
+        };
+
+        let converter =
+            Converter::new(&book, RateMethod::LastBefore, Some(currency));
+        let gains =
+            fx_gains::fx_gains(&book, &converter, currency, self.period)?;
+
+        let currency = book.commodity(currency);
+        let mnemonic = currency.map_or("", |currency| &currency.mnemonic);
+
+        if gains.is_empty() {
+            println!(
+                "There is no balance in foreign currencies {}.",
+                self.period
+            );
+            return Ok(());
+        }
+
+        let fraction = currency.map_or(100, Commodity::fraction);
+
+        let mut rows: Vec<_> = gains
+            .iter()
+            .map(|gain| {
+                [
+                    gain.account.clone(),
+                    gain.currency.mnemonic.clone(),
+                    gain.balance.to_fixed(gain.currency.fraction()),
+                    gain.cost.to_fixed(fraction),
+                    gain.value.to_fixed(fraction),
+                    gain.unrealised().to_fixed(fraction),
+                    gain.realised.to_fixed(fraction),
+                ]
+            })
+            .collect();
+
+        rows.push([
+            String::from("Total"),
+            String::new(),
+            String::new(),
+            total(&gains, |gain| gain.cost, fraction),
+            total(&gains, |gain| gain.value, fraction),
+            total(&gains, FxGain::unrealised, fraction),
+            total(&gains, |gain| gain.realised, fraction),
+        ]);
+
+        println!("Gains in {mnemonic} {}:\n", self.period);
+        print_table(HEADERS, &rows, &[2, 3, 4, 5, 6]);
+
+        Ok(())
+    }
+}
+
+/// Formats the total of an amount of the gains.
+fn total<'a>(
+    gains: &[FxGain<'a>],
+    amount: impl Fn(&FxGain<'a>) -> Numeric,
+    fraction: i128,
+) -> String {
+    gains.iter().map(amount).sum::<Numeric>().to_fixed(fraction)
+}
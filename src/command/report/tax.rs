@@ -0,0 +1,175 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `report tax` subcommand.
+
+use std::{collections::BTreeMap, fs::File, io, path::PathBuf};
+
+use chrono::Local;
+use clap::{Parser, ValueEnum};
+use eyre::{Context as _, Result};
+
+use crate::{
+    book::{Commodity, Database, Numeric},
+    command::helpers::print_table,
+    report::tax::{self, TaxLine},
+    success, warning,
+};
+
+/// The headers of the columns.
+const HEADERS: [&str; 4] = ["Code", "Account", "Payer", "Amount"];
+
+/// Arguments for `gnucash-toolbox report tax`.
+#[derive(Debug, Parser)]
+pub struct Tax {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// The year of the report.
+    #[arg(short, long)]
+    year: i32,
+    /// The format of the report.
+    #[arg(short, long, default_value = "table")]
+    format: Format,
+    /// The output file, instead of the standard output.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+/// The format of the tax report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// A table with the totals per code.
+    Table,
+    /// A TXF file, to import in tax software.
+    Txf,
+    /// A CSV file, for any kind of codes.
+    Csv,
+}
+
+impl crate::command::Command for Tax {
+    #[tracing::instrument(name = "report_tax", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running report tax");
+
+        let book = Database::open_read_only(&self.book)?.load()?;
+        let lines = tax::tax_lines(&book, self.year);
+
+        for account in tax::accounts_without_code(&book) {
+            warning!("{account} is tax-related but has no code.");
+        }
+
+        if self.format == Format::Table {
+            print_report(&lines, self.year);
+            return Ok(());
+        }
+
+        match &self.output {
+            Some(path) => {
+                let file = File::create(path).wrap_err_with(|| {
+                    format!("failed to create {}", path.display())
+                })?;
+
+                self.write(&lines, file)?;
+                success!(
+                    "The tax report has been written to {}.",
+                    path.display()
+                );
+            }
+            None => self.write(&lines, io::stdout())?,
+        }
+
+        Ok(())
+    }
+}
+
+impl Tax {
+    /// Writes the report in the TXF or CSV format.
+    fn write(
+        &self,
+        lines: &[TaxLine<'_>],
+        output: impl io::Write,
+    ) -> Result<()> {
+        match self.format {
+            Format::Txf => {
+                tax::write_txf(lines, Local::now().date_naive(), output)?;
+            }
+            Format::Csv => tax::write_csv(lines, output)?,
+            Format::Table => (),
+        }
+
+        Ok(())
+    }
+}
+
+/// Prints the report as a table, with a total for each code.
+fn print_report(lines: &[TaxLine<'_>], year: i32) {
+    if lines.is_empty() {
+        println!("There is no amount on tax-related accounts in {year}.");
+        return;
+    }
+
+    let mut rows = vec![];
+
+    for code in lines.chunk_by(|line, next| line.code == next.code) {
+        rows.extend(code.iter().map(|line| {
+            [
+                line.code.to_owned(),
+                line.account.clone(),
+                line.payer.unwrap_or_default().to_owned(),
+                amount(line.amount, line.currency),
+            ]
+        }));
+
+        if code.len() > 1 {
+            rows.extend(totals(code));
+        }
+    }
+
+    print_table(HEADERS, &rows, &[3]);
+}
+
+/// Builds the total rows of a code, one per currency.
+fn totals(lines: &[TaxLine<'_>]) -> Vec<[String; 4]> {
+    let mut totals: BTreeMap<_, (_, Numeric)> = BTreeMap::new();
+
+    for line in lines {
+        let key = line.currency.map(|currency| currency.guid);
+        let (_, total) =
+            totals.entry(key).or_insert((line.currency, Numeric::ZERO));
+        *total += line.amount;
+    }
+
+    totals
+        .values()
+        .map(|(currency, total)| {
+            [
+                String::new(),
+                String::from("Total"),
+                String::new(),
+                amount(*total, *currency),
+            ]
+        })
+        .collect()
+}
+
+/// Formats an amount with its currency.
+fn amount(amount: Numeric, currency: Option<&Commodity>) -> String {
+    let fraction = currency.map_or(100, Commodity::fraction);
+    let mnemonic = currency.map_or("", |currency| &currency.mnemonic);
+    format!("{} {mnemonic}", amount.to_fixed(fraction))
+        .trim_end()
+        .to_owned()
+}
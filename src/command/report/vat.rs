@@ -0,0 +1,182 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `report vat` subcommand.
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use clap::Parser;
+use eyre::Result;
+
+use crate::{
+    book::{Commodity, Database, Numeric},
+    command::helpers::print_table,
+    report::{
+        period::Period,
+        vat::{RateTotal, VatKind, VatReturn},
+    },
+    warning,
+};
+
+/// Arguments for `gnucash-toolbox report vat`.
+#[derive(Debug, Parser)]
+pub struct Vat {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// The period of the return.
+    ///
+    /// It can be a year like 2024, a quarter like 2024-Q1, a month like
+    /// 2024-03, or a range of dates like 2024-01-01..2024-06-30.
+    #[arg(short, long)]
+    period: Period,
+}
+
+impl crate::command::Command for Vat {
+    #[tracing::instrument(name = "report_vat", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running report vat");
+
+        let book = Database::open_read_only(&self.book)?.load()?;
+        let vat_return = VatReturn::new(&book, self.period);
+
+        if vat_return.is_empty() {
+            println!(
+                "There is no tax on the documents posted {}.",
+                self.period
+            );
+            return Ok(());
+        }
+
+        let rates: Vec<_> = vat_return
+            .rates
+            .iter()
+            .map(|total| {
+                let fraction = fraction(total.currency);
+
+                [
+                    kind(total.kind).to_owned(),
+                    total.rate.map_or_else(
+                        || String::from("fixed"),
+                        |rate| format!("{rate}%"),
+                    ),
+                    mnemonic(total.currency),
+                    total.base.to_fixed(fraction),
+                    total.tax.to_fixed(fraction),
+                ]
+            })
+            .collect();
+
+        print_table(
+            ["Kind", "Rate", "Currency", "Base", "Tax"],
+            &rates,
+            &[3, 4],
+        );
+        println!();
+
+        print_table(
+            ["Currency", "Output tax", "Input tax", "Net payable"],
+            &totals(&vat_return.rates),
+            &[1, 2, 3],
+        );
+        println!();
+
+        let accounts: Vec<_> = vat_return
+            .accounts
+            .iter()
+            .map(|check| {
+                let fraction = fraction(check.currency);
+
+                [
+                    check.account.clone(),
+                    mnemonic(check.currency),
+                    check.computed.to_fixed(fraction),
+                    check.booked.to_fixed(fraction),
+                    check.difference().to_fixed(fraction),
+                ]
+            })
+            .collect();
+
+        print_table(
+            ["Account", "Currency", "Documents", "Booked", "Difference"],
+            &accounts,
+            &[2, 3, 4],
+        );
+
+        for check in &vat_return.accounts {
+            if !check.difference().is_zero() {
+                warning!(
+                    "{} differs from the documents by {} {}.",
+                    check.account,
+                    check.difference().to_fixed(fraction(check.currency)),
+                    mnemonic(check.currency),
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Builds the total rows, one per currency.
+fn totals(rates: &[RateTotal<'_>]) -> Vec<[String; 4]> {
+    let mut totals: BTreeMap<_, (_, Numeric, Numeric)> = BTreeMap::new();
+
+    for total in rates {
+        let key = total.currency.map(|currency| currency.guid);
+        let (_, output, input) = totals.entry(key).or_insert((
+            total.currency,
+            Numeric::ZERO,
+            Numeric::ZERO,
+        ));
+
+        match total.kind {
+            VatKind::Output => *output += total.tax,
+            VatKind::Input => *input += total.tax,
+        }
+    }
+
+    totals
+        .values()
+        .map(|(currency, output, input)| {
+            let fraction = fraction(*currency);
+
+            [
+                mnemonic(*currency),
+                output.to_fixed(fraction),
+                input.to_fixed(fraction),
+                (*output - *input).to_fixed(fraction),
+            ]
+        })
+        .collect()
+}
+
+/// Returns the name of a kind of tax.
+const fn kind(kind: VatKind) -> &'static str {
+    match kind {
+        VatKind::Output => "Output",
+        VatKind::Input => "Input",
+    }
+}
+
+/// Returns the fraction of a currency.
+fn fraction(currency: Option<&Commodity>) -> i128 {
+    currency.map_or(100, Commodity::fraction)
+}
+
+/// Returns the mnemonic of a currency.
+fn mnemonic(currency: Option<&Commodity>) -> String {
+    currency.map_or_else(String::new, |currency| currency.mnemonic.clone())
+}
@@ -0,0 +1,89 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `slots` subcommand.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use eyre::Result;
+use thiserror::Error;
+
+use crate::book::{Database, Frame, Guid, SlotValue, SLOT_SEPARATOR};
+
+/// Arguments for `gnucash-toolbox slots`.
+#[derive(Debug, Parser)]
+pub struct Slots {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// The GUID of the object to dump the slots of.
+    guid: Guid,
+}
+
+/// An error when looking up an object.
+#[derive(Debug, Error)]
+#[error("there is no object with the GUID {0} in the book")]
+pub struct UnknownGuid(Guid);
+
+impl super::Command for Slots {
+    #[tracing::instrument(name = "slots", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running slots");
+
+        let book = Database::open_read_only(&self.book)?.load()?;
+        let (kind, slots) =
+            book.slots(self.guid).ok_or(UnknownGuid(self.guid))?;
+
+        if slots.is_empty() {
+            println!("The {kind} {} has no slots.", self.guid);
+        } else {
+            println!("Slots of the {kind} {}:", self.guid);
+            dump_frame(slots, "");
+        }
+
+        Ok(())
+    }
+}
+
+/// Prints the slots of a frame, one value per line with its full path.
+fn dump_frame(frame: &Frame, prefix: &str) {
+    for (name, value) in frame {
+        dump_value(value, &format!("{prefix}{name}"));
+    }
+}
+
+/// Prints a value and, for frames and lists, their content.
+fn dump_value(value: &SlotValue, path: &str) {
+    println!("  {path} ({}): {value}", value.kind());
+
+    match value {
+        SlotValue::Frame(frame) => {
+            dump_frame(frame, &format!("{path}{SLOT_SEPARATOR}"));
+        }
+        SlotValue::List(values) => {
+            for (index, value) in values.iter().enumerate() {
+                dump_value(value, &format!("{path}[{index}]"));
+            }
+        }
+        SlotValue::Int64(_)
+        | SlotValue::Double(_)
+        | SlotValue::Numeric(_)
+        | SlotValue::String(_)
+        | SlotValue::Guid(_)
+        | SlotValue::Timespec(_)
+        | SlotValue::GDate(_) => (),
+    }
+}
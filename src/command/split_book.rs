@@ -0,0 +1,90 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `split-book` subcommand.
+
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use clap::Parser;
+use eyre::Result;
+
+use crate::{
+    book::Database, closing, command::helpers::write_replacing, success,
+};
+
+/// Arguments for `gnucash-toolbox split-book`.
+#[derive(Debug, Parser)]
+pub struct SplitBook {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// The first day of the new book.
+    #[arg(long)]
+    at: NaiveDate,
+    /// The equity account balancing the opening balances.
+    ///
+    /// Balances in other currencies than the one of this account go to a child
+    /// account named after their currency, which is created if needed.
+    #[arg(short, long, default_value = "Equity:Opening Balances")]
+    equity: String,
+    /// The description of the opening balance transactions.
+    #[arg(short = 'D', long, default_value = "Opening Balances")]
+    description: String,
+    /// Overwrite the output if it already exists.
+    #[arg(short, long)]
+    force: bool,
+    /// The path of the new book.
+    output: PathBuf,
+}
+
+impl super::Command for SplitBook {
+    #[tracing::instrument(name = "split_book", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running split-book");
+
+        let book = Database::open_read_only(&self.book)?.load()?;
+        let opening = closing::opening_book(
+            &book,
+            self.at,
+            &self.equity,
+            &self.description,
+        )?;
+
+        write_replacing(&self.output, self.force, |path| {
+            Database::create(path, &opening.book)?;
+            Ok(())
+        })?;
+
+        for account in &opening.accounts {
+            println!(
+                "New account: {}",
+                opening.book.account_path(account.guid)
+            );
+        }
+
+        println!(
+            "{} opening balance transactions, {} unreconciled splits carried \
+            over.",
+            opening.openings, opening.unreconciled
+        );
+        success!(
+            "The new book has been created in {}.",
+            self.output.display()
+        );
+
+        Ok(())
+    }
+}
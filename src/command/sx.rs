@@ -0,0 +1,50 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `sx` subcommand.
+
+mod list;
+mod run;
+
+use clap::{Parser, Subcommand};
+use eyre::Result;
+
+use self::{list::List, run::SinceLastRun};
+
+/// Arguments for `gnucash-toolbox sx`.
+#[derive(Debug, Parser)]
+pub struct Sx {
+    /// The scheduled transaction command to run.
+    #[command(subcommand)]
+    command: SxCommand,
+}
+
+/// The subcommands of `gnucash-toolbox sx`.
+#[derive(Debug, Subcommand)]
+pub enum SxCommand {
+    /// List the scheduled transactions.
+    List(List),
+    /// Create the transactions due since the last run.
+    Run(SinceLastRun),
+}
+
+impl super::Command for Sx {
+    fn run(&self) -> Result<()> {
+        match &self.command {
+            SxCommand::List(list) => list.run(),
+            SxCommand::Run(run) => run.run(),
+        }
+    }
+}
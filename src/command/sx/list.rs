@@ -0,0 +1,102 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `sx list` subcommand.
+
+use std::path::PathBuf;
+
+use clap::Parser;
+use eyre::Result;
+
+use crate::{
+    book::{Database, ScheduledTransaction},
+    since_last_run::schedule_name,
+};
+
+/// Arguments for `gnucash-toolbox sx list`.
+#[derive(Debug, Parser)]
+pub struct List {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+}
+
+impl crate::command::Command for List {
+    #[tracing::instrument(name = "sx_list", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running sx list");
+
+        let book = Database::open_read_only(&self.book)?.load()?;
+
+        let mut schedules: Vec<_> = book.schedules.values().collect();
+        schedules.sort_by_key(|schedule| schedule_name(schedule));
+
+        if schedules.is_empty() {
+            println!("There is no scheduled transaction in the book.");
+        }
+
+        for (index, schedule) in schedules.into_iter().enumerate() {
+            if index > 0 {
+                println!();
+            }
+
+            print_schedule(schedule);
+        }
+
+        Ok(())
+    }
+}
+
+/// Prints a scheduled transaction.
+fn print_schedule(schedule: &ScheduledTransaction) {
+    println!("{} ({})", schedule_name(schedule), schedule.guid);
+
+    for recurrence in &schedule.recurrences {
+        println!("  Recurrence: {recurrence}");
+    }
+
+    let next = if schedule.enabled {
+        schedule
+            .next_occurrence()
+            .map_or_else(|| "none".to_owned(), |date| date.to_string())
+    } else {
+        "disabled".to_owned()
+    };
+
+    println!("  Next:       {next}");
+    println!("  Ends:       {}", end_condition(schedule));
+
+    match schedule.last_occur {
+        Some(last_occur) => println!(
+            "  Last:       {last_occur} ({} created)",
+            schedule.instance_count
+        ),
+        None => println!("  Last:       never"),
+    }
+}
+
+/// Describes when a scheduled transaction ends.
+fn end_condition(schedule: &ScheduledTransaction) -> String {
+    if let Some(end_date) = schedule.end_date {
+        format!("on {end_date}")
+    } else if schedule.has_occurrence_count() {
+        format!(
+            "after {} occurrences, {} remaining",
+            schedule.num_occur, schedule.rem_occur
+        )
+    } else {
+        "never".to_owned()
+    }
+}
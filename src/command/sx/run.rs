@@ -0,0 +1,106 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `sx run` subcommand.
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use chrono::{Local, NaiveDate};
+use clap::Parser;
+use eyre::Result;
+
+use crate::{
+    book::{Database, Numeric},
+    since_last_run::{self, schedule_name},
+    success,
+};
+
+/// Arguments for `gnucash-toolbox sx run`.
+#[derive(Debug, Parser)]
+pub struct SinceLastRun {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// Create the occurrences up to this date, included.
+    #[arg(short, long, default_value_t = Local::now().date_naive())]
+    until: NaiveDate,
+    /// The value of a variable used in formulas, as `NAME=VALUE`.
+    #[arg(long = "var", value_name = "NAME=VALUE", value_parser = parse_variable)]
+    variables: Vec<(String, Numeric)>,
+    /// Show the transactions to create without modifying the book.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+impl crate::command::Command for SinceLastRun {
+    #[tracing::instrument(name = "sx_run", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running sx run");
+
+        let mut database = Database::open(&self.book)?;
+        let book = database.load()?;
+
+        let variables: BTreeMap<_, _> =
+            self.variables.iter().cloned().collect();
+
+        let runs = book
+            .schedules
+            .values()
+            .filter(|schedule| schedule.enabled)
+            .map(|schedule| {
+                since_last_run::run(&book, schedule, self.until, &variables)
+            })
+            .filter(|run| {
+                run.as_ref().map_or(true, |run| !run.occurrences.is_empty())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for run in &runs {
+            for date in &run.occurrences {
+                println!("{date}  {}", schedule_name(&run.schedule));
+            }
+        }
+
+        let transactions: Vec<_> = runs
+            .iter()
+            .flat_map(|run| run.transactions.iter().cloned())
+            .collect();
+
+        if self.dry_run {
+            success!("{} transactions to create.", transactions.len());
+        } else {
+            let schedules: Vec<_> =
+                runs.into_iter().map(|run| run.schedule).collect();
+            database.insert_scheduled_transactions(
+                &book,
+                &transactions,
+                &schedules,
+            )?;
+            success!("{} transactions created.", transactions.len());
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses a variable given as `NAME=VALUE`.
+fn parse_variable(s: &str) -> Result<(String, Numeric), String> {
+    let (name, value) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected NAME=VALUE, got {s:?}"))?;
+    let value = value.trim().parse().map_err(|error| format!("{error}"))?;
+
+    Ok((name.trim().to_owned(), value))
+}
@@ -0,0 +1,50 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `tx` subcommand.
+
+mod add;
+mod apply;
+
+use clap::{Parser, Subcommand};
+use eyre::Result;
+
+use self::{add::Add, apply::Apply};
+
+/// Arguments for `gnucash-toolbox tx`.
+#[derive(Debug, Parser)]
+pub struct Tx {
+    /// The transaction command to run.
+    #[command(subcommand)]
+    command: TxCommand,
+}
+
+/// The subcommands of `gnucash-toolbox tx`.
+#[derive(Debug, Subcommand)]
+pub enum TxCommand {
+    /// Add a transaction to a book.
+    Add(Add),
+    /// Apply a journal file to a book.
+    Apply(Apply),
+}
+
+impl super::Command for Tx {
+    fn run(&self) -> Result<()> {
+        match &self.command {
+            TxCommand::Add(add) => add.run(),
+            TxCommand::Apply(apply) => apply.run(),
+        }
+    }
+}
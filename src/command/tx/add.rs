@@ -0,0 +1,126 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `tx add` subcommand.
+
+use std::{path::PathBuf, slice};
+
+use chrono::{Local, NaiveDate};
+use clap::Parser;
+use eyre::Result;
+
+use crate::{
+    book::{Database, PostingDraft, PriceSpec, TransactionDraft},
+    success,
+};
+
+/// Arguments for `gnucash-toolbox tx add`.
+#[derive(Debug, Parser)]
+pub struct Add {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// The date of the transaction.
+    #[arg(short, long, default_value_t = Local::now().date_naive())]
+    date: NaiveDate,
+    /// The description of the transaction.
+    #[arg(short = 'D', long, default_value = "")]
+    description: String,
+    /// The number of the transaction.
+    #[arg(short, long, default_value = "")]
+    num: String,
+    /// The notes of the transaction.
+    #[arg(long)]
+    notes: Option<String>,
+    /// The currency of the transaction.
+    ///
+    /// By default, it is the currency of the accounts of the splits.
+    #[arg(short, long)]
+    currency: Option<String>,
+    /// The splits, as `ACCOUNT=AMOUNT`.
+    ///
+    /// The amount is in the commodity of the account. When it is not the
+    /// transaction currency, a price must be given as `ACCOUNT=AMOUNT@PRICE`
+    /// for a unit price, or `ACCOUNT=AMOUNT@@TOTAL` for a total price.
+    #[arg(required = true, value_parser = parse_split)]
+    splits: Vec<PostingDraft>,
+}
+
+impl crate::command::Command for Add {
+    #[tracing::instrument(name = "tx_add", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running tx add");
+
+        let Self {
+            book: path,
+            date,
+            description,
+            num,
+            notes,
+            currency,
+            splits,
+        } = self;
+
+        let mut database = Database::open(path)?;
+        let book = database.load()?;
+
+        let draft = TransactionDraft {
+            date: *date,
+            num: num.clone(),
+            description: description.clone(),
+            notes: notes.clone(),
+            currency: currency.clone(),
+            postings: splits.clone(),
+        };
+
+        let transaction = draft.build(&book)?;
+        database.insert_transactions(&book, slice::from_ref(&transaction))?;
+
+        success!("Transaction {} added.", transaction.guid);
+
+        Ok(())
+    }
+}
+
+/// Parses a split given as `ACCOUNT=AMOUNT[@PRICE|@@TOTAL]`.
+fn parse_split(s: &str) -> Result<PostingDraft, String> {
+    let (account, amount) = s
+        .rsplit_once('=')
+        .ok_or_else(|| format!("expected ACCOUNT=AMOUNT, got {s:?}"))?;
+
+    let (amount, price) = match amount.split_once('@') {
+        Some((amount, price)) => {
+            let price = match price.strip_prefix('@') {
+                Some(total) => PriceSpec::Total(parse_amount(total)?),
+                None => PriceSpec::Unit(parse_amount(price)?),
+            };
+
+            (amount, Some(price))
+        }
+        None => (amount, None),
+    };
+
+    Ok(PostingDraft {
+        account: account.trim().to_owned(),
+        amount: Some(parse_amount(amount)?),
+        price,
+        ..PostingDraft::default()
+    })
+}
+
+/// Parses an amount.
+fn parse_amount(s: &str) -> Result<crate::book::Numeric, String> {
+    s.trim().parse().map_err(|error| format!("{error}"))
+}
@@ -0,0 +1,88 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The `tx apply` subcommand.
+
+use std::{collections::BTreeSet, fs, path::PathBuf};
+
+use clap::Parser;
+use eyre::{Context as _, Result};
+
+use crate::{book::Database, journal, success};
+
+/// Arguments for `gnucash-toolbox tx apply`.
+#[derive(Debug, Parser)]
+pub struct Apply {
+    /// The GnuCash book.
+    #[arg(short, long, env = "GNUCASH_BOOK")]
+    book: PathBuf,
+    /// Check the journal without modifying the book.
+    #[arg(long)]
+    dry_run: bool,
+    /// The journal file, in ledger format.
+    ///
+    /// Transactions already applied, identified by their `id` tag or by their
+    /// content, are skipped. Nothing is applied if any transaction is invalid.
+    file: PathBuf,
+}
+
+impl crate::command::Command for Apply {
+    #[tracing::instrument(name = "tx_apply", level = "trace", skip_all)]
+    fn run(&self) -> Result<()> {
+        tracing::info!(params = ?self, "running tx apply");
+
+        let Self {
+            book: path,
+            dry_run,
+            file,
+        } = self;
+
+        let input = fs::read_to_string(file)
+            .wrap_err_with(|| format!("failed to read {}", file.display()))?;
+        let entries = journal::parse(&input)?;
+
+        let mut database = Database::open(path)?;
+        let book = database.load()?;
+
+        let applied: BTreeSet<&str> = book
+            .transactions
+            .values()
+            .filter_map(|transaction| transaction.journal_id())
+            .collect();
+
+        let transactions = entries
+            .iter()
+            .filter(|entry| !applied.contains(entry.id.as_str()))
+            .map(|entry| entry.build(&book))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let skipped = entries.len() - transactions.len();
+
+        if *dry_run {
+            success!(
+                "{} transactions to add, {skipped} already in the book.",
+                transactions.len()
+            );
+        } else {
+            database.insert_transactions(&book, &transactions)?;
+            success!(
+                "{} transactions added, {skipped} already in the book.",
+                transactions.len()
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,240 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Conversion of amounts between commodities.
+//!
+//! Rates are taken from the price database of the book. A price of a
+//! commodity in a currency gives the rate in both directions. When there is no
+//! price between two commodities, the rate is triangulated through a base
+//! currency. Missing rates are errors, so that they are never silently taken
+//! as zero.
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use thiserror::Error;
+
+use crate::{
+    book::{Book, Guid, Numeric, MAX_FRACTION},
+    report::period::Period,
+};
+
+/// How to choose the rate for a period among the prices.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RateMethod {
+    /// The price nearest to the end of the period, before or after it.
+    Nearest,
+    /// The last price on or before the end of the period.
+    #[default]
+    LastBefore,
+    /// The average of the prices during the period.
+    Average,
+}
+
+/// A converter of amounts between commodities.
+#[derive(Debug)]
+pub struct Converter<'a> {
+    /// The book.
+    book: &'a Book,
+    /// How to choose the rates.
+    method: RateMethod,
+    /// The currency through which rates are triangulated.
+    base: Option<Guid>,
+    /// The rates between pairs of commodities, sorted by date.
+    rates: BTreeMap<(Guid, Guid), Vec<(NaiveDate, Numeric)>>,
+}
+
+/// Errors that can occur when converting amounts.
+#[derive(Debug, Error)]
+pub enum ConversionError {
+    /// There is no rate between two commodities.
+    #[error("there is no rate from {from} to {to} {period}")]
+    MissingRate {
+        /// The commodity to convert from.
+        from: String,
+        /// The commodity to convert to.
+        to: String,
+        /// The period of the rate.
+        period: Period,
+    },
+    /// A commodity is not in the book.
+    #[error("unknown commodity: {0}")]
+    UnknownCommodity(String),
+    /// The book has no account in a currency.
+    #[error("the book has no account in a currency")]
+    NoCurrency,
+}
+
+impl<'a> Converter<'a> {
+    /// Builds a converter from the prices of a book.
+    ///
+    /// When no base currency is given, the currency used by most accounts is
+    /// used to triangulate rates.
+    pub fn new(book: &'a Book, method: RateMethod, base: Option<Guid>) -> Self {
+        let mut rates: BTreeMap<_, Vec<_>> = BTreeMap::new();
+
+        for price in book.sorted_prices() {
+            let date = price.date.date_naive();
+
+            rates
+                .entry((price.commodity, price.currency))
+                .or_default()
+                .push((date, price.value));
+
+            if let Some(inverse) = price.value.recip() {
+                rates
+                    .entry((price.currency, price.commodity))
+                    .or_default()
+                    .push((date, inverse));
+            }
+        }
+
+        Self {
+            book,
+            method,
+            base: base.or_else(|| main_currency(book)),
+            rates,
+        }
+    }
+
+    /// Returns the rate from a commodity to another for a period.
+    pub fn rate(
+        &self,
+        from: Guid,
+        to: Guid,
+        period: Period,
+    ) -> Result<Numeric, ConversionError> {
+        if from == to {
+            return Ok(Numeric::ONE);
+        }
+
+        self.direct_rate(from, to, period)
+            .or_else(|| {
+                let base =
+                    self.base.filter(|&base| base != from && base != to)?;
+                Some(
+                    self.direct_rate(from, base, period)?
+                        * self.direct_rate(base, to, period)?,
+                )
+            })
+            .ok_or_else(|| ConversionError::MissingRate {
+                from: self.mnemonic(from),
+                to: self.mnemonic(to),
+                period,
+            })
+    }
+
+    /// Converts an amount from a commodity to another for a period.
+    ///
+    /// The result is not rounded.
+    pub fn convert(
+        &self,
+        amount: Numeric,
+        from: Guid,
+        to: Guid,
+        period: Period,
+    ) -> Result<Numeric, ConversionError> {
+        if amount.is_zero() {
+            return Ok(Numeric::ZERO);
+        }
+
+        Ok(amount * self.rate(from, to, period)?)
+    }
+
+    /// Returns the rate from a price between two commodities, if any.
+    fn direct_rate(
+        &self,
+        from: Guid,
+        to: Guid,
+        period: Period,
+    ) -> Option<Numeric> {
+        let rates = self.rates.get(&(from, to))?;
+
+        match self.method {
+            RateMethod::LastBefore => rates
+                .iter()
+                .rev()
+                .find(|(date, _)| *date <= period.end)
+                .map(|(_, rate)| *rate),
+            RateMethod::Nearest => rates
+                .iter()
+                .min_by_key(|(date, _)| {
+                    ((*date - period.end).num_days().abs(), *date > period.end)
+                })
+                .map(|(_, rate)| *rate),
+            RateMethod::Average => {
+                // Inverse rates have unrelated denominators, so that their
+                // exact sum over a long period would not fit in a numeric.
+                let rates: Vec<_> = rates
+                    .iter()
+                    .filter(|(date, _)| period.contains(*date))
+                    .map(|(_, rate)| rate.round_to(MAX_FRACTION))
+                    .collect();
+
+                let count =
+                    Numeric::new(rates.len().try_into().ok()?, 1).ok()?;
+                rates
+                    .into_iter()
+                    .try_fold(Numeric::ZERO, Numeric::checked_add)?
+                    .checked_div(count)
+            }
+        }
+    }
+
+    /// Returns the mnemonic of a commodity.
+    fn mnemonic(&self, guid: Guid) -> String {
+        self.book.commodity(guid).map_or_else(
+            || guid.to_string(),
+            |commodity| commodity.mnemonic.clone(),
+        )
+    }
+}
+
+/// Returns the commodity with the given mnemonic.
+///
+/// Currencies are preferred when several commodities share a mnemonic.
+pub fn find_commodity(
+    book: &Book,
+    mnemonic: &str,
+) -> Result<Guid, ConversionError> {
+    book.currency(mnemonic)
+        .or_else(|| {
+            book.commodities
+                .values()
+                .find(|commodity| commodity.mnemonic == mnemonic)
+        })
+        .map(|commodity| commodity.guid)
+        .ok_or_else(|| ConversionError::UnknownCommodity(mnemonic.to_owned()))
+}
+
+/// Returns the currency used by most accounts of a book.
+pub fn main_currency(book: &Book) -> Option<Guid> {
+    let mut counts: BTreeMap<Guid, usize> = BTreeMap::new();
+
+    for account in book.accounts.values() {
+        if let Some(commodity) =
+            account.commodity.and_then(|guid| book.commodity(guid))
+        {
+            if commodity.is_currency() {
+                *counts.entry(commodity.guid).or_default() += 1;
+            }
+        }
+    }
+
+    counts
+        .into_iter()
+        .max_by_key(|(_, count)| *count)
+        .map(|(guid, _)| guid)
+}
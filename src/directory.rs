@@ -0,0 +1,1054 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! The directory of customers, vendors and employees.
+//!
+//! Contacts are read and changed through a common set of fields, named as the
+//! columns of the CSV files used to import and export the directory. Not all
+//! fields apply to all kinds of contacts: employees have no billing terms for
+//! instance.
+
+use std::{collections::BTreeMap, fmt, io, str::FromStr};
+
+use thiserror::Error;
+
+use crate::{
+    book::{
+        Address, Book, Contact, Customer, Employee, Frame, Guid, Numeric,
+        Owner, OwnerType, TaxIncluded, Vendor,
+    },
+    conversion,
+};
+
+/// The kinds of contacts in the directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContactKind {
+    /// A customer.
+    Customer,
+    /// A vendor.
+    Vendor,
+    /// An employee.
+    Employee,
+}
+
+/// A field of a contact.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Field {
+    /// The ID.
+    Id,
+    /// The name, or the username of employees.
+    Name,
+    /// Whether the contact is active.
+    Active,
+    /// The currency, by ISO code.
+    Currency,
+    /// The billing terms, by name.
+    Terms,
+    /// The tax table, by name.
+    TaxTable,
+    /// The credit limit.
+    CreditLimit,
+    /// The notes.
+    Notes,
+    /// The name of the addressee.
+    AddressName,
+    /// The first line of the address.
+    Address1,
+    /// The second line of the address.
+    Address2,
+    /// The third line of the address.
+    Address3,
+    /// The fourth line of the address.
+    Address4,
+    /// The phone number.
+    Phone,
+    /// The fax number.
+    Fax,
+    /// The email address.
+    Email,
+}
+
+/// Changes to a contact, with the values of its fields as text.
+///
+/// Empty values clear the optional fields.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContactDraft {
+    /// The values, by field.
+    pub fields: BTreeMap<Field, String>,
+}
+
+/// The contacts of a kind, being changed.
+#[derive(Debug)]
+pub struct Directory<'a> {
+    /// The book.
+    book: &'a Book,
+    /// The kind of contacts.
+    kind: ContactKind,
+    /// The contacts, with the changes applied.
+    contacts: Vec<Contact>,
+    /// The indices of the changed contacts, in order.
+    changed: Vec<usize>,
+    /// The new value of the counter, when IDs have been generated.
+    counter: Option<i64>,
+}
+
+/// The changes made to a directory, ready to be saved.
+#[derive(Debug)]
+pub struct Changes {
+    /// The new or edited contacts.
+    pub contacts: Vec<Contact>,
+    /// The number of new contacts.
+    pub added: usize,
+    /// The new value of the counter, when IDs have been generated.
+    pub counter: Option<i64>,
+}
+
+/// Errors that can occur when changing the directory.
+#[derive(Debug, Error)]
+pub enum DirectoryError {
+    /// There is no contact with the given name or ID.
+    #[error("unknown {kind}: {name}")]
+    UnknownContact {
+        /// The kind of contact.
+        kind: ContactKind,
+        /// The name or ID.
+        name: String,
+    },
+    /// The ID is already used by another contact.
+    #[error("there is already a {kind} with the ID {id}")]
+    DuplicateId {
+        /// The kind of contact.
+        kind: ContactKind,
+        /// The ID.
+        id: String,
+    },
+    /// A new contact has no name.
+    #[error("a new {0} needs a name")]
+    MissingName(ContactKind),
+    /// A new contact has no currency.
+    #[error("a new {0} needs a currency")]
+    MissingCurrency(ContactKind),
+    /// The currency is not in the book.
+    #[error("unknown currency: {0}")]
+    UnknownCurrency(String),
+    /// The currency of a contact with documents cannot change.
+    #[error("the currency of {0} cannot change as it has documents")]
+    CurrencyInUse(String),
+    /// The billing terms do not exist.
+    #[error("unknown billing terms: {0}")]
+    UnknownTerms(String),
+    /// The tax table does not exist.
+    #[error("unknown tax table: {0}")]
+    UnknownTaxTable(String),
+    /// A value is invalid for its field.
+    #[error("invalid {field}: {value:?}")]
+    InvalidValue {
+        /// The field.
+        field: Field,
+        /// The value.
+        value: String,
+    },
+    /// A field does not apply to the kind of contact.
+    #[error("{kind}s have no {field}")]
+    NotApplicable {
+        /// The field.
+        field: Field,
+        /// The kind of contact.
+        kind: ContactKind,
+    },
+    /// A column of a CSV file is not a field.
+    #[error("unknown column: {0}")]
+    UnknownColumn(String),
+    /// A CSV file is invalid.
+    #[error("invalid CSV: {0}")]
+    Csv(#[from] csv::Error),
+    /// An error occured on a line of a CSV file.
+    #[error("line {line}: {source}")]
+    Line {
+        /// The line.
+        line: u64,
+        /// The error.
+        source: Box<Self>,
+    },
+}
+
+impl<'a> Directory<'a> {
+    /// Opens the directory of contacts of a kind, sorted by ID.
+    pub fn new(book: &'a Book, kind: ContactKind) -> Self {
+        let owner = |guid: &Guid| Owner {
+            kind: kind.owner_type(),
+            guid: *guid,
+        };
+
+        let guids: Vec<_> = match kind {
+            ContactKind::Customer => {
+                book.business.customers.keys().map(owner).collect()
+            }
+            ContactKind::Vendor => {
+                book.business.vendors.keys().map(owner).collect()
+            }
+            ContactKind::Employee => {
+                book.business.employees.keys().map(owner).collect()
+            }
+        };
+
+        let mut contacts: Vec<_> = guids
+            .into_iter()
+            .filter_map(|owner| book.business.contact(owner))
+            .collect();
+        contacts.sort_by(|lhs, rhs| id(lhs).cmp(id(rhs)));
+
+        Self {
+            book,
+            kind,
+            contacts,
+            changed: Vec::new(),
+            counter: None,
+        }
+    }
+
+    /// Returns the contacts.
+    pub fn contacts(&self) -> &[Contact] {
+        &self.contacts
+    }
+
+    /// Finds a contact by ID or name.
+    ///
+    /// IDs take precedence over names.
+    pub fn find(&self, name_or_id: &str) -> Result<&Contact, DirectoryError> {
+        self.find_by_id(name_or_id)
+            .or_else(|| {
+                self.contacts
+                    .iter()
+                    .find(|contact| name(contact) == name_or_id)
+            })
+            .ok_or_else(|| DirectoryError::UnknownContact {
+                kind: self.kind,
+                name: name_or_id.to_owned(),
+            })
+    }
+
+    /// Returns the value of a field of a contact, as text.
+    pub fn value(&self, contact: &Contact, field: Field) -> String {
+        value(self.book, contact, field)
+    }
+
+    /// Adds a new contact.
+    ///
+    /// When no ID is given or it is empty, the next one from the counter of the
+    /// book is used, as in GnuCash. Likewise, the currency defaults to the main
+    /// currency of the book.
+    pub fn add(
+        &mut self,
+        draft: &ContactDraft,
+    ) -> Result<Contact, DirectoryError> {
+        let mut fields = draft.fields.clone();
+
+        if fields.get(&Field::Name).map_or(true, String::is_empty) {
+            return Err(DirectoryError::MissingName(self.kind));
+        }
+
+        let currency = match fields
+            .remove(&Field::Currency)
+            .filter(|currency| !currency.is_empty())
+        {
+            Some(currency) => {
+                self.book
+                    .currency(&currency)
+                    .ok_or(DirectoryError::UnknownCurrency(currency))?
+                    .guid
+            }
+            None => conversion::main_currency(self.book)
+                .ok_or(DirectoryError::MissingCurrency(self.kind))?,
+        };
+
+        if fields.get(&Field::Id).map_or(true, String::is_empty) {
+            let counter = self.next_counter();
+            self.counter = Some(counter);
+            fields.insert(Field::Id, format!("{counter:06}"));
+        }
+
+        let mut contact = self.kind.new_contact(Guid::new(), currency);
+
+        for (field, value) in &fields {
+            self.set(&mut contact, *field, value)?;
+        }
+
+        self.changed.push(self.contacts.len());
+        self.contacts.push(contact.clone());
+
+        Ok(contact)
+    }
+
+    /// Edits a contact given by name or ID.
+    pub fn edit(
+        &mut self,
+        name_or_id: &str,
+        draft: &ContactDraft,
+    ) -> Result<Contact, DirectoryError> {
+        let mut contact = self.find(name_or_id)?.clone();
+
+        for (field, value) in &draft.fields {
+            self.set(&mut contact, *field, value)?;
+        }
+
+        let owner = contact.owner();
+        if let Some(index) = self
+            .contacts
+            .iter()
+            .position(|other| other.owner() == owner)
+        {
+            if let Some(previous) = self.contacts.get_mut(index) {
+                previous.clone_from(&contact);
+            }
+
+            if !self.changed.contains(&index) {
+                self.changed.push(index);
+            }
+        }
+
+        Ok(contact)
+    }
+
+    /// Imports contacts from CSV.
+    ///
+    /// The first row names the fields given in each column. Contacts are
+    /// matched by ID: the existing ones are updated, and the others are added.
+    /// The fields not given keep their value.
+    pub fn import(
+        &mut self,
+        input: impl io::Read,
+    ) -> Result<(), DirectoryError> {
+        let mut reader = csv::Reader::from_reader(input);
+
+        let fields = reader
+            .headers()?
+            .iter()
+            .map(|column| {
+                column.trim().parse::<Field>().map_err(|_error| {
+                    DirectoryError::UnknownColumn(column.to_owned())
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for record in reader.records() {
+            let record = record?;
+            let line = record.position().map_or(0, csv::Position::line);
+
+            let draft = ContactDraft {
+                fields: fields
+                    .iter()
+                    .copied()
+                    .zip(record.iter().map(|value| value.trim().to_owned()))
+                    .collect(),
+            };
+
+            let existing = draft
+                .fields
+                .get(&Field::Id)
+                .filter(|id| !id.is_empty())
+                .filter(|id| self.find_by_id(id).is_some());
+
+            let result = match existing {
+                Some(id) => self.edit(id, &draft),
+                None => self.add(&draft),
+            };
+
+            result.map_err(|source| DirectoryError::Line {
+                line,
+                source: Box::new(source),
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Exports the contacts to CSV, with all the fields of their kind.
+    pub fn export(&self, output: impl io::Write) -> Result<(), DirectoryError> {
+        let mut writer = csv::Writer::from_writer(output);
+        let fields = self.kind.fields();
+
+        writer.write_record(fields.iter().map(|field| field.as_str()))?;
+
+        for contact in &self.contacts {
+            writer.write_record(
+                fields.iter().map(|field| self.value(contact, *field)),
+            )?;
+        }
+
+        writer.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+
+    /// Returns the changes made to the directory.
+    pub fn into_changes(self) -> Changes {
+        let contacts: Vec<_> = self
+            .changed
+            .iter()
+            .filter_map(|index| self.contacts.get(*index))
+            .cloned()
+            .collect();
+
+        let added = contacts
+            .iter()
+            .filter(|contact| {
+                self.book.business.contact(contact.owner()).is_none()
+            })
+            .count();
+
+        Changes {
+            contacts,
+            added,
+            counter: self.counter,
+        }
+    }
+
+    /// Finds a contact by ID.
+    fn find_by_id(&self, id: &str) -> Option<&Contact> {
+        self.contacts.iter().find(|contact| self::id(contact) == id)
+    }
+
+    /// Returns the next value of the counter of the book.
+    ///
+    /// As for invoices, the counter is also checked against the highest
+    /// numeric ID, in case it is missing.
+    fn next_counter(&self) -> i64 {
+        let counter = self
+            .counter
+            .unwrap_or_else(|| self.book.counter(self.kind.counter()));
+        let highest = self
+            .contacts
+            .iter()
+            .filter_map(|contact| id(contact).parse::<i64>().ok())
+            .max()
+            .unwrap_or(0);
+
+        counter.max(highest).saturating_add(1)
+    }
+
+    /// Sets a field of a contact.
+    fn set(
+        &self,
+        contact: &mut Contact,
+        field: Field,
+        value: &str,
+    ) -> Result<(), DirectoryError> {
+        if !self.kind.fields().contains(&field) {
+            return Err(DirectoryError::NotApplicable {
+                field,
+                kind: self.kind,
+            });
+        }
+
+        let invalid = || DirectoryError::InvalidValue {
+            field,
+            value: value.to_owned(),
+        };
+
+        match field {
+            Field::Id => {
+                if value.is_empty() {
+                    return Err(invalid());
+                }
+
+                let owner = contact.owner();
+                if self
+                    .contacts
+                    .iter()
+                    .any(|other| id(other) == value && other.owner() != owner)
+                {
+                    return Err(DirectoryError::DuplicateId {
+                        kind: self.kind,
+                        id: value.to_owned(),
+                    });
+                }
+
+                value.clone_into(id_mut(contact));
+            }
+            Field::Name => {
+                if value.is_empty() {
+                    return Err(invalid());
+                }
+
+                value.clone_into(name_mut(contact));
+            }
+            Field::Active => {
+                *active_mut(contact) = parse_bool(value).ok_or_else(invalid)?;
+            }
+            Field::Currency => self.set_currency(contact, value)?,
+            Field::Terms => self.set_terms(contact, value)?,
+            Field::TaxTable => self.set_tax_table(contact, value)?,
+            Field::CreditLimit => {
+                let credit = if value.is_empty() {
+                    Numeric::ZERO
+                } else {
+                    value.parse().map_err(|_error| invalid())?
+                };
+
+                if let Contact::Customer(customer) = contact {
+                    customer.credit = credit;
+                }
+            }
+            Field::Notes => match contact {
+                Contact::Customer(Customer { notes, .. })
+                | Contact::Vendor(Vendor { notes, .. }) => {
+                    value.clone_into(notes);
+                }
+                Contact::Employee(_) => (),
+            },
+            Field::AddressName
+            | Field::Address1
+            | Field::Address2
+            | Field::Address3
+            | Field::Address4
+            | Field::Phone
+            | Field::Fax
+            | Field::Email => {
+                if let Some(part) =
+                    address_part_mut(address_mut(contact), field)
+                {
+                    *part = Some(value.to_owned());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sets the currency of a contact.
+    ///
+    /// It cannot change when the contact has documents, as they would not
+    /// be in its currency anymore.
+    fn set_currency(
+        &self,
+        contact: &mut Contact,
+        mnemonic: &str,
+    ) -> Result<(), DirectoryError> {
+        let currency = self
+            .book
+            .currency(mnemonic)
+            .ok_or_else(|| {
+                DirectoryError::UnknownCurrency(mnemonic.to_owned())
+            })?
+            .guid;
+
+        if self::currency(contact) != currency
+            && self.has_documents(contact.owner())
+        {
+            return Err(DirectoryError::CurrencyInUse(
+                name(contact).to_owned(),
+            ));
+        }
+
+        *currency_mut(contact) = currency;
+        Ok(())
+    }
+
+    /// Sets the billing terms of a contact.
+    fn set_terms(
+        &self,
+        contact: &mut Contact,
+        name: &str,
+    ) -> Result<(), DirectoryError> {
+        let terms = if name.is_empty() {
+            None
+        } else {
+            let terms = self
+                .book
+                .business
+                .bill_terms
+                .values()
+                .find(|term| {
+                    term.name == name
+                        && term.parent.is_none()
+                        && !term.invisible
+                })
+                .ok_or_else(|| DirectoryError::UnknownTerms(name.to_owned()))?;
+            Some(terms.guid)
+        };
+
+        match contact {
+            Contact::Customer(customer) => customer.terms = terms,
+            Contact::Vendor(vendor) => vendor.terms = terms,
+            Contact::Employee(_) => (),
+        }
+
+        Ok(())
+    }
+
+    /// Sets the tax table of a contact.
+    ///
+    /// As in the dialogs of GnuCash, the tax table is used as soon as it is
+    /// set.
+    fn set_tax_table(
+        &self,
+        contact: &mut Contact,
+        name: &str,
+    ) -> Result<(), DirectoryError> {
+        let tax_table = if name.is_empty() {
+            None
+        } else {
+            let table = self
+                .book
+                .business
+                .tax_tables
+                .values()
+                .find(|table| {
+                    table.name == name
+                        && table.parent.is_none()
+                        && !table.invisible
+                })
+                .ok_or_else(|| {
+                    DirectoryError::UnknownTaxTable(name.to_owned())
+                })?;
+            Some(table.guid)
+        };
+
+        match contact {
+            Contact::Customer(customer) => {
+                customer.tax_table = tax_table;
+                customer.tax_override = tax_table.is_some();
+            }
+            Contact::Vendor(vendor) => {
+                vendor.tax_table = tax_table;
+                vendor.tax_override = tax_table.is_some();
+            }
+            Contact::Employee(_) => (),
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether an owner has documents, directly or through its jobs.
+    fn has_documents(&self, owner: Owner) -> bool {
+        self.book.business.invoices.values().any(|invoice| {
+            invoice.owner.is_some_and(|invoice_owner| {
+                self.book.business.end_owner(invoice_owner) == owner
+            })
+        })
+    }
+}
+
+impl ContactKind {
+    /// Returns the fields of the contacts of this kind.
+    pub const fn fields(self) -> &'static [Field] {
+        match self {
+            Self::Customer => &Field::ALL,
+            Self::Vendor => &[
+                Field::Id,
+                Field::Name,
+                Field::Active,
+                Field::Currency,
+                Field::Terms,
+                Field::TaxTable,
+                Field::Notes,
+                Field::AddressName,
+                Field::Address1,
+                Field::Address2,
+                Field::Address3,
+                Field::Address4,
+                Field::Phone,
+                Field::Fax,
+                Field::Email,
+            ],
+            Self::Employee => &[
+                Field::Id,
+                Field::Name,
+                Field::Active,
+                Field::Currency,
+                Field::AddressName,
+                Field::Address1,
+                Field::Address2,
+                Field::Address3,
+                Field::Address4,
+                Field::Phone,
+                Field::Fax,
+                Field::Email,
+            ],
+        }
+    }
+
+    /// Returns the type of owner of the contacts of this kind.
+    pub const fn owner_type(self) -> OwnerType {
+        match self {
+            Self::Customer => OwnerType::Customer,
+            Self::Vendor => OwnerType::Vendor,
+            Self::Employee => OwnerType::Employee,
+        }
+    }
+
+    /// Returns the name of the counter of the book for this kind.
+    pub const fn counter(self) -> &'static str {
+        match self {
+            Self::Customer => "gncCustomer",
+            Self::Vendor => "gncVendor",
+            Self::Employee => "gncEmployee",
+        }
+    }
+
+    /// Builds a new contact of this kind, with the defaults of GnuCash.
+    fn new_contact(self, guid: Guid, currency: Guid) -> Contact {
+        match self {
+            Self::Customer => Contact::Customer(Customer {
+                guid,
+                name: String::new(),
+                id: String::new(),
+                notes: String::new(),
+                active: true,
+                discount: Numeric::ZERO,
+                credit: Numeric::ZERO,
+                currency,
+                tax_override: false,
+                address: empty_address(),
+                ship_address: empty_address(),
+                terms: None,
+                tax_included: Some(TaxIncluded::UseGlobal),
+                tax_table: None,
+                slots: Frame::default(),
+            }),
+            Self::Vendor => Contact::Vendor(Vendor {
+                guid,
+                name: String::new(),
+                id: String::new(),
+                notes: String::new(),
+                currency,
+                active: true,
+                tax_override: false,
+                address: empty_address(),
+                terms: None,
+                tax_included: Some(TaxIncluded::UseGlobal),
+                tax_table: None,
+                slots: Frame::default(),
+            }),
+            Self::Employee => Contact::Employee(Employee {
+                guid,
+                username: String::new(),
+                id: String::new(),
+                language: String::new(),
+                acl: String::new(),
+                active: true,
+                currency,
+                credit_card: None,
+                workday: Numeric::ZERO,
+                rate: Numeric::ZERO,
+                address: empty_address(),
+                slots: Frame::default(),
+            }),
+        }
+    }
+}
+
+impl Field {
+    /// All the fields, in the order of the columns of CSV files.
+    pub const ALL: [Self; 16] = [
+        Self::Id,
+        Self::Name,
+        Self::Active,
+        Self::Currency,
+        Self::Terms,
+        Self::TaxTable,
+        Self::CreditLimit,
+        Self::Notes,
+        Self::AddressName,
+        Self::Address1,
+        Self::Address2,
+        Self::Address3,
+        Self::Address4,
+        Self::Phone,
+        Self::Fax,
+        Self::Email,
+    ];
+
+    /// Returns the name of the field, as used in CSV files.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Id => "id",
+            Self::Name => "name",
+            Self::Active => "active",
+            Self::Currency => "currency",
+            Self::Terms => "terms",
+            Self::TaxTable => "tax_table",
+            Self::CreditLimit => "credit_limit",
+            Self::Notes => "notes",
+            Self::AddressName => "address_name",
+            Self::Address1 => "address1",
+            Self::Address2 => "address2",
+            Self::Address3 => "address3",
+            Self::Address4 => "address4",
+            Self::Phone => "phone",
+            Self::Fax => "fax",
+            Self::Email => "email",
+        }
+    }
+
+    /// Returns the label of the field, for humans.
+    pub const fn label(self) -> &'static str {
+        match self {
+            Self::Id => "ID",
+            Self::Name => "Name",
+            Self::Active => "Active",
+            Self::Currency => "Currency",
+            Self::Terms => "Terms",
+            Self::TaxTable => "Tax table",
+            Self::CreditLimit => "Credit limit",
+            Self::Notes => "Notes",
+            Self::AddressName => "Address name",
+            Self::Address1 => "Address 1",
+            Self::Address2 => "Address 2",
+            Self::Address3 => "Address 3",
+            Self::Address4 => "Address 4",
+            Self::Phone => "Phone",
+            Self::Fax => "Fax",
+            Self::Email => "Email",
+        }
+    }
+}
+
+impl FromStr for Field {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|field| field.as_str() == s)
+            .ok_or(())
+    }
+}
+
+impl fmt::Display for ContactKind {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Customer => write!(formatter, "customer"),
+            Self::Vendor => write!(formatter, "vendor"),
+            Self::Employee => write!(formatter, "employee"),
+        }
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}", self.as_str().replace('_', " "))
+    }
+}
+
+/// Returns the value of a field of a contact, as text.
+fn value(book: &Book, contact: &Contact, field: Field) -> String {
+    let text = |value: Option<&str>| value.unwrap_or_default().to_owned();
+
+    match (field, contact) {
+        (Field::Id, _) => id(contact).to_owned(),
+        (Field::Name, _) => name(contact).to_owned(),
+        (Field::Active, _) => active(contact).to_string(),
+        (Field::Currency, _) => text(
+            book.commodity(currency(contact))
+                .map(|currency| currency.mnemonic.as_str()),
+        ),
+        (Field::Terms, _) => text(
+            contact
+                .terms()
+                .and_then(|guid| book.business.bill_terms.get(&guid))
+                .map(|terms| terms.name.as_str()),
+        ),
+        (Field::TaxTable, _) => text(
+            contact
+                .tax_table()
+                .and_then(|guid| book.business.tax_tables.get(&guid))
+                .map(|table| table.name.as_str()),
+        ),
+        (Field::CreditLimit, Contact::Customer(customer)) => {
+            customer.credit.to_string()
+        }
+        (
+            Field::Notes,
+            Contact::Customer(Customer { notes, .. })
+            | Contact::Vendor(Vendor { notes, .. }),
+        ) => notes.clone(),
+        (Field::CreditLimit | Field::Notes, _) => String::new(),
+        (
+            Field::AddressName
+            | Field::Address1
+            | Field::Address2
+            | Field::Address3
+            | Field::Address4
+            | Field::Phone
+            | Field::Fax
+            | Field::Email,
+            _,
+        ) => text(address_part(address(contact), field)),
+    }
+}
+
+/// Returns the ID of a contact.
+fn id(contact: &Contact) -> &str {
+    match contact {
+        Contact::Customer(customer) => &customer.id,
+        Contact::Vendor(vendor) => &vendor.id,
+        Contact::Employee(employee) => &employee.id,
+    }
+}
+
+/// Returns the name of a contact.
+///
+/// Employees are named by their username.
+pub fn name(contact: &Contact) -> &str {
+    match contact {
+        Contact::Customer(customer) => &customer.name,
+        Contact::Vendor(vendor) => &vendor.name,
+        Contact::Employee(employee) => &employee.username,
+    }
+}
+
+/// Returns whether a contact is active.
+pub const fn active(contact: &Contact) -> bool {
+    match contact {
+        Contact::Customer(customer) => customer.active,
+        Contact::Vendor(vendor) => vendor.active,
+        Contact::Employee(employee) => employee.active,
+    }
+}
+
+/// Returns the currency of a contact.
+const fn currency(contact: &Contact) -> Guid {
+    match contact {
+        Contact::Customer(customer) => customer.currency,
+        Contact::Vendor(vendor) => vendor.currency,
+        Contact::Employee(employee) => employee.currency,
+    }
+}
+
+/// Returns the address of a contact.
+const fn address(contact: &Contact) -> &Address {
+    match contact {
+        Contact::Customer(customer) => &customer.address,
+        Contact::Vendor(vendor) => &vendor.address,
+        Contact::Employee(employee) => &employee.address,
+    }
+}
+
+/// Returns a mutable reference to the ID of a contact.
+fn id_mut(contact: &mut Contact) -> &mut String {
+    match contact {
+        Contact::Customer(customer) => &mut customer.id,
+        Contact::Vendor(vendor) => &mut vendor.id,
+        Contact::Employee(employee) => &mut employee.id,
+    }
+}
+
+/// Returns a mutable reference to the name of a contact.
+fn name_mut(contact: &mut Contact) -> &mut String {
+    match contact {
+        Contact::Customer(customer) => &mut customer.name,
+        Contact::Vendor(vendor) => &mut vendor.name,
+        Contact::Employee(employee) => &mut employee.username,
+    }
+}
+
+/// Returns a mutable reference to the active flag of a contact.
+fn active_mut(contact: &mut Contact) -> &mut bool {
+    match contact {
+        Contact::Customer(customer) => &mut customer.active,
+        Contact::Vendor(vendor) => &mut vendor.active,
+        Contact::Employee(employee) => &mut employee.active,
+    }
+}
+
+/// Returns a mutable reference to the currency of a contact.
+fn currency_mut(contact: &mut Contact) -> &mut Guid {
+    match contact {
+        Contact::Customer(customer) => &mut customer.currency,
+        Contact::Vendor(vendor) => &mut vendor.currency,
+        Contact::Employee(employee) => &mut employee.currency,
+    }
+}
+
+/// Returns a mutable reference to the address of a contact.
+fn address_mut(contact: &mut Contact) -> &mut Address {
+    match contact {
+        Contact::Customer(customer) => &mut customer.address,
+        Contact::Vendor(vendor) => &mut vendor.address,
+        Contact::Employee(employee) => &mut employee.address,
+    }
+}
+
+/// Returns the part of an address corresponding to a field.
+fn address_part(address: &Address, field: Field) -> Option<&str> {
+    match field {
+        Field::AddressName => address.name.as_deref(),
+        Field::Address1 => address.addr1.as_deref(),
+        Field::Address2 => address.addr2.as_deref(),
+        Field::Address3 => address.addr3.as_deref(),
+        Field::Address4 => address.addr4.as_deref(),
+        Field::Phone => address.phone.as_deref(),
+        Field::Fax => address.fax.as_deref(),
+        Field::Email => address.email.as_deref(),
+        Field::Id
+        | Field::Name
+        | Field::Active
+        | Field::Currency
+        | Field::Terms
+        | Field::TaxTable
+        | Field::CreditLimit
+        | Field::Notes => None,
+    }
+}
+
+/// Returns a mutable reference to the part of an address corresponding to a
+/// field.
+fn address_part_mut(
+    address: &mut Address,
+    field: Field,
+) -> Option<&mut Option<String>> {
+    match field {
+        Field::AddressName => Some(&mut address.name),
+        Field::Address1 => Some(&mut address.addr1),
+        Field::Address2 => Some(&mut address.addr2),
+        Field::Address3 => Some(&mut address.addr3),
+        Field::Address4 => Some(&mut address.addr4),
+        Field::Phone => Some(&mut address.phone),
+        Field::Fax => Some(&mut address.fax),
+        Field::Email => Some(&mut address.email),
+        Field::Id
+        | Field::Name
+        | Field::Active
+        | Field::Currency
+        | Field::Terms
+        | Field::TaxTable
+        | Field::CreditLimit
+        | Field::Notes => None,
+    }
+}
+
+/// Returns an address with empty parts, as GnuCash creates them.
+fn empty_address() -> Address {
+    Address {
+        name: Some(String::new()),
+        addr1: Some(String::new()),
+        addr2: Some(String::new()),
+        addr3: Some(String::new()),
+        addr4: Some(String::new()),
+        phone: Some(String::new()),
+        fax: Some(String::new()),
+        email: Some(String::new()),
+    }
+}
+
+/// Parses a boolean, as written by humans or spreadsheets.
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" | "y" | "1" => Some(true),
+        "false" | "no" | "n" | "0" => Some(false),
+        _ => None,
+    }
+}
@@ -0,0 +1,619 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Export to the Beancount format.
+//!
+//! Account and commodity names are sanitised to follow the Beancount rules:
+//! accounts are put under the root matching their type, and invalid characters
+//! are replaced with dashes. Commodity splits are written with their total cost
+//! when they augment a position, and with an empty cost and their total price
+//! when they reduce it, so that Beancount picks the lot. A balance assertion is
+//! added the day after each reconcile date.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::{self, Write},
+};
+
+use chrono::{DateTime, Days, Local, NaiveDate};
+
+use crate::book::{
+    AccountType, Book, Commodity, Guid, Numeric, ReconcileState, Split,
+    Transaction, ACCOUNT_SEPARATOR,
+};
+
+/// The maximum length of a commodity name.
+const MAX_COMMODITY_LENGTH: usize = 24;
+
+/// A name changed to follow the Beancount rules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rename {
+    /// The name in GnuCash.
+    pub original: String,
+    /// The name in Beancount.
+    pub renamed: String,
+}
+
+/// The Beancount names of the accounts and commodities of a book.
+struct Names {
+    /// The names of the accounts, by GUID.
+    accounts: BTreeMap<Guid, String>,
+    /// The names of the commodities, by GUID.
+    commodities: BTreeMap<Guid, String>,
+    /// The names that have been changed.
+    renames: Vec<Rename>,
+}
+
+/// A dated directive, to be sorted before being written.
+struct Directive {
+    /// The date of the directive.
+    date: NaiveDate,
+    /// The order of the directive among the ones of the same date.
+    order: u8,
+    /// The text of the directive.
+    text: String,
+}
+
+/// Writes the book in the Beancount format.
+///
+/// The names that have been changed are returned.
+pub fn write(book: &Book, output: &mut impl Write) -> io::Result<Vec<Rename>> {
+    let names = Names::new(book);
+    let transactions = book.regular_transactions();
+
+    let first_date = transactions
+        .first()
+        .map(|transaction| transaction.date())
+        .into_iter()
+        .chain(
+            book.sorted_prices()
+                .first()
+                .map(|price| price.date.date_naive()),
+        )
+        .min()
+        .unwrap_or(DateTime::UNIX_EPOCH.date_naive());
+
+    if let Some(currency) = operating_currency(&transactions) {
+        writeln!(
+            output,
+            "option \"operating_currency\" \"{}\"\n",
+            names.commodity(currency)
+        )?;
+    }
+
+    let mut directives = vec![];
+    directives.extend(commodities(book, &names, first_date));
+    directives.extend(opens(book, &names, &transactions, first_date));
+    directives.extend(prices(book, &names));
+    directives.extend(balances(book, &names, &transactions));
+    directives.extend(transactions.iter().map(|transaction| Directive {
+        date: transaction.date(),
+        order: 4,
+        text: transaction_text(book, &names, transaction),
+    }));
+
+    directives.sort_by_key(|directive| (directive.date, directive.order));
+
+    let mut previous = None;
+    for directive in directives {
+        // Group the simple directives, and separate the transactions.
+        if previous.is_some_and(|order| order != directive.order || order == 4)
+        {
+            writeln!(output)?;
+        }
+
+        writeln!(output, "{} {}", directive.date, directive.text)?;
+        previous = Some(directive.order);
+    }
+
+    Ok(names.renames)
+}
+
+impl Names {
+    /// Builds the Beancount names for a book.
+    fn new(book: &Book) -> Self {
+        let mut names = Self {
+            accounts: BTreeMap::new(),
+            commodities: BTreeMap::new(),
+            renames: vec![],
+        };
+
+        let mut taken = BTreeSet::new();
+        for commodity in book
+            .commodities
+            .values()
+            .filter(|commodity| !commodity.is_template())
+        {
+            let name = unique(&commodity_name(&commodity.mnemonic), &mut taken);
+            names.insert_commodity(commodity.guid, &commodity.mnemonic, name);
+        }
+
+        let mut accounts: Vec<_> = book
+            .accounts
+            .values()
+            .filter(|account| {
+                account.kind != AccountType::Root
+                    && !book.is_template_account(account.guid)
+            })
+            .map(|account| (book.account_path(account.guid), account))
+            .collect();
+
+        accounts.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+
+        let mut taken = BTreeSet::new();
+        for (path, account) in accounts {
+            let name = unique(&account_name(&path, account.kind), &mut taken);
+            names.insert_account(account.guid, path, name);
+        }
+
+        names
+    }
+
+    /// Returns the name of an account.
+    fn account(&self, guid: Guid) -> &str {
+        self.accounts.get(&guid).map_or("", String::as_str)
+    }
+
+    /// Returns the name of a commodity.
+    fn commodity(&self, guid: Guid) -> &str {
+        self.commodities.get(&guid).map_or("", String::as_str)
+    }
+
+    /// Inserts the name of an account.
+    fn insert_account(&mut self, guid: Guid, original: String, name: String) {
+        if name != original {
+            self.renames.push(Rename {
+                original,
+                renamed: name.clone(),
+            });
+        }
+
+        self.accounts.insert(guid, name);
+    }
+
+    /// Inserts the name of a commodity.
+    fn insert_commodity(&mut self, guid: Guid, original: &str, name: String) {
+        if name != original {
+            self.renames.push(Rename {
+                original: original.to_owned(),
+                renamed: name.clone(),
+            });
+        }
+
+        self.commodities.insert(guid, name);
+    }
+}
+
+/// Returns the `commodity` directives.
+///
+/// The commodity of the template accounts is not a real one: it is skipped.
+fn commodities(book: &Book, names: &Names, date: NaiveDate) -> Vec<Directive> {
+    book.commodities
+        .values()
+        .filter(|commodity| !commodity.is_template())
+        .map(|commodity| {
+            let name = names.commodity(commodity.guid);
+            let text = match &commodity.fullname {
+                Some(fullname) => {
+                    format!("commodity {name}\n  name: {}", quote(fullname))
+                }
+                None => format!("commodity {name}"),
+            };
+
+            Directive {
+                date,
+                order: 0,
+                text,
+            }
+        })
+        .collect()
+}
+
+/// Returns the `open` directives.
+///
+/// Accounts are opened at the date of their first split, with the currencies
+/// used by their splits. Accounts without splits are opened only if they have
+/// no children.
+fn opens(
+    book: &Book,
+    names: &Names,
+    transactions: &[&Transaction],
+    first_date: NaiveDate,
+) -> Vec<Directive> {
+    let mut usage: BTreeMap<Guid, (NaiveDate, BTreeSet<Guid>)> =
+        BTreeMap::new();
+
+    for transaction in transactions {
+        for split in &transaction.splits {
+            let (_, currencies) = usage
+                .entry(split.account)
+                .or_insert_with(|| (transaction.date(), BTreeSet::new()));
+
+            currencies.insert(split_commodity(
+                book,
+                transaction,
+                split.account,
+                split.quantity,
+            ));
+        }
+    }
+
+    names
+        .accounts
+        .iter()
+        .filter_map(|(&guid, name)| {
+            let (date, currencies) = match usage.get(&guid) {
+                Some((date, currencies)) => (*date, currencies.clone()),
+                None if book.children(guid).next().is_none() => {
+                    let commodity = book.account(guid)?.commodity;
+                    (first_date, commodity.into_iter().collect())
+                }
+                None => return None,
+            };
+
+            let currencies: Vec<_> = currencies
+                .into_iter()
+                .map(|currency| names.commodity(currency))
+                .collect();
+
+            Some(Directive {
+                date,
+                order: 1,
+                text: format!("open {name} {}", currencies.join(",")),
+            })
+        })
+        .collect()
+}
+
+/// Returns the `price` directives.
+fn prices(book: &Book, names: &Names) -> Vec<Directive> {
+    book.sorted_prices()
+        .into_iter()
+        .filter_map(|price| {
+            let currency = book.commodity(price.currency)?;
+
+            Some(Directive {
+                date: price.date.date_naive(),
+                order: 2,
+                text: format!(
+                    "price {} {} {}",
+                    names.commodity(price.commodity),
+                    price.value.to_exact(currency.fraction()),
+                    names.commodity(currency.guid)
+                ),
+            })
+        })
+        .collect()
+}
+
+/// Returns the `balance` assertions.
+///
+/// For each reconcile date of an account, the balance of the account at that
+/// date is asserted on the next day, as Beancount checks balances at the
+/// beginning of the day. Reconcile dates are stored at the end of the statement
+/// day in local time, and Beancount includes the postings of the descendants of
+/// an account in its balance, in the commodity of the assertion. Descendants
+/// are found from the exported names, as an account can be moved to the root
+/// of its type.
+fn balances(
+    book: &Book,
+    names: &Names,
+    transactions: &[&Transaction],
+) -> Vec<Directive> {
+    let mut reconcile_dates: BTreeMap<Guid, BTreeSet<NaiveDate>> =
+        BTreeMap::new();
+
+    for split in transactions.iter().flat_map(|tx| &tx.splits) {
+        if let (ReconcileState::Reconciled, Some(date)) =
+            (split.reconcile_state, split.reconcile_date)
+        {
+            reconcile_dates
+                .entry(split.account)
+                .or_default()
+                .insert(date.with_timezone(&Local).date_naive());
+        }
+    }
+
+    let mut directives = vec![];
+
+    for (account, dates) in reconcile_dates {
+        let Some(commodity) = book.account_commodity(account) else {
+            continue;
+        };
+
+        let name = names.account(account);
+        let prefix = format!("{name}:");
+
+        for date in dates {
+            let balance: Numeric = transactions
+                .iter()
+                .filter(|transaction| transaction.date() <= date)
+                .flat_map(|transaction| {
+                    transaction.splits.iter().map(move |split| {
+                        (
+                            split.account,
+                            written_amount(book, transaction, split),
+                        )
+                    })
+                })
+                .filter(|(account, (split_commodity, _))| {
+                    let account = names.account(*account);
+
+                    *split_commodity == commodity.guid
+                        && (account == name || account.starts_with(&prefix))
+                })
+                .map(|(_, (_, amount))| amount)
+                .sum();
+
+            directives.push(Directive {
+                date: date + Days::new(1),
+                order: 3,
+                text: format!(
+                    "balance {name} {} {}",
+                    balance.to_exact(commodity.fraction()),
+                    names.commodity(commodity.guid)
+                ),
+            });
+        }
+    }
+
+    directives
+}
+
+/// Returns the text of a transaction, without its date.
+fn transaction_text(
+    book: &Book,
+    names: &Names,
+    transaction: &Transaction,
+) -> String {
+    let mut lines = vec![format!("* {}", quote(&transaction.description))];
+
+    if let Some(id) = transaction.journal_id() {
+        lines.push(format!("  id: {}", quote(id)));
+    }
+
+    if !transaction.num.is_empty() {
+        lines.push(format!("  num: {}", quote(&transaction.num)));
+    }
+
+    if let Some(notes) = transaction.notes() {
+        lines.push(format!("  notes: {}", quote(notes)));
+    }
+
+    let currency = book.commodity(transaction.currency);
+    let currency_fraction = currency.map_or(1, Commodity::fraction);
+    let currency_name = names.commodity(transaction.currency);
+
+    for split in &transaction.splits {
+        let account = names.account(split.account);
+        let value = format!(
+            "{} {currency_name}",
+            split.value.to_exact(currency_fraction)
+        );
+
+        let amount = match book.account_commodity(split.account) {
+            Some(commodity)
+                if commodity.guid != transaction.currency
+                    && !split.quantity.is_zero() =>
+            {
+                let quantity = format!(
+                    "{} {}",
+                    split.quantity.to_exact(commodity.fraction()),
+                    names.commodity(commodity.guid)
+                );
+
+                let total = format!(
+                    "{} {currency_name}",
+                    split.value.abs().to_exact(currency_fraction)
+                );
+
+                if split.quantity.is_negative() {
+                    format!("{quantity} {{}} @@ {total}")
+                } else {
+                    format!("{quantity} {{{{{total}}}}}")
+                }
+            }
+            _ => value,
+        };
+
+        lines.push(format!("  {account}  {amount}"));
+
+        if !split.memo.is_empty() {
+            lines.push(format!("    memo: {}", quote(&split.memo)));
+        }
+
+        if !split.action.is_empty() {
+            lines.push(format!("    action: {}", quote(&split.action)));
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Returns the commodity in which a split is written.
+fn split_commodity(
+    book: &Book,
+    transaction: &Transaction,
+    account: Guid,
+    quantity: Numeric,
+) -> Guid {
+    match book.account_commodity(account) {
+        Some(commodity) if !quantity.is_zero() => commodity.guid,
+        Some(commodity) if commodity.guid == transaction.currency => {
+            commodity.guid
+        }
+        _ => transaction.currency,
+    }
+}
+
+/// Returns the commodity and amount with which a split is written.
+fn written_amount(
+    book: &Book,
+    transaction: &Transaction,
+    split: &Split,
+) -> (Guid, Numeric) {
+    let commodity =
+        split_commodity(book, transaction, split.account, split.quantity);
+
+    if book
+        .account(split.account)
+        .and_then(|account| account.commodity)
+        == Some(commodity)
+    {
+        (commodity, split.quantity)
+    } else {
+        (commodity, split.value)
+    }
+}
+
+/// Returns the currency used by most transactions.
+fn operating_currency(transactions: &[&Transaction]) -> Option<Guid> {
+    let mut counts: BTreeMap<Guid, usize> = BTreeMap::new();
+
+    for transaction in transactions {
+        *counts.entry(transaction.currency).or_default() += 1;
+    }
+
+    counts
+        .into_iter()
+        .max_by_key(|&(_, count)| count)
+        .map(|(currency, _)| currency)
+}
+
+/// Returns the Beancount name of an account.
+fn account_name(path: &str, kind: AccountType) -> String {
+    let root = root_name(kind);
+
+    let mut components: Vec<_> =
+        path.split(ACCOUNT_SEPARATOR).map(component_name).collect();
+
+    if components.first().map(String::as_str) == Some(root) {
+        components.remove(0);
+    }
+
+    // Top-level accounts named after their root are the root themselves.
+    if components.is_empty() {
+        return root.to_owned();
+    }
+
+    format!("{root}:{}", components.join(":"))
+}
+
+/// Returns the Beancount root account for an account type.
+const fn root_name(kind: AccountType) -> &'static str {
+    match kind {
+        AccountType::Credit
+        | AccountType::Liability
+        | AccountType::Payable
+        | AccountType::CreditLine => "Liabilities",
+        AccountType::Income => "Income",
+        AccountType::Expense => "Expenses",
+        AccountType::Equity | AccountType::Trading => "Equity",
+        AccountType::Root
+        | AccountType::Bank
+        | AccountType::Cash
+        | AccountType::Asset
+        | AccountType::Stock
+        | AccountType::Mutual
+        | AccountType::Currency
+        | AccountType::Receivable
+        | AccountType::Checking
+        | AccountType::Savings
+        | AccountType::MoneyMarket => "Assets",
+    }
+}
+
+/// Sanitises an account name component.
+///
+/// Components can only contain letters, digits and dashes, and must start with
+/// an uppercase letter or a digit.
+fn component_name(name: &str) -> String {
+    let sanitised = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect::<String>();
+
+    let mut component = collapse_dashes(&sanitised);
+
+    match component.chars().next() {
+        None => "X".to_owned(),
+        Some(first) if first.is_lowercase() => {
+            let rest = component.split_off(first.len_utf8());
+            first.to_uppercase().chain(rest.chars()).collect()
+        }
+        Some(first) if first.is_uppercase() || first.is_ascii_digit() => {
+            component
+        }
+        Some(_) => format!("X{component}"),
+    }
+}
+
+/// Sanitises a commodity name.
+///
+/// Commodities are uppercase, can contain letters, digits, `'`, `.`, `_` and
+/// `-`, must start with a letter, end with a letter or digit and be at most 24
+/// characters long.
+fn commodity_name(mnemonic: &str) -> String {
+    let sanitised = mnemonic
+        .to_uppercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_uppercase()
+                || c.is_ascii_digit()
+                || matches!(c, '\'' | '.' | '_' | '-')
+            {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>();
+
+    let mut name = collapse_dashes(&sanitised);
+    if !name.starts_with(|c: char| c.is_ascii_uppercase()) {
+        name.insert(0, 'C');
+    }
+
+    name.truncate(MAX_COMMODITY_LENGTH);
+    name.trim_end_matches(|c: char| !c.is_ascii_alphanumeric())
+        .to_owned()
+}
+
+/// Collapses consecutive dashes and trims them at both ends.
+fn collapse_dashes(name: &str) -> String {
+    name.split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Makes a name unique by adding a numeric suffix if it is already taken.
+fn unique(name: &str, taken: &mut BTreeSet<String>) -> String {
+    let mut unique = name.to_owned();
+    let mut index = 1_u32;
+
+    while taken.contains(&unique) {
+        index += 1;
+        unique = format!("{name}-{index}");
+    }
+
+    taken.insert(unique.clone());
+    unique
+}
+
+/// Quotes a string.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,287 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Export to the ledger / hledger journal format.
+//!
+//! The journal starts with the commodity and account declarations, followed by
+//! the prices as `P` directives and the transactions sorted by date. Amounts are
+//! in the commodity of their account: when it differs from the transaction
+//! currency, the value of the split is given as a total price so that the
+//! journal balances to the same totals as GnuCash. Ledger has no reconciled
+//! state: cleared and reconciled splits are both marked as cleared with `*`.
+
+use std::io::{self, Write};
+
+use crate::book::{
+    Account, AccountType, Book, Commodity, Numeric, ReconcileState, Split,
+    Transaction,
+};
+
+/// Writes the book as a ledger journal.
+pub fn write(book: &Book, output: &mut impl Write) -> io::Result<()> {
+    write_commodities(book, output)?;
+    write_accounts(book, output)?;
+    write_prices(book, output)?;
+
+    for transaction in book.regular_transactions() {
+        writeln!(output)?;
+        write_transaction(book, transaction, output)?;
+    }
+
+    Ok(())
+}
+
+/// Writes the commodity declarations.
+fn write_commodities(book: &Book, output: &mut impl Write) -> io::Result<()> {
+    for commodity in book.commodities.values() {
+        let symbol = symbol(commodity);
+        let format = Numeric::ZERO.to_fixed(commodity.fraction());
+
+        writeln!(output, "commodity {symbol}")?;
+        writeln!(
+            output,
+            "    format 1000{} {symbol}",
+            format.trim_start_matches('0')
+        )?;
+    }
+
+    writeln!(output)
+}
+
+/// Writes the account declarations.
+fn write_accounts(book: &Book, output: &mut impl Write) -> io::Result<()> {
+    let mut accounts: Vec<_> = book
+        .accounts
+        .values()
+        .filter(|account| {
+            account.kind != AccountType::Root
+                && !book.is_template_account(account.guid)
+        })
+        .map(|account| (book.account_path(account.guid), account))
+        .collect();
+
+    accounts.sort_by(|(lhs, _), (rhs, _)| lhs.cmp(rhs));
+
+    for (path, account) in accounts {
+        write!(output, "account {path}  ; type:{}", account_type(account))?;
+
+        if let Some(code) = account.code.as_deref().filter(|c| !c.is_empty()) {
+            write!(output, ", code:{code}")?;
+        }
+
+        writeln!(output)?;
+
+        if let Some(description) = account
+            .description
+            .as_deref()
+            .filter(|description| !description.is_empty())
+        {
+            write_comment("    ", description, output)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes the prices as `P` directives.
+fn write_prices(book: &Book, output: &mut impl Write) -> io::Result<()> {
+    let prices = book.sorted_prices();
+
+    if !prices.is_empty() {
+        writeln!(output)?;
+    }
+
+    for price in prices {
+        let (Some(commodity), Some(currency)) = (
+            book.commodity(price.commodity),
+            book.commodity(price.currency),
+        ) else {
+            continue;
+        };
+
+        writeln!(
+            output,
+            "P {} {} {}",
+            price.date.date_naive(),
+            symbol(commodity),
+            format_amount(price.value, currency)
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Writes a transaction.
+fn write_transaction(
+    book: &Book,
+    transaction: &Transaction,
+    output: &mut impl Write,
+) -> io::Result<()> {
+    write!(output, "{}", transaction.date())?;
+
+    if !transaction.num.is_empty() {
+        write!(output, " ({})", single_line(&transaction.num))?;
+    }
+
+    write!(output, " {}", single_line(&transaction.description))?;
+
+    if let Some(id) = transaction.journal_id() {
+        write!(output, "  ; id:{id}")?;
+    }
+
+    writeln!(output)?;
+
+    if let Some(notes) = transaction.notes() {
+        write_comment("    ", notes, output)?;
+    }
+
+    let postings: Vec<_> = transaction
+        .splits
+        .iter()
+        .map(|split| {
+            let status = match split.reconcile_state {
+                ReconcileState::Cleared
+                | ReconcileState::Reconciled
+                | ReconcileState::Frozen => "* ",
+                ReconcileState::NotReconciled | ReconcileState::Voided => "",
+            };
+
+            let account = book.account_path(split.account);
+            (format!("{status}{account}"), split)
+        })
+        .collect();
+
+    let width = postings
+        .iter()
+        .map(|(account, _)| account.chars().count())
+        .max()
+        .unwrap_or_default();
+
+    for (account, split) in postings {
+        let amount = amount(book, transaction, split);
+        write!(output, "    {account:width$}  {amount}")?;
+
+        if !split.action.is_empty() {
+            write!(output, "  ; action:{}", single_line(&split.action))?;
+        }
+
+        writeln!(output)?;
+        write_comment("        ", &split.memo, output)?;
+    }
+
+    Ok(())
+}
+
+/// Formats the amount of a split.
+///
+/// The amount is the quantity in the commodity of the account. When it is not
+/// the transaction currency, the value is added as a total price, which is
+/// negative when the value and quantity have opposite signs, as in some stock
+/// splits and corrections. Splits with a value but no quantity, like realised
+/// gains, are written in the currency.
+fn amount(book: &Book, transaction: &Transaction, split: &Split) -> String {
+    let currency = book.commodity(transaction.currency);
+    let commodity = book.account_commodity(split.account);
+
+    let value = match currency {
+        Some(currency) => format_amount(split.value, currency),
+        None => split.value.to_string(),
+    };
+
+    match commodity {
+        Some(commodity) if commodity.guid == transaction.currency => value,
+        Some(_) if split.quantity.is_zero() => value,
+        Some(commodity) => {
+            let quantity = format_amount(split.quantity, commodity);
+            let total = if split.quantity.is_negative() {
+                -split.value
+            } else {
+                split.value
+            };
+            let price = match currency {
+                Some(currency) => format_amount(total, currency),
+                None => total.to_string(),
+            };
+
+            format!("{quantity} @@ {price}")
+        }
+        None => value,
+    }
+}
+
+/// Formats an amount in a commodity.
+fn format_amount(amount: Numeric, commodity: &Commodity) -> String {
+    format!(
+        "{} {}",
+        amount.to_exact(commodity.fraction()),
+        symbol(commodity)
+    )
+}
+
+/// Returns the symbol of a commodity, quoted if needed.
+fn symbol(commodity: &Commodity) -> String {
+    if commodity.mnemonic.chars().all(char::is_alphabetic) {
+        commodity.mnemonic.clone()
+    } else {
+        format!("\"{}\"", commodity.mnemonic)
+    }
+}
+
+/// Joins the lines of a text, which must fit on the line of a transaction.
+fn single_line(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Returns the hledger type of an account.
+const fn account_type(account: &Account) -> &'static str {
+    match account.kind {
+        AccountType::Cash => "C",
+        AccountType::Credit
+        | AccountType::Liability
+        | AccountType::Payable
+        | AccountType::CreditLine => "L",
+        AccountType::Income => "R",
+        AccountType::Expense => "X",
+        AccountType::Equity => "E",
+        AccountType::Trading => "V",
+        AccountType::Root
+        | AccountType::Bank
+        | AccountType::Asset
+        | AccountType::Stock
+        | AccountType::Mutual
+        | AccountType::Currency
+        | AccountType::Receivable
+        | AccountType::Checking
+        | AccountType::Savings
+        | AccountType::MoneyMarket => "A",
+    }
+}
+
+/// Writes a possibly multi-line comment.
+fn write_comment(
+    indent: &str,
+    comment: &str,
+    output: &mut impl Write,
+) -> io::Result<()> {
+    for line in comment.lines() {
+        writeln!(output, "{indent}; {line}")?;
+    }
+
+    Ok(())
+}
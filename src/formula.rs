@@ -0,0 +1,179 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Formulas of scheduled transaction templates.
+//!
+//! GnuCash stores the amounts of template splits as formulas, like `800` or
+//! `(rent + 50) / 2`. The four operations and parentheses are supported, as
+//! well as variables whose values are given when evaluating the formula.
+
+use std::collections::BTreeMap;
+
+use thiserror::Error;
+
+use crate::book::Numeric;
+
+/// Errors that can occur when evaluating a formula.
+#[derive(Debug, Error)]
+pub enum FormulaError {
+    /// The formula is not valid.
+    #[error("syntax error at {0:?}")]
+    Syntax(String),
+    /// A number is not valid.
+    #[error("invalid number: {0:?}")]
+    InvalidNumber(String),
+    /// A variable has no value.
+    #[error("the variable {0} has no value")]
+    UnknownVariable(String),
+    /// The formula divides by zero.
+    #[error("division by zero")]
+    DivisionByZero,
+}
+
+/// A parser evaluating a formula as it reads it.
+struct Parser<'a> {
+    /// The rest of the formula.
+    rest: &'a str,
+    /// The values of the variables.
+    variables: &'a BTreeMap<String, Numeric>,
+}
+
+/// Evaluates a formula.
+///
+/// An empty formula evaluates to zero.
+pub fn evaluate(
+    formula: &str,
+    variables: &BTreeMap<String, Numeric>,
+) -> Result<Numeric, FormulaError> {
+    if formula.trim().is_empty() {
+        return Ok(Numeric::ZERO);
+    }
+
+    let mut parser = Parser {
+        rest: formula,
+        variables,
+    };
+
+    let value = parser.expression()?;
+
+    if parser.peek().is_some() {
+        return Err(FormulaError::Syntax(parser.rest.trim().to_owned()));
+    }
+
+    Ok(value)
+}
+
+impl<'a> Parser<'a> {
+    /// Parses a sum of terms.
+    fn expression(&mut self) -> Result<Numeric, FormulaError> {
+        let mut value = self.term()?;
+
+        while let Some(operator @ ('+' | '-')) = self.peek() {
+            self.advance(operator);
+            let term = self.term()?;
+
+            if operator == '+' {
+                value += term;
+            } else {
+                value -= term;
+            }
+        }
+
+        Ok(value)
+    }
+
+    /// Parses a product of factors.
+    fn term(&mut self) -> Result<Numeric, FormulaError> {
+        let mut value = self.factor()?;
+
+        while let Some(operator @ ('*' | '/')) = self.peek() {
+            self.advance(operator);
+            let factor = self.factor()?;
+
+            value = if operator == '*' {
+                value * factor
+            } else {
+                value * factor.recip().ok_or(FormulaError::DivisionByZero)?
+            };
+        }
+
+        Ok(value)
+    }
+
+    /// Parses a number, a variable, a signed factor or a parenthesised
+    /// expression.
+    fn factor(&mut self) -> Result<Numeric, FormulaError> {
+        match self.peek() {
+            Some(sign @ ('+' | '-')) => {
+                self.advance(sign);
+                let value = self.factor()?;
+                Ok(if sign == '-' { -value } else { value })
+            }
+            Some('(') => {
+                self.advance('(');
+                let value = self.expression()?;
+
+                if self.peek() == Some(')') {
+                    self.advance(')');
+                    Ok(value)
+                } else {
+                    Err(self.syntax_error())
+                }
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => {
+                let number =
+                    self.take_while(|c| c.is_ascii_digit() || c == '.');
+                number.parse().map_err(|_error| {
+                    FormulaError::InvalidNumber(number.to_owned())
+                })
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let name = self.take_while(|c| c.is_alphanumeric() || c == '_');
+                self.variables.get(name).copied().ok_or_else(|| {
+                    FormulaError::UnknownVariable(name.to_owned())
+                })
+            }
+            _ => Err(self.syntax_error()),
+        }
+    }
+
+    /// Returns the next non-whitespace character.
+    fn peek(&mut self) -> Option<char> {
+        self.rest = self.rest.trim_start();
+        self.rest.chars().next()
+    }
+
+    /// Consumes the next character.
+    fn advance(&mut self, c: char) {
+        self.rest = self.rest.strip_prefix(c).unwrap_or(self.rest);
+    }
+
+    /// Consumes the characters matching the predicate.
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> &'a str {
+        let end = self.rest.find(|c| !predicate(c)).unwrap_or(self.rest.len());
+        let (taken, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        taken
+    }
+
+    /// Returns a syntax error at the current position.
+    fn syntax_error(&self) -> FormulaError {
+        if self.rest.is_empty() {
+            FormulaError::Syntax("end of formula".to_owned())
+        } else {
+            FormulaError::Syntax(self.rest.to_owned())
+        }
+    }
+}
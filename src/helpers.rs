@@ -15,6 +15,16 @@
 
 //! General helpers.
 
+use std::fmt;
+
+use chrono::NaiveDate;
+use serde::{
+    de::{self, value::MapAccessDeserializer, MapAccess, Visitor},
+    Deserialize, Deserializer,
+};
+
+use crate::book::Numeric;
+
 /// Uncapitalises the first character in s.
 pub fn uncapitalise(s: &str) -> String {
     let mut chars = s.chars();
@@ -23,3 +33,102 @@ pub fn uncapitalise(s: &str) -> String {
         Some(c) => c.to_lowercase().collect::<String>() + chars.as_str(),
     }
 }
+
+/// Deserialises a date, given as a string or a TOML date.
+pub fn deserialize_date<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<NaiveDate, D::Error> {
+    deserializer.deserialize_any(DateVisitor)
+}
+
+/// Deserialises a numeric, given as a string or a number.
+pub fn deserialize_numeric<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Numeric, D::Error> {
+    deserializer.deserialize_any(NumericVisitor)
+}
+
+/// Deserialises an optional date, given as a string or a TOML date.
+///
+/// This is meant to be used with `#[serde(default)]`.
+pub fn deserialize_optional_date<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<NaiveDate>, D::Error> {
+    deserialize_date(deserializer).map(Some)
+}
+
+/// Deserialises an optional numeric, given as a string or a number.
+///
+/// This is meant to be used with `#[serde(default)]`.
+pub fn deserialize_optional_numeric<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Numeric>, D::Error> {
+    deserialize_numeric(deserializer).map(Some)
+}
+
+/// A visitor for dates.
+struct DateVisitor;
+
+/// A visitor for numerics.
+struct NumericVisitor;
+
+impl<'de> Visitor<'de> for DateVisitor {
+    type Value = NaiveDate;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("a date like 2024-01-31")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        value.parse().map_err(E::custom)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(
+        self,
+        map: A,
+    ) -> Result<Self::Value, A::Error> {
+        // TOML dates are deserialised as a special map.
+        let datetime = toml::value::Datetime::deserialize(
+            MapAccessDeserializer::new(map),
+        )?;
+
+        datetime
+            .date
+            .filter(|_| datetime.time.is_none())
+            .and_then(|date| {
+                NaiveDate::from_ymd_opt(
+                    i32::from(date.year),
+                    u32::from(date.month),
+                    u32::from(date.day),
+                )
+            })
+            .ok_or_else(|| {
+                de::Error::custom(format!("invalid date: {datetime}"))
+            })
+    }
+}
+
+impl Visitor<'_> for NumericVisitor {
+    type Value = Numeric;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("an amount like -1234.56")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        value.parse().map_err(E::custom)
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+        Numeric::new(i128::from(value), 1).map_err(E::custom)
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        Numeric::new(i128::from(value), 1).map_err(E::custom)
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Self::Value, E> {
+        // The shortest representation of a float is the written decimal.
+        self.visit_str(&value.to_string())
+    }
+}
@@ -0,0 +1,514 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Invoices, bills and expense vouchers.
+//!
+//! GnuCash stores the entries of a document with their quantity, price,
+//! discount and tax table, but not their amounts: they are computed here the
+//! same way GnuCash does. Once posted, a document is linked to a lot in its
+//! posting account, which also gathers its payments.
+
+pub mod draft;
+pub mod payment;
+pub mod render;
+
+use std::{fmt, ops::Neg};
+
+use chrono::NaiveDate;
+
+use crate::book::{
+    AmountType, Book, Commodity, DiscountHow, Entry, Guid, Invoice, Numeric,
+    Owner, OwnerType, SlotValue, TaxTableEntry,
+};
+
+/// The slot of the posting transaction storing the due date.
+const DUE_DATE_SLOT: &str = "trans-date-due";
+
+/// The slot marking an invoice as a credit note.
+const CREDIT_NOTE_SLOT: &str = "credit-note";
+
+/// The slot of a transaction storing its type.
+const TXN_TYPE_SLOT: &str = "trans-txn-type";
+
+/// The slot of a lot storing the type of its owner.
+const OWNER_TYPE_SLOT: &str = "gncOwner/owner-type";
+
+/// The slot of a lot storing the GUID of its owner.
+const OWNER_GUID_SLOT: &str = "gncOwner/owner-guid";
+
+/// The kind of a document, depending on its owner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentKind {
+    /// An invoice, sent to a customer.
+    Invoice,
+    /// A bill, received from a vendor.
+    Bill,
+    /// An expense voucher, filed by an employee.
+    ExpenseVoucher,
+}
+
+/// The payment status of a document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// The document has not been posted yet.
+    Unposted,
+    /// No payment has been made.
+    Unpaid,
+    /// Some payments have been made, but not for the full amount.
+    PartiallyPaid,
+    /// The document has been fully paid.
+    Paid,
+}
+
+/// An invoice, bill or expense voucher with its computed amounts.
+#[derive(Debug)]
+pub struct Document<'a> {
+    /// The invoice.
+    pub invoice: &'a Invoice,
+    /// The kind of document.
+    pub kind: DocumentKind,
+    /// The customer, vendor or employee of the document.
+    ///
+    /// Jobs are resolved to their owner.
+    pub owner: Option<Owner>,
+    /// The currency of the document.
+    pub currency: Option<&'a Commodity>,
+    /// The lines of the document.
+    pub lines: Vec<Line<'a>>,
+    /// The date at which the document is due, once posted.
+    pub due_date: Option<NaiveDate>,
+    /// The amount remaining to be paid.
+    pub balance: Numeric,
+}
+
+/// A line of a document.
+#[derive(Debug)]
+pub struct Line<'a> {
+    /// The entry.
+    pub entry: &'a Entry,
+    /// The amounts of the entry.
+    pub amounts: EntryAmounts,
+}
+
+/// The amounts of an entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EntryAmounts {
+    /// The value, after discount and without taxes.
+    pub value: Numeric,
+    /// The discount.
+    pub discount: Numeric,
+    /// The taxes, by account.
+    pub taxes: Vec<Tax>,
+}
+
+/// A tax on an entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tax {
+    /// The account receiving the tax.
+    pub account: Guid,
+    /// The rate of the tax in percents, unless it is a fixed value.
+    pub rate: Option<Numeric>,
+    /// The amount of the tax.
+    pub amount: Numeric,
+}
+
+/// The terms of an entry, either for an invoice or for a bill.
+struct EntryTerms<'a> {
+    /// The unit price.
+    price: Numeric,
+    /// Whether the price includes taxes.
+    tax_included: bool,
+    /// The entries of the tax table, if the entry is taxable.
+    taxes: &'a [TaxTableEntry],
+    /// The discount.
+    discount: Numeric,
+    /// Whether the discount is a value or a percentage.
+    discount_type: AmountType,
+    /// How the discount is applied relatively to taxes.
+    discount_how: DiscountHow,
+}
+
+impl<'a> Document<'a> {
+    /// Computes the amounts of an invoice.
+    pub fn new(book: &'a Book, invoice: &'a Invoice) -> Self {
+        let owner = invoice.owner.map(|owner| book.business.end_owner(owner));
+        let kind = owner.map_or(DocumentKind::Invoice, |owner| {
+            DocumentKind::from_owner_type(owner.kind)
+        });
+
+        let currency = book.commodity(invoice.currency);
+        let fraction = currency.map_or(100, Commodity::fraction);
+        let credit_note = is_credit_note(invoice);
+
+        let lines: Vec<_> = book
+            .business
+            .invoice_entries(invoice)
+            .into_iter()
+            .map(|entry| {
+                let amounts = EntryAmounts::new(book, entry, kind, fraction);
+
+                Line {
+                    entry,
+                    amounts: if credit_note { -amounts } else { amounts },
+                }
+            })
+            .collect();
+
+        let mut document = Self {
+            invoice,
+            kind,
+            owner,
+            currency,
+            lines,
+            due_date: due_date(book, invoice),
+            balance: Numeric::ZERO,
+        };
+
+        document.balance = match invoice.post_lot {
+            Some(lot) => {
+                document.kind.sign() * lot_balance(book, lot, NaiveDate::MAX)
+            }
+            None => document.total(),
+        };
+
+        document
+    }
+
+    /// Computes the amounts of all the documents of a book.
+    ///
+    /// The documents are sorted by opening date, then by ID.
+    pub fn all(book: &'a Book) -> Vec<Self> {
+        let mut documents: Vec<_> = book
+            .business
+            .invoices
+            .values()
+            .map(|invoice| Self::new(book, invoice))
+            .collect();
+
+        documents.sort_by(|lhs, rhs| {
+            (lhs.invoice.date_opened, &lhs.invoice.id)
+                .cmp(&(rhs.invoice.date_opened, &rhs.invoice.id))
+        });
+
+        documents
+    }
+
+    /// Finds the document of the given kind with the given ID.
+    ///
+    /// GnuCash numbers invoices, bills and expense vouchers independently, so
+    /// the same ID can be used by documents of different kinds.
+    pub fn find(book: &'a Book, kind: DocumentKind, id: &str) -> Option<Self> {
+        book.business
+            .invoices
+            .values()
+            .filter(|invoice| invoice.id == id)
+            .map(|invoice| Self::new(book, invoice))
+            .find(|document| document.kind == kind)
+    }
+
+    /// Returns the date at which the document has been opened.
+    pub fn opened(&self) -> Option<NaiveDate> {
+        self.invoice.date_opened.map(|date| date.date_naive())
+    }
+
+    /// Returns the date at which the document has been posted.
+    pub fn posted(&self) -> Option<NaiveDate> {
+        self.invoice.date_posted.map(|date| date.date_naive())
+    }
+
+    /// Returns the total of the document without taxes.
+    pub fn subtotal(&self) -> Numeric {
+        self.lines.iter().map(|line| line.amounts.value).sum()
+    }
+
+    /// Returns the total of the taxes of the document.
+    pub fn tax(&self) -> Numeric {
+        self.lines
+            .iter()
+            .flat_map(|line| &line.amounts.taxes)
+            .map(|tax| tax.amount)
+            .sum()
+    }
+
+    /// Returns the total of the document, taxes included.
+    pub fn total(&self) -> Numeric {
+        self.subtotal() + self.tax()
+    }
+
+    /// Returns the amount remaining to be paid at the end of `date`.
+    ///
+    /// Documents not posted yet on `date` have nothing to be paid.
+    pub fn balance_on(&self, book: &Book, date: NaiveDate) -> Numeric {
+        match self.invoice.post_lot {
+            Some(lot) if self.posted().is_some_and(|posted| posted <= date) => {
+                self.kind.sign() * lot_balance(book, lot, date)
+            }
+            Some(_) | None => Numeric::ZERO,
+        }
+    }
+
+    /// Returns the payment status of the document.
+    pub fn status(&self) -> Status {
+        if self.invoice.post_txn.is_none() {
+            Status::Unposted
+        } else if self.balance.is_zero() {
+            Status::Paid
+        } else if self.balance == self.total() {
+            Status::Unpaid
+        } else {
+            Status::PartiallyPaid
+        }
+    }
+}
+
+impl DocumentKind {
+    /// Returns the kind of document owned by the given type of owner.
+    const fn from_owner_type(kind: OwnerType) -> Self {
+        match kind {
+            OwnerType::Customer | OwnerType::Job => Self::Invoice,
+            OwnerType::Vendor => Self::Bill,
+            OwnerType::Employee => Self::ExpenseVoucher,
+        }
+    }
+
+    /// Returns the sign of the balance of the posting account for an amount
+    /// due.
+    ///
+    /// Invoices are posted to the debit of a receivable account, while bills
+    /// and expense vouchers are posted to the credit of a payable account.
+    pub fn sign(self) -> Numeric {
+        match self {
+            Self::Invoice => Numeric::ONE,
+            Self::Bill | Self::ExpenseVoucher => -Numeric::ONE,
+        }
+    }
+
+    /// Returns the name of the kind of document.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Invoice => "invoice",
+            Self::Bill => "bill",
+            Self::ExpenseVoucher => "expense voucher",
+        }
+    }
+}
+
+impl Status {
+    /// Returns the name of the status.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Unposted => "unposted",
+            Self::Unpaid => "unpaid",
+            Self::PartiallyPaid => "partially paid",
+            Self::Paid => "paid",
+        }
+    }
+}
+
+impl EntryAmounts {
+    /// Computes the amounts of an entry, rounded to `fraction`.
+    ///
+    /// This follows `gncEntryComputeValue` in GnuCash: when the price includes
+    /// taxes, they are first removed to get the pre-tax amount. The discount is
+    /// then computed on the pre-tax amount, or on the amount with taxes when it
+    /// applies after them. Taxes are computed after the discount only when it
+    /// applies before them.
+    fn new(
+        book: &Book,
+        entry: &Entry,
+        kind: DocumentKind,
+        fraction: i128,
+    ) -> Self {
+        let terms = EntryTerms::new(book, entry, kind);
+        let aggregate = entry.quantity.unwrap_or_default() * terms.price;
+
+        let (tax_percent, tax_value) = terms.taxes.iter().fold(
+            (Numeric::ZERO, Numeric::ZERO),
+            |(percent, value), tax| match tax.kind {
+                AmountType::Percent => (percent + tax.amount.percent(), value),
+                AmountType::Value => (percent, value + tax.amount),
+            },
+        );
+
+        let pretax = if terms.tax_included {
+            (Numeric::ONE + tax_percent)
+                .recip()
+                .map_or(Numeric::ZERO, |inverse| {
+                    (aggregate - tax_value) * inverse
+                })
+        } else {
+            aggregate
+        };
+
+        let discount = match (terms.discount_type, terms.discount_how) {
+            (AmountType::Value, _) => terms.discount,
+            (
+                AmountType::Percent,
+                DiscountHow::PreTax | DiscountHow::SameTime,
+            ) => pretax * terms.discount.percent(),
+            (AmountType::Percent, DiscountHow::PostTax) => {
+                (pretax * (Numeric::ONE + tax_percent) + tax_value)
+                    * terms.discount.percent()
+            }
+        };
+
+        let taxable = match terms.discount_how {
+            DiscountHow::PreTax => pretax - discount,
+            DiscountHow::SameTime | DiscountHow::PostTax => pretax,
+        };
+
+        let taxes = terms
+            .taxes
+            .iter()
+            .map(|tax| Tax {
+                account: tax.account,
+                rate: (tax.kind == AmountType::Percent).then_some(tax.amount),
+                amount: match tax.kind {
+                    AmountType::Percent => taxable * tax.amount.percent(),
+                    AmountType::Value => tax.amount,
+                }
+                .round_to(fraction),
+            })
+            .collect();
+
+        Self {
+            value: (pretax - discount).round_to(fraction),
+            discount: discount.round_to(fraction),
+            taxes,
+        }
+    }
+}
+
+impl<'a> EntryTerms<'a> {
+    /// Gets the terms of an entry for the given kind of document.
+    ///
+    /// Only invoices have discounts.
+    fn new(book: &'a Book, entry: &Entry, kind: DocumentKind) -> Self {
+        let (price, taxable, tax_included, tax_table) = match kind {
+            DocumentKind::Invoice => (
+                entry.invoice_price,
+                entry.invoice_taxable,
+                entry.invoice_tax_included,
+                entry.invoice_tax_table,
+            ),
+            DocumentKind::Bill | DocumentKind::ExpenseVoucher => (
+                entry.bill_price,
+                entry.bill_taxable,
+                entry.bill_tax_included,
+                entry.bill_tax_table,
+            ),
+        };
+
+        let taxes = tax_table
+            .filter(|_| taxable)
+            .and_then(|guid| book.business.tax_tables.get(&guid))
+            .map_or(&[][..], |table| table.entries.as_slice());
+
+        let (discount, discount_type, discount_how) = match kind {
+            DocumentKind::Invoice => (
+                entry.invoice_discount.unwrap_or_default(),
+                entry.invoice_discount_type.unwrap_or(AmountType::Percent),
+                entry.invoice_discount_how.unwrap_or(DiscountHow::PreTax),
+            ),
+            DocumentKind::Bill | DocumentKind::ExpenseVoucher => {
+                (Numeric::ZERO, AmountType::Value, DiscountHow::PreTax)
+            }
+        };
+
+        Self {
+            price: price.unwrap_or_default(),
+            tax_included,
+            taxes,
+            discount,
+            discount_type,
+            discount_how,
+        }
+    }
+}
+
+impl Neg for EntryAmounts {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self {
+            value: -self.value,
+            discount: -self.discount,
+            taxes: self
+                .taxes
+                .into_iter()
+                .map(|tax| Tax {
+                    amount: -tax.amount,
+                    ..tax
+                })
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for DocumentKind {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+/// Returns whether an invoice is a credit note.
+fn is_credit_note(invoice: &Invoice) -> bool {
+    matches!(
+        invoice.slots.get(CREDIT_NOTE_SLOT),
+        Some(SlotValue::Int64(value)) if *value != 0
+    )
+}
+
+/// Returns the date at which a posted invoice is due.
+///
+/// GnuCash stores the due date in the posting transaction. When it is missing,
+/// it is computed from the billing terms.
+fn due_date(book: &Book, invoice: &Invoice) -> Option<NaiveDate> {
+    let posted = invoice.date_posted?.date_naive();
+
+    let stored = invoice
+        .post_txn
+        .and_then(|guid| book.transactions.get(&guid))
+        .and_then(|transaction| match transaction.slots.get(DUE_DATE_SLOT) {
+            Some(SlotValue::Timespec(date)) => Some(date.date_naive()),
+            Some(SlotValue::GDate(date)) => Some(*date),
+            _ => None,
+        });
+
+    stored.or_else(|| {
+        let terms = invoice
+            .terms
+            .and_then(|guid| book.business.bill_terms.get(&guid));
+
+        Some(terms.map_or(posted, |terms| terms.due_date(posted)))
+    })
+}
+
+/// Returns the balance of a lot at the end of `date`.
+fn lot_balance(book: &Book, lot: Guid, date: NaiveDate) -> Numeric {
+    book.transactions
+        .values()
+        .filter(|transaction| transaction.date() <= date)
+        .flat_map(|transaction| &transaction.splits)
+        .filter(|split| split.lot == Some(lot))
+        .map(|split| split.quantity)
+        .sum()
+}
@@ -0,0 +1,681 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Drafts of invoices to add to a book.
+//!
+//! Invoices can be written in TOML:
+//!
+//! ```toml
+//! customer = "ACME"
+//! date = 2024-03-01
+//! terms = "Net 30"
+//!
+//! [[entries]]
+//! description = "Consulting"
+//! action = "Hours"
+//! quantity = 10
+//! price = "100.00"
+//! account = "Income:Sales"
+//!
+//! [post]
+//! account = "Assets:Accounts Receivable"
+//! ```
+//!
+//! Posting an invoice follows `gncInvoicePostToAccount` in GnuCash, with the
+//! default preference to accumulate splits: the posting transaction has one
+//! split per income or tax account, and one split for the total in the
+//! receivable account, which opens the lot of the invoice.
+
+use chrono::{NaiveDate, Utc};
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::{
+    DocumentKind, EntryAmounts, CREDIT_NOTE_SLOT, DUE_DATE_SLOT,
+    OWNER_GUID_SLOT, OWNER_TYPE_SLOT, TXN_TYPE_SLOT,
+};
+use crate::{
+    book::{
+        post_date, Account, AccountType, AmountType, BillTerm, Book, Commodity,
+        Customer, DiscountHow, Entry, Frame, Guid, Invoice, Lot, Numeric,
+        Owner, OwnerType, PaymentType, ReconcileState, SlotValue, Split,
+        TaxIncluded, TaxTable, Transaction,
+    },
+    helpers::{
+        deserialize_numeric, deserialize_optional_date,
+        deserialize_optional_numeric,
+    },
+};
+
+/// The counter of the book for invoices.
+const COUNTER: &str = "gncInvoice";
+
+/// The book option giving the default tax table of customers.
+const DEFAULT_TAX_TABLE_SLOT: &str =
+    "options/Business/Default Customer TaxTable";
+
+/// The slot linking a lot or a transaction to its invoice.
+const INVOICE_GUID_SLOT: &str = "gncInvoice/invoice-guid";
+
+/// The type of the transactions posting an invoice.
+const TXN_TYPE_INVOICE: &str = "I";
+
+/// The slot marking a transaction as read-only, with the reason.
+const READ_ONLY_SLOT: &str = "trans-read-only";
+
+/// The reason why GnuCash makes posting transactions read-only.
+const READ_ONLY_REASON: &str =
+    "Generated from an invoice. Try unposting the invoice.";
+
+/// The slot storing the title of a lot.
+const LOT_TITLE_SLOT: &str = "title";
+
+/// The name GnuCash uses for invoices in split actions and lot titles.
+const INVOICE_TYPE: &str = "Invoice";
+
+/// A draft of an invoice, with its customer and accounts referenced by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InvoiceDraft {
+    /// The name or ID of the customer.
+    pub customer: Option<String>,
+    /// The ID of the invoice.
+    ///
+    /// When not set, the next value of the invoice counter of the book is used.
+    pub id: Option<String>,
+    /// The date at which the invoice is opened.
+    #[serde(default, deserialize_with = "deserialize_optional_date")]
+    pub date: Option<NaiveDate>,
+    /// The billing ID, like the reference of a purchase order.
+    pub billing_id: Option<String>,
+    /// The notes of the invoice.
+    pub notes: Option<String>,
+    /// The name of the billing terms, instead of those of the customer.
+    pub terms: Option<String>,
+    /// The name of the tax table of the entries, instead of the one of the
+    /// customer.
+    pub tax_table: Option<String>,
+    /// The entries of the invoice.
+    #[serde(default)]
+    pub entries: Vec<EntryDraft>,
+    /// How to post the invoice, if it should be.
+    pub post: Option<PostDraft>,
+}
+
+/// A draft of an invoice entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EntryDraft {
+    /// The description of the entry.
+    pub description: String,
+    /// The action of the entry, like `Hours` or `Material`.
+    #[serde(default)]
+    pub action: String,
+    /// The quantity.
+    #[serde(deserialize_with = "deserialize_numeric")]
+    pub quantity: Numeric,
+    /// The unit price.
+    #[serde(deserialize_with = "deserialize_numeric")]
+    pub price: Numeric,
+    /// The full path of the income account.
+    pub account: String,
+    /// The discount, in percents, applied before taxes.
+    ///
+    /// It defaults to the discount of the customer.
+    #[serde(default, deserialize_with = "deserialize_optional_numeric")]
+    pub discount: Option<Numeric>,
+    /// The name of the tax table, instead of the one of the invoice.
+    pub tax_table: Option<String>,
+}
+
+/// How to post an invoice.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PostDraft {
+    /// The full path of the receivable account.
+    pub account: String,
+    /// The date at which the invoice is posted.
+    ///
+    /// When not set, the invoice is posted on its opening date.
+    #[serde(default, deserialize_with = "deserialize_optional_date")]
+    pub date: Option<NaiveDate>,
+    /// The date at which the invoice is due.
+    ///
+    /// When not set, it is computed from the billing terms.
+    #[serde(default, deserialize_with = "deserialize_optional_date")]
+    pub due: Option<NaiveDate>,
+    /// The memo of the splits of the posting transaction.
+    #[serde(default)]
+    pub memo: String,
+}
+
+/// An invoice built from a draft, ready to be inserted in a book.
+#[derive(Debug)]
+pub struct NewInvoice {
+    /// The invoice.
+    pub invoice: Invoice,
+    /// The entries of the invoice.
+    pub entries: Vec<Entry>,
+    /// The lot and transaction posting the invoice, if it is posted.
+    pub posting: Option<(Lot, Transaction)>,
+    /// The total of the invoice, taxes included.
+    pub total: Numeric,
+    /// The new value of the invoice counter, when the ID has been generated.
+    pub counter: Option<i64>,
+}
+
+/// Errors that can occur when building an invoice from a draft.
+#[derive(Debug, Error)]
+pub enum InvoiceDraftError {
+    /// The invoice file is invalid.
+    #[error("invalid invoice file: {0}")]
+    Toml(#[from] toml::de::Error),
+    /// No customer has been given.
+    #[error("no customer given for the invoice")]
+    MissingCustomer,
+    /// The customer does not exist.
+    #[error("unknown customer: {0}")]
+    UnknownCustomer(String),
+    /// The currency of the customer is not in the book.
+    #[error("the currency of {0} is not in the book")]
+    UnknownCurrency(String),
+    /// An invoice with the same ID already exists.
+    #[error("there is already an invoice with the ID {0}")]
+    DuplicateId(String),
+    /// The invoice has no entry.
+    #[error("an invoice needs at least one entry")]
+    NoEntries,
+    /// The billing terms do not exist.
+    #[error("unknown billing terms: {0}")]
+    UnknownTerms(String),
+    /// The tax table does not exist.
+    #[error("unknown tax table: {0}")]
+    UnknownTaxTable(String),
+    /// An account does not exist.
+    #[error("unknown account: {0}")]
+    UnknownAccount(String),
+    /// An account is a placeholder.
+    #[error("the account {0} is a placeholder")]
+    PlaceholderAccount(String),
+    /// An account is not in the currency of the invoice.
+    #[error(
+        "the account {account} is not in {currency}, the invoice currency"
+    )]
+    WrongCurrency {
+        /// The account.
+        account: String,
+        /// The currency of the invoice.
+        currency: String,
+    },
+    /// The posting account is not a receivable account.
+    #[error("the account {0} is not an accounts receivable account")]
+    NotReceivable(String),
+}
+
+impl InvoiceDraft {
+    /// Parses a draft from TOML.
+    pub fn from_toml(input: &str) -> Result<Self, InvoiceDraftError> {
+        Ok(toml::from_str(input)?)
+    }
+
+    /// Builds an invoice ready to be inserted in `book`.
+    ///
+    /// The invoice is opened on `today` unless it has a date. The billing
+    /// terms, tax table and currency default to those of the customer.
+    pub fn build(
+        &self,
+        book: &Book,
+        today: NaiveDate,
+    ) -> Result<NewInvoice, InvoiceDraftError> {
+        let name = self
+            .customer
+            .as_deref()
+            .ok_or(InvoiceDraftError::MissingCustomer)?;
+        let customer = book.business.find_customer(name).ok_or_else(|| {
+            InvoiceDraftError::UnknownCustomer(name.to_owned())
+        })?;
+        let currency = book.commodity(customer.currency).ok_or_else(|| {
+            InvoiceDraftError::UnknownCurrency(customer.name.clone())
+        })?;
+
+        if self.entries.is_empty() {
+            return Err(InvoiceDraftError::NoEntries);
+        }
+
+        let (id, counter) = self.id(book)?;
+
+        let terms = match &self.terms {
+            Some(name) => Some(find_terms(book, name)?),
+            None => customer
+                .terms
+                .and_then(|guid| book.business.bill_terms.get(&guid)),
+        };
+
+        let date = self.date.unwrap_or(today);
+        let mut invoice = self.invoice(id, date, customer, terms);
+
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| {
+                self.entry(book, customer, currency, invoice.guid, date, entry)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let amounts: Vec<_> = entries
+            .iter()
+            .map(|entry| {
+                EntryAmounts::new(
+                    book,
+                    entry,
+                    DocumentKind::Invoice,
+                    currency.fraction(),
+                )
+            })
+            .collect();
+
+        let total = amounts
+            .iter()
+            .map(|amounts| {
+                amounts.value + amounts.taxes.iter().map(|tax| tax.amount).sum()
+            })
+            .sum();
+
+        let posting = self
+            .post
+            .as_ref()
+            .map(|post| {
+                let posted = post.date.unwrap_or(date);
+                let due = post.due.unwrap_or_else(|| {
+                    terms.map_or(posted, |terms| terms.due_date(posted))
+                });
+
+                let posting = Posting {
+                    book,
+                    invoice: &invoice,
+                    customer,
+                    currency,
+                    date: posted,
+                    due,
+                    memo: &post.memo,
+                };
+
+                posting.build(&post.account, &entries, &amounts, total)
+            })
+            .transpose()?;
+
+        if let Some((lot, transaction)) = &posting {
+            invoice.date_posted = Some(transaction.post_date);
+            invoice.post_txn = Some(transaction.guid);
+            invoice.post_lot = Some(lot.guid);
+            invoice.post_account = lot.account;
+        }
+
+        Ok(NewInvoice {
+            invoice,
+            entries,
+            posting,
+            total,
+            counter,
+        })
+    }
+
+    /// Returns the ID of the invoice, with the new value of the counter when it
+    /// is generated.
+    fn id(
+        &self,
+        book: &Book,
+    ) -> Result<(String, Option<i64>), InvoiceDraftError> {
+        let (id, counter) = self.id.as_ref().map_or_else(
+            || {
+                let counter = next_counter(book);
+                (format!("{counter:06}"), Some(counter))
+            },
+            |id| (id.clone(), None),
+        );
+
+        if book.business.invoices.values().any(|invoice| {
+            invoice.id == id && is_customer_invoice(book, invoice)
+        }) {
+            return Err(InvoiceDraftError::DuplicateId(id));
+        }
+
+        Ok((id, counter))
+    }
+
+    /// Builds the invoice, not posted yet.
+    ///
+    /// This follows `gncInvoiceCreate` in GnuCash for the values not given in
+    /// the draft.
+    fn invoice(
+        &self,
+        id: String,
+        date: NaiveDate,
+        customer: &Customer,
+        terms: Option<&BillTerm>,
+    ) -> Invoice {
+        Invoice {
+            guid: Guid::new(),
+            id,
+            date_opened: Some(post_date(date)),
+            date_posted: None,
+            notes: self.notes.clone().unwrap_or_default(),
+            active: true,
+            currency: customer.currency,
+            owner: Some(Owner {
+                kind: OwnerType::Customer,
+                guid: customer.guid,
+            }),
+            terms: terms.map(|terms| terms.guid),
+            billing_id: Some(self.billing_id.clone().unwrap_or_default()),
+            post_txn: None,
+            post_lot: None,
+            post_account: None,
+            bill_to: None,
+            charge_amount: Some(Numeric::ZERO),
+            slots: [(CREDIT_NOTE_SLOT.to_owned(), SlotValue::Int64(0))]
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// Builds an entry of the invoice.
+    ///
+    /// This follows `gncEntryCreate` in GnuCash for the values not given in
+    /// the draft, except for the ones the entry ledger of GnuCash takes from
+    /// the customer: the discount, whether prices include taxes and the tax
+    /// table. The tax table is the default one of the book, unless the customer
+    /// overrides it with its own.
+    fn entry(
+        &self,
+        book: &Book,
+        customer: &Customer,
+        currency: &Commodity,
+        invoice: Guid,
+        date: NaiveDate,
+        draft: &EntryDraft,
+    ) -> Result<Entry, InvoiceDraftError> {
+        let account = find_account(book, &draft.account, currency)?;
+
+        let tax_table =
+            match draft.tax_table.as_ref().or(self.tax_table.as_ref()) {
+                Some(name) => Some(find_tax_table(book, name)?.guid),
+                None if customer.tax_override => customer.tax_table,
+                None => default_tax_table(book),
+            };
+
+        Ok(Entry {
+            guid: Guid::new(),
+            date: post_date(date),
+            date_entered: Some(Utc::now()),
+            description: Some(draft.description.clone()),
+            action: Some(draft.action.clone()),
+            notes: Some(String::new()),
+            quantity: Some(draft.quantity),
+            invoice_account: Some(account.guid),
+            invoice_price: Some(draft.price),
+            invoice_discount: Some(draft.discount.unwrap_or(customer.discount)),
+            invoice: Some(invoice),
+            invoice_discount_type: Some(AmountType::Percent),
+            invoice_discount_how: Some(DiscountHow::PreTax),
+            invoice_taxable: tax_table.is_some(),
+            invoice_tax_included: customer.tax_included
+                == Some(TaxIncluded::Yes),
+            invoice_tax_table: tax_table,
+            bill_account: None,
+            bill_price: None,
+            bill: None,
+            bill_taxable: true,
+            bill_tax_included: false,
+            bill_tax_table: None,
+            bill_payment: Some(PaymentType::Cash),
+            billable: false,
+            bill_to: None,
+            order: None,
+            slots: Frame::default(),
+        })
+    }
+}
+
+impl NewInvoice {
+    /// Updates the invoice counter of the book.
+    ///
+    /// The counter is only updated when the ID has been generated from it.
+    pub fn update_counter(&self, book: &mut Book) {
+        if let Some(counter) = self.counter {
+            book.set_counter(COUNTER, counter);
+        }
+    }
+}
+
+/// The context needed to post an invoice.
+struct Posting<'a> {
+    /// The book.
+    book: &'a Book,
+    /// The invoice to post.
+    invoice: &'a Invoice,
+    /// The customer of the invoice.
+    customer: &'a Customer,
+    /// The currency of the invoice.
+    currency: &'a Commodity,
+    /// The date at which the invoice is posted.
+    date: NaiveDate,
+    /// The date at which the invoice is due.
+    due: NaiveDate,
+    /// The memo of the accumulated splits.
+    memo: &'a str,
+}
+
+impl Posting<'_> {
+    /// Builds the lot and transaction posting the invoice to `account`.
+    fn build(
+        &self,
+        account: &str,
+        entries: &[Entry],
+        amounts: &[EntryAmounts],
+        total: Numeric,
+    ) -> Result<(Lot, Transaction), InvoiceDraftError> {
+        let account = find_account(self.book, account, self.currency)?;
+
+        if account.kind != AccountType::Receivable {
+            return Err(InvoiceDraftError::NotReceivable(
+                self.book.account_path(account.guid),
+            ));
+        }
+
+        let mut lot_slots = Frame::default();
+        lot_slots.insert(INVOICE_GUID_SLOT, SlotValue::Guid(self.invoice.guid));
+        lot_slots.insert(
+            LOT_TITLE_SLOT,
+            SlotValue::String(format!("{INVOICE_TYPE} {}", self.invoice.id)),
+        );
+        lot_slots.insert(
+            OWNER_TYPE_SLOT,
+            SlotValue::Int64(OwnerType::Customer.code()),
+        );
+        lot_slots.insert(OWNER_GUID_SLOT, SlotValue::Guid(self.customer.guid));
+
+        let lot = Lot {
+            guid: Guid::new(),
+            account: Some(account.guid),
+            is_closed: false,
+            slots: lot_slots,
+        };
+
+        // Values are accumulated by account, in the order of the entries.
+        let mut values: Vec<(Guid, Numeric)> = Vec::new();
+        let mut add_value = |account: Guid, value: Numeric| match values
+            .iter_mut()
+            .find(|(guid, _)| *guid == account)
+        {
+            Some((_, sum)) => *sum += value,
+            None => values.push((account, value)),
+        };
+
+        for (entry, amounts) in entries.iter().zip(amounts) {
+            if let Some(account) = entry.invoice_account {
+                add_value(account, amounts.value);
+            }
+
+            for tax in &amounts.taxes {
+                add_value(tax.account, tax.amount);
+            }
+        }
+
+        let mut splits: Vec<_> = values
+            .into_iter()
+            .map(|(account, value)| self.split(account, -value, None))
+            .collect();
+
+        if !total.is_zero() {
+            splits.push(self.split(account.guid, total, Some(lot.guid)));
+        }
+
+        let transaction = Transaction {
+            guid: Guid::new(),
+            currency: self.currency.guid,
+            num: self.invoice.id.clone(),
+            post_date: post_date(self.date),
+            enter_date: Utc::now(),
+            description: self.customer.name.clone(),
+            splits,
+            slots: self.transaction_slots(),
+        };
+
+        Ok((lot, transaction))
+    }
+
+    /// Builds a split of the posting transaction.
+    fn split(&self, account: Guid, value: Numeric, lot: Option<Guid>) -> Split {
+        Split {
+            guid: Guid::new(),
+            account,
+            memo: self.memo.to_owned(),
+            action: INVOICE_TYPE.to_owned(),
+            reconcile_state: ReconcileState::NotReconciled,
+            reconcile_date: None,
+            value,
+            quantity: value,
+            lot,
+            slots: Frame::default(),
+        }
+    }
+
+    /// Returns the slots of the posting transaction.
+    fn transaction_slots(&self) -> Frame {
+        let mut slots = Frame::default();
+        slots.insert("date-posted", SlotValue::GDate(self.date));
+        slots.insert(DUE_DATE_SLOT, SlotValue::Timespec(post_date(self.due)));
+        slots.insert(
+            TXN_TYPE_SLOT,
+            SlotValue::String(TXN_TYPE_INVOICE.to_owned()),
+        );
+        slots.insert(
+            READ_ONLY_SLOT,
+            SlotValue::String(READ_ONLY_REASON.to_owned()),
+        );
+        slots.insert(INVOICE_GUID_SLOT, SlotValue::Guid(self.invoice.guid));
+        slots
+    }
+}
+
+/// Returns the next value of the invoice counter.
+///
+/// The counter is kept in the slots of the book, but is also checked against
+/// the existing numeric IDs in case they have been set by hand.
+fn next_counter(book: &Book) -> i64 {
+    let counter = book.counter(COUNTER);
+
+    let highest = book
+        .business
+        .invoices
+        .values()
+        .filter(|invoice| is_customer_invoice(book, invoice))
+        .filter_map(|invoice| invoice.id.parse::<i64>().ok())
+        .max()
+        .unwrap_or(0);
+
+    counter.max(highest).saturating_add(1)
+}
+
+/// Returns whether an invoice is owned by a customer, directly or through a
+/// job.
+fn is_customer_invoice(book: &Book, invoice: &Invoice) -> bool {
+    invoice.owner.is_some_and(|owner| {
+        book.business.end_owner(owner).kind == OwnerType::Customer
+    })
+}
+
+/// Finds an account usable in an invoice in the given currency.
+fn find_account<'a>(
+    book: &'a Book,
+    path: &str,
+    currency: &Commodity,
+) -> Result<&'a Account, InvoiceDraftError> {
+    let account = book
+        .find_account(path)
+        .ok_or_else(|| InvoiceDraftError::UnknownAccount(path.to_owned()))?;
+
+    if account.placeholder {
+        return Err(InvoiceDraftError::PlaceholderAccount(path.to_owned()));
+    }
+
+    if account.commodity != Some(currency.guid) {
+        return Err(InvoiceDraftError::WrongCurrency {
+            account: path.to_owned(),
+            currency: currency.mnemonic.clone(),
+        });
+    }
+
+    Ok(account)
+}
+
+/// Finds billing terms by name.
+///
+/// Copies of billing terms made by GnuCash for its documents are ignored.
+fn find_terms<'a>(
+    book: &'a Book,
+    name: &str,
+) -> Result<&'a BillTerm, InvoiceDraftError> {
+    book.business
+        .bill_terms
+        .values()
+        .find(|terms| terms.name == name && terms.parent.is_none())
+        .ok_or_else(|| InvoiceDraftError::UnknownTerms(name.to_owned()))
+}
+
+/// Finds a tax table by name.
+///
+/// Copies of tax tables made by GnuCash for its documents are ignored.
+fn find_tax_table<'a>(
+    book: &'a Book,
+    name: &str,
+) -> Result<&'a TaxTable, InvoiceDraftError> {
+    book.business
+        .tax_tables
+        .values()
+        .find(|table| table.name == name && table.parent.is_none())
+        .ok_or_else(|| InvoiceDraftError::UnknownTaxTable(name.to_owned()))
+}
+
+/// Returns the default tax table of customers set in the options of the book.
+fn default_tax_table(book: &Book) -> Option<Guid> {
+    match book.slots.get(DEFAULT_TAX_TABLE_SLOT)? {
+        SlotValue::Guid(guid) => {
+            book.business.tax_tables.contains_key(guid).then_some(*guid)
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,495 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Payments of invoices, bills and expense vouchers.
+//!
+//! Payments are recorded the way the Process Payment dialog of GnuCash does:
+//! the payment transaction has a split for the whole amount in the transfer
+//! account, and a split in the posting account for each document paid, added
+//! to the lot of the document. What remains is left in a new lot of the owner,
+//! as a pre-payment that GnuCash can apply to later documents.
+
+use chrono::{NaiveDate, Utc};
+use thiserror::Error;
+
+use super::{
+    Document, DocumentKind, Status, OWNER_GUID_SLOT, OWNER_TYPE_SLOT,
+    TXN_TYPE_SLOT,
+};
+use crate::book::{
+    post_date, Account, AccountType, Book, Commodity, Frame, Guid, Lot,
+    Numeric, Owner, ReconcileState, SlotValue, Split, Transaction,
+};
+
+/// The type of payment transactions.
+const TXN_TYPE_PAYMENT: &str = "P";
+
+/// The action GnuCash sets on the splits of payments.
+const PAYMENT_ACTION: &str = "Payment";
+
+/// A payment to record, with its accounts referenced by their full path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentDraft {
+    /// The customer, vendor or employee.
+    pub owner: Owner,
+    /// The full path of the transfer account, like a bank account.
+    pub account: String,
+    /// The amount of the payment.
+    pub amount: Numeric,
+    /// The date of the payment.
+    pub date: NaiveDate,
+    /// The number of the payment, like a check number.
+    pub num: String,
+    /// The memo of the splits.
+    pub memo: String,
+    /// The documents to pay, in order.
+    ///
+    /// When empty, the open documents of the owner are paid oldest first.
+    pub allocations: Vec<Allocation>,
+    /// The full path of the posting account.
+    ///
+    /// When not set, it is the posting account of the documents paid.
+    pub post_account: Option<String>,
+}
+
+/// A document to pay explicitly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Allocation {
+    /// The ID of the document.
+    pub id: String,
+    /// The amount to pay.
+    ///
+    /// When not set, the document is paid as much as possible.
+    pub amount: Option<Numeric>,
+}
+
+/// A payment built from a draft, ready to be inserted in a book.
+#[derive(Debug)]
+pub struct NewPayment<'a> {
+    /// The payment transaction.
+    pub transaction: Transaction,
+    /// The documents paid, with the amount applied to each of them.
+    pub applied: Vec<(Document<'a>, Numeric)>,
+    /// The lot of the pre-payment, if the documents are over-paid.
+    pub prepayment: Option<Lot>,
+    /// The lots of the documents fully paid, to be closed.
+    pub closed_lots: Vec<Guid>,
+}
+
+/// Errors that can occur when building a payment.
+#[derive(Debug, Error)]
+pub enum PaymentError {
+    /// The amount of the payment is not positive.
+    #[error("the amount of a payment must be positive")]
+    NotPositive,
+    /// The currency of the owner is not in the book.
+    #[error("the currency of {0} is not in the book")]
+    UnknownCurrency(String),
+    /// An amount is more precise than the currency.
+    #[error("{amount} is too precise for {currency}")]
+    TooPrecise {
+        /// The amount.
+        amount: Numeric,
+        /// The currency.
+        currency: String,
+    },
+    /// An account does not exist.
+    #[error("unknown account: {0}")]
+    UnknownAccount(String),
+    /// An account is a placeholder.
+    #[error("the account {0} is a placeholder")]
+    PlaceholderAccount(String),
+    /// An account is not in the currency of the owner.
+    #[error("the account {account} is not in {currency}, the owner currency")]
+    WrongCurrency {
+        /// The account.
+        account: String,
+        /// The currency of the owner.
+        currency: String,
+    },
+    /// The posting account has not the right type.
+    #[error("the account {account} is not an accounts {side} account")]
+    WrongPostAccount {
+        /// The account.
+        account: String,
+        /// The expected side, receivable or payable.
+        side: &'static str,
+    },
+    /// There is no document with the given ID.
+    #[error("there is no {kind} with the ID {id} for {owner}")]
+    UnknownDocument {
+        /// The name of the owner.
+        owner: String,
+        /// The kind of document.
+        kind: DocumentKind,
+        /// The ID.
+        id: String,
+    },
+    /// The document has nothing left to pay.
+    #[error("the {kind} {id} has nothing left to pay")]
+    NotOpen {
+        /// The kind of document.
+        kind: DocumentKind,
+        /// The ID.
+        id: String,
+    },
+    /// The amount to pay exceeds the balance of the document.
+    #[error("{amount} exceeds the {balance} left to pay on the {kind} {id}")]
+    ExceedsBalance {
+        /// The amount to pay.
+        amount: Numeric,
+        /// The balance of the document.
+        balance: Numeric,
+        /// The kind of document.
+        kind: DocumentKind,
+        /// The ID.
+        id: String,
+    },
+    /// The amounts to pay exceed the payment.
+    #[error("the amounts to pay exceed the payment of {0}")]
+    ExceedsPayment(Numeric),
+    /// The documents are posted to different accounts.
+    #[error("the documents to pay are posted to different accounts")]
+    DifferentPostAccounts,
+    /// The posting account cannot be inferred.
+    #[error("there is no document to pay to infer the posting account from")]
+    MissingPostAccount,
+}
+
+impl PaymentDraft {
+    /// Builds a payment ready to be inserted in `book`.
+    ///
+    /// Only the posted documents of the owner in its currency can be paid.
+    pub fn build<'a>(
+        &self,
+        book: &'a Book,
+    ) -> Result<NewPayment<'a>, PaymentError> {
+        if !self.amount.is_positive() {
+            return Err(PaymentError::NotPositive);
+        }
+
+        let owner_name = book
+            .business
+            .owner_name(self.owner)
+            .unwrap_or_default()
+            .to_owned();
+        let currency = book
+            .business
+            .owner_currency(self.owner)
+            .and_then(|guid| book.commodity(guid))
+            .ok_or_else(|| PaymentError::UnknownCurrency(owner_name.clone()))?;
+
+        check_precision(self.amount, currency)?;
+
+        let kind = DocumentKind::from_owner_type(self.owner.kind);
+        let transfer = find_account(book, &self.account, currency)?;
+        let post_account = self
+            .post_account
+            .as_deref()
+            .map(|path| find_post_account(book, path, currency, kind))
+            .transpose()?;
+
+        let open: Vec<_> = Document::all(book)
+            .into_iter()
+            .filter(|document| {
+                document.owner == Some(self.owner)
+                    && document.kind == kind
+                    && document.invoice.currency == currency.guid
+                    && document.balance.is_positive()
+                    && post_account.map_or(true, |account| {
+                        document.invoice.post_account == Some(account.guid)
+                    })
+            })
+            .collect();
+
+        let applied = self.apply(book, &owner_name, kind, currency, open)?;
+
+        let post_account = match post_account {
+            Some(account) => account.guid,
+            None => posting_account(&applied)?,
+        };
+
+        let remaining =
+            self.amount - applied.iter().map(|(_, amount)| *amount).sum();
+        let sign = kind.sign();
+
+        let mut splits = vec![self.split(transfer.guid, sign * self.amount)];
+        splits.extend(applied.iter().map(|(document, amount)| {
+            let mut split = self.split(post_account, -(sign * *amount));
+            split.lot = document.invoice.post_lot;
+            split
+        }));
+
+        let prepayment = remaining.is_positive().then(|| {
+            let lot = self.prepayment_lot(post_account);
+            let mut split = self.split(post_account, -(sign * remaining));
+            split.lot = Some(lot.guid);
+            splits.push(split);
+            lot
+        });
+
+        let closed_lots = applied
+            .iter()
+            .filter(|(document, amount)| *amount == document.balance)
+            .filter_map(|(document, _)| document.invoice.post_lot)
+            .collect();
+
+        let mut slots = Frame::default();
+        slots.insert("date-posted", SlotValue::GDate(self.date));
+        slots.insert(
+            TXN_TYPE_SLOT,
+            SlotValue::String(TXN_TYPE_PAYMENT.to_owned()),
+        );
+
+        let transaction = Transaction {
+            guid: Guid::new(),
+            currency: currency.guid,
+            num: self.num.clone(),
+            post_date: post_date(self.date),
+            enter_date: Utc::now(),
+            description: owner_name,
+            splits,
+            slots,
+        };
+
+        Ok(NewPayment {
+            transaction,
+            applied,
+            prepayment,
+            closed_lots,
+        })
+    }
+
+    /// Applies the payment to the open documents.
+    ///
+    /// Without explicit allocations, the documents are paid by due date.
+    fn apply<'a>(
+        &self,
+        book: &'a Book,
+        owner_name: &str,
+        kind: DocumentKind,
+        currency: &Commodity,
+        mut open: Vec<Document<'a>>,
+    ) -> Result<Vec<(Document<'a>, Numeric)>, PaymentError> {
+        let mut remaining = self.amount;
+        let mut applied = Vec::new();
+
+        if self.allocations.is_empty() {
+            open.sort_by(|lhs, rhs| {
+                (lhs.due_date, lhs.posted(), &lhs.invoice.id).cmp(&(
+                    rhs.due_date,
+                    rhs.posted(),
+                    &rhs.invoice.id,
+                ))
+            });
+
+            for document in open {
+                if !remaining.is_positive() {
+                    break;
+                }
+
+                let amount = remaining.min(document.balance);
+                remaining -= amount;
+                applied.push((document, amount));
+            }
+
+            return Ok(applied);
+        }
+
+        for allocation in &self.allocations {
+            let position = open
+                .iter()
+                .position(|document| document.invoice.id == allocation.id)
+                .ok_or_else(|| {
+                    unpayable(book, self.owner, owner_name, kind, allocation)
+                })?;
+            let document = open.swap_remove(position);
+
+            let amount = match allocation.amount {
+                Some(amount) => {
+                    check_precision(amount, currency)?;
+
+                    if !amount.is_positive() {
+                        return Err(PaymentError::NotPositive);
+                    }
+
+                    if amount > document.balance {
+                        return Err(PaymentError::ExceedsBalance {
+                            amount,
+                            balance: document.balance,
+                            kind,
+                            id: allocation.id.clone(),
+                        });
+                    }
+
+                    amount
+                }
+                None => remaining.min(document.balance),
+            };
+
+            if amount > remaining {
+                return Err(PaymentError::ExceedsPayment(self.amount));
+            }
+
+            remaining -= amount;
+            applied.push((document, amount));
+        }
+
+        Ok(applied)
+    }
+
+    /// Builds a split of the payment transaction.
+    fn split(&self, account: Guid, value: Numeric) -> Split {
+        Split {
+            guid: Guid::new(),
+            account,
+            memo: self.memo.clone(),
+            action: PAYMENT_ACTION.to_owned(),
+            reconcile_state: ReconcileState::NotReconciled,
+            reconcile_date: None,
+            value,
+            quantity: value,
+            lot: None,
+            slots: Frame::default(),
+        }
+    }
+
+    /// Builds a lot of the owner for a pre-payment.
+    fn prepayment_lot(&self, account: Guid) -> Lot {
+        let mut slots = Frame::default();
+        slots.insert(OWNER_TYPE_SLOT, SlotValue::Int64(self.owner.kind.code()));
+        slots.insert(OWNER_GUID_SLOT, SlotValue::Guid(self.owner.guid));
+
+        Lot {
+            guid: Guid::new(),
+            account: Some(account),
+            is_closed: false,
+            slots,
+        }
+    }
+}
+
+/// Returns the posting account shared by the documents paid.
+fn posting_account(
+    applied: &[(Document<'_>, Numeric)],
+) -> Result<Guid, PaymentError> {
+    let mut accounts = applied
+        .iter()
+        .filter_map(|(document, _)| document.invoice.post_account);
+
+    let first = accounts.next().ok_or(PaymentError::MissingPostAccount)?;
+
+    if accounts.all(|account| account == first) {
+        Ok(first)
+    } else {
+        Err(PaymentError::DifferentPostAccounts)
+    }
+}
+
+/// Returns the error explaining why a document cannot be paid.
+fn unpayable(
+    book: &Book,
+    owner: Owner,
+    owner_name: &str,
+    kind: DocumentKind,
+    allocation: &Allocation,
+) -> PaymentError {
+    let exists =
+        Document::find(book, kind, &allocation.id).is_some_and(|document| {
+            document.owner == Some(owner)
+                && document.status() != Status::Unposted
+        });
+
+    if exists {
+        PaymentError::NotOpen {
+            kind,
+            id: allocation.id.clone(),
+        }
+    } else {
+        PaymentError::UnknownDocument {
+            owner: owner_name.to_owned(),
+            kind,
+            id: allocation.id.clone(),
+        }
+    }
+}
+
+/// Checks an amount is not more precise than the currency.
+fn check_precision(
+    amount: Numeric,
+    currency: &Commodity,
+) -> Result<(), PaymentError> {
+    if amount.fits_fraction(currency.fraction()) {
+        Ok(())
+    } else {
+        Err(PaymentError::TooPrecise {
+            amount,
+            currency: currency.mnemonic.clone(),
+        })
+    }
+}
+
+/// Finds an account usable in a payment in the given currency.
+fn find_account<'a>(
+    book: &'a Book,
+    path: &str,
+    currency: &Commodity,
+) -> Result<&'a Account, PaymentError> {
+    let account = book
+        .find_account(path)
+        .ok_or_else(|| PaymentError::UnknownAccount(path.to_owned()))?;
+
+    if account.placeholder {
+        return Err(PaymentError::PlaceholderAccount(path.to_owned()));
+    }
+
+    if account.commodity != Some(currency.guid) {
+        return Err(PaymentError::WrongCurrency {
+            account: path.to_owned(),
+            currency: currency.mnemonic.clone(),
+        });
+    }
+
+    Ok(account)
+}
+
+/// Finds the posting account of a payment.
+///
+/// It must be a receivable account for invoices, and a payable account for
+/// bills and expense vouchers.
+fn find_post_account<'a>(
+    book: &'a Book,
+    path: &str,
+    currency: &Commodity,
+    kind: DocumentKind,
+) -> Result<&'a Account, PaymentError> {
+    let account = find_account(book, path, currency)?;
+
+    let (expected, side) = match kind {
+        DocumentKind::Invoice => (AccountType::Receivable, "receivable"),
+        DocumentKind::Bill | DocumentKind::ExpenseVoucher => {
+            (AccountType::Payable, "payable")
+        }
+    };
+
+    if account.kind == expected {
+        Ok(account)
+    } else {
+        Err(PaymentError::WrongPostAccount {
+            account: path.to_owned(),
+            side,
+        })
+    }
+}
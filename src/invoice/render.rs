@@ -0,0 +1,514 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Rendering of documents from templates.
+//!
+//! Templates use the Jinja syntax, as implemented by `minijinja`. The values
+//! are escaped depending on the extension of the template: `.html` and `.htm`
+//! templates are escaped for HTML and `.typ` templates for Typst markup. Other
+//! templates are not escaped.
+//!
+//! The amounts are given as strings formatted with the number of decimals of
+//! the currency, so that they can be printed as is. Missing values are written
+//! as empty strings.
+
+use minijinja::{
+    AutoEscape, Environment, Error, Output, State, UndefinedBehavior, Value,
+};
+use serde::Serialize;
+
+use super::{Document, Tax};
+use crate::book::{
+    Address, Book, Commodity, Entry, Frame, Numeric, Owner, OwnerType,
+};
+
+/// The slots of the book storing the company information.
+const COMPANY_SLOTS: &str = "options/Business";
+
+/// The auto-escaping mode for Typst markup.
+const TYPST: AutoEscape = AutoEscape::Custom("typst");
+
+/// The characters with a meaning in Typst markup.
+const TYPST_SPECIAL_CHARS: &[char] = &[
+    '\\', '#', '$', '*', '_', '`', '<', '>', '@', '[', ']', '~', '/',
+];
+
+/// The characters with a meaning at the start of a line in Typst markup.
+const TYPST_LINE_START_CHARS: &[char] = &['=', '-', '+'];
+
+/// The values given to templates.
+#[derive(Debug, Serialize)]
+struct Context<'a> {
+    /// The company issuing the invoices.
+    company: Company<'a>,
+    /// The document.
+    invoice: InvoiceContext<'a>,
+    /// The customer, vendor or employee.
+    owner: Option<OwnerContext<'a>>,
+    /// The job of the document.
+    job: Option<JobContext<'a>>,
+    /// The lines of the document.
+    entries: Vec<EntryContext<'a>>,
+    /// The taxes, by account and rate.
+    taxes: Vec<TaxContext<'a>>,
+    /// The totals of the document.
+    totals: Totals,
+}
+
+/// The company information, from the options of the book.
+#[derive(Debug, Serialize)]
+struct Company<'a> {
+    /// The name of the company.
+    name: Option<&'a str>,
+    /// The address of the company, as written in the options.
+    address: Option<&'a str>,
+    /// The identifier of the company, like its VAT number.
+    id: Option<&'a str>,
+    /// The contact person.
+    contact: Option<&'a str>,
+    /// The phone number.
+    phone: Option<&'a str>,
+    /// The fax number.
+    fax: Option<&'a str>,
+    /// The email address.
+    email: Option<&'a str>,
+    /// The website.
+    website: Option<&'a str>,
+}
+
+/// The information about a document.
+#[derive(Debug, Serialize)]
+struct InvoiceContext<'a> {
+    /// The ID of the document.
+    id: &'a str,
+    /// The kind of document.
+    kind: &'static str,
+    /// Whether the document is a credit note.
+    credit_note: bool,
+    /// The billing ID, like a purchase order number.
+    billing_id: Option<&'a str>,
+    /// The notes of the document.
+    notes: Option<&'a str>,
+    /// The date at which the document has been opened.
+    opened: Option<String>,
+    /// The date at which the document has been posted.
+    posted: Option<String>,
+    /// The date at which the document is due.
+    due: Option<String>,
+    /// The description of the billing terms.
+    terms: Option<&'a str>,
+    /// The ISO code of the currency.
+    currency: Option<&'a str>,
+    /// The payment status.
+    status: &'static str,
+}
+
+/// The information about an owner.
+#[derive(Debug, Serialize)]
+struct OwnerContext<'a> {
+    /// The kind of owner.
+    kind: &'static str,
+    /// The ID of the owner.
+    id: &'a str,
+    /// The name of the owner.
+    name: &'a str,
+    /// The billing address.
+    address: AddressContext<'a>,
+    /// The shipping address, for customers.
+    shipping_address: Option<AddressContext<'a>>,
+}
+
+/// A postal address with contact information.
+#[derive(Debug, Serialize)]
+struct AddressContext<'a> {
+    /// The name of the addressee.
+    name: Option<&'a str>,
+    /// The non-empty lines of the address.
+    lines: Vec<&'a str>,
+    /// The phone number.
+    phone: Option<&'a str>,
+    /// The fax number.
+    fax: Option<&'a str>,
+    /// The email address.
+    email: Option<&'a str>,
+}
+
+/// The information about a job.
+#[derive(Debug, Serialize)]
+struct JobContext<'a> {
+    /// The ID of the job.
+    id: &'a str,
+    /// The name of the job.
+    name: &'a str,
+    /// The reference of the job.
+    reference: Option<&'a str>,
+}
+
+/// A line of a document.
+#[derive(Debug, Serialize)]
+struct EntryContext<'a> {
+    /// The date of the entry.
+    date: String,
+    /// The description of the entry.
+    description: Option<&'a str>,
+    /// The action of the entry, like `Hours`.
+    action: Option<&'a str>,
+    /// The notes of the entry.
+    notes: Option<&'a str>,
+    /// The quantity.
+    quantity: String,
+    /// The unit price.
+    price: String,
+    /// The discount.
+    discount: String,
+    /// The value, after discount and without taxes.
+    value: String,
+    /// The taxes of the entry.
+    taxes: Vec<TaxContext<'a>>,
+    /// The total of the taxes.
+    tax: String,
+    /// The value with taxes.
+    total: String,
+}
+
+/// A tax.
+#[derive(Debug, Serialize)]
+struct TaxContext<'a> {
+    /// The name of the tax account.
+    name: &'a str,
+    /// The rate of the tax, like `20%`, unless it is a fixed value.
+    rate: Option<String>,
+    /// The amount of the tax.
+    amount: String,
+}
+
+/// The totals of a document.
+#[derive(Debug, Serialize)]
+struct Totals {
+    /// The total without taxes.
+    subtotal: String,
+    /// The total of the taxes.
+    tax: String,
+    /// The total with taxes.
+    total: String,
+    /// The amount already paid.
+    paid: String,
+    /// The amount remaining to be paid.
+    due: String,
+}
+
+/// Renders a document with a template.
+///
+/// The name of the template is used to choose how to escape the values.
+pub fn render(
+    book: &Book,
+    document: &Document<'_>,
+    name: &str,
+    template: &str,
+) -> Result<String, Error> {
+    let mut environment = Environment::new();
+    environment.set_undefined_behavior(UndefinedBehavior::Strict);
+    environment.set_auto_escape_callback(auto_escape);
+    environment.set_formatter(format_value);
+    environment.add_template(name, template)?;
+
+    let context = Context::new(book, document);
+    environment.get_template(name)?.render(context)
+}
+
+impl<'a> Context<'a> {
+    /// Builds the context of a document.
+    fn new(book: &'a Book, document: &Document<'a>) -> Self {
+        let fraction = document.currency.map_or(100, Commodity::fraction);
+        let amount = |amount: Numeric| amount.to_fixed(fraction);
+
+        let owner = document
+            .owner
+            .and_then(|owner| OwnerContext::new(book, owner));
+
+        let job = document
+            .invoice
+            .owner
+            .filter(|owner| owner.kind == OwnerType::Job)
+            .and_then(|owner| book.business.jobs.get(&owner.guid))
+            .map(|job| JobContext {
+                id: &job.id,
+                name: &job.name,
+                reference: non_empty(&job.reference),
+            });
+
+        let entries = document
+            .lines
+            .iter()
+            .map(|line| {
+                let amounts = &line.amounts;
+                let tax: Numeric =
+                    amounts.taxes.iter().map(|tax| tax.amount).sum();
+
+                EntryContext {
+                    date: line.entry.date.date_naive().to_string(),
+                    description: line.entry.description.as_deref(),
+                    action: line.entry.action.as_deref(),
+                    notes: line.entry.notes.as_deref().and_then(non_empty),
+                    quantity: line
+                        .entry
+                        .quantity
+                        .unwrap_or_default()
+                        .to_string(),
+                    price: unit_price(line.entry, document).to_exact(fraction),
+                    discount: amount(amounts.discount),
+                    value: amount(amounts.value),
+                    taxes: amounts
+                        .taxes
+                        .iter()
+                        .map(|tax| TaxContext::new(book, tax, fraction))
+                        .collect(),
+                    tax: amount(tax),
+                    total: amount(amounts.value + tax),
+                }
+            })
+            .collect();
+
+        let taxes = tax_summary(document)
+            .iter()
+            .map(|tax| TaxContext::new(book, tax, fraction))
+            .collect();
+
+        Self {
+            company: Company::new(&book.slots),
+            invoice: InvoiceContext::new(book, document),
+            owner,
+            job,
+            entries,
+            taxes,
+            totals: Totals {
+                subtotal: amount(document.subtotal()),
+                tax: amount(document.tax()),
+                total: amount(document.total()),
+                paid: amount(document.total() - document.balance),
+                due: amount(document.balance),
+            },
+        }
+    }
+}
+
+impl<'a> Company<'a> {
+    /// Gets the company information from the slots of the book.
+    fn new(slots: &'a Frame) -> Self {
+        let option = |name: &str| {
+            slots
+                .string(&format!("{COMPANY_SLOTS}/{name}"))
+                .and_then(non_empty)
+        };
+
+        Self {
+            name: option("Company Name"),
+            address: option("Company Address"),
+            id: option("Company ID"),
+            contact: option("Company Contact Person"),
+            phone: option("Company Phone Number"),
+            fax: option("Company Fax Number"),
+            email: option("Company Email Address"),
+            website: option("Company Website URL"),
+        }
+    }
+}
+
+impl<'a> InvoiceContext<'a> {
+    /// Builds the information about a document.
+    fn new(book: &'a Book, document: &Document<'a>) -> Self {
+        let invoice = document.invoice;
+
+        let terms = invoice
+            .terms
+            .and_then(|guid| book.business.bill_terms.get(&guid))
+            .map(|terms| non_empty(&terms.description).unwrap_or(&terms.name));
+
+        Self {
+            id: &invoice.id,
+            kind: document.kind.as_str(),
+            credit_note: super::is_credit_note(invoice),
+            billing_id: invoice.billing_id.as_deref().and_then(non_empty),
+            notes: non_empty(&invoice.notes),
+            opened: document.opened().map(|date| date.to_string()),
+            posted: document.posted().map(|date| date.to_string()),
+            due: document.due_date.map(|date| date.to_string()),
+            terms,
+            currency: document
+                .currency
+                .map(|currency| currency.mnemonic.as_str()),
+            status: document.status().as_str(),
+        }
+    }
+}
+
+impl<'a> OwnerContext<'a> {
+    /// Builds the information about an owner.
+    fn new(book: &'a Book, owner: Owner) -> Option<Self> {
+        let business = &book.business;
+
+        let (address, shipping_address) = match owner.kind {
+            OwnerType::Customer => {
+                business.customers.get(&owner.guid).map(|customer| {
+                    (&customer.address, Some(&customer.ship_address))
+                })
+            }
+            OwnerType::Vendor => business
+                .vendors
+                .get(&owner.guid)
+                .map(|vendor| (&vendor.address, None)),
+            OwnerType::Employee => business
+                .employees
+                .get(&owner.guid)
+                .map(|employee| (&employee.address, None)),
+            OwnerType::Job => None,
+        }?;
+
+        Some(Self {
+            kind: match owner.kind {
+                OwnerType::Customer => "customer",
+                OwnerType::Job => "job",
+                OwnerType::Vendor => "vendor",
+                OwnerType::Employee => "employee",
+            },
+            id: business.owner_id(owner)?,
+            name: business.owner_name(owner)?,
+            address: AddressContext::new(address),
+            shipping_address: shipping_address
+                .map(AddressContext::new)
+                .filter(|address| !address.lines.is_empty()),
+        })
+    }
+}
+
+impl<'a> AddressContext<'a> {
+    /// Builds a postal address, skipping the empty fields.
+    fn new(address: &'a Address) -> Self {
+        let field =
+            |field: &'a Option<String>| field.as_deref().and_then(non_empty);
+
+        Self {
+            name: field(&address.name),
+            lines: [
+                &address.addr1,
+                &address.addr2,
+                &address.addr3,
+                &address.addr4,
+            ]
+            .into_iter()
+            .filter_map(field)
+            .collect(),
+            phone: field(&address.phone),
+            fax: field(&address.fax),
+            email: field(&address.email),
+        }
+    }
+}
+
+impl<'a> TaxContext<'a> {
+    /// Builds the information about a tax.
+    fn new(book: &'a Book, tax: &Tax, fraction: i128) -> Self {
+        Self {
+            name: book
+                .account(tax.account)
+                .map_or("", |account| account.name.as_str()),
+            rate: tax.rate.map(|rate| format!("{rate}%")),
+            amount: tax.amount.to_fixed(fraction),
+        }
+    }
+}
+
+/// Returns the unit price of an entry in a document.
+fn unit_price(entry: &Entry, document: &Document<'_>) -> Numeric {
+    match document.kind {
+        super::DocumentKind::Invoice => entry.invoice_price,
+        super::DocumentKind::Bill | super::DocumentKind::ExpenseVoucher => {
+            entry.bill_price
+        }
+    }
+    .unwrap_or_default()
+}
+
+/// Sums the taxes of a document by account and rate.
+fn tax_summary(document: &Document<'_>) -> Vec<Tax> {
+    let mut taxes: Vec<Tax> = vec![];
+
+    for tax in document.lines.iter().flat_map(|line| &line.amounts.taxes) {
+        let same_tax = |other: &&mut Tax| {
+            other.account == tax.account && other.rate == tax.rate
+        };
+
+        if let Some(total) = taxes.iter_mut().find(same_tax) {
+            total.amount += tax.amount;
+        } else {
+            taxes.push(*tax);
+        }
+    }
+
+    taxes
+}
+
+/// Returns how to escape the values in a template, given its name.
+fn auto_escape(name: &str) -> AutoEscape {
+    match name.rsplit_once('.').map(|(_, extension)| extension) {
+        Some("html" | "htm") => AutoEscape::Html,
+        Some("typ") => TYPST,
+        _ => AutoEscape::None,
+    }
+}
+
+/// Writes a value in a template, escaping it for Typst markup if needed.
+///
+/// Missing values, like an address without fax number, are written as empty
+/// strings.
+fn format_value(
+    output: &mut Output<'_>,
+    state: &State<'_, '_>,
+    value: &Value,
+) -> Result<(), Error> {
+    if value.is_none() {
+        Ok(())
+    } else if state.auto_escape() == TYPST && !value.is_safe() {
+        let escaped = escape_typst(&value.to_string());
+        output.write_str(&escaped).map_err(Error::from)
+    } else {
+        minijinja::escape_formatter(output, state, value)
+    }
+}
+
+/// Escapes the characters with a meaning in Typst markup.
+///
+/// As a value can be written at the start of a line, its first character is
+/// also escaped if it would start a heading or a list.
+fn escape_typst(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for (index, c) in text.chars().enumerate() {
+        if TYPST_SPECIAL_CHARS.contains(&c)
+            || (index == 0 && TYPST_LINE_START_CHARS.contains(&c))
+        {
+            escaped.push('\\');
+        }
+
+        escaped.push(c);
+    }
+
+    escaped
+}
+
+/// Returns the string unless it is empty.
+fn non_empty(string: &str) -> Option<&str> {
+    (!string.is_empty()).then_some(string)
+}
@@ -0,0 +1,616 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Ledger-style plain text journals.
+//!
+//! Only the subset of the ledger / hledger syntax needed to write transactions
+//! is supported:
+//!
+//! ```text
+//! ; A comment.
+//! 2024-02-01 * (42) Bakery  ; id:2024-02-bakery, birthday cake
+//!     Expenses:Food                12.34 EUR  ; memo
+//!     Assets:Broker:AAPL           10 AAPL @ 150.25 EUR
+//!     Assets:Bank:Checking
+//! ```
+//!
+//! Account names end with two spaces or a tab. At most one posting per
+//! transaction can omit its amount. Comments start with a `;` preceded by
+//! whitespace. As in ledger, `*` marks cleared postings and `!` pending ones,
+//! which are not reconciled yet in GnuCash. The `id` tag gives the transaction
+//! a stable ID: when it is missing, an ID is derived from the content of the
+//! transaction and its occurrence among identical ones.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::NaiveDate;
+use thiserror::Error;
+
+use crate::book::{
+    Book, DraftError, Numeric, PostingDraft, PriceSpec, ReconcileState,
+    SlotValue, Transaction, TransactionDraft,
+};
+
+/// The directives that are ignored, with their indented sub-directives.
+const IGNORED_DIRECTIVES: &[&str] = &[
+    "account",
+    "commodity",
+    "decimal-mark",
+    "D",
+    "P",
+    "payee",
+    "tag",
+];
+
+/// The formats accepted for dates.
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%Y/%m/%d", "%Y.%m.%d"];
+
+/// A transaction entry from a journal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The line of the entry in the journal.
+    pub line: usize,
+    /// The ID of the entry.
+    pub id: String,
+    /// The draft of the transaction.
+    pub draft: TransactionDraft,
+    /// The commodity symbols written with the amount of each posting.
+    pub symbols: Vec<Option<String>>,
+}
+
+/// An error in a journal.
+#[derive(Debug, Error)]
+#[error("line {line}: {kind}")]
+pub struct JournalError {
+    /// The line at which the error occured.
+    pub line: usize,
+    /// The kind of error.
+    pub kind: JournalErrorKind,
+}
+
+/// The kind of errors that can occur in a journal.
+#[derive(Debug, Error)]
+pub enum JournalErrorKind {
+    /// The date of a transaction is invalid.
+    #[error("invalid date: {0:?}")]
+    InvalidDate(String),
+    /// An amount is invalid.
+    #[error("invalid amount: {0:?}")]
+    InvalidAmount(String),
+    /// A posting is outside of a transaction.
+    #[error("indented line outside of a transaction")]
+    OrphanLine,
+    /// A posting is virtual.
+    #[error("virtual postings are not supported")]
+    VirtualPosting,
+    /// A posting has a balance assertion.
+    #[error("balance assertions are not supported")]
+    BalanceAssertion,
+    /// A directive is not supported.
+    #[error("unsupported directive: {0}")]
+    UnsupportedDirective(String),
+    /// Several currencies are used for prices in the same transaction.
+    #[error("prices must all be in the same currency")]
+    SeveralPriceCurrencies,
+    /// The commodity of an amount is not the one of its account.
+    #[error("the account {account} is in {expected}, not {actual}")]
+    CommodityMismatch {
+        /// The account.
+        account: String,
+        /// The commodity of the account.
+        expected: String,
+        /// The commodity of the amount.
+        actual: String,
+    },
+    /// The same ID is used by several transactions.
+    #[error("duplicate ID: {0}")]
+    DuplicateId(String),
+    /// The transaction is invalid.
+    #[error(transparent)]
+    Draft(#[from] DraftError),
+}
+
+/// Where comments are attached while parsing a transaction.
+#[derive(Debug, Clone, Copy)]
+enum CommentTarget {
+    /// The notes of the transaction.
+    Notes,
+    /// The memo of the last posting.
+    Memo,
+}
+
+/// A transaction entry being parsed.
+struct PendingEntry {
+    /// The entry.
+    entry: Entry,
+    /// The status of the transaction, for postings without their own.
+    status: Option<ReconcileState>,
+    /// Where to attach the next comments.
+    target: CommentTarget,
+}
+
+/// A parsed amount, with its optional commodity symbol.
+struct Amount {
+    /// The quantity.
+    quantity: Numeric,
+    /// The commodity symbol.
+    symbol: Option<String>,
+}
+
+impl Entry {
+    /// Builds the transaction for this entry in the given book.
+    pub fn build(&self, book: &Book) -> Result<Transaction, JournalError> {
+        let error = |kind| JournalError {
+            line: self.line,
+            kind,
+        };
+
+        for (posting, symbol) in self.draft.postings.iter().zip(&self.symbols) {
+            let (Some(symbol), Some(account)) =
+                (symbol, book.find_account(&posting.account))
+            else {
+                continue;
+            };
+
+            if let Some(commodity) = book.account_commodity(account.guid) {
+                if commodity.mnemonic != *symbol {
+                    return Err(error(JournalErrorKind::CommodityMismatch {
+                        account: posting.account.clone(),
+                        expected: commodity.mnemonic.clone(),
+                        actual: symbol.clone(),
+                    }));
+                }
+            }
+        }
+
+        let mut transaction = self
+            .draft
+            .build(book)
+            .map_err(|draft_error| error(draft_error.into()))?;
+
+        transaction
+            .slots
+            .insert("journal-id", SlotValue::String(self.id.clone()));
+        Ok(transaction)
+    }
+}
+
+impl JournalError {
+    /// Builds a new journal error.
+    const fn new(line: usize, kind: JournalErrorKind) -> Self {
+        Self { line, kind }
+    }
+}
+
+/// Parses a journal.
+pub fn parse(input: &str) -> Result<Vec<Entry>, JournalError> {
+    let mut entries = vec![];
+    let mut current: Option<PendingEntry> = None;
+    let mut in_directive = false;
+
+    for (index, raw_line) in input.lines().enumerate() {
+        let line = index + 1;
+        let content = raw_line.trim();
+
+        if content.is_empty() {
+            entries.extend(current.take().map(|pending| pending.entry));
+            in_directive = false;
+        } else if raw_line.starts_with([' ', '\t']) {
+            if in_directive {
+                continue;
+            }
+
+            let pending = current
+                .as_mut()
+                .ok_or(JournalError::new(line, JournalErrorKind::OrphanLine))?;
+
+            parse_indented_line(pending, content, line)?;
+        } else {
+            entries.extend(current.take().map(|pending| pending.entry));
+            in_directive = false;
+
+            if content.starts_with([';', '#', '*', '%', '|']) {
+                // This is a comment.
+            } else if content.starts_with(|c: char| c.is_ascii_digit()) {
+                current = Some(parse_header(content, line)?);
+            } else {
+                let directive =
+                    content.split_whitespace().next().unwrap_or_default();
+
+                if IGNORED_DIRECTIVES.contains(&directive) {
+                    in_directive = true;
+                } else {
+                    return Err(JournalError::new(
+                        line,
+                        JournalErrorKind::UnsupportedDirective(
+                            directive.to_owned(),
+                        ),
+                    ));
+                }
+            }
+        }
+    }
+
+    entries.extend(current.take().map(|pending| pending.entry));
+    check_unique_ids(&entries)?;
+    set_implicit_ids(&mut entries);
+
+    Ok(entries)
+}
+
+/// Parses the header of a transaction.
+fn parse_header(
+    content: &str,
+    line: usize,
+) -> Result<PendingEntry, JournalError> {
+    let (date, rest) = content
+        .split_once(char::is_whitespace)
+        .unwrap_or((content, ""));
+
+    // Secondary dates are ignored.
+    let date = date.split_once('=').map_or(date, |(primary, _)| primary);
+    let date = DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(date, format).ok())
+        .ok_or_else(|| {
+            JournalError::new(
+                line,
+                JournalErrorKind::InvalidDate(date.to_owned()),
+            )
+        })?;
+
+    let (rest, comment) = split_comment(rest);
+    let (status, rest) = parse_status(rest.trim());
+
+    let (num, description) = match rest.strip_prefix('(') {
+        Some(rest) => rest.split_once(')').unwrap_or((rest, "")),
+        None => ("", rest),
+    };
+
+    let (id, notes) = comment.map_or((None, None), parse_tags);
+
+    let draft = TransactionDraft {
+        date,
+        num: num.trim().to_owned(),
+        description: description.trim().to_owned(),
+        notes,
+        ..TransactionDraft::default()
+    };
+
+    let entry = Entry {
+        line,
+        id: id.unwrap_or_default(),
+        draft,
+        symbols: vec![],
+    };
+
+    Ok(PendingEntry {
+        entry,
+        status,
+        target: CommentTarget::Notes,
+    })
+}
+
+/// Parses an indented line of a transaction.
+fn parse_indented_line(
+    pending: &mut PendingEntry,
+    content: &str,
+    line: usize,
+) -> Result<(), JournalError> {
+    if let Some(comment) = content.strip_prefix([';', '#']) {
+        let comment = comment.trim();
+        let entry = &mut pending.entry;
+
+        match (pending.target, entry.draft.postings.last_mut()) {
+            (CommentTarget::Memo, Some(posting)) => {
+                append(&mut posting.memo, comment);
+            }
+            _ => append(
+                entry.draft.notes.get_or_insert_with(String::new),
+                comment,
+            ),
+        }
+
+        return Ok(());
+    }
+
+    let (posting, symbol) = parse_posting(pending, content, line)?;
+    pending.entry.draft.postings.push(posting);
+    pending.entry.symbols.push(symbol);
+    pending.target = CommentTarget::Memo;
+
+    Ok(())
+}
+
+/// Parses a posting, returning it with the commodity symbol of its amount.
+fn parse_posting(
+    pending: &mut PendingEntry,
+    content: &str,
+    line: usize,
+) -> Result<(PostingDraft, Option<String>), JournalError> {
+    let error = |kind| JournalError::new(line, kind);
+
+    let (status, content) = parse_status(content);
+    let (content, memo) = split_comment(content);
+
+    let (account, amount) = content
+        .split_once("  ")
+        .or_else(|| content.split_once('\t'))
+        .unwrap_or((content, ""));
+
+    let account = account.trim();
+    if account.starts_with(['(', '[']) {
+        return Err(error(JournalErrorKind::VirtualPosting));
+    }
+
+    let amount = amount.trim();
+    if amount.contains('=') {
+        return Err(error(JournalErrorKind::BalanceAssertion));
+    }
+
+    let (amount, price) = match amount.split_once('@') {
+        Some((amount, price)) => (amount, Some(price)),
+        None => (amount, None),
+    };
+
+    let amount = (!amount.trim().is_empty())
+        .then(|| parse_amount(amount))
+        .transpose()
+        .map_err(error)?;
+
+    let price = price
+        .map(|price| {
+            let (total, price) = match price.strip_prefix('@') {
+                Some(total) => (true, total),
+                None => (false, price),
+            };
+
+            let price = parse_amount(price).map_err(error)?;
+            set_price_currency(&mut pending.entry, price.symbol.as_deref())
+                .map_err(error)?;
+
+            Ok(if total {
+                PriceSpec::Total(price.quantity)
+            } else {
+                PriceSpec::Unit(price.quantity)
+            })
+        })
+        .transpose()?;
+
+    let posting = PostingDraft {
+        account: account.to_owned(),
+        amount: amount.as_ref().map(|amount| amount.quantity),
+        price,
+        memo: memo.unwrap_or_default().trim().to_owned(),
+        reconcile_state: status.or(pending.status).unwrap_or_default(),
+    };
+
+    Ok((posting, amount.and_then(|amount| amount.symbol)))
+}
+
+/// Sets the currency of the transaction from the currency of a price.
+fn set_price_currency(
+    entry: &mut Entry,
+    symbol: Option<&str>,
+) -> Result<(), JournalErrorKind> {
+    let Some(symbol) = symbol else {
+        return Ok(());
+    };
+
+    match &entry.draft.currency {
+        Some(currency) if currency != symbol => {
+            Err(JournalErrorKind::SeveralPriceCurrencies)
+        }
+        _ => {
+            entry.draft.currency = Some(symbol.to_owned());
+            Ok(())
+        }
+    }
+}
+
+/// Checks all the IDs given by `id` tags in the journal are unique.
+fn check_unique_ids(entries: &[Entry]) -> Result<(), JournalError> {
+    let mut ids = BTreeSet::new();
+
+    for entry in entries.iter().filter(|entry| !entry.id.is_empty()) {
+        if !ids.insert(entry.id.as_str()) {
+            return Err(JournalError::new(
+                entry.line,
+                JournalErrorKind::DuplicateId(entry.id.clone()),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Gives the entries without an `id` tag an ID derived from their content.
+///
+/// Identical entries, like two coffees on the same day, are told apart by their
+/// occurrence, which is mixed in the ID from the second one on.
+fn set_implicit_ids(entries: &mut [Entry]) {
+    let mut occurrences: BTreeMap<String, usize> = BTreeMap::new();
+
+    for entry in entries.iter_mut().filter(|entry| entry.id.is_empty()) {
+        let content = content(&entry.draft);
+        let occurrence = occurrences.entry(content.clone()).or_default();
+
+        entry.id = content_id(&content, *occurrence);
+        *occurrence += 1;
+    }
+}
+
+/// Parses the status mark at the beginning of a header or posting.
+fn parse_status(content: &str) -> (Option<ReconcileState>, &str) {
+    if let Some(rest) = content.strip_prefix('*') {
+        (Some(ReconcileState::Cleared), rest.trim_start())
+    } else if let Some(rest) = content.strip_prefix('!') {
+        (Some(ReconcileState::NotReconciled), rest.trim_start())
+    } else {
+        (None, content)
+    }
+}
+
+/// Splits a line at its comment, if any.
+///
+/// A comment starts with a `;` at the beginning of the line or after some
+/// whitespace, so that descriptions like `A;B` are kept whole.
+fn split_comment(content: &str) -> (&str, Option<&str>) {
+    let mut previous = None;
+
+    for (index, c) in content.char_indices() {
+        if c == ';' && previous.map_or(true, char::is_whitespace) {
+            let (content, comment) = content.split_at(index);
+            let comment = comment.strip_prefix(';').unwrap_or(comment);
+            return (content, Some(comment.trim()));
+        }
+
+        previous = Some(c);
+    }
+
+    (content, None)
+}
+
+/// Extracts the `id` tag from a comment, returning it with the rest of the
+/// comment.
+fn parse_tags(comment: &str) -> (Option<String>, Option<String>) {
+    let mut id = None;
+    let mut text = vec![];
+
+    for part in comment.split(',') {
+        match part.trim().strip_prefix("id:") {
+            Some(value) => id = Some(value.trim().to_owned()),
+            None => text.push(part.trim()),
+        }
+    }
+
+    let text = text.join(", ");
+    (id, (!text.is_empty()).then_some(text))
+}
+
+/// Parses an amount like `-12.34 EUR`, `EUR -12.34` or `-12.34`.
+fn parse_amount(amount: &str) -> Result<Amount, JournalErrorKind> {
+    let invalid = || JournalErrorKind::InvalidAmount(amount.trim().to_owned());
+
+    let amount = amount.trim();
+    let (negative, rest) = match amount.strip_prefix('-') {
+        Some(rest) => (true, rest.trim_start()),
+        None => (false, amount),
+    };
+
+    let is_number =
+        |c: char| c.is_ascii_digit() || matches!(c, '.' | ',' | '-' | '+');
+
+    let (number, symbol) = if rest.starts_with(is_number) {
+        let end = rest.find(|c: char| !is_number(c)).unwrap_or(rest.len());
+        let (number, symbol) = rest.split_at(end);
+        (number, symbol.trim())
+    } else {
+        let (symbol, number) = split_symbol(rest);
+        (number.trim(), symbol)
+    };
+
+    if number.contains(',') {
+        return Err(invalid());
+    }
+
+    let quantity: Numeric = number.parse().map_err(|_error| invalid())?;
+    let quantity = if negative { -quantity } else { quantity };
+
+    let symbol = symbol.trim_matches('"');
+    let symbol = match symbol {
+        "" => None,
+        "€" => Some("EUR".to_owned()),
+        "$" => Some("USD".to_owned()),
+        "£" => Some("GBP".to_owned()),
+        _ => Some(symbol.to_owned()),
+    };
+
+    Ok(Amount { quantity, symbol })
+}
+
+/// Splits a leading commodity symbol from the rest of an amount.
+fn split_symbol(amount: &str) -> (&str, &str) {
+    if let Some(quoted) = amount.strip_prefix('"') {
+        if let Some(end) = quoted.find('"') {
+            return quoted.split_at(end);
+        }
+    }
+
+    let end = amount
+        .find(|c: char| c.is_ascii_digit() || c.is_whitespace() || c == '-')
+        .unwrap_or(amount.len());
+
+    amount.split_at(end)
+}
+
+/// Appends a line to a text.
+fn append(text: &mut String, line: &str) {
+    if !text.is_empty() {
+        text.push('\n');
+    }
+
+    text.push_str(line);
+}
+
+/// Returns the content of a transaction from which its implicit ID is derived.
+fn content(draft: &TransactionDraft) -> String {
+    let postings = draft.postings.iter().map(|posting| {
+        let amount = posting.amount.map(|amount| amount.to_string());
+        let price = posting.price.map(|price| match price {
+            PriceSpec::Unit(price) => format!(" @ {price}"),
+            PriceSpec::Total(price) => format!(" @@ {price}"),
+        });
+        format!(
+            "{}={}{}",
+            posting.account,
+            amount.unwrap_or_default(),
+            price.unwrap_or_default()
+        )
+    });
+
+    let header = [
+        draft.date.to_string(),
+        draft.num.clone(),
+        draft.description.clone(),
+    ];
+
+    header
+        .into_iter()
+        .chain(postings)
+        .collect::<Vec<_>>()
+        .join("|")
+}
+
+/// Computes an ID from the content of a transaction and its occurrence among
+/// identical transactions.
+///
+/// This is a 64-bit FNV-1a hash, which is stable across platforms and
+/// versions. The first occurrence is hashed alone, so that adding an identical
+/// transaction to a journal keeps the ID of the existing one.
+fn content_id(content: &str, occurrence: usize) -> String {
+    let occurrence = (occurrence > 0)
+        .then(|| format!("|#{occurrence}"))
+        .unwrap_or_default();
+
+    let hash = content
+        .bytes()
+        .chain(occurrence.bytes())
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+
+    format!("{hash:016x}")
+}
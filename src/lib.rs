@@ -15,9 +15,24 @@
 
 //! A CLI toolbox to work with GnuCash databases.
 
+mod assertion;
+mod book;
+mod chart;
+mod check;
+mod closing;
 mod command;
+mod conversion;
+mod directory;
+mod export;
+mod formula;
 mod helpers;
+mod invoice;
+mod journal;
+mod reconcile;
+mod report;
+mod since_last_run;
 mod tracing;
+mod trading;
 
 #[doc(hidden)]
 pub use command::GnucashToolbox;
@@ -0,0 +1,245 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Reconciliation of accounts against bank statements.
+//!
+//! A reconciliation starts from the balance of the splits already reconciled
+//! in an account. The splits that are not reconciled yet and posted up to the
+//! statement date are candidates: once the selected candidates bring the
+//! balance to the statement ending balance, they can be marked as reconciled.
+
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, TimeZone as _, Utc};
+
+use crate::book::{
+    Account, Book, Guid, Numeric, ReconcileState, SlotValue, Split, Transaction,
+};
+
+/// The slot storing the date of the last reconciliation of an account.
+pub const LAST_DATE_SLOT: &str = "reconcile-info/last-date";
+
+/// The slot storing a postponed reconciliation of an account.
+pub const POSTPONE_SLOT: &str = "reconcile-info/postpone";
+
+/// The maximum number of steps when looking for matching splits.
+const MAX_MATCH_STEPS: usize = 1 << 20;
+
+/// A reconciliation in progress.
+#[derive(Debug)]
+pub struct Reconciliation<'a> {
+    /// The reconciled account.
+    pub account: &'a Account,
+    /// The statement date.
+    pub date: NaiveDate,
+    /// The statement ending balance.
+    pub balance: Numeric,
+    /// The balance of the splits already reconciled.
+    pub starting_balance: Numeric,
+    /// The splits that can be reconciled, sorted by date.
+    pub candidates: Vec<Candidate<'a>>,
+}
+
+/// A split that can be reconciled.
+#[derive(Debug)]
+pub struct Candidate<'a> {
+    /// The transaction of the split.
+    pub transaction: &'a Transaction,
+    /// The split.
+    pub split: &'a Split,
+    /// Whether the split is selected to be reconciled.
+    pub selected: bool,
+}
+
+impl<'a> Reconciliation<'a> {
+    /// Starts the reconciliation of an account.
+    ///
+    /// As in GnuCash, the cleared splits are selected from the start.
+    pub fn new(
+        book: &'a Book,
+        account: &'a Account,
+        date: NaiveDate,
+        balance: Numeric,
+    ) -> Self {
+        let mut starting_balance = Numeric::ZERO;
+        let mut candidates = vec![];
+
+        for transaction in book.regular_transactions() {
+            for split in &transaction.splits {
+                if split.account != account.guid {
+                    continue;
+                }
+
+                match split.reconcile_state {
+                    ReconcileState::Reconciled | ReconcileState::Frozen => {
+                        starting_balance += split.quantity;
+                    }
+                    ReconcileState::NotReconciled | ReconcileState::Cleared
+                        if transaction.date() <= date =>
+                    {
+                        candidates.push(Candidate {
+                            transaction,
+                            split,
+                            selected: split.reconcile_state
+                                == ReconcileState::Cleared,
+                        });
+                    }
+                    ReconcileState::NotReconciled
+                    | ReconcileState::Cleared
+                    | ReconcileState::Voided => (),
+                }
+            }
+        }
+
+        Self {
+            account,
+            date,
+            balance,
+            starting_balance,
+            candidates,
+        }
+    }
+
+    /// Returns the balance of the reconciled and selected splits.
+    pub fn cleared_balance(&self) -> Numeric {
+        self.starting_balance
+            + self
+                .candidates
+                .iter()
+                .filter(|candidate| candidate.selected)
+                .map(|candidate| candidate.split.quantity)
+                .sum::<Numeric>()
+    }
+
+    /// Returns the difference between the statement and cleared balances.
+    pub fn difference(&self) -> Numeric {
+        self.balance - self.cleared_balance()
+    }
+
+    /// Returns whether the selected splits match the statement.
+    pub fn is_balanced(&self) -> bool {
+        self.difference().is_zero()
+    }
+
+    /// Toggles the selection of a candidate.
+    pub fn toggle(&mut self, index: usize) -> bool {
+        if let Some(candidate) = self.candidates.get_mut(index) {
+            candidate.selected = !candidate.selected;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Selects the candidates whose amounts match the statement balance.
+    ///
+    /// All the candidates are tried first, then the older splits are
+    /// preferred. Returns whether a match has been found: if not, the
+    /// selection is left unchanged.
+    pub fn auto_match(&mut self) -> bool {
+        let target = self.balance - self.starting_balance;
+        let amounts: Vec<_> = self
+            .candidates
+            .iter()
+            .map(|candidate| candidate.split.quantity)
+            .collect();
+
+        let selection = if amounts.iter().sum::<Numeric>() == target {
+            Some(vec![true; amounts.len()])
+        } else {
+            let mut selection = vec![false; amounts.len()];
+            let mut steps = 0;
+            find_subset(&amounts, target, &mut selection, &mut steps)
+                .then_some(selection)
+        };
+
+        let Some(selection) = selection else {
+            return false;
+        };
+
+        for (candidate, selected) in self.candidates.iter_mut().zip(selection) {
+            candidate.selected = selected;
+        }
+
+        true
+    }
+
+    /// Returns the GUIDs of the selected splits.
+    pub fn selected_splits(&self) -> Vec<Guid> {
+        self.candidates
+            .iter()
+            .filter(|candidate| candidate.selected)
+            .map(|candidate| candidate.split.guid)
+            .collect()
+    }
+
+    /// Returns the reconcile date to store, like GnuCash does.
+    ///
+    /// GnuCash uses the end of the statement day, in local time.
+    pub fn reconcile_date(&self) -> DateTime<Utc> {
+        let end_of_day = self
+            .date
+            .and_time(NaiveTime::from_hms_opt(23, 59, 59).unwrap_or_default());
+
+        Local
+            .from_local_datetime(&end_of_day)
+            .earliest()
+            .map_or_else(|| end_of_day.and_utc(), |date| date.to_utc())
+    }
+
+    /// Returns the account with its reconciliation slots updated.
+    pub fn reconciled_account(&self) -> Account {
+        let mut account = self.account.clone();
+        account.slots.remove(POSTPONE_SLOT);
+        account.slots.insert(
+            LAST_DATE_SLOT,
+            SlotValue::Int64(self.reconcile_date().timestamp()),
+        );
+        account
+    }
+}
+
+/// Looks for a subset of `amounts` summing to `target`.
+///
+/// The search includes the first amounts first, and gives up after
+/// [`MAX_MATCH_STEPS`] steps.
+fn find_subset(
+    amounts: &[Numeric],
+    target: Numeric,
+    selection: &mut [bool],
+    steps: &mut usize,
+) -> bool {
+    *steps += 1;
+
+    if target.is_zero() {
+        return true;
+    }
+
+    let (Some((&amount, rest)), Some((selected, rest_selection))) =
+        (amounts.split_first(), selection.split_first_mut())
+    else {
+        return false;
+    };
+
+    if *steps > MAX_MATCH_STEPS {
+        return false;
+    }
+
+    *selected = true;
+    if find_subset(rest, target - amount, rest_selection, steps) {
+        return true;
+    }
+
+    *selected = false;
+    find_subset(rest, target, rest_selection, steps)
+}
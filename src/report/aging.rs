@@ -0,0 +1,121 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Aging of receivables and payables.
+//!
+//! The open balances of posted documents are grouped by owner in buckets,
+//! depending on how many days they are past due, like the aging reports of
+//! GnuCash.
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+
+use crate::{
+    book::{Book, Commodity, Guid, Numeric},
+    invoice::{Document, DocumentKind},
+};
+
+/// The names of the buckets, from the most recent to the oldest.
+pub const BUCKETS: [&str; 5] = ["Current", "0-30", "31-60", "61-90", "90+"];
+
+/// The side of the aging report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// The invoices due by customers.
+    Receivable,
+    /// The bills and expense vouchers due to vendors and employees.
+    Payable,
+}
+
+/// The aged balances of an owner in a currency.
+#[derive(Debug)]
+pub struct AgingRow<'a> {
+    /// The name of the owner.
+    pub owner: &'a str,
+    /// The currency of the balances.
+    pub currency: Option<&'a Commodity>,
+    /// The balances in each bucket.
+    pub buckets: [Numeric; BUCKETS.len()],
+}
+
+impl Side {
+    /// Returns whether a kind of document is on this side.
+    const fn includes(self, kind: DocumentKind) -> bool {
+        match self {
+            Self::Receivable => matches!(kind, DocumentKind::Invoice),
+            Self::Payable => {
+                matches!(
+                    kind,
+                    DocumentKind::Bill | DocumentKind::ExpenseVoucher
+                )
+            }
+        }
+    }
+}
+
+/// Computes the aging of the open documents of a side at the end of `date`.
+///
+/// The due dates are the ones computed from the billing terms when the
+/// documents have been posted. The rows are sorted by owner name, then by
+/// currency.
+pub fn aging(book: &Book, side: Side, date: NaiveDate) -> Vec<AgingRow<'_>> {
+    let mut rows: BTreeMap<(&str, Guid, Guid), AgingRow<'_>> = BTreeMap::new();
+
+    for document in Document::all(book) {
+        if !side.includes(document.kind) {
+            continue;
+        }
+
+        let (Some(owner), Some(due_date)) = (document.owner, document.due_date)
+        else {
+            continue;
+        };
+
+        let balance = document.balance_on(book, date);
+
+        if balance.is_zero() {
+            continue;
+        }
+
+        let name = book.business.owner_name(owner).unwrap_or_default();
+        let key = (name, owner.guid, document.invoice.currency);
+
+        let row = rows.entry(key).or_insert_with(|| AgingRow {
+            owner: name,
+            currency: document.currency,
+            buckets: [Numeric::ZERO; BUCKETS.len()],
+        });
+
+        if let Some(amount) =
+            row.buckets.get_mut(bucket((date - due_date).num_days()))
+        {
+            *amount += balance;
+        }
+    }
+
+    rows.into_values().collect()
+}
+
+/// Returns the index of the bucket for a number of days past due.
+const fn bucket(days_past_due: i64) -> usize {
+    match days_past_due {
+        i64::MIN..=-1 => 0,
+        0..=30 => 1,
+        31..=60 => 2,
+        61..=90 => 3,
+        91..=i64::MAX => 4,
+    }
+}
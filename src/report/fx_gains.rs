@@ -0,0 +1,198 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Foreign exchange gains and losses.
+//!
+//! The balances of the accounts in foreign currencies are followed with their
+//! cost in the reporting currency, using the average cost method. Each
+//! decrease of a balance realises the difference between its value and its
+//! share of the cost, while the remaining balance has an unrealised gain
+//! against its value at the end of the period.
+//!
+//! The values of splits are converted from the currency of their transaction
+//! to the reporting currency with the price database, and rounded to the
+//! smallest unit of the reporting currency. Trading accounts only
+//! mirror the other splits, so they are left out: books with and without
+//! trading accounts give the same gains.
+
+use crate::{
+    book::{Book, Commodity, Guid, Numeric},
+    conversion::{ConversionError, Converter},
+};
+
+use super::period::Period;
+
+/// The gains on an account in a foreign currency.
+#[derive(Debug)]
+pub struct FxGain<'a> {
+    /// The full path of the account.
+    pub account: String,
+    /// The currency of the account.
+    pub currency: &'a Commodity,
+    /// The balance of the account at the end of the period.
+    pub balance: Numeric,
+    /// The cost of the balance, in the reporting currency.
+    pub cost: Numeric,
+    /// The value of the balance at the end of the period, in the reporting
+    /// currency.
+    pub value: Numeric,
+    /// The gains realised during the period, in the reporting currency.
+    pub realised: Numeric,
+}
+
+/// The position of an account during the computation.
+#[derive(Debug, Default)]
+struct Position {
+    /// The balance in the currency of the account.
+    balance: Numeric,
+    /// The cost of the balance in the reporting currency.
+    cost: Numeric,
+    /// The gains realised during the period.
+    realised: Numeric,
+}
+
+impl FxGain<'_> {
+    /// Returns the unrealised gain at the end of the period.
+    pub fn unrealised(&self) -> Numeric {
+        self.value - self.cost
+    }
+}
+
+impl Position {
+    /// Adds a movement of `quantity` with a value of `value` in the reporting
+    /// currency.
+    ///
+    /// The part of the movement reducing the balance releases its share of
+    /// the cost, and realises a gain when `realise` is set. The rest of the
+    /// movement increases the balance with its cost.
+    fn add(&mut self, quantity: Numeric, value: Numeric, realise: bool) {
+        let opposite = (quantity.is_negative() && self.balance.is_positive())
+            || (quantity.is_positive() && self.balance.is_negative());
+
+        if !opposite {
+            self.balance += quantity;
+            self.cost += value;
+            return;
+        }
+
+        let reduced = if quantity.abs() > self.balance.abs() {
+            -self.balance
+        } else {
+            quantity
+        };
+
+        let share = self
+            .balance
+            .recip()
+            .map_or(Numeric::ZERO, |inverse| -reduced * inverse);
+        let released = self.cost * share;
+        let reduced_value = quantity
+            .recip()
+            .map_or(Numeric::ZERO, |inverse| value * reduced * inverse);
+
+        if realise {
+            self.realised += -reduced_value - released;
+        }
+
+        self.balance += reduced;
+        self.cost -= released;
+
+        let rest = quantity - reduced;
+        if !rest.is_zero() {
+            self.balance += rest;
+            self.cost += value - reduced_value;
+        }
+    }
+}
+
+/// Computes the gains on the accounts in currencies other than `currency`.
+///
+/// The unrealised gains are computed at the end of the period, and the
+/// realised gains are the ones of the transactions during the period. Only
+/// the accounts with a balance or a realised gain are returned, sorted by
+/// path.
+pub fn fx_gains<'a>(
+    book: &'a Book,
+    converter: &Converter<'_>,
+    currency: Guid,
+    period: Period,
+) -> Result<Vec<FxGain<'a>>, ConversionError> {
+    let transactions = book.regular_transactions();
+    let fraction = book.commodity(currency).map_or(1, Commodity::fraction);
+    let mut gains = vec![];
+
+    for account in book.accounts.values() {
+        let Some(commodity) = book.account_commodity(account.guid) else {
+            continue;
+        };
+
+        if !commodity.is_currency()
+            || commodity.guid == currency
+            || !account.kind.is_balance_sheet()
+        {
+            continue;
+        }
+
+        let mut position = Position::default();
+
+        for transaction in &transactions {
+            let date = transaction.date();
+
+            if date > period.end {
+                break;
+            }
+
+            for split in &transaction.splits {
+                if split.account != account.guid {
+                    continue;
+                }
+
+                let value = converter
+                    .convert(
+                        split.value,
+                        transaction.currency,
+                        currency,
+                        Period::day(date),
+                    )?
+                    .round_to(fraction);
+
+                position.add(split.quantity, value, date >= period.start);
+            }
+        }
+
+        if position.balance.is_zero() && position.realised.is_zero() {
+            continue;
+        }
+
+        let value = converter.convert(
+            position.balance,
+            commodity.guid,
+            currency,
+            Period::day(period.end),
+        )?;
+
+        gains.push(FxGain {
+            account: book.account_path(account.guid),
+            currency: commodity,
+            balance: position.balance,
+            cost: position.cost,
+            value,
+            realised: position.realised,
+        });
+    }
+
+    gains.sort_by(|gain, other| gain.account.cmp(&other.account));
+    Ok(gains)
+}
@@ -0,0 +1,102 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Periods of time covered by reports.
+
+use std::{fmt, str::FromStr};
+
+use chrono::{Months, NaiveDate};
+use thiserror::Error;
+
+/// A period of time, from a start date to an end date included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Period {
+    /// The first day of the period.
+    pub start: NaiveDate,
+    /// The last day of the period.
+    pub end: NaiveDate,
+}
+
+/// An error that can occur when parsing a period.
+#[derive(Debug, Error)]
+#[error("invalid period: {0:?}")]
+pub struct ParsePeriodError(String);
+
+impl Period {
+    /// Builds the period of a single day.
+    pub const fn day(date: NaiveDate) -> Self {
+        Self {
+            start: date,
+            end: date,
+        }
+    }
+
+    /// Returns whether the period contains the given date.
+    pub fn contains(self, date: NaiveDate) -> bool {
+        (self.start..=self.end).contains(&date)
+    }
+
+    /// Builds the period of `months` months starting on the first day of
+    /// `month` in `year`.
+    fn months(year: i32, month: u32, months: u32) -> Option<Self> {
+        let start = NaiveDate::from_ymd_opt(year, month, 1)?;
+        let end = start.checked_add_months(Months::new(months))?.pred_opt()?;
+        Some(Self { start, end })
+    }
+}
+
+impl FromStr for Period {
+    type Err = ParsePeriodError;
+
+    /// Parses a year like `2024`, a quarter like `2024-Q1`, a month like
+    /// `2024-03`, or a range of dates like `2024-01-01..2024-06-30`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParsePeriodError(s.to_owned());
+
+        let period = if let Some((start, end)) = s.split_once("..") {
+            let start = start.parse().map_err(|_error| error())?;
+            let end = end.parse().map_err(|_error| error())?;
+            (start <= end).then_some(Self { start, end })
+        } else if let Some((year, quarter)) = s.split_once("-Q") {
+            let year = year.parse().map_err(|_error| error())?;
+            let quarter: u32 = quarter.parse().map_err(|_error| error())?;
+
+            if (1..=4).contains(&quarter) {
+                Self::months(year, 3 * quarter - 2, 3)
+            } else {
+                None
+            }
+        } else if let Some((year, month)) = s.split_once('-') {
+            let year = year.parse().map_err(|_error| error())?;
+            let month = month.parse().map_err(|_error| error())?;
+            Self::months(year, month, 1)
+        } else {
+            let year = s.parse().map_err(|_error| error())?;
+            Self::months(year, 1, 12)
+        };
+
+        period.ok_or_else(error)
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.start == self.end {
+            write!(formatter, "on {}", self.start)
+        } else {
+            write!(formatter, "from {} to {}", self.start, self.end)
+        }
+    }
+}
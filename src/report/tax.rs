@@ -0,0 +1,257 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Totals of the tax-related accounts.
+//!
+//! GnuCash lets accounts be flagged as tax-related, with a TXF code and
+//! details on the payer stored in their slots. The accounts are totalled for a
+//! year, so that they can be exported to tax software in the TXF format, or
+//! to CSV when the codes are not TXF codes.
+
+use std::{collections::BTreeMap, io};
+
+use chrono::{Datelike as _, NaiveDate};
+use thiserror::Error;
+
+use crate::book::{
+    Account, AccountType, Book, Commodity, Guid, Numeric, SlotValue,
+};
+
+/// The slot flagging an account as tax-related.
+const TAX_RELATED_SLOT: &str = "tax-related";
+
+/// The slot containing the tax code of an account.
+const CODE_SLOT: &str = "tax-US/code";
+
+/// The slot telling where to take the name of the payer from.
+const PAYER_NAME_SOURCE_SLOT: &str = "tax-US/payer-name-source";
+
+/// The slot containing the copy number of the form.
+const COPY_NUMBER_SLOT: &str = "tax-US/copy-number";
+
+/// The version of the TXF format.
+const TXF_VERSION: &str = "V042";
+
+/// The total of a tax-related account for a year.
+#[derive(Debug)]
+pub struct TaxLine<'a> {
+    /// The tax code of the account.
+    pub code: &'a str,
+    /// The full path of the account.
+    pub account: String,
+    /// The name of the payer, when the code needs one.
+    pub payer: Option<&'a str>,
+    /// The copy number of the form.
+    pub copy: i64,
+    /// The commodity of the account.
+    pub currency: Option<&'a Commodity>,
+    /// The total of the account, with the sign of its normal balance.
+    pub amount: Numeric,
+}
+
+/// Errors that can occur when exporting the tax report.
+#[derive(Debug, Error)]
+pub enum TaxError {
+    /// A code is not a TXF code.
+    #[error("the code {code} of {account} is not a TXF code")]
+    NotTxfCode {
+        /// The code.
+        code: String,
+        /// The path of the account.
+        account: String,
+    },
+    /// An error occured when writing the TXF file.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// An error occured when writing the CSV file.
+    #[error(transparent)]
+    Csv(#[from] csv::Error),
+}
+
+/// Computes the totals of the tax-related accounts with a code for a year.
+///
+/// The accounts without any amount during the year are left out, as GnuCash
+/// does. Income, liability and equity accounts are totalled with their sign
+/// reversed, so that the amounts are positive in the common case. The lines
+/// are sorted by code, then by account path.
+pub fn tax_lines(book: &Book, year: i32) -> Vec<TaxLine<'_>> {
+    let mut totals: BTreeMap<Guid, Numeric> = BTreeMap::new();
+
+    for transaction in book.regular_transactions() {
+        if transaction.date().year() != year {
+            continue;
+        }
+
+        for split in &transaction.splits {
+            *totals.entry(split.account).or_default() += split.quantity;
+        }
+    }
+
+    let mut lines: Vec<_> = book
+        .accounts
+        .values()
+        .filter(|account| is_tax_related(account))
+        .filter_map(|account| {
+            let code = account.slots.string(CODE_SLOT)?;
+
+            let amount = totals.get(&account.guid).copied()?;
+
+            if amount.is_zero() {
+                return None;
+            }
+
+            let copy = match account.slots.get(COPY_NUMBER_SLOT) {
+                Some(SlotValue::Int64(copy)) => *copy,
+                _ => 1,
+            };
+
+            Some(TaxLine {
+                code,
+                account: book.account_path(account.guid),
+                payer: payer(book, account),
+                copy,
+                currency: book.account_commodity(account.guid),
+                amount: if is_credit_normal(account.kind) {
+                    -amount
+                } else {
+                    amount
+                },
+            })
+        })
+        .collect();
+
+    lines.sort_by(|line, other| {
+        (line.code, &line.account).cmp(&(other.code, &other.account))
+    });
+    lines
+}
+
+/// Returns the paths of the tax-related accounts without a code.
+pub fn accounts_without_code(book: &Book) -> Vec<String> {
+    let mut accounts: Vec<_> = book
+        .accounts
+        .values()
+        .filter(|account| {
+            is_tax_related(account) && account.slots.string(CODE_SLOT).is_none()
+        })
+        .map(|account| book.account_path(account.guid))
+        .collect();
+
+    accounts.sort();
+    accounts
+}
+
+/// Writes the lines as a TXF file created on `date`.
+///
+/// Each account is written as a summary record. The codes must be TXF codes,
+/// like `N261`.
+pub fn write_txf(
+    lines: &[TaxLine<'_>],
+    date: NaiveDate,
+    mut output: impl io::Write,
+) -> Result<(), TaxError> {
+    writeln!(output, "{TXF_VERSION}")?;
+    writeln!(output, "A{}", env!("CARGO_PKG_NAME"))?;
+    writeln!(output, "D{}", date.format("%m/%d/%Y"))?;
+    writeln!(output, "^")?;
+
+    for line in lines {
+        let number =
+            txf_number(line.code).ok_or_else(|| TaxError::NotTxfCode {
+                code: line.code.to_owned(),
+                account: line.account.clone(),
+            })?;
+
+        writeln!(output, "TS")?;
+        writeln!(output, "N{number}")?;
+        writeln!(output, "C{}", line.copy)?;
+        writeln!(output, "L1")?;
+
+        if let Some(payer) = line.payer {
+            writeln!(output, "P{payer}")?;
+        }
+
+        writeln!(output, "${}", line.amount.to_fixed(100))?;
+        writeln!(output, "^")?;
+    }
+
+    output.flush()?;
+    Ok(())
+}
+
+/// Writes the lines as a CSV file.
+pub fn write_csv(
+    lines: &[TaxLine<'_>],
+    output: impl io::Write,
+) -> Result<(), TaxError> {
+    let mut writer = csv::Writer::from_writer(output);
+    writer.write_record(["code", "account", "payer", "currency", "amount"])?;
+
+    for line in lines {
+        let fraction = line.currency.map_or(100, Commodity::fraction);
+
+        writer.write_record([
+            line.code,
+            &line.account,
+            line.payer.unwrap_or_default(),
+            line.currency.map_or("", |currency| &currency.mnemonic),
+            &line.amount.to_exact(fraction),
+        ])?;
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Returns whether an account is flagged as tax-related.
+fn is_tax_related(account: &Account) -> bool {
+    match account.slots.get(TAX_RELATED_SLOT) {
+        Some(SlotValue::Int64(flag)) => *flag != 0,
+        Some(SlotValue::String(flag)) => flag == "true",
+        _ => false,
+    }
+}
+
+/// Returns the name of the payer of an account, if set.
+///
+/// GnuCash uses either the name of the account or of its parent.
+fn payer<'a>(book: &'a Book, account: &'a Account) -> Option<&'a str> {
+    match account.slots.string(PAYER_NAME_SOURCE_SLOT)? {
+        "current" => Some(&account.name),
+        "parent" => account
+            .parent
+            .and_then(|parent| book.account(parent))
+            .map(|parent| parent.name.as_str()),
+        _ => None,
+    }
+}
+
+/// Returns the number of a TXF code, like `261` for `N261`.
+fn txf_number(code: &str) -> Option<u32> {
+    code.strip_prefix('N').unwrap_or(code).parse().ok()
+}
+
+/// Returns whether the normal balance of a type of account is a credit.
+const fn is_credit_normal(kind: AccountType) -> bool {
+    matches!(
+        kind,
+        AccountType::Income
+            | AccountType::Liability
+            | AccountType::Credit
+            | AccountType::CreditLine
+            | AccountType::Payable
+            | AccountType::Equity
+    )
+}
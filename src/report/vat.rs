@@ -0,0 +1,160 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! VAT returns.
+//!
+//! The output tax collected on invoices and the input tax paid on bills and
+//! expense vouchers are computed from the entries of the documents posted
+//! during a period, using their tax tables. They are then reconciled against
+//! the movements of the accounts receiving the taxes.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    book::{Book, Commodity, Guid, Numeric},
+    invoice::{Document, DocumentKind},
+};
+
+use super::period::Period;
+
+/// The kind of tax.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum VatKind {
+    /// The tax collected on invoices.
+    Output,
+    /// The tax paid on bills and expense vouchers.
+    Input,
+}
+
+/// The total of a kind of tax at a rate, in a currency.
+#[derive(Debug)]
+pub struct RateTotal<'a> {
+    /// The kind of tax.
+    pub kind: VatKind,
+    /// The rate of the tax in percents, unless it is a fixed value.
+    pub rate: Option<Numeric>,
+    /// The currency of the documents.
+    pub currency: Option<&'a Commodity>,
+    /// The total of the entries subject to the tax.
+    pub base: Numeric,
+    /// The total of the tax.
+    pub tax: Numeric,
+}
+
+/// The reconciliation of an account receiving taxes.
+#[derive(Debug)]
+pub struct AccountCheck<'a> {
+    /// The full path of the account.
+    pub account: String,
+    /// The commodity of the account.
+    pub currency: Option<&'a Commodity>,
+    /// The net tax computed from the documents.
+    pub computed: Numeric,
+    /// The movement of the account during the period, as a liability.
+    pub booked: Numeric,
+}
+
+/// A VAT return for a period.
+#[derive(Debug)]
+pub struct VatReturn<'a> {
+    /// The totals by kind of tax, rate and currency.
+    pub rates: Vec<RateTotal<'a>>,
+    /// The reconciliation of the accounts receiving taxes.
+    pub accounts: Vec<AccountCheck<'a>>,
+}
+
+impl<'a> VatReturn<'a> {
+    /// Computes the VAT return of a period.
+    ///
+    /// Documents are accounted for on their posting date. Credit notes reduce
+    /// the totals.
+    pub fn new(book: &'a Book, period: Period) -> Self {
+        let mut rates = BTreeMap::new();
+        let mut computed: BTreeMap<Guid, Numeric> = BTreeMap::new();
+
+        for document in Document::all(book) {
+            if !document.posted().is_some_and(|date| period.contains(date)) {
+                continue;
+            }
+
+            let kind = match document.kind {
+                DocumentKind::Invoice => VatKind::Output,
+                DocumentKind::Bill | DocumentKind::ExpenseVoucher => {
+                    VatKind::Input
+                }
+            };
+
+            for line in &document.lines {
+                for tax in &line.amounts.taxes {
+                    let key = (kind, document.invoice.currency, tax.rate);
+
+                    let total = rates.entry(key).or_insert(RateTotal {
+                        kind,
+                        rate: tax.rate,
+                        currency: document.currency,
+                        base: Numeric::ZERO,
+                        tax: Numeric::ZERO,
+                    });
+
+                    total.base += line.amounts.value;
+                    total.tax += tax.amount;
+
+                    *computed.entry(tax.account).or_default() += match kind {
+                        VatKind::Output => tax.amount,
+                        VatKind::Input => -tax.amount,
+                    };
+                }
+            }
+        }
+
+        let accounts = computed
+            .into_iter()
+            .map(|(account, computed)| AccountCheck {
+                account: book.account_path(account),
+                currency: book.account_commodity(account),
+                computed,
+                booked: -movement(book, account, period),
+            })
+            .collect();
+
+        Self {
+            rates: rates.into_values().collect(),
+            accounts,
+        }
+    }
+
+    /// Returns whether there is no tax in the return.
+    pub fn is_empty(&self) -> bool {
+        self.rates.is_empty()
+    }
+}
+
+impl AccountCheck<'_> {
+    /// Returns the difference between the account and the documents.
+    pub fn difference(&self) -> Numeric {
+        self.booked - self.computed
+    }
+}
+
+/// Returns the movement of an account during a period.
+fn movement(book: &Book, account: Guid, period: Period) -> Numeric {
+    book.regular_transactions()
+        .into_iter()
+        .filter(|transaction| period.contains(transaction.date()))
+        .flat_map(|transaction| &transaction.splits)
+        .filter(|split| split.account == account)
+        .map(|split| split.quantity)
+        .sum()
+}
@@ -0,0 +1,203 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Creation of the transactions of scheduled transactions.
+//!
+//! Like the Since Last Run dialog of GnuCash, the due occurrences of a schedule
+//! are created from its template transactions. The account and amount of each
+//! template split are stored in its `sched-xaction` slots, the amount as a
+//! debit or credit formula.
+
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use thiserror::Error;
+
+use crate::{
+    book::{
+        Book, DraftError, Numeric, PostingDraft, ScheduledTransaction,
+        SlotValue, Split, Transaction, TransactionDraft,
+    },
+    formula::{self, FormulaError},
+};
+
+/// The slot of a template split holding the GUID of the real account.
+pub const ACCOUNT_SLOT: &str = "sched-xaction/account";
+
+/// The slot of a template split holding the debit formula.
+const DEBIT_FORMULA_SLOT: &str = "sched-xaction/debit-formula";
+
+/// The slot of a template split holding the credit formula.
+const CREDIT_FORMULA_SLOT: &str = "sched-xaction/credit-formula";
+
+/// The slot of a created transaction linking to its scheduled transaction.
+const FROM_SCHEDULE_SLOT: &str = "from-sched-xaction";
+
+/// The transactions created for a scheduled transaction.
+#[derive(Debug)]
+pub struct Run {
+    /// The scheduled transaction, updated with the created occurrences.
+    pub schedule: ScheduledTransaction,
+    /// The dates of the created occurrences.
+    pub occurrences: Vec<NaiveDate>,
+    /// The created transactions.
+    pub transactions: Vec<Transaction>,
+}
+
+/// An error when creating an occurrence of a scheduled transaction.
+#[derive(Debug, Error)]
+#[error("cannot create {schedule} on {date}: {kind}")]
+pub struct SinceLastRunError {
+    /// The name of the scheduled transaction.
+    pub schedule: String,
+    /// The date of the occurrence.
+    pub date: NaiveDate,
+    /// The kind of error.
+    pub kind: SinceLastRunErrorKind,
+}
+
+/// The kind of errors that can occur when creating an occurrence.
+#[derive(Debug, Error)]
+pub enum SinceLastRunErrorKind {
+    /// A template split has no account.
+    #[error("a template split has no account")]
+    MissingAccount,
+    /// A formula cannot be evaluated.
+    #[error("invalid formula {formula:?}: {source}")]
+    Formula {
+        /// The formula.
+        formula: String,
+        /// The source error.
+        source: FormulaError,
+    },
+    /// The transaction is invalid.
+    #[error(transparent)]
+    Draft(#[from] DraftError),
+}
+
+/// Creates the occurrences of a scheduled transaction up to `until`.
+///
+/// The formulas of the template splits are evaluated with `variables`.
+pub fn run(
+    book: &Book,
+    schedule: &ScheduledTransaction,
+    until: NaiveDate,
+    variables: &BTreeMap<String, Numeric>,
+) -> Result<Run, SinceLastRunError> {
+    let occurrences = schedule.occurrences_until(until);
+    let templates = schedule.templates(book);
+    let mut transactions = vec![];
+
+    for &date in &occurrences {
+        for template in &templates {
+            let transaction =
+                instantiate(book, schedule, template, date, variables)
+                    .map_err(|kind| SinceLastRunError {
+                        schedule: schedule_name(schedule),
+                        date,
+                        kind,
+                    })?;
+
+            transactions.push(transaction);
+        }
+    }
+
+    let mut schedule = schedule.clone();
+    schedule.record_occurrences(&occurrences);
+
+    Ok(Run {
+        schedule,
+        occurrences,
+        transactions,
+    })
+}
+
+/// Returns the name of a scheduled transaction, for display.
+pub fn schedule_name(schedule: &ScheduledTransaction) -> String {
+    schedule
+        .name
+        .clone()
+        .unwrap_or_else(|| schedule.guid.to_string())
+}
+
+/// Creates the transaction of a template for the given date.
+fn instantiate(
+    book: &Book,
+    schedule: &ScheduledTransaction,
+    template: &Transaction,
+    date: NaiveDate,
+    variables: &BTreeMap<String, Numeric>,
+) -> Result<Transaction, SinceLastRunErrorKind> {
+    let postings = template
+        .splits
+        .iter()
+        .map(|split| posting(book, split, variables))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let draft = TransactionDraft {
+        date,
+        num: template.num.clone(),
+        description: template.description.clone(),
+        notes: template.notes().map(ToOwned::to_owned),
+        currency: book
+            .commodity(template.currency)
+            .map(|currency| currency.mnemonic.clone()),
+        postings,
+    };
+
+    let mut transaction = draft.build(book)?;
+
+    for (split, template_split) in
+        transaction.splits.iter_mut().zip(&template.splits)
+    {
+        split.action.clone_from(&template_split.action);
+    }
+
+    transaction
+        .slots
+        .insert(FROM_SCHEDULE_SLOT, SlotValue::Guid(schedule.guid));
+
+    Ok(transaction)
+}
+
+/// Builds the posting of a template split.
+fn posting(
+    book: &Book,
+    split: &Split,
+    variables: &BTreeMap<String, Numeric>,
+) -> Result<PostingDraft, SinceLastRunErrorKind> {
+    let Some(SlotValue::Guid(account)) = split.slots.get(ACCOUNT_SLOT) else {
+        return Err(SinceLastRunErrorKind::MissingAccount);
+    };
+
+    let evaluate = |path| {
+        let formula = split.slots.string(path).unwrap_or_default();
+        formula::evaluate(formula, variables).map_err(|source| {
+            SinceLastRunErrorKind::Formula {
+                formula: formula.to_owned(),
+                source,
+            }
+        })
+    };
+
+    Ok(PostingDraft {
+        account: book.account_path(*account),
+        amount: Some(
+            evaluate(DEBIT_FORMULA_SLOT)? - evaluate(CREDIT_FORMULA_SLOT)?,
+        ),
+        memo: split.memo.clone(),
+        ..PostingDraft::default()
+    })
+}
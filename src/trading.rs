@@ -0,0 +1,235 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Trading accounts.
+//!
+//! When the “Use Trading Accounts” option of a book is enabled, GnuCash
+//! balances each multi-commodity transaction in every commodity: for each
+//! commodity, a split in a `Trading:NAMESPACE:COMMODITY` account takes the
+//! opposite of the amount and value of the other splits in that commodity.
+
+use std::collections::BTreeMap;
+
+use crate::{
+    book::{
+        Account, AccountType, Book, Commodity, Frame, Guid, Numeric,
+        ReconcileState, Split, Transaction, ACCOUNT_SEPARATOR,
+    },
+    conversion,
+};
+
+/// The book option enabling trading accounts.
+const OPTION_SLOT: &str = "options/Accounts/Use Trading Accounts";
+
+/// The name of the top-level trading account.
+const TRADING_ACCOUNT: &str = "Trading";
+
+/// The imbalance of a transaction in a commodity.
+#[derive(Debug)]
+pub struct Imbalance<'a> {
+    /// The commodity.
+    pub commodity: &'a Commodity,
+    /// The total amount of the splits in the commodity.
+    pub amount: Numeric,
+    /// The total value of the splits in the commodity, in the currency of the
+    /// transaction.
+    pub value: Numeric,
+}
+
+/// The accounts and splits added to balance transactions with trading
+/// accounts.
+#[derive(Debug, Default)]
+pub struct TradingSplits {
+    /// The trading accounts created.
+    pub accounts: Vec<Account>,
+    /// The splits added, with the GUID of their transaction.
+    pub splits: Vec<(Guid, Split)>,
+    /// The number of transactions balanced.
+    pub transactions: usize,
+}
+
+/// Returns whether the book uses trading accounts.
+pub fn is_enabled(book: &Book) -> bool {
+    book.slots.string(OPTION_SLOT) == Some("t")
+}
+
+/// Returns the imbalances of a multi-commodity transaction per commodity.
+///
+/// Transactions with splits in a single commodity are always balanced, as
+/// GnuCash does not use trading accounts for them.
+pub fn imbalances<'a>(
+    book: &'a Book,
+    transaction: &Transaction,
+) -> Vec<Imbalance<'a>> {
+    let mut totals: BTreeMap<Guid, (Numeric, Numeric)> = BTreeMap::new();
+    let mut is_multi_commodity = false;
+
+    for split in &transaction.splits {
+        let Some(commodity) = book.account_commodity(split.account) else {
+            continue;
+        };
+
+        let is_trading = book
+            .account(split.account)
+            .is_some_and(|account| account.kind == AccountType::Trading);
+
+        is_multi_commodity |=
+            !is_trading && commodity.guid != transaction.currency;
+
+        let (amount, value) = totals.entry(commodity.guid).or_default();
+        *amount += split.quantity;
+        *value += split.value;
+    }
+
+    if !is_multi_commodity {
+        return vec![];
+    }
+
+    totals
+        .into_iter()
+        .filter(|(_, (amount, value))| !amount.is_zero() || !value.is_zero())
+        .filter_map(|(commodity, (amount, value))| {
+            Some(Imbalance {
+                commodity: book.commodity(commodity)?,
+                amount,
+                value,
+            })
+        })
+        .collect()
+}
+
+/// Balances the multi-commodity transactions of a book with trading splits.
+///
+/// The missing trading accounts are created under `Trading`. The accounts and
+/// splits are added to the book, and returned so that they can be saved.
+pub fn add_trading_splits(book: &mut Book) -> TradingSplits {
+    let mut added = TradingSplits::default();
+
+    let missing: Vec<_> = book
+        .regular_transactions()
+        .into_iter()
+        .map(|transaction| {
+            let imbalances: Vec<_> = imbalances(book, transaction)
+                .into_iter()
+                .map(|imbalance| {
+                    (
+                        imbalance.commodity.guid,
+                        imbalance.amount,
+                        imbalance.value,
+                    )
+                })
+                .collect();
+
+            (transaction.guid, imbalances)
+        })
+        .filter(|(_, imbalances)| !imbalances.is_empty())
+        .collect();
+
+    for (transaction, imbalances) in missing {
+        for (commodity, amount, value) in imbalances {
+            let account = trading_account(book, commodity, &mut added.accounts);
+
+            let split = Split {
+                guid: Guid::new(),
+                account,
+                memo: String::new(),
+                action: String::new(),
+                reconcile_state: ReconcileState::NotReconciled,
+                reconcile_date: None,
+                value: -value,
+                quantity: -amount,
+                lot: None,
+                slots: Frame::default(),
+            };
+
+            if let Some(transaction) = book.transactions.get_mut(&transaction) {
+                transaction.splits.push(split.clone());
+            }
+
+            added.splits.push((transaction, split));
+        }
+
+        added.transactions += 1;
+    }
+
+    added
+}
+
+/// Returns the trading account of a commodity, creating it if needed.
+///
+/// The accounts created are added to the book and to `created`.
+fn trading_account(
+    book: &mut Book,
+    commodity: Guid,
+    created: &mut Vec<Account>,
+) -> Guid {
+    let (namespace, mnemonic, fraction) = book
+        .commodity(commodity)
+        .map(|commodity| {
+            (
+                commodity.namespace.clone(),
+                commodity.mnemonic.clone(),
+                commodity.fraction,
+            )
+        })
+        .unwrap_or_default();
+
+    let currency = conversion::main_currency(book).unwrap_or(commodity);
+    let currency_scu = book
+        .commodity(currency)
+        .map_or(100, |currency| currency.fraction);
+
+    let levels = [
+        (TRADING_ACCOUNT.to_owned(), currency, currency_scu, true),
+        (namespace, currency, currency_scu, true),
+        (mnemonic, commodity, fraction, false),
+    ];
+
+    let mut parent = book.root_account;
+    let mut path = String::new();
+
+    for (name, commodity, scu, placeholder) in levels {
+        if !path.is_empty() {
+            path.push(ACCOUNT_SEPARATOR);
+        }
+        path.push_str(&name);
+
+        parent = if let Some(account) = book.find_account(&path) {
+            account.guid
+        } else {
+            let account = Account {
+                guid: Guid::new(),
+                name,
+                kind: AccountType::Trading,
+                commodity: Some(commodity),
+                commodity_scu: scu,
+                non_std_scu: false,
+                parent: Some(parent),
+                code: None,
+                description: None,
+                hidden: false,
+                placeholder,
+                slots: Frame::default(),
+            };
+
+            let guid = account.guid;
+            book.accounts.insert(guid, account.clone());
+            created.push(account);
+            guid
+        };
+    }
+
+    parent
+}
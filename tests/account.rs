@@ -0,0 +1,766 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! CLI tests for `gnucash-toolbox account`.
+
+// NOTE: rexpect is only compatible with Unix-like systems, so let’s just not
+// compile the CLI tests on Windows.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::pedantic, clippy::restriction)]
+
+mod common;
+
+use std::{fs, path::PathBuf, process::Command};
+
+use eyre::Result;
+use rexpect::session::spawn_command;
+
+use self::common::{gnc, Book, TIMEOUT};
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  Helpers                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+fn gnucash_toolbox_account(book: &Book, args: &[&str]) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.arg("account").args(args);
+    cmd
+}
+
+/// Returns the path of the account template fixture.
+fn template() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/acctchrt_test.gnucash-xea")
+}
+
+/// Returns the name, type and parent of an account.
+fn account(book: &Book, guid: &str) -> Result<String> {
+    book.query(&format!(
+        "SELECT name || ' ' || account_type || ' ' || parent_guid
+        FROM accounts WHERE guid = '{guid}'"
+    ))
+}
+
+/// Returns the number of splits in an account.
+fn split_count(book: &Book, account: &str) -> Result<i64> {
+    book.query(&format!(
+        "SELECT COUNT(*) FROM splits WHERE account_guid = '{account}'"
+    ))
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                    add                                     //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn adds_an_account() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(
+            &book,
+            &[
+                "add",
+                "--type",
+                "expense",
+                "--code",
+                "63",
+                "-D",
+                "Business trips",
+                "Expenses:Travel",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("The account Expenses:Travel has been added.")?;
+    process.exp_eof()?;
+
+    let account: String = book.query(
+        "SELECT account_type || ' ' || parent_guid || ' ' || commodity_guid
+            || ' ' || commodity_scu || ' ' || code || ' ' || description
+        FROM accounts WHERE name = 'Travel'",
+    )?;
+    assert_eq!(
+        account,
+        "EXPENSE a0000000000000000000000000000030 \
+        c0000000000000000000000000000001 100 63 Business trips"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn adds_an_account_in_another_commodity() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(
+            &book,
+            &[
+                "add",
+                "--type",
+                "bank",
+                "--commodity",
+                "USD",
+                "--placeholder",
+                "Assets:Bank:US Savings",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("The account Assets:Bank:US Savings has been added.")?;
+    process.exp_eof()?;
+
+    let account: String = book.query(
+        "SELECT commodity_guid || ' ' || placeholder || ' ' || hidden
+        FROM accounts WHERE name = 'US Savings'",
+    )?;
+    assert_eq!(account, "c0000000000000000000000000000002 1 0");
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_add_an_income_account_under_assets() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(
+            &book,
+            &["add", "--type", "income", "Assets:Interests"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "Error: an account of type INCOME cannot be under Assets, of type \
+        ASSET.",
+    )?;
+    process.exp_string("Use --force to do it anyway.")?;
+    process.exp_eof()?;
+
+    let count: i64 =
+        book.query("SELECT COUNT(*) FROM accounts WHERE name = 'Interests'")?;
+    assert_eq!(count, 0);
+
+    Ok(())
+}
+
+#[test]
+fn adds_an_incompatible_account_with_force() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(
+            &book,
+            &["add", "--type", "income", "--force", "Assets:Interests"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("The account Assets:Interests has been added.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_add_an_account_under_an_unknown_parent() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(
+            &book,
+            &["add", "--type", "expense", "Expenses:Travel:Hotels"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("Error: unknown account: Expenses:Travel.")?;
+    process.exp_string(
+        "Accounts are given by their full path, like Assets:Bank.",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_add_an_existing_account() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(
+            &book,
+            &["add", "--type", "expense", "Expenses:Food"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("Error: the account Expenses:Food already exists.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                     mv                                     //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn moves_an_account_into_another_one() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(&book, &["mv", "Assets:Bank", "Liabilities"]),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "The account Assets:Bank has been moved to Liabilities:Bank.",
+    )?;
+    process.exp_eof()?;
+
+    assert_eq!(
+        account(&book, "a0000000000000000000000000000002")?,
+        "Bank BANK a0000000000000000000000000000010"
+    );
+    assert_eq!(
+        account(&book, "a0000000000000000000000000000003")?,
+        "Checking BANK a0000000000000000000000000000002"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn moves_an_account_to_a_new_path() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(
+            &book,
+            &["mv", "Expenses:Food", "Expenses:Rent:Meals"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "The account Expenses:Food has been moved to Expenses:Rent:Meals.",
+    )?;
+    process.exp_eof()?;
+
+    assert_eq!(
+        account(&book, "a0000000000000000000000000000031")?,
+        "Meals EXPENSE a0000000000000000000000000000032"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_move_an_expense_account_under_assets() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(&book, &["mv", "Expenses:Food", "Assets"]),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "Error: an account of type EXPENSE cannot be under Assets, of type \
+        ASSET.",
+    )?;
+    process.exp_string("Use --force to do it anyway.")?;
+    process.exp_eof()?;
+
+    assert_eq!(
+        account(&book, "a0000000000000000000000000000031")?,
+        "Food EXPENSE a0000000000000000000000000000030"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_move_an_account_under_itself() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(&book, &["mv", "Assets", "Assets:Bank"]),
+        TIMEOUT,
+    )?;
+    process
+        .exp_string("Error: cannot move the account Assets under itself.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                   rename                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn renames_an_account() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(
+            &book,
+            &["rename", "Expenses:Food", "Groceries"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "The account Expenses:Food has been renamed to Expenses:Groceries.",
+    )?;
+    process.exp_eof()?;
+
+    assert_eq!(
+        account(&book, "a0000000000000000000000000000031")?,
+        "Groceries EXPENSE a0000000000000000000000000000030"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_rename_an_account_to_the_name_of_a_sibling() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(&book, &["rename", "Expenses:Food", "Rent"]),
+        TIMEOUT,
+    )?;
+    process.exp_string("Error: the account Expenses:Rent already exists.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                   merge                                    //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn merges_an_account_into_another_one() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(
+            &book,
+            &["merge", "Expenses:Rent", "Expenses:Food"],
+        ),
+        TIMEOUT,
+    )?;
+    process
+        .exp_string("2 splits moved from Expenses:Rent to Expenses:Food.")?;
+    process.exp_string("The account Expenses:Rent has been deleted.")?;
+    process.exp_eof()?;
+
+    assert_eq!(split_count(&book, "a0000000000000000000000000000031")?, 2);
+
+    let accounts: i64 = book.query(
+        "SELECT COUNT(*) FROM accounts
+        WHERE guid = 'a0000000000000000000000000000032'",
+    )?;
+    assert_eq!(accounts, 0);
+
+    let budgets: i64 = book.query(
+        "SELECT COUNT(*) FROM budget_amounts
+        WHERE account_guid = 'a0000000000000000000000000000031'",
+    )?;
+    assert_eq!(budgets, 3);
+
+    let template: String = book.query(
+        "SELECT guid_val FROM slots
+        WHERE obj_guid IN (
+            SELECT guid_val FROM slots WHERE obj_guid = '500000000000000000000000000000f1'
+        )
+        AND name = 'sched-xaction/account'",
+    )?;
+    assert_eq!(template, "a0000000000000000000000000000031");
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_merge_an_account_with_sub_accounts() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(
+            &book,
+            &["merge", "Assets:Bank", "Assets:Broker"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("Error: the account Assets:Bank has sub-accounts.")?;
+    process.exp_string("Move or merge its sub-accounts first.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_merge_accounts_in_different_commodities() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(
+            &book,
+            &[
+                "merge",
+                "--force",
+                "Assets:Bank:US Checking",
+                "Assets:Bank:Checking",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "Error: the accounts Assets:Bank:US Checking and Assets:Bank:Checking \
+        have different commodities.",
+    )?;
+    process.exp_eof()?;
+
+    assert_eq!(split_count(&book, "a0000000000000000000000000000003")?, 4);
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_merge_an_expense_account_into_an_asset_account() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(
+            &book,
+            &["merge", "Expenses:Food", "Assets:Bank:Savings"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "Error: the accounts Expenses:Food (EXPENSE) and Assets:Bank:Savings \
+        (BANK) have incompatible types.",
+    )?;
+    process.exp_string("Use --force to do it anyway.")?;
+    process.exp_eof()?;
+
+    assert_eq!(split_count(&book, "a0000000000000000000000000000031")?, 1);
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                    hide                                    //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn hides_an_account() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(&book, &["hide", "Assets:Bank:Savings"]),
+        TIMEOUT,
+    )?;
+    process.exp_string("The account Assets:Bank:Savings is now hidden.")?;
+    process.exp_eof()?;
+
+    let hidden: bool = book.query(
+        "SELECT hidden FROM accounts
+        WHERE guid = 'a0000000000000000000000000000004'",
+    )?;
+    assert!(hidden);
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(
+            &book,
+            &["hide", "--unset", "Assets:Bank:Savings"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("The account Assets:Bank:Savings is now visible.")?;
+    process.exp_eof()?;
+
+    let hidden: bool = book.query(
+        "SELECT hidden FROM accounts
+        WHERE guid = 'a0000000000000000000000000000004'",
+    )?;
+    assert!(!hidden);
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                placeholder                                 //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn marks_an_account_as_a_placeholder() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(&book, &["placeholder", "Expenses:Food"]),
+        TIMEOUT,
+    )?;
+    process.exp_string("The account Expenses:Food is now a placeholder.")?;
+    process.exp_eof()?;
+
+    let placeholder: bool = book.query(
+        "SELECT placeholder FROM accounts
+        WHERE guid = 'a0000000000000000000000000000031'",
+    )?;
+    assert!(placeholder);
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(
+            &book,
+            &["placeholder", "--unset", "Expenses:Food"],
+        ),
+        TIMEOUT,
+    )?;
+    process
+        .exp_string("The account Expenses:Food is no longer a placeholder.")?;
+    process.exp_eof()?;
+
+    let placeholder: bool = book.query(
+        "SELECT placeholder FROM accounts
+        WHERE guid = 'a0000000000000000000000000000031'",
+    )?;
+    assert!(!placeholder);
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                   export                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn exports_the_accounts_to_csv() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_account(&book, &["export"]), TIMEOUT)?;
+    process.exp_string(
+        r#""type","full_name","name","code","description","color","notes","symbol","namespace","hidden","tax","place_holder""#,
+    )?;
+    process.exp_string(
+        r#""BANK","Assets:Bank:Checking","Checking","111","Main checking account","","","EUR","CURRENCY","F","F","F""#,
+    )?;
+    process.exp_string(
+        r#""BANK","Assets:Bank:US Checking","US Checking","113","","","","USD","CURRENCY","F","F","F""#,
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn exports_an_account_template() -> Result<()> {
+    let book = Book::new()?;
+    let output = book.dir.path().join("chart.gnucash-xea");
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(
+            &book,
+            &[
+                "export",
+                "--template",
+                "--title",
+                "Standard chart",
+                "-o",
+                output.to_str().unwrap(),
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string(&format!(
+        "The accounts have been exported to {}.",
+        output.display()
+    ))?;
+    process.exp_eof()?;
+
+    let template = fs::read_to_string(&output)?;
+    assert!(template.starts_with("<?xml"));
+    assert!(template.contains("<gnc-account-example"));
+    assert!(template.contains("<gnc-act:title>Standard chart</gnc-act:title>"));
+    assert!(template.contains("<act:name>US Checking</act:name>"));
+    assert!(!template.contains("<act:name>Template Root</act:name>"));
+
+    Ok(())
+}
+
+#[test]
+fn imports_its_own_template_without_changes() -> Result<()> {
+    let book = Book::new()?;
+    let output = book.dir.path().join("chart.gnucash-xea");
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(
+            &book,
+            &["export", "--template", "-o", output.to_str().unwrap()],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_eof()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(
+            &book,
+            &["import-template", output.to_str().unwrap()],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("0 accounts added, 16 already in the book.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                              import-template                               //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn imports_the_missing_accounts_of_a_template() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(
+            &book,
+            &["import-template", template().to_str().unwrap()],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("New account: Assets:Current Assets")?;
+    process.exp_string("New account: Assets:Current Assets:Petty Cash")?;
+    process.exp_string("New account: Expenses:Travel")?;
+    process.exp_string("New account: Income:Interest Income")?;
+    process.exp_string("4 accounts added, 3 already in the book.")?;
+    process.exp_eof()?;
+
+    let account: String = book.query(
+        "SELECT account_type || ' ' || commodity_guid || ' ' || description
+            || ' ' || placeholder
+        FROM accounts WHERE name = 'Current Assets'",
+    )?;
+    assert_eq!(
+        account,
+        "ASSET c0000000000000000000000000000002 Current Assets 1"
+    );
+
+    let account: String = book.query(
+        "SELECT account_type || ' ' || parent_guid FROM accounts
+        WHERE name = 'Travel'",
+    )?;
+    assert_eq!(account, "EXPENSE a0000000000000000000000000000030");
+
+    Ok(())
+}
+
+#[test]
+fn imports_a_template_in_another_currency() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(
+            &book,
+            &[
+                "import-template",
+                "--currency",
+                "EUR",
+                template().to_str().unwrap(),
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("4 accounts added, 3 already in the book.")?;
+    process.exp_eof()?;
+
+    let currencies: i64 = book.query(
+        "SELECT COUNT(*) FROM accounts
+        WHERE name IN ('Current Assets', 'Petty Cash', 'Travel', 'Interest Income')
+        AND commodity_guid = 'c0000000000000000000000000000001'",
+    )?;
+    assert_eq!(currencies, 4);
+
+    Ok(())
+}
+
+#[test]
+fn does_not_modify_the_book_when_importing_a_template_on_dry_run() -> Result<()>
+{
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(
+            &book,
+            &["import-template", "--dry-run", template().to_str().unwrap()],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("New account: Expenses:Travel")?;
+    process.exp_string("4 accounts to add, 3 already in the book.")?;
+    process.exp_eof()?;
+
+    let count: i64 = book.query("SELECT COUNT(*) FROM accounts")?;
+    assert_eq!(count, 18);
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_import_a_template_with_incompatible_types() -> Result<()> {
+    let book = Book::new()?;
+    book.connection()?.execute(
+        "UPDATE accounts SET account_type = 'EXPENSE'
+        WHERE guid = 'a0000000000000000000000000000001'",
+        [],
+    )?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(
+            &book,
+            &["import-template", template().to_str().unwrap()],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "Error: an account of type ASSET cannot be under Assets, of type \
+        EXPENSE.",
+    )?;
+    process.exp_string("Use --force to do it anyway.")?;
+    process.exp_eof()?;
+
+    let count: i64 = book.query("SELECT COUNT(*) FROM accounts")?;
+    assert_eq!(count, 18);
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_import_a_file_which_is_not_a_template() -> Result<()> {
+    let book = Book::new()?;
+    let file = book.dir.path().join("book.xml");
+    fs::write(&file, "<gnc-v2></gnc-v2>")?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_account(
+            &book,
+            &["import-template", file.to_str().unwrap()],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("Error: the file is not a GnuCash account template.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
@@ -0,0 +1,220 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! CLI tests for `gnucash-toolbox archive`.
+
+// NOTE: rexpect is only compatible with Unix-like systems, so let’s just not
+// compile the CLI tests on Windows.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::pedantic, clippy::restriction)]
+
+mod common;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use eyre::Result;
+use rexpect::session::spawn_command;
+
+use self::common::{check, gnc, query, Book, TIMEOUT};
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  Helpers                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+fn gnucash_toolbox_archive(book: &Book, before: &str) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.args(["archive", "--before", before])
+        .arg(archive_book(book));
+    cmd
+}
+
+/// Returns the path of the archive book.
+fn archive_book(book: &Book) -> PathBuf {
+    book.dir.path().join("archive.gnucash")
+}
+
+/// Returns the descriptions of the transactions of a book, by date.
+fn descriptions(path: &Path) -> Result<String> {
+    query(
+        path,
+        "SELECT GROUP_CONCAT(description, ', ') FROM (
+            SELECT description FROM transactions
+            ORDER BY post_date, description
+        )",
+    )
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  archive                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn replaces_reconciled_transactions_by_a_summary() -> Result<()> {
+    let book = Book::new()?;
+    let archive = archive_book(&book);
+
+    let mut process =
+        spawn_command(gnucash_toolbox_archive(&book, "2024-02-01"), TIMEOUT)?;
+    process.exp_string(&format!(
+        "2 transactions have been archived in {}, replaced by 1 summary \
+        transactions.",
+        archive.display()
+    ))?;
+    process.exp_eof()?;
+
+    assert_eq!(descriptions(&archive)?, "Opening balance, Salary");
+    assert_eq!(
+        descriptions(&book.path)?,
+        "Groceries, Rent, Archived transactions"
+    );
+
+    let balance: i64 = book.query(
+        "SELECT SUM(quantity_num) FROM splits
+        WHERE account_guid = 'a0000000000000000000000000000003'",
+    )?;
+    assert_eq!(balance, 265_750);
+
+    let summary: String = book.query(
+        "SELECT post_date || ' ' || reconcile_state || ' ' || quantity_num
+        FROM splits JOIN transactions ON transactions.guid = splits.tx_guid
+        WHERE account_guid = 'a0000000000000000000000000000003'
+        AND description = 'Archived transactions'",
+    )?;
+    assert_eq!(summary, "2024-01-31 10:59:00 y 350000");
+
+    check(&book.path)?;
+    check(&archive)
+}
+
+#[test]
+fn keeps_the_transactions_with_lots() -> Result<()> {
+    let book = Book::full()?;
+    let archive = archive_book(&book);
+
+    let mut process =
+        spawn_command(gnucash_toolbox_archive(&book, "2025-01-01"), TIMEOUT)?;
+    process.exp_string("transactions have been archived in")?;
+    process.exp_eof()?;
+
+    let invoices: i64 =
+        book.query("SELECT COUNT(*) FROM transactions WHERE num = '000001'")?;
+    assert_eq!(invoices, 1);
+
+    let archived: i64 = query(
+        &archive,
+        "SELECT COUNT(*) FROM transactions WHERE num = '000001'",
+    )?;
+    assert_eq!(archived, 0);
+
+    check(&book.path)?;
+    check(&archive)
+}
+
+#[test]
+fn does_nothing_without_transactions_to_archive() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_archive(&book, "2024-01-01"), TIMEOUT)?;
+    process
+        .exp_string("There is no transaction to archive before 2024-01-01.")?;
+    process.exp_eof()?;
+
+    assert!(!archive_book(&book).exists());
+
+    Ok(())
+}
+
+#[test]
+fn refuses_to_overwrite_an_existing_archive() -> Result<()> {
+    let book = Book::new()?;
+    let archive = archive_book(&book);
+    fs::write(&archive, "existing")?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_archive(&book, "2024-02-01"), TIMEOUT)?;
+    process
+        .exp_string(&format!("Error: {} already exists.", archive.display()))?;
+    process.exp_string("Archive to a new book")?;
+    process.exp_eof()?;
+
+    assert_eq!(fs::read_to_string(&archive)?, "existing");
+
+    let transactions: i64 = book.query("SELECT COUNT(*) FROM transactions")?;
+    assert_eq!(transactions, 4);
+
+    Ok(())
+}
+
+#[test]
+fn removes_the_archive_when_the_book_cannot_be_updated() -> Result<()> {
+    let book = Book::new()?;
+    let archive = archive_book(&book);
+    book.connection()?.execute_batch(
+        "CREATE TRIGGER keep_transactions BEFORE DELETE ON transactions
+        BEGIN SELECT RAISE(ABORT, 'transactions are locked'); END;",
+    )?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_archive(&book, "2024-02-01"), TIMEOUT)?;
+    process.exp_string("transactions are locked")?;
+    process.exp_eof()?;
+
+    assert!(!archive.exists());
+
+    book.connection()?
+        .execute_batch("DROP TRIGGER keep_transactions;")?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_archive(&book, "2024-02-01"), TIMEOUT)?;
+    process.exp_string("2 transactions have been archived in")?;
+    process.exp_eof()?;
+
+    check(&archive)
+}
+
+#[test]
+fn never_replaces_an_earlier_archive() -> Result<()> {
+    let book = Book::new()?;
+    let archive = archive_book(&book);
+
+    let mut process =
+        spawn_command(gnucash_toolbox_archive(&book, "2024-02-01"), TIMEOUT)?;
+    process.exp_eof()?;
+
+    let mut command = gnucash_toolbox_archive(&book, "2024-03-01");
+    command.arg("--force");
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string("error: unexpected argument '--force' found")?;
+    process.exp_eof()?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_archive(&book, "2024-03-01"), TIMEOUT)?;
+    process
+        .exp_string(&format!("Error: {} already exists.", archive.display()))?;
+    process.exp_eof()?;
+
+    assert_eq!(descriptions(&archive)?, "Opening balance, Salary");
+    assert_eq!(
+        descriptions(&book.path)?,
+        "Groceries, Rent, Archived transactions"
+    );
+
+    Ok(())
+}
@@ -0,0 +1,234 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! CLI tests for `gnucash-toolbox assert`.
+
+// NOTE: rexpect is only compatible with Unix-like systems, so let’s just not
+// compile the CLI tests on Windows.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::pedantic, clippy::restriction)]
+
+mod common;
+
+use std::{fs, process::Command};
+
+use eyre::Result;
+use indoc::indoc;
+use rexpect::session::spawn_command;
+
+use self::common::{gnc, Book, TIMEOUT};
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  Helpers                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+fn gnucash_toolbox_assert(
+    book: &Book,
+    name: &str,
+    assertions: &str,
+) -> Result<Command> {
+    let path = book.dir.path().join(name);
+    fs::write(&path, assertions)?;
+
+    let mut cmd = gnc(&book.path);
+    cmd.arg("assert").arg(path);
+    Ok(cmd)
+}
+
+fn assert_yaml(
+    book: &Book,
+    account: &str,
+    date: &str,
+    balance: &str,
+) -> Result<Command> {
+    gnucash_toolbox_assert(
+        book,
+        "assertions.yaml",
+        &format!(
+            "assertions:\n  - account: {account}\n    date: {date}\n    balance: {balance}\n"
+        ),
+    )
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                   assert                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn checks_assertions_in_yaml() -> Result<()> {
+    let book = Book::new()?;
+
+    let command = gnucash_toolbox_assert(
+        &book,
+        "assertions.yaml",
+        indoc! {"
+            assertions:
+              - account: Assets:Bank:Checking
+                date: 2024-01-10
+                balance: 3457.5
+              - account: Assets:Bank:Checking
+                date: 2024-01-31
+                balance: '2657.50'
+        "},
+    )?;
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string(
+        "Assets:Bank:Checking on 2024-01-10: 3457.50 EUR, as expected.",
+    )?;
+    process.exp_string(
+        "Assets:Bank:Checking on 2024-01-31: 2657.50 EUR, as expected.",
+    )?;
+    process.exp_string("All 2 balance assertions hold.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn checks_assertions_in_toml() -> Result<()> {
+    let book = Book::new()?;
+
+    let command = gnucash_toolbox_assert(
+        &book,
+        "assertions.toml",
+        indoc! {r#"
+            [[assertions]]
+            account = "Assets:Bank:Checking"
+            date = 2024-01-05
+            balance = 3500
+
+            [[assertions]]
+            account = "Assets:Bank:Checking"
+            date = "2024-01-31"
+            balance = "2657.50"
+        "#},
+    )?;
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string(
+        "Assets:Bank:Checking on 2024-01-05: 3500.00 EUR, as expected.",
+    )?;
+    process.exp_string("All 2 balance assertions hold.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn reports_splits_posted_after_the_date() -> Result<()> {
+    let book = Book::new()?;
+
+    let command =
+        assert_yaml(&book, "Assets:Bank:Checking", "2024-01-14", "2657.50")?;
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string(
+        "Assets:Bank:Checking on 2024-01-14: 3457.50 EUR instead of 2657.50 EUR \
+        (difference: -800.00 EUR).",
+    )?;
+    process.exp_string("Likely culprits:")?;
+    process.exp_regex(
+        r"2024-01-15  Rent +-800\.00 EUR  \(posted after the date with the missing amount\)",
+    )?;
+    process.exp_regex(
+        r"2024-01-10  Groceries +-42\.50 EUR  \(posted near the date\)",
+    )?;
+    process.exp_string("Error: 1 of 1 balance assertions failed.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn reports_splits_with_a_wrong_sign() -> Result<()> {
+    let book = Book::new()?;
+
+    let command =
+        assert_yaml(&book, "Assets:Bank:Checking", "2024-01-10", "3542.50")?;
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_regex(
+        r"2024-01-10  Groceries +-42\.50 EUR  \(its sign may be wrong\)",
+    )?;
+    process.exp_string("Error: 1 of 1 balance assertions failed.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn reports_splits_with_swapped_digits() -> Result<()> {
+    let book = Book::new()?;
+
+    let command =
+        assert_yaml(&book, "Assets:Bank:Checking", "2024-01-10", "3475.50")?;
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_regex(
+        r"2024-01-10  Groceries +-42\.50 EUR  \(its digits may be swapped\)",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn reports_extra_splits() -> Result<()> {
+    let book = Book::new()?;
+
+    let command =
+        assert_yaml(&book, "Assets:Bank:Checking", "2024-01-15", "3457.50")?;
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_regex(
+        r"2024-01-15  Rent +-800\.00 EUR  \(duplicated or posted too early\)",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn refuses_unknown_accounts() -> Result<()> {
+    let book = Book::new()?;
+
+    let command = assert_yaml(&book, "Assets:Nowhere", "2024-01-31", "0")?;
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string("Error: unknown account Assets:Nowhere.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn refuses_unsupported_files() -> Result<()> {
+    let book = Book::new()?;
+
+    let command = gnucash_toolbox_assert(
+        &book,
+        "assertions.json",
+        "{\"assertions\": []}",
+    )?;
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string("Error: unsupported assertion file: ")?;
+    process
+        .exp_string("Assertion files must end with .yaml, .yml or .toml.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
@@ -0,0 +1,126 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! CLI tests for `gnucash-toolbox check`.
+
+// NOTE: rexpect is only compatible with Unix-like systems, so let’s just not
+// compile the CLI tests on Windows.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::pedantic, clippy::restriction)]
+
+mod common;
+
+use std::process::Command;
+
+use eyre::Result;
+use rexpect::session::spawn_command;
+
+use self::common::{
+    add_foreign_currency_transactions, add_trading_splits,
+    enable_trading_accounts, gnc, Book, TIMEOUT,
+};
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  Helpers                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+fn gnucash_toolbox_check(book: &Book) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.arg("check");
+    cmd
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                   check                                    //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn accepts_a_consistent_book() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(gnucash_toolbox_check(&book), TIMEOUT)?;
+    process.exp_string("The book is consistent.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn reports_unbalanced_transactions() -> Result<()> {
+    let book = Book::new()?;
+    book.connection()?.execute(
+        "UPDATE splits SET value_num = 4500, quantity_num = 4500
+        WHERE guid = '50000000000000000000000000000031'",
+        [],
+    )?;
+
+    let mut process = spawn_command(gnucash_toolbox_check(&book), TIMEOUT)?;
+    process.exp_string("2024-01-10 Groceries: unbalanced by 2.50 EUR.")?;
+    process.exp_string("Error: the book has 1 integrity issues.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn ignores_trading_splits_when_trading_accounts_are_disabled() -> Result<()> {
+    let book = Book::new()?;
+    add_foreign_currency_transactions(&book)?;
+
+    let mut process = spawn_command(gnucash_toolbox_check(&book), TIMEOUT)?;
+    process.exp_string("The book is consistent.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn reports_missing_trading_splits() -> Result<()> {
+    let book = Book::new()?;
+    add_foreign_currency_transactions(&book)?;
+    enable_trading_accounts(&book)?;
+
+    let mut process = spawn_command(gnucash_toolbox_check(&book), TIMEOUT)?;
+    process.exp_string(
+        "2024-01-31 Buy USD: missing trading splits for -920.00 EUR.",
+    )?;
+    process.exp_string(
+        "2024-01-31 Buy USD: missing trading splits for 1000.00 USD.",
+    )?;
+    process.exp_string(
+        "2024-02-15 Dinner in New York: missing trading splits for 376.00 EUR.",
+    )?;
+    process.exp_string(
+        "2024-02-15 Dinner in New York: missing trading splits for -400.00 USD.",
+    )?;
+    process.exp_string("Error: the book has 4 integrity issues.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn accepts_transactions_with_trading_splits() -> Result<()> {
+    let book = Book::new()?;
+    add_foreign_currency_transactions(&book)?;
+    add_trading_splits(&book)?;
+    enable_trading_accounts(&book)?;
+
+    let mut process = spawn_command(gnucash_toolbox_check(&book), TIMEOUT)?;
+    process.exp_string("The book is consistent.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
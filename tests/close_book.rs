@@ -0,0 +1,293 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! CLI tests for `gnucash-toolbox close-book`.
+
+// NOTE: rexpect is only compatible with Unix-like systems, so let’s just not
+// compile the CLI tests on Windows.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::pedantic, clippy::restriction)]
+
+mod common;
+
+use std::process::Command;
+
+use eyre::Result;
+use indoc::indoc;
+use rexpect::session::spawn_command;
+
+use self::common::{balance, check, gnc, Book, TIMEOUT};
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  Helpers                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+fn gnucash_toolbox_close_book(book: &Book, args: &[&str]) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.arg("close-book").args(args);
+    cmd
+}
+
+/// Adds an Equity:Retained Earnings account in EUR.
+fn add_retained_earnings(book: &Book) -> Result<()> {
+    book.connection()?.execute_batch(indoc! {"
+        INSERT INTO accounts VALUES ('a0000000000000000000000000000042', 'Retained Earnings', 'EQUITY', 'c0000000000000000000000000000001', 100, 0, 'a0000000000000000000000000000040', '32', '', 0, 0);
+    "})?;
+
+    Ok(())
+}
+
+/// Adds an Expenses:Travel account in USD, with 150 USD spent on 2024-01-20.
+fn add_travel_expenses(book: &Book) -> Result<()> {
+    book.connection()?.execute_batch(indoc! {"
+        INSERT INTO accounts VALUES ('a0000000000000000000000000000033', 'Travel', 'EXPENSE', 'c0000000000000000000000000000002', 100, 0, 'a0000000000000000000000000000030', '63', '', 0, 0);
+        INSERT INTO transactions VALUES ('70000000000000000000000000000020', 'c0000000000000000000000000000002', '', '2024-01-20 10:59:00', '2024-01-20 12:00:00', 'Hotel in New York');
+        INSERT INTO splits VALUES ('50000000000000000000000000000201', '70000000000000000000000000000020', 'a0000000000000000000000000000033', '', '', 'n', NULL, 15000, 100, 15000, 100, NULL);
+        INSERT INTO splits VALUES ('50000000000000000000000000000202', '70000000000000000000000000000020', 'a0000000000000000000000000000005', '', '', 'n', NULL, -15000, 100, -15000, 100, NULL);
+    "})?;
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                 close-book                                 //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn closes_income_and_expenses_into_equity() -> Result<()> {
+    let book = Book::new()?;
+    add_retained_earnings(&book)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_close_book(
+            &book,
+            &[
+                "--date",
+                "2024-01-31",
+                "--equity",
+                "Equity:Retained Earnings",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("2024-01-31 Closing Entries (EUR):")?;
+    process.exp_string("Account                     Amount")?;
+    process.exp_string("Expenses:Food               -42.50")?;
+    process.exp_string("Expenses:Rent              -800.00")?;
+    process.exp_string("Income:Salary              2500.00")?;
+    process.exp_string("Equity:Retained Earnings  -1657.50")?;
+    process.exp_string("1 closing transactions created.")?;
+    process.exp_eof()?;
+
+    assert_eq!(balance(&book.path, "a0000000000000000000000000000021")?, 0);
+    assert_eq!(balance(&book.path, "a0000000000000000000000000000031")?, 0);
+    assert_eq!(balance(&book.path, "a0000000000000000000000000000032")?, 0);
+    assert_eq!(
+        balance(&book.path, "a0000000000000000000000000000042")?,
+        -165_750
+    );
+
+    let closing: i64 = book.query(
+        "SELECT COUNT(*) FROM slots WHERE name = 'book_closing'
+        AND int64_val = 1",
+    )?;
+    assert_eq!(closing, 1);
+
+    Ok(())
+}
+
+#[test]
+fn only_closes_the_splits_up_to_the_date() -> Result<()> {
+    let book = Book::new()?;
+    add_retained_earnings(&book)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_close_book(
+            &book,
+            &[
+                "--date",
+                "2024-01-10",
+                "--equity",
+                "Equity:Retained Earnings",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("1 closing transactions created.")?;
+    process.exp_eof()?;
+
+    assert_eq!(
+        balance(&book.path, "a0000000000000000000000000000032")?,
+        80000
+    );
+    assert_eq!(
+        balance(&book.path, "a0000000000000000000000000000042")?,
+        -245_750
+    );
+
+    Ok(())
+}
+
+#[test]
+fn closes_each_currency_separately() -> Result<()> {
+    let book = Book::new()?;
+    add_retained_earnings(&book)?;
+    add_travel_expenses(&book)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_close_book(
+            &book,
+            &[
+                "--date",
+                "2024-01-31",
+                "--equity",
+                "Equity:Retained Earnings",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("New account: Equity:Retained Earnings:USD")?;
+    process.exp_string("2024-01-31 Closing Entries (EUR):")?;
+    process.exp_string("Equity:Retained Earnings  -1657.50")?;
+    process.exp_string("2024-01-31 Closing Entries (USD):")?;
+    process.exp_string("Expenses:Travel               -150.00")?;
+    process.exp_string("Equity:Retained Earnings:USD   150.00")?;
+    process.exp_string("2 closing transactions created.")?;
+    process.exp_eof()?;
+
+    assert_eq!(balance(&book.path, "a0000000000000000000000000000033")?, 0);
+
+    let commodity: String = book.query(
+        "SELECT commodity_guid FROM accounts WHERE name = 'USD'
+        AND parent_guid = 'a0000000000000000000000000000042'",
+    )?;
+    assert_eq!(commodity, "c0000000000000000000000000000002");
+
+    check(&book.path)
+}
+
+#[test]
+fn does_not_close_a_closed_book_again() -> Result<()> {
+    let book = Book::new()?;
+    add_retained_earnings(&book)?;
+
+    for expected in ["1 closing", "0 closing"] {
+        let mut process = spawn_command(
+            gnucash_toolbox_close_book(
+                &book,
+                &["-d", "2024-01-31", "-e", "Equity:Retained Earnings"],
+            ),
+            TIMEOUT,
+        )?;
+        process.exp_string(&format!("{expected} transactions created."))?;
+        process.exp_eof()?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn sets_the_read_only_threshold() -> Result<()> {
+    let book = Book::new()?;
+    add_retained_earnings(&book)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_close_book(
+            &book,
+            &[
+                "--date",
+                "2024-01-31",
+                "--equity",
+                "Equity:Retained Earnings",
+                "--read-only-days",
+                "30",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("Transactions older than 30 days will be read-only.")?;
+    process.exp_string("1 closing transactions created.")?;
+    process.exp_eof()?;
+
+    let days: f64 = book.query(
+        "SELECT double_val FROM slots
+        WHERE name = 'options/Accounts/Day Threshold for Read-Only Transactions'",
+    )?;
+    assert_eq!(days, 30.0);
+
+    Ok(())
+}
+
+#[test]
+fn does_not_modify_the_book_on_dry_run() -> Result<()> {
+    let book = Book::new()?;
+    add_retained_earnings(&book)?;
+    add_travel_expenses(&book)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_close_book(
+            &book,
+            &[
+                "--date",
+                "2024-01-31",
+                "--equity",
+                "Equity:Retained Earnings",
+                "--read-only-days",
+                "30",
+                "--dry-run",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("New account: Equity:Retained Earnings:USD")?;
+    process.exp_string("Equity:Retained Earnings  -1657.50")?;
+    process.exp_string("2 closing transactions to create.")?;
+    process.exp_eof()?;
+
+    let transactions: i64 = book.query("SELECT COUNT(*) FROM transactions")?;
+    assert_eq!(transactions, 5);
+
+    let accounts: i64 =
+        book.query("SELECT COUNT(*) FROM accounts WHERE name = 'USD'")?;
+    assert_eq!(accounts, 0);
+
+    let slots: i64 =
+        book.query("SELECT COUNT(*) FROM slots WHERE name LIKE 'options%'")?;
+    assert_eq!(slots, 0);
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_close_into_an_account_that_is_not_equity() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_close_book(
+            &book,
+            &["--date", "2024-01-31", "--equity", "Assets:Bank:Checking"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "Error: the account Assets:Bank:Checking is not an equity account.",
+    )?;
+    process.exp_string(
+        "The balances can only be closed into an equity account.",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
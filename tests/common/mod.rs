@@ -0,0 +1,170 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Helpers shared by the CLI tests.
+
+#![allow(dead_code)]
+
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use assert_cmd::cargo::cargo_bin;
+use assert_fs::TempDir;
+use eyre::Result;
+use rexpect::session::spawn_command;
+use rusqlite::Connection;
+
+pub const TIMEOUT: Option<u64> = Some(5_000);
+
+/// The SQL script creating the fixture book.
+const BOOK_SQL: &str = include_str!("../fixtures/book.sql");
+
+/// The SQL script completing the fixture book with every kind of entity.
+const FULL_SQL: &str = include_str!("../fixtures/full.sql");
+
+/// The SQL script adding foreign currency transactions to the fixture book.
+const FOREIGN_CURRENCY_SQL: &str =
+    include_str!("../fixtures/foreign_currency.sql");
+
+/// The SQL script adding the trading accounts to the fixture book.
+const TRADING_ACCOUNTS_SQL: &str =
+    include_str!("../fixtures/trading_accounts.sql");
+
+/// The SQL script adding the trading splits of the foreign currency
+/// transactions.
+const TRADING_SPLITS_SQL: &str = include_str!("../fixtures/trading_splits.sql");
+
+/// The SQL script enabling the trading accounts option of the book.
+const TRADING_OPTION_SQL: &str = include_str!("../fixtures/trading_option.sql");
+
+/// The SQL script adding a bill to the full fixture book.
+const BILL_SQL: &str = include_str!("../fixtures/bill.sql");
+
+/// The SQL script adding a payment to the full fixture book.
+const PAYMENT_SQL: &str = include_str!("../fixtures/payment.sql");
+
+/// A temporary copy of the fixture book.
+pub struct Book {
+    pub dir: TempDir,
+    pub path: PathBuf,
+}
+
+impl Book {
+    /// Creates a new fixture book in a temporary directory.
+    pub fn new() -> Result<Self> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("book.gnucash");
+
+        let connection = Connection::open(&path)?;
+        connection.execute_batch(BOOK_SQL)?;
+
+        Ok(Self { dir, path })
+    }
+
+    /// Creates a new fixture book using every kind of entity.
+    pub fn full() -> Result<Self> {
+        let book = Self::new()?;
+        book.connection()?.execute_batch(FULL_SQL)?;
+        Ok(book)
+    }
+
+    /// Opens a connection to the book.
+    pub fn connection(&self) -> Result<Connection> {
+        Ok(Connection::open(&self.path)?)
+    }
+
+    /// Runs a query returning a single value.
+    pub fn query<T: rusqlite::types::FromSql>(&self, sql: &str) -> Result<T> {
+        query(&self.path, sql)
+    }
+}
+
+/// Returns a `gnc` command working on the given book.
+pub fn gnc(book: &Path) -> Command {
+    let mut cmd = Command::new(cargo_bin("gnc"));
+    cmd.env("NO_COLOR", "true").env("GNUCASH_BOOK", book);
+    cmd
+}
+
+/// Runs a query returning a single value on the book at `path`.
+pub fn query<T: rusqlite::types::FromSql>(path: &Path, sql: &str) -> Result<T> {
+    Ok(Connection::open(path)?.query_row(sql, [], |row| row.get(0))?)
+}
+
+/// Returns the balance of an account of the book at `path`, in cents.
+pub fn balance(path: &Path, account: &str) -> Result<i64> {
+    query(
+        path,
+        &format!(
+            "SELECT COALESCE(SUM(quantity_num), 0) FROM splits
+            WHERE account_guid = '{account}'"
+        ),
+    )
+}
+
+/// Checks the book at `path` is consistent with `gnc check`.
+pub fn check(path: &Path) -> Result<()> {
+    let mut check = gnc(path);
+    check.arg("check");
+    let mut process = spawn_command(check, TIMEOUT)?;
+    process.exp_string("The book is consistent.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+/// Buys 1000 USD for 920 EUR on 2024-01-31, then spends 400 USD for 376 EUR
+/// when the rate is 0.94 on 2024-02-15. The rate is 0.95 on 2024-03-31.
+pub fn add_foreign_currency_transactions(book: &Book) -> Result<()> {
+    book.connection()?.execute_batch(FOREIGN_CURRENCY_SQL)?;
+    Ok(())
+}
+
+/// Adds the trading accounts GnuCash creates when the book option is enabled,
+/// without any split.
+pub fn add_trading_accounts(book: &Book) -> Result<()> {
+    book.connection()?.execute_batch(TRADING_ACCOUNTS_SQL)?;
+    Ok(())
+}
+
+/// Adds the trading accounts and splits GnuCash uses when the book option is
+/// enabled to the foreign currency transactions.
+pub fn add_trading_splits(book: &Book) -> Result<()> {
+    add_trading_accounts(book)?;
+    book.connection()?.execute_batch(TRADING_SPLITS_SQL)?;
+    Ok(())
+}
+
+/// Enables the “Use Trading Accounts” option of the book.
+pub fn enable_trading_accounts(book: &Book) -> Result<()> {
+    book.connection()?.execute_batch(TRADING_OPTION_SQL)?;
+    Ok(())
+}
+
+/// Adds a bill of 50 EUR from Office Supplies, posted on 2024-02-26 and due on
+/// 2024-04-30 from its terms.
+pub fn add_bill(book: &Book) -> Result<()> {
+    book.connection()?.execute_batch(BILL_SQL)?;
+    Ok(())
+}
+
+/// Pays `amount` cents of the ACME invoice on 2024-03-10.
+pub fn pay_invoice(book: &Book, amount: i64) -> Result<()> {
+    let sql = PAYMENT_SQL.replace("{amount}", &amount.to_string());
+    book.connection()?.execute_batch(&sql)?;
+    Ok(())
+}
@@ -0,0 +1,293 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! CLI tests for `gnucash-toolbox convert`.
+
+// NOTE: rexpect is only compatible with Unix-like systems, so let’s just not
+// compile the CLI tests on Windows.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::pedantic, clippy::restriction)]
+
+mod common;
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use eyre::Result;
+use flate2::read::GzDecoder;
+use rexpect::session::spawn_command;
+use rusqlite::{types::Value, Connection};
+
+use self::common::{gnc, Book, TIMEOUT};
+
+/// The tables holding the entities of a book.
+const TABLES: &[&str] = &[
+    "books",
+    "commodities",
+    "accounts",
+    "transactions",
+    "splits",
+    "prices",
+    "lots",
+    "budgets",
+    "budget_amounts",
+    "recurrences",
+    "schedxactions",
+    "billterms",
+    "taxtables",
+    "taxtable_entries",
+    "customers",
+    "vendors",
+    "employees",
+    "jobs",
+    "invoices",
+    "entries",
+    "orders",
+];
+
+/// The columns of the slots table, with frame and list GUIDs blanked.
+///
+/// The GUIDs linking frames and lists to their content are internal to the
+/// database and are regenerated on conversion.
+const SLOT_COLUMNS: &str = "
+    CASE WHEN obj_guid IN (SELECT guid_val FROM slots) THEN ''
+        ELSE obj_guid END,
+    name, slot_type, int64_val, string_val, double_val, timespec_val,
+    CASE WHEN slot_type IN (8, 9) THEN '' ELSE guid_val END,
+    numeric_val_num, numeric_val_denom, gdate_val";
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  Helpers                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+fn gnucash_toolbox_convert(input: &Path, output: &Path, to: &str) -> Command {
+    let mut cmd = gnc(input);
+    cmd.arg("convert").arg(input).arg(output).args(["--to", to]);
+    cmd
+}
+
+fn convert(input: &Path, to: &str) -> Result<PathBuf> {
+    let output = input.with_extension(format!("{to}.gnucash"));
+    let result = gnucash_toolbox_convert(input, &output, to).output()?;
+    assert!(result.status.success());
+    Ok(output)
+}
+
+fn decompress(path: &Path) -> Result<String> {
+    let mut xml = String::new();
+    GzDecoder::new(fs::File::open(path)?).read_to_string(&mut xml)?;
+    Ok(xml)
+}
+
+/// Returns the content of a book, table by table.
+///
+/// Commodities have no GUID in XML books, so their GUIDs are replaced by their
+/// name to compare books converted from XML. Auto-incremented IDs are local to
+/// the database and are left out.
+fn content(path: &Path) -> Result<Vec<(String, Vec<String>)>> {
+    let connection = Connection::open(path)?;
+
+    let commodities = connection
+        .prepare("SELECT guid, namespace || ':' || mnemonic FROM commodities")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<rusqlite::Result<HashMap<String, String>>>()?;
+
+    let column = |value| match value {
+        Value::Null => "NULL".to_owned(),
+        Value::Integer(integer) => integer.to_string(),
+        Value::Real(real) => real.to_string(),
+        Value::Text(text) => commodities.get(&text).cloned().unwrap_or(text),
+        Value::Blob(blob) => format!("{blob:?}"),
+    };
+
+    let queries = TABLES
+        .iter()
+        .map(|table| (table.to_string(), format!("SELECT * FROM {table}")))
+        .chain([(
+            "slots".to_owned(),
+            format!("SELECT {SLOT_COLUMNS} FROM slots"),
+        )]);
+
+    queries
+        .map(|(table, query)| {
+            let mut statement = connection.prepare(&query)?;
+            let skip_id = statement.column_name(0)? == "id";
+
+            let mut rows = statement
+                .query_map([], |row| {
+                    let columns = (usize::from(skip_id)
+                        ..row.as_ref().column_count())
+                        .map(|index| row.get(index).map(column))
+                        .collect::<rusqlite::Result<Vec<_>>>()?;
+                    Ok(columns.join("|"))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()?;
+
+            rows.sort();
+            Ok((table, rows))
+        })
+        .collect()
+}
+
+fn assert_same_books(expected: &Path, actual: &Path) -> Result<()> {
+    for ((table, expected), (_, actual)) in
+        content(expected)?.into_iter().zip(content(actual)?)
+    {
+        assert_eq!(expected, actual, "{table} differ");
+    }
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  convert                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn converts_a_sqlite_book_to_xml() -> Result<()> {
+    let book = Book::new()?;
+    let output = book.dir.path().join("book.xml.gnucash");
+
+    let mut process = spawn_command(
+        gnucash_toolbox_convert(&book.path, &output, "xml"),
+        TIMEOUT,
+    )?;
+    process.exp_string(&format!(
+        "The book has been converted to {}.",
+        output.display()
+    ))?;
+    process.exp_eof()?;
+
+    let xml = decompress(&output)?;
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"utf-8\" ?>\n"));
+    assert!(xml.contains(
+        "<act:name>Checking</act:name>\n      \
+        <act:id type=\"guid\">a0000000000000000000000000000003</act:id>"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn converting_to_sqlite_is_lossless() -> Result<()> {
+    let book = Book::full()?;
+
+    let output = convert(&book.path, "sqlite")?;
+
+    assert_same_books(&book.path, &output)
+}
+
+#[test]
+fn converting_to_xml_and_back_is_lossless() -> Result<()> {
+    let book = Book::full()?;
+
+    let xml = convert(&book.path, "xml")?;
+    let sqlite = convert(&xml, "sqlite")?;
+
+    assert_same_books(&book.path, &sqlite)
+}
+
+#[test]
+fn converting_from_xml_to_xml_is_lossless() -> Result<()> {
+    let book = Book::full()?;
+
+    let xml = convert(&book.path, "xml")?;
+    let sqlite = convert(&xml, "sqlite")?;
+    let again = convert(&sqlite, "xml")?;
+
+    assert_eq!(decompress(&xml)?, decompress(&again)?);
+
+    Ok(())
+}
+
+#[test]
+fn reads_uncompressed_xml_books() -> Result<()> {
+    let book = Book::full()?;
+
+    let xml = convert(&book.path, "xml")?;
+    let uncompressed = book.dir.path().join("uncompressed.gnucash");
+    fs::write(&uncompressed, decompress(&xml)?)?;
+    let sqlite = convert(&uncompressed, "sqlite")?;
+
+    assert_same_books(&book.path, &sqlite)
+}
+
+#[test]
+fn refuses_to_overwrite_an_existing_book() -> Result<()> {
+    let book = Book::new()?;
+    let output = book.dir.path().join("existing.gnucash");
+    fs::write(&output, "existing")?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_convert(&book.path, &output, "sqlite"),
+        TIMEOUT,
+    )?;
+    process
+        .exp_string(&format!("Error: {} already exists.", output.display()))?;
+    process.exp_string("Use --force to overwrite it.")?;
+    process.exp_eof()?;
+
+    assert_eq!(fs::read_to_string(&output)?, "existing");
+
+    Ok(())
+}
+
+#[test]
+fn overwrites_an_existing_book_with_force() -> Result<()> {
+    let book = Book::new()?;
+    let output = book.dir.path().join("existing.gnucash");
+    fs::write(&output, "existing")?;
+
+    let mut command = gnucash_toolbox_convert(&book.path, &output, "sqlite");
+    command.arg("--force");
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_eof()?;
+
+    assert_same_books(&book.path, &output)?;
+
+    // The new book is written to a temporary file renamed over the old one.
+    let files = fs::read_dir(book.dir.path())?
+        .map(|entry| Ok(entry?.file_name()))
+        .collect::<Result<Vec<_>>>()?;
+    assert!(!files
+        .iter()
+        .any(|file| file.to_string_lossy().ends_with(".tmp")));
+
+    Ok(())
+}
+
+#[test]
+fn reports_files_that_are_not_books() -> Result<()> {
+    let book = Book::new()?;
+    let input = book.dir.path().join("notes.txt");
+    fs::write(&input, "Not a book")?;
+    let output = book.dir.path().join("output.gnucash");
+
+    let mut process = spawn_command(
+        gnucash_toolbox_convert(&input, &output, "sqlite"),
+        TIMEOUT,
+    )?;
+    process.exp_string("Error: the XML document is not well-formed.")?;
+    process.exp_eof()?;
+
+    assert!(!output.exists());
+
+    Ok(())
+}
@@ -0,0 +1,375 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! CLI tests for `gnucash-toolbox customer`.
+
+// NOTE: rexpect is only compatible with Unix-like systems, so let’s just not
+// compile the CLI tests on Windows.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::pedantic, clippy::restriction)]
+
+mod common;
+
+use std::{fs, process::Command};
+
+use eyre::Result;
+use indoc::indoc;
+use rexpect::session::spawn_command;
+
+use self::common::{gnc, Book, TIMEOUT};
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  Helpers                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+fn gnucash_toolbox_customer(book: &Book, args: &[&str]) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.arg("customer").args(args);
+    cmd
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                               customer list                                //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn lists_the_customers() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_customer(&book, &["list"]), TIMEOUT)?;
+    process
+        .exp_string("ID      Name  Currency  Email                 Active")?;
+    process.exp_string("000001  ACME  EUR       billing@acme.example  yes")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                               customer show                                //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn shows_a_customer() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_customer(&book, &["show", "000001"]),
+        TIMEOUT,
+    )?;
+    process.exp_string("Name          ACME")?;
+    process.exp_string("Terms         Net 30")?;
+    process.exp_string("Tax table     VAT 20%")?;
+    process.exp_string("Notes         Our first customer")?;
+    process.exp_string("Address 1     1 Main Street")?;
+    process.exp_string("Email         billing@acme.example")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                customer add                                //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn adds_a_customer_with_the_next_id() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_customer(
+            &book,
+            &[
+                "add",
+                "--currency",
+                "EUR",
+                "--terms",
+                "EOM",
+                "--credit-limit",
+                "5000",
+                "--email",
+                "contact@globex.example",
+                "Globex",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process
+        .exp_string("The customer Globex has been added with the ID 000002.")?;
+    process.exp_eof()?;
+
+    let customer: (String, String, i64, i64, String, String) =
+        book.connection()?.query_row(
+            "SELECT id, currency, active, credit_num, addr_email, addr_phone
+            FROM customers WHERE name = 'Globex'",
+            [],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                    row.get(5)?,
+                ))
+            },
+        )?;
+    assert_eq!(
+        customer,
+        (
+            "000002".to_owned(),
+            "c0000000000000000000000000000001".to_owned(),
+            1,
+            5000,
+            "contact@globex.example".to_owned(),
+            String::new(),
+        )
+    );
+
+    assert_eq!(
+        book.query::<i64>(
+            "SELECT refcount FROM billterms
+            WHERE guid = 'b3000000000000000000000000000002'"
+        )?,
+        1
+    );
+
+    Ok(())
+}
+
+#[test]
+fn adds_a_customer_in_the_main_currency_of_the_book() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_customer(&book, &["add", "Globex"]),
+        TIMEOUT,
+    )?;
+    process
+        .exp_string("The customer Globex has been added with the ID 000002.")?;
+    process.exp_eof()?;
+
+    let currency: String =
+        book.query("SELECT currency FROM customers WHERE name = 'Globex'")?;
+    assert_eq!(currency, "c0000000000000000000000000000001");
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                               customer edit                                //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn edits_a_customer() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_customer(
+            &book,
+            &["edit", "ACME", "--active", "false", "--tax-table", ""],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("The customer ACME has been updated.")?;
+    process.exp_eof()?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_customer(&book, &["list"]), TIMEOUT)?;
+    process.exp_string("There is no customer in the book.")?;
+    process.exp_eof()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_customer(&book, &["list", "--all"]),
+        TIMEOUT,
+    )?;
+    process.exp_string("000001  ACME  EUR       billing@acme.example  no")?;
+    process.exp_eof()?;
+
+    assert_eq!(
+        book.query::<i64>(
+            "SELECT refcount FROM taxtables
+            WHERE guid = 'b4000000000000000000000000000001'"
+        )?,
+        0
+    );
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_change_the_currency_of_a_customer_with_documents() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_customer(&book, &["edit", "ACME", "--currency", "USD"]),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "Error: the currency of ACME cannot change as it has documents.",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                              customer export                               //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn exports_the_customers_to_csv() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_customer(&book, &["export"]), TIMEOUT)?;
+    process.exp_string(
+        "id,name,active,currency,terms,tax_table,credit_limit,notes,\
+        address_name,address1,address2,address3,address4,phone,fax,email",
+    )?;
+    process.exp_string(
+        "000001,ACME,true,EUR,Net 30,VAT 20%,0,Our first customer,ACME Corp,\
+        1 Main Street,75001 Paris,,,+33 1 23 45 67 89,,billing@acme.example",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                              customer import                               //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn imports_customers_from_csv() -> Result<()> {
+    let book = Book::full()?;
+    let file = book.dir.path().join("customers.csv");
+    fs::write(
+        &file,
+        indoc! {r#"
+            id,name,currency,phone
+            000001,ACME,EUR,+33 9 87 65 43 21
+            ,"Globex, Inc.",USD,
+        "#},
+    )?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_customer(&book, &["import", file.to_str().unwrap()]),
+        TIMEOUT,
+    )?;
+    process.exp_string("1 customers added, 1 updated.")?;
+    process.exp_eof()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_customer(&book, &["show", "Globex, Inc."]),
+        TIMEOUT,
+    )?;
+    process.exp_string("ID            000002")?;
+    process.exp_string("Currency      USD")?;
+    process.exp_eof()?;
+
+    assert_eq!(
+        book.query::<String>(
+            "SELECT addr_phone FROM customers
+            WHERE guid = 'cc000000000000000000000000000001'"
+        )?,
+        "+33 9 87 65 43 21"
+    );
+    assert_eq!(
+        book.query::<String>(
+            "SELECT addr_email FROM customers
+            WHERE guid = 'cc000000000000000000000000000001'"
+        )?,
+        "billing@acme.example"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn imports_customers_without_a_currency_column() -> Result<()> {
+    let book = Book::full()?;
+    let file = book.dir.path().join("customers.csv");
+    fs::write(&file, "name,email\nGlobex,contact@globex.example\n")?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_customer(&book, &["import", file.to_str().unwrap()]),
+        TIMEOUT,
+    )?;
+    process.exp_string("1 customers added, 0 updated.")?;
+    process.exp_eof()?;
+
+    let currency: String =
+        book.query("SELECT currency FROM customers WHERE name = 'Globex'")?;
+    assert_eq!(currency, "c0000000000000000000000000000001");
+
+    Ok(())
+}
+
+#[test]
+fn checks_an_import_without_modifying_the_book() -> Result<()> {
+    let book = Book::full()?;
+    let file = book.dir.path().join("customers.csv");
+    fs::write(&file, "name,currency\nGlobex,EUR\n")?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_customer(
+            &book,
+            &["import", "--dry-run", file.to_str().unwrap()],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("1 customers to add, 0 to update.")?;
+    process.exp_eof()?;
+
+    assert_eq!(book.query::<i64>("SELECT COUNT(*) FROM customers")?, 1);
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_import_an_unknown_column() -> Result<()> {
+    let book = Book::full()?;
+    let file = book.dir.path().join("customers.csv");
+    fs::write(&file, "id,nom\n000001,ACME\n")?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_customer(&book, &["import", file.to_str().unwrap()]),
+        TIMEOUT,
+    )?;
+    process.exp_string("Error: unknown column: nom.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn tells_on_which_line_an_import_fails() -> Result<()> {
+    let book = Book::full()?;
+    let file = book.dir.path().join("customers.csv");
+    fs::write(&file, "name,currency\nGlobex,EUR\nInitech,XYZ\n")?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_customer(&book, &["import", file.to_str().unwrap()]),
+        TIMEOUT,
+    )?;
+    process.exp_string("Error: line 3: unknown currency: XYZ.")?;
+    process.exp_eof()?;
+
+    assert_eq!(book.query::<i64>("SELECT COUNT(*) FROM customers")?, 1);
+
+    Ok(())
+}
@@ -0,0 +1,94 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! CLI tests for `gnucash-toolbox employee`.
+
+// NOTE: rexpect is only compatible with Unix-like systems, so let’s just not
+// compile the CLI tests on Windows.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::pedantic, clippy::restriction)]
+
+mod common;
+
+use std::process::Command;
+
+use eyre::Result;
+use rexpect::session::spawn_command;
+
+use self::common::{gnc, Book, TIMEOUT};
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  Helpers                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+fn gnucash_toolbox_employee(book: &Book, args: &[&str]) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.arg("employee").args(args);
+    cmd
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                employee add                                //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn adds_an_employee() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_employee(
+            &book,
+            &[
+                "add",
+                "--currency",
+                "EUR",
+                "--address-name",
+                "Jane Smith",
+                "jsmith",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process
+        .exp_string("The employee jsmith has been added with the ID 000002.")?;
+    process.exp_eof()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_employee(&book, &["show", "jsmith"]),
+        TIMEOUT,
+    )?;
+    process.exp_string("ID            000002")?;
+    process.exp_string("Name          jsmith")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_give_billing_terms_to_an_employee() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_employee(
+            &book,
+            &["add", "--currency", "EUR", "--terms", "Net 30", "jsmith"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("Error: employees have no terms.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
@@ -0,0 +1,402 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! CLI tests for `gnucash-toolbox export`.
+
+// NOTE: rexpect is only compatible with Unix-like systems, so let’s just not
+// compile the CLI tests on Windows.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::pedantic, clippy::restriction)]
+
+mod common;
+
+use std::{fs, process::Command};
+
+use eyre::Result;
+use indoc::indoc;
+use rexpect::session::spawn_command;
+
+use self::common::{gnc, Book, TIMEOUT};
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  Helpers                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+fn gnucash_toolbox_export(book: &Book, format: &str) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.args(["export", format]);
+    cmd
+}
+
+fn buy_aapl(book: &Book) -> Result<()> {
+    let output = gnc(&book.path)
+        .args([
+            "tx",
+            "add",
+            "--date",
+            "2024-02-03",
+            "--description",
+            "Buy AAPL",
+            "Assets:Broker:AAPL=10@150.25",
+            "Assets:Bank:Checking=-1502.50",
+        ])
+        .output()?;
+
+    assert!(output.status.success());
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                               export ledger                                //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn exports_declarations_and_prices_to_ledger() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_export(&book, "ledger"), TIMEOUT)?;
+    process.exp_string("commodity AAPL\r\n    format 1000.0000 AAPL")?;
+    process.exp_string(
+        "account Assets:Bank:Checking  ; type:A, code:111\r\n    \
+        ; Main checking account",
+    )?;
+    process.exp_string("account Liabilities:Credit Card  ; type:L, code:21")?;
+    process.exp_string("P 2024-01-31 AAPL 180.50 EUR")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn exports_transactions_to_ledger() -> Result<()> {
+    let book = Book::new()?;
+    buy_aapl(&book)?;
+
+    let output = book.dir.path().join("book.journal");
+    let mut command = gnucash_toolbox_export(&book, "ledger");
+    command.arg("--output").arg(&output);
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_eof()?;
+
+    let journal = fs::read_to_string(output)?;
+
+    assert!(journal.contains(indoc! {"
+        2024-01-05 Salary
+            * Assets:Bank:Checking  2500.00 EUR
+            Income:Salary           -2500.00 EUR
+                ; January
+    "}));
+    assert!(journal.contains(indoc! {"
+        2024-01-10 Groceries
+            ; Weekly shopping
+            Expenses:Food           42.50 EUR
+            * Assets:Bank:Checking  -42.50 EUR
+    "}));
+    assert!(journal.contains("2024-01-15 (101) Rent\n"));
+    assert!(journal
+        .contains("    Assets:Broker:AAPL    10.0000 AAPL @@ 1502.50 EUR\n"));
+
+    Ok(())
+}
+
+#[test]
+fn keeps_multi_line_texts_out_of_the_ledger_syntax() -> Result<()> {
+    let book = Book::new()?;
+
+    book.connection()?.execute_batch(
+        "UPDATE transactions SET description = 'Salary' || char(10) || 'bonus'
+        WHERE description = 'Salary';
+        UPDATE splits SET memo = 'January' || char(10) || 'note: paid late'
+        WHERE memo = 'January';",
+    )?;
+
+    let output = book.dir.path().join("book.journal");
+    let mut command = gnucash_toolbox_export(&book, "ledger");
+    command.arg("--output").arg(&output);
+    spawn_command(command, TIMEOUT)?.exp_eof()?;
+
+    let journal = fs::read_to_string(output)?;
+
+    assert!(journal.contains(indoc! {"
+        2024-01-05 Salary bonus
+            * Assets:Bank:Checking  2500.00 EUR
+            Income:Salary           -2500.00 EUR
+                ; January
+                ; note: paid late
+    "}));
+
+    Ok(())
+}
+
+#[test]
+fn signs_total_prices_opposite_to_their_quantity() -> Result<()> {
+    let book = Book::new()?;
+    buy_aapl(&book)?;
+
+    // A split whose value and quantity have opposite signs.
+    book.connection()?.execute(
+        "UPDATE splits SET quantity_num = -quantity_num
+        WHERE account_guid = 'a0000000000000000000000000000007'",
+        [],
+    )?;
+
+    let output = book.dir.path().join("book.journal");
+    let mut command = gnucash_toolbox_export(&book, "ledger");
+    command.arg("--output").arg(&output);
+    spawn_command(command, TIMEOUT)?.exp_eof()?;
+
+    let journal = fs::read_to_string(output)?;
+
+    assert!(journal.contains(
+        "    Assets:Broker:AAPL    -10.0000 AAPL @@ -1502.50 EUR
+"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn exported_journals_can_be_applied_again() -> Result<()> {
+    let book = Book::new()?;
+
+    let output = book.dir.path().join("book.journal");
+    let mut command = gnucash_toolbox_export(&book, "ledger");
+    command.arg("--output").arg(&output);
+    spawn_command(command, TIMEOUT)?.exp_eof()?;
+
+    let other = Book::new()?;
+    other
+        .connection()?
+        .execute_batch("DELETE FROM splits; DELETE FROM transactions;")?;
+
+    let mut command = gnc(&other.path);
+    command.args(["tx", "apply"]).arg(&output);
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string("4 transactions added, 0 already in the book.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                              export beancount                              //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn exports_open_directives_and_prices_to_beancount() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_export(&book, "beancount"), TIMEOUT)?;
+    process.exp_string("option \"operating_currency\" \"EUR\"")?;
+    process.exp_string("2024-01-01 open Assets:Bank:Checking EUR")?;
+    process.exp_string("2024-01-01 open Assets:Bank:US-Checking USD")?;
+    process.exp_string("2024-01-31 price AAPL 180.50 EUR")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn exports_transactions_to_beancount() -> Result<()> {
+    let book = Book::new()?;
+    buy_aapl(&book)?;
+
+    let output = book.dir.path().join("book.beancount");
+    let mut command = gnucash_toolbox_export(&book, "beancount");
+    command.arg("--output").arg(&output);
+    spawn_command(command, TIMEOUT)?.exp_eof()?;
+
+    let ledger = fs::read_to_string(output)?;
+
+    assert!(ledger.contains(indoc! {r#"
+        2024-01-10 * "Groceries"
+          notes: "Weekly shopping"
+          Expenses:Food  42.50 EUR
+          Assets:Bank:Checking  -42.50 EUR
+    "#}));
+    assert!(ledger.contains(indoc! {r#"
+        2024-02-03 * "Buy AAPL"
+          Assets:Broker:AAPL  10.0000 AAPL {{1502.50 EUR}}
+          Assets:Bank:Checking  -1502.50 EUR
+    "#}));
+
+    Ok(())
+}
+
+#[test]
+fn exports_partial_sales_of_lots_to_beancount() -> Result<()> {
+    let book = Book::new()?;
+    buy_aapl(&book)?;
+
+    let output = gnc(&book.path)
+        .args([
+            "tx",
+            "add",
+            "--date",
+            "2024-02-20",
+            "--description",
+            "Sell AAPL",
+            "Assets:Broker:AAPL=-4@200",
+            "Assets:Bank:Checking=800",
+        ])
+        .output()?;
+    assert!(output.status.success());
+
+    let output = book.dir.path().join("book.beancount");
+    let mut command = gnucash_toolbox_export(&book, "beancount");
+    command.arg("--output").arg(&output);
+    spawn_command(command, TIMEOUT)?.exp_eof()?;
+
+    let ledger = fs::read_to_string(output)?;
+
+    assert!(ledger.contains(indoc! {r#"
+        2024-02-20 * "Sell AAPL"
+          Assets:Broker:AAPL  -4.0000 AAPL {} @@ 800.00 EUR
+          Assets:Bank:Checking  800.00 EUR
+    "#}));
+
+    Ok(())
+}
+
+#[test]
+fn exports_balance_assertions_at_reconcile_dates() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_export(&book, "beancount"), TIMEOUT)?;
+    process
+        .exp_string("2024-02-01 balance Assets:Bank:Checking 2657.50 EUR")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn includes_descendants_in_balance_assertions() -> Result<()> {
+    let book = Book::new()?;
+    book.connection()?.execute(
+        "UPDATE accounts SET placeholder = 0 WHERE name = 'Bank'",
+        [],
+    )?;
+
+    let output = gnc(&book.path)
+        .args([
+            "tx",
+            "add",
+            "--date",
+            "2024-02-10",
+            "--description",
+            "Bonus",
+            "Assets:Bank=100",
+            "Income:Salary=-100",
+        ])
+        .output()?;
+    assert!(output.status.success());
+
+    // The end of 2024-02-10 in New York.
+    book.connection()?.execute(
+        "UPDATE splits SET reconcile_state = 'y',
+            reconcile_date = '2024-02-11 04:59:59'
+        WHERE account_guid = 'a0000000000000000000000000000002'",
+        [],
+    )?;
+
+    let mut command = gnucash_toolbox_export(&book, "beancount");
+    command.env("TZ", "America/New_York");
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process
+        .exp_string("2024-02-01 balance Assets:Bank:Checking 2657.50 EUR")?;
+    process.exp_string("2024-02-11 balance Assets:Bank 2757.50 EUR")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn excludes_accounts_moved_to_another_root_from_balance_assertions(
+) -> Result<()> {
+    let book = Book::new()?;
+    book.connection()?.execute_batch(
+        "UPDATE accounts SET placeholder = 0 WHERE name = 'Bank';
+        UPDATE accounts SET account_type = 'LIABILITY' WHERE name = 'Savings';",
+    )?;
+
+    for (account, amount) in [("Assets:Bank", 100), ("Assets:Bank:Savings", 50)]
+    {
+        let output = gnc(&book.path)
+            .args(["tx", "add", "--date", "2024-02-10", "--description"])
+            .arg(account)
+            .arg(format!("{account}={amount}"))
+            .arg(format!("Income:Salary=-{amount}"))
+            .output()?;
+        assert!(output.status.success());
+    }
+
+    book.connection()?.execute(
+        "UPDATE splits SET reconcile_state = 'y',
+            reconcile_date = '2024-02-11 04:59:59'
+        WHERE account_guid = 'a0000000000000000000000000000002'",
+        [],
+    )?;
+
+    let mut command = gnucash_toolbox_export(&book, "beancount");
+    command.env("TZ", "America/New_York");
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string("open Liabilities:Assets:Bank:Savings EUR")?;
+    process.exp_string("2024-02-11 balance Assets:Bank 2757.50 EUR")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn does_not_export_the_template_commodity_to_beancount() -> Result<()> {
+    let book = Book::full()?;
+
+    let output = book.dir.path().join("book.beancount");
+    let mut command = gnucash_toolbox_export(&book, "beancount");
+    command.arg("--output").arg(&output);
+    spawn_command(command, TIMEOUT)?.exp_eof()?;
+
+    let ledger = fs::read_to_string(output)?;
+
+    assert!(ledger.contains("commodity EUR"));
+    assert!(!ledger.contains("TEMPLATE"));
+
+    Ok(())
+}
+
+#[test]
+fn reports_renamed_accounts() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_export(&book, "beancount"), TIMEOUT)?;
+    process.exp_string(
+        "Some names have been changed to follow the Beancount rules:\r\n  \
+        Assets:Bank:US Checking -> Assets:Bank:US-Checking\r\n  \
+        Equity:Opening Balances -> Equity:Opening-Balances\r\n  \
+        Liabilities:Credit Card -> Liabilities:Credit-Card",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
@@ -0,0 +1,11 @@
+-- Bill added to the fixture book of full.sql.
+--
+-- Office Supplies bills 50 EUR on 2024-02-26, posted to A/P without a stored
+-- due date: its terms make it due on 2024-04-30.
+
+INSERT INTO lots VALUES ('60000000000000000000000000000003', 'a0000000000000000000000000000012', 0);
+INSERT INTO transactions VALUES ('70000000000000000000000000000010', 'c0000000000000000000000000000001', 'B-001', '2024-02-26 10:59:00', '2024-02-26 12:00:00', 'Office Supplies');
+INSERT INTO splits VALUES ('50000000000000000000000000000101', '70000000000000000000000000000010', 'a0000000000000000000000000000012', '', 'Bill', 'n', NULL, -5000, 100, -5000, 100, '60000000000000000000000000000003');
+INSERT INTO splits VALUES ('50000000000000000000000000000102', '70000000000000000000000000000010', 'a0000000000000000000000000000031', 'Paper', '', 'n', NULL, 5000, 100, 5000, 100, NULL);
+INSERT INTO invoices VALUES ('d0000000000000000000000000000003', 'B-001', '2024-02-26 10:59:00', '2024-02-26 10:59:00', '', 1, 'c0000000000000000000000000000001', 4, 'dd000000000000000000000000000001', 'b3000000000000000000000000000002', '', '70000000000000000000000000000010', '60000000000000000000000000000003', 'a0000000000000000000000000000012', NULL, NULL, 0, 1);
+INSERT INTO entries (guid, date, description, quantity_num, quantity_denom, i_taxable, i_taxincluded, b_acct, b_price_num, b_price_denom, bill, b_taxable, b_taxincluded, billable) VALUES ('e2000000000000000000000000000003', '2024-02-26 10:59:00', 'Paper', 1, 1, 0, 0, 'a0000000000000000000000000000031', 50, 1, 'd0000000000000000000000000000003', 0, 0, 0);
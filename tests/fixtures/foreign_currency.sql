@@ -0,0 +1,13 @@
+-- Foreign currency transactions added to the fixture book of book.sql.
+--
+-- 1000 USD are bought for 920 EUR on 2024-01-31, then 400 USD are spent when
+-- the rate is 0.94 on 2024-02-15. The rate is 0.95 on 2024-03-31.
+
+INSERT INTO prices VALUES ('90000000000000000000000000000011', 'c0000000000000000000000000000002', 'c0000000000000000000000000000001', '2024-02-15 10:59:00', 'user:price-editor', 'last', 94, 100);
+INSERT INTO prices VALUES ('90000000000000000000000000000012', 'c0000000000000000000000000000002', 'c0000000000000000000000000000001', '2024-03-31 10:59:00', 'user:price-editor', 'last', 95, 100);
+INSERT INTO transactions VALUES ('70000000000000000000000000000020', 'c0000000000000000000000000000001', '', '2024-01-31 10:59:00', '2024-01-31 12:00:00', 'Buy USD');
+INSERT INTO splits VALUES ('50000000000000000000000000000201', '70000000000000000000000000000020', 'a0000000000000000000000000000003', '', '', 'n', NULL, -92000, 100, -92000, 100, NULL);
+INSERT INTO splits VALUES ('50000000000000000000000000000202', '70000000000000000000000000000020', 'a0000000000000000000000000000005', '', '', 'n', NULL, 92000, 100, 100000, 100, NULL);
+INSERT INTO transactions VALUES ('70000000000000000000000000000021', 'c0000000000000000000000000000002', '', '2024-02-15 10:59:00', '2024-02-15 12:00:00', 'Dinner in New York');
+INSERT INTO splits VALUES ('50000000000000000000000000000211', '70000000000000000000000000000021', 'a0000000000000000000000000000005', '', '', 'n', NULL, -40000, 100, -40000, 100, NULL);
+INSERT INTO splits VALUES ('50000000000000000000000000000212', '70000000000000000000000000000021', 'a0000000000000000000000000000031', '', '', 'n', NULL, 40000, 100, 37600, 100, NULL);
@@ -0,0 +1,111 @@
+-- Additional data completing the fixture book of book.sql.
+--
+-- This script is run after book.sql to get a book using every entity and slot
+-- type supported by gnucash-toolbox: lots, budgets, scheduled transactions,
+-- business objects and slots of every type.
+
+-- Commodities
+
+INSERT INTO commodities VALUES ('c00000000000000000000000000000ff', 'template', 'template', 'template', 'template', 1, 0, NULL, NULL);
+
+-- Accounts
+
+INSERT INTO accounts VALUES ('a000000000000000000000000000000c', 'Accounts Receivable', 'RECEIVABLE', 'c0000000000000000000000000000001', 100, 0, 'a0000000000000000000000000000001', '13', '', 0, 0);
+INSERT INTO accounts VALUES ('a000000000000000000000000000000d', 'Old Account', 'BANK', 'c0000000000000000000000000000001', 100, 0, 'a0000000000000000000000000000002', '119', 'Closed in 2020', 1, 0);
+INSERT INTO accounts VALUES ('a0000000000000000000000000000012', 'Accounts Payable', 'PAYABLE', 'c0000000000000000000000000000001', 100, 0, 'a0000000000000000000000000000010', '22', '', 0, 0);
+INSERT INTO accounts VALUES ('a0000000000000000000000000000013', 'VAT', 'LIABILITY', 'c0000000000000000000000000000001', 100, 0, 'a0000000000000000000000000000010', '23', '', 0, 0);
+INSERT INTO accounts VALUES ('a0000000000000000000000000000022', 'Sales', 'INCOME', 'c0000000000000000000000000000001', 100, 0, 'a0000000000000000000000000000020', '42', '', 0, 0);
+INSERT INTO accounts VALUES ('a00000000000000000000000000000f1', 'e0000000000000000000000000000001', 'BANK', 'c00000000000000000000000000000ff', 1, 0, 'a00000000000000000000000000000ff', '', '', 0, 0);
+
+-- Slots of every type
+
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('b0000000000000000000000000000001', 'options', 9, 0, NULL, 0.0, NULL, 'f0000000000000000000000000000001', 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('f0000000000000000000000000000001', 'options/Business', 9, 0, NULL, 0.0, NULL, 'f0000000000000000000000000000002', 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('f0000000000000000000000000000002', 'options/Business/Company Name', 4, 0, 'ACME Consulting', 0.0, NULL, NULL, 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('f0000000000000000000000000000001', 'options/Budgeting', 9, 0, NULL, 0.0, NULL, 'f0000000000000000000000000000003', 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('f0000000000000000000000000000003', 'options/Budgeting/Default Budget', 5, 0, NULL, 0.0, NULL, 'b5000000000000000000000000000001', 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('b0000000000000000000000000000001', 'counters', 8, 0, NULL, 0.0, NULL, 'f0000000000000000000000000000004', 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('f0000000000000000000000000000004', 'counters', 1, 42, NULL, 0.0, NULL, NULL, 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('f0000000000000000000000000000004', 'counters', 3, 0, NULL, 0.0, NULL, NULL, 3, 2, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('a0000000000000000000000000000003', 'reconcile-info', 9, 0, NULL, 0.0, NULL, 'f0000000000000000000000000000005', 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('f0000000000000000000000000000005', 'reconcile-info/last-date', 1, 1706745599, NULL, 0.0, NULL, NULL, 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('a0000000000000000000000000000003', 'color', 4, 0, '#1e90ff', 0.0, NULL, NULL, 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('a0000000000000000000000000000007', 'price-ratio', 2, 0, NULL, 1.5, NULL, NULL, 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('c0000000000000000000000000000003', 'user_symbol', 4, 0, '', 0.0, NULL, NULL, 0, 1, NULL);
+
+-- Lots
+
+INSERT INTO lots VALUES ('60000000000000000000000000000001', 'a0000000000000000000000000000007', 0);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('60000000000000000000000000000001', 'title', 4, 0, 'Lot 1', 0.0, NULL, NULL, 0, 1, NULL);
+INSERT INTO lots VALUES ('60000000000000000000000000000002', 'a000000000000000000000000000000c', 0);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('60000000000000000000000000000002', 'gncInvoice', 9, 0, NULL, 0.0, NULL, 'f0000000000000000000000000000006', 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('f0000000000000000000000000000006', 'gncInvoice/invoice-guid', 5, 0, NULL, 0.0, NULL, 'd0000000000000000000000000000001', 0, 1, NULL);
+
+-- Transactions
+
+INSERT INTO transactions VALUES ('70000000000000000000000000000005', 'c0000000000000000000000000000001', '', '2024-01-20 10:59:00', '2024-01-20 12:00:00', 'Buy AAPL');
+INSERT INTO splits VALUES ('50000000000000000000000000000051', '70000000000000000000000000000005', 'a0000000000000000000000000000007', '', 'Buy', 'n', NULL, 180500, 100, 100000, 10000, '60000000000000000000000000000001');
+INSERT INTO splits VALUES ('50000000000000000000000000000052', '70000000000000000000000000000005', 'a0000000000000000000000000000003', '', '', 'n', NULL, -180500, 100, -180500, 100, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('70000000000000000000000000000005', 'date-posted', 10, 0, NULL, 0.0, NULL, NULL, 0, 1, '20240120');
+
+INSERT INTO transactions VALUES ('70000000000000000000000000000006', 'c0000000000000000000000000000001', '000001', '2024-02-01 10:59:00', '2024-02-01 12:00:00', 'ACME');
+INSERT INTO splits VALUES ('50000000000000000000000000000061', '70000000000000000000000000000006', 'a000000000000000000000000000000c', '', 'Invoice', 'n', NULL, 120000, 100, 120000, 100, '60000000000000000000000000000002');
+INSERT INTO splits VALUES ('50000000000000000000000000000062', '70000000000000000000000000000006', 'a0000000000000000000000000000022', 'Consulting', 'Hours', 'n', NULL, -100000, 100, -100000, 100, NULL);
+INSERT INTO splits VALUES ('50000000000000000000000000000063', '70000000000000000000000000000006', 'a0000000000000000000000000000013', '', '', 'n', NULL, -20000, 100, -20000, 100, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('70000000000000000000000000000006', 'date-posted', 10, 0, NULL, 0.0, NULL, NULL, 0, 1, '20240201');
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('70000000000000000000000000000006', 'trans-txn-type', 4, 0, 'I', 0.0, NULL, NULL, 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('70000000000000000000000000000006', 'trans-date-due', 6, 0, NULL, 0.0, '2024-03-02 10:59:00', NULL, 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('70000000000000000000000000000006', 'gncInvoice', 9, 0, NULL, 0.0, NULL, 'f0000000000000000000000000000007', 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('f0000000000000000000000000000007', 'gncInvoice/invoice-guid', 5, 0, NULL, 0.0, NULL, 'd0000000000000000000000000000001', 0, 1, NULL);
+
+-- Prices
+
+INSERT INTO prices VALUES ('90000000000000000000000000000003', 'c0000000000000000000000000000003', 'c0000000000000000000000000000001', '2024-01-20 10:59:00', 'user:xfer-dialog', 'transaction', 18050, 100);
+
+-- Budgets
+
+INSERT INTO budgets VALUES ('b5000000000000000000000000000001', 'Budget 2024', 'Yearly budget', 12);
+INSERT INTO recurrences (obj_guid, recurrence_mult, recurrence_period_type, recurrence_period_start, recurrence_weekend_adjust) VALUES ('b5000000000000000000000000000001', 1, 'month', '20240101', 'none');
+INSERT INTO budget_amounts (budget_guid, account_guid, period_num, amount_num, amount_denom) VALUES ('b5000000000000000000000000000001', 'a0000000000000000000000000000031', 0, 30000, 100);
+INSERT INTO budget_amounts (budget_guid, account_guid, period_num, amount_num, amount_denom) VALUES ('b5000000000000000000000000000001', 'a0000000000000000000000000000031', 1, 32050, 100);
+INSERT INTO budget_amounts (budget_guid, account_guid, period_num, amount_num, amount_denom) VALUES ('b5000000000000000000000000000001', 'a0000000000000000000000000000032', 0, 80000, 100);
+
+-- Scheduled transactions
+
+INSERT INTO schedxactions VALUES ('e0000000000000000000000000000001', 'Rent', 1, '20240115', NULL, '20240115', 0, 0, 0, 0, 0, 0, 1, 'a00000000000000000000000000000f1');
+INSERT INTO recurrences (obj_guid, recurrence_mult, recurrence_period_type, recurrence_period_start, recurrence_weekend_adjust) VALUES ('e0000000000000000000000000000001', 1, 'month', '20240115', 'back');
+
+INSERT INTO transactions VALUES ('700000000000000000000000000000f1', 'c0000000000000000000000000000001', '', '2024-01-15 10:59:00', '2024-01-15 12:00:00', 'Rent');
+INSERT INTO splits VALUES ('500000000000000000000000000000f1', '700000000000000000000000000000f1', 'a00000000000000000000000000000f1', '', '', 'n', NULL, 0, 100, 0, 1, NULL);
+INSERT INTO splits VALUES ('500000000000000000000000000000f2', '700000000000000000000000000000f1', 'a00000000000000000000000000000f1', '', '', 'n', NULL, 0, 100, 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('500000000000000000000000000000f1', 'sched-xaction', 9, 0, NULL, 0.0, NULL, 'f0000000000000000000000000000008', 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('f0000000000000000000000000000008', 'sched-xaction/account', 5, 0, NULL, 0.0, NULL, 'a0000000000000000000000000000032', 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('f0000000000000000000000000000008', 'sched-xaction/debit-formula', 4, 0, '800', 0.0, NULL, NULL, 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('f0000000000000000000000000000008', 'sched-xaction/debit-numeric', 3, 0, NULL, 0.0, NULL, NULL, 800, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('500000000000000000000000000000f2', 'sched-xaction', 9, 0, NULL, 0.0, NULL, 'f0000000000000000000000000000009', 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('f0000000000000000000000000000009', 'sched-xaction/account', 5, 0, NULL, 0.0, NULL, 'a0000000000000000000000000000003', 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('f0000000000000000000000000000009', 'sched-xaction/credit-formula', 4, 0, '800', 0.0, NULL, NULL, 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('f0000000000000000000000000000009', 'sched-xaction/credit-numeric', 3, 0, NULL, 0.0, NULL, NULL, 800, 1, NULL);
+
+-- Business
+
+INSERT INTO billterms VALUES ('b3000000000000000000000000000001', 'Net 30', '30 days, 2% discount within 10 days', 1, 0, NULL, 'GNC_TERM_TYPE_DAYS', 30, 10, 2, 1, 0);
+INSERT INTO billterms VALUES ('b3000000000000000000000000000002', 'EOM', 'End of next month', 0, 0, NULL, 'GNC_TERM_TYPE_PROXIMO', 31, 0, 0, 1, 25);
+
+INSERT INTO taxtables VALUES ('b4000000000000000000000000000001', 'VAT 20%', 1, 0, NULL);
+INSERT INTO taxtable_entries (taxtable, account, amount_num, amount_denom, type) VALUES ('b4000000000000000000000000000001', 'a0000000000000000000000000000013', 20, 1, 2);
+
+INSERT INTO customers VALUES ('cc000000000000000000000000000001', 'ACME', '000001', 'Our first customer', 1, 0, 1, 0, 1, 'c0000000000000000000000000000001', 1, 'ACME Corp', '1 Main Street', '75001 Paris', '', '', '+33 1 23 45 67 89', '', 'billing@acme.example', '', '', '', '', '', '', '', '', 'b3000000000000000000000000000001', 2, 'b4000000000000000000000000000001');
+
+INSERT INTO vendors VALUES ('dd000000000000000000000000000001', 'Office Supplies', '000001', '', 'c0000000000000000000000000000001', 1, 0, 'Office Supplies Ltd', '2 High Street', '', '', '', '', '', '', 'b3000000000000000000000000000002', 'USEGLOBAL', NULL);
+
+INSERT INTO employees VALUES ('e1000000000000000000000000000001', 'jdoe', '000001', '', '', 1, 'c0000000000000000000000000000001', 'a0000000000000000000000000000011', 8, 1, 50, 1, 'John Doe', '3 Side Street', '', '', '', '', '', 'jdoe@acme.example');
+
+INSERT INTO jobs VALUES ('b1000000000000000000000000000001', '000001', 'Website', 'PO-42', 1, 2, 'cc000000000000000000000000000001');
+
+INSERT INTO orders VALUES ('b2000000000000000000000000000001', '000001', '', 'PO-42', 1, '2024-01-25 10:59:00', '1970-01-01 00:00:00', 2, 'cc000000000000000000000000000001');
+
+INSERT INTO invoices VALUES ('d0000000000000000000000000000001', '000001', '2024-02-01 10:59:00', '2024-02-01 10:59:00', '', 1, 'c0000000000000000000000000000001', 3, 'b1000000000000000000000000000001', 'b3000000000000000000000000000001', 'PO-42', '70000000000000000000000000000006', '60000000000000000000000000000002', 'a000000000000000000000000000000c', NULL, NULL, 0, 1);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('d0000000000000000000000000000001', 'credit-note', 1, 0, NULL, 0.0, NULL, NULL, 0, 1, NULL);
+
+INSERT INTO entries VALUES ('e2000000000000000000000000000001', '2024-02-01 10:59:00', '2024-02-01 12:00:00', 'Consulting', 'Hours', '', 10, 1, 'a0000000000000000000000000000022', 100, 1, 0, 1, 'd0000000000000000000000000000001', 'PERCENT', 'PRETAX', 1, 0, 'b4000000000000000000000000000001', NULL, NULL, NULL, NULL, 0, 0, NULL, 1, 0, NULL, NULL, 'b2000000000000000000000000000001');
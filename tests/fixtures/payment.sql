@@ -0,0 +1,8 @@
+-- Payment added to the fixture book of full.sql.
+--
+-- ACME pays {amount} cents of its invoice on 2024-03-10. The loader replaces
+-- {amount} before running the script.
+
+INSERT INTO transactions VALUES ('70000000000000000000000000000011', 'c0000000000000000000000000000001', '', '2024-03-10 10:59:00', '2024-03-10 12:00:00', 'ACME');
+INSERT INTO splits VALUES ('50000000000000000000000000000111', '70000000000000000000000000000011', 'a000000000000000000000000000000c', '', 'Payment', 'n', NULL, -{amount}, 100, -{amount}, 100, '60000000000000000000000000000002');
+INSERT INTO splits VALUES ('50000000000000000000000000000112', '70000000000000000000000000000011', 'a0000000000000000000000000000003', '', 'Payment', 'n', NULL, {amount}, 100, {amount}, 100, NULL);
@@ -0,0 +1,6 @@
+-- The trading accounts GnuCash creates when the book option is enabled.
+
+INSERT INTO accounts VALUES ('a0000000000000000000000000000050', 'Trading', 'TRADING', 'c0000000000000000000000000000001', 100, 0, 'a0000000000000000000000000000000', '', '', 0, 1);
+INSERT INTO accounts VALUES ('a0000000000000000000000000000051', 'CURRENCY', 'TRADING', 'c0000000000000000000000000000001', 100, 0, 'a0000000000000000000000000000050', '', '', 0, 1);
+INSERT INTO accounts VALUES ('a0000000000000000000000000000052', 'EUR', 'TRADING', 'c0000000000000000000000000000001', 100, 0, 'a0000000000000000000000000000051', '', '', 0, 0);
+INSERT INTO accounts VALUES ('a0000000000000000000000000000053', 'USD', 'TRADING', 'c0000000000000000000000000000002', 100, 0, 'a0000000000000000000000000000051', '', '', 0, 0);
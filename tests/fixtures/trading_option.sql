@@ -0,0 +1,5 @@
+-- Enables the “Use Trading Accounts” option of the book.
+
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('b0000000000000000000000000000001', 'options', 9, 0, NULL, 0.0, NULL, 'f00000000000000000000000000000b1', 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('f00000000000000000000000000000b1', 'options/Accounts', 9, 0, NULL, 0.0, NULL, 'f00000000000000000000000000000b2', 0, 1, NULL);
+INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('f00000000000000000000000000000b2', 'options/Accounts/Use Trading Accounts', 4, 0, 't', 0.0, NULL, NULL, 0, 1, NULL);
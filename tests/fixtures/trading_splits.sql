@@ -0,0 +1,9 @@
+-- The trading splits GnuCash adds to the transactions of
+-- foreign_currency.sql when the book option is enabled.
+--
+-- This script is run after foreign_currency.sql and trading_accounts.sql.
+
+INSERT INTO splits VALUES ('50000000000000000000000000000203', '70000000000000000000000000000020', 'a0000000000000000000000000000053', '', '', 'n', NULL, -92000, 100, -100000, 100, NULL);
+INSERT INTO splits VALUES ('50000000000000000000000000000204', '70000000000000000000000000000020', 'a0000000000000000000000000000052', '', '', 'n', NULL, 92000, 100, 92000, 100, NULL);
+INSERT INTO splits VALUES ('50000000000000000000000000000213', '70000000000000000000000000000021', 'a0000000000000000000000000000053', '', '', 'n', NULL, 40000, 100, 40000, 100, NULL);
+INSERT INTO splits VALUES ('50000000000000000000000000000214', '70000000000000000000000000000021', 'a0000000000000000000000000000052', '', '', 'n', NULL, -40000, 100, -37600, 100, NULL);
@@ -0,0 +1,1144 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! CLI tests for `gnucash-toolbox invoice`.
+
+// NOTE: rexpect is only compatible with Unix-like systems, so let’s just not
+// compile the CLI tests on Windows.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::pedantic, clippy::restriction)]
+
+mod common;
+
+use std::{fs, path::PathBuf, process::Command};
+
+use eyre::Result;
+use indoc::indoc;
+use rexpect::session::spawn_command;
+
+use self::common::{add_bill, gnc, pay_invoice, Book, TIMEOUT};
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  Helpers                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+fn gnucash_toolbox_invoice_list(book: &Book, args: &[&str]) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.args(["invoice", "list"]).args(args);
+    cmd
+}
+
+fn gnucash_toolbox_invoice_render(book: &Book, args: &[&str]) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.args(["invoice", "render"]).args(args);
+    cmd
+}
+
+fn gnucash_toolbox_invoice_new(book: &Book, args: &[&str]) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.args(["invoice", "new"]).args(args);
+    cmd
+}
+
+fn gnucash_toolbox_invoice_pay(book: &Book, args: &[&str]) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.args(["invoice", "pay"]).args(args);
+    cmd
+}
+
+/// Writes a template next to the book.
+fn write_template(book: &Book, name: &str, template: &str) -> Result<PathBuf> {
+    let path = book.dir.path().join(name);
+    fs::write(&path, template)?;
+    Ok(path)
+}
+
+/// Adds an unposted invoice to ACME, with a discount.
+fn add_unposted_invoice(book: &Book) -> Result<()> {
+    book.connection()?.execute_batch(indoc! {"
+        INSERT INTO invoices VALUES ('d0000000000000000000000000000002', '000002', '2024-03-01 10:59:00', NULL, '', 1, 'c0000000000000000000000000000001', 2, 'cc000000000000000000000000000001', 'b3000000000000000000000000000001', '', NULL, NULL, NULL, NULL, NULL, 0, 1);
+        INSERT INTO entries (guid, date, description, quantity_num, quantity_denom, i_acct, i_price_num, i_price_denom, i_discount_num, i_discount_denom, invoice, i_disc_type, i_disc_how, i_taxable, i_taxincluded, i_taxtable, b_taxable, b_taxincluded, billable) VALUES ('e2000000000000000000000000000002', '2024-03-01 10:59:00', 'Hosting', 2, 1, 'a0000000000000000000000000000022', 50, 1, 10, 1, 'd0000000000000000000000000000002', 'PERCENT', 'PRETAX', 1, 0, 'b4000000000000000000000000000001', 0, 0, 0);
+    "})?;
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                invoice list                                //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn lists_the_invoices() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_invoice_list(&book, &[]), TIMEOUT)?;
+    process.exp_string(
+        "ID      Type     Owner  Opened      Posted      Due         \
+        Account                           Total  Status",
+    )?;
+    process.exp_string(
+        "000001  invoice  ACME   2024-02-01  2024-02-01  2024-03-02  \
+        Assets:Accounts Receivable  1200.00 EUR  unpaid",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn lists_unposted_invoices_with_their_discount() -> Result<()> {
+    let book = Book::full()?;
+    add_unposted_invoice(&book)?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_invoice_list(&book, &[]), TIMEOUT)?;
+    process.exp_string(
+        "000001  invoice  ACME   2024-02-01  2024-02-01  2024-03-02  \
+        Assets:Accounts Receivable  1200.00 EUR  unpaid",
+    )?;
+    process.exp_string(
+        "000002  invoice  ACME   2024-03-01  -           -           \
+        -                            108.00 EUR  unposted",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn lists_bills_with_their_due_date_from_the_terms() -> Result<()> {
+    let book = Book::full()?;
+    add_bill(&book)?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_invoice_list(&book, &[]), TIMEOUT)?;
+    process.exp_string(
+        "000001  invoice  ACME             2024-02-01  2024-02-01  2024-03-02  \
+        Assets:Accounts Receivable    1200.00 EUR  unpaid",
+    )?;
+    process.exp_string(
+        "B-001   bill     Office Supplies  2024-02-26  2024-02-26  2024-04-30  \
+        Liabilities:Accounts Payable    50.00 EUR  unpaid",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn shows_partially_paid_invoices() -> Result<()> {
+    let book = Book::full()?;
+    pay_invoice(&book, 20000)?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_invoice_list(&book, &[]), TIMEOUT)?;
+    process.exp_string(
+        "000001  invoice  ACME   2024-02-01  2024-02-01  2024-03-02  \
+        Assets:Accounts Receivable  1200.00 EUR  partially paid",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn shows_paid_invoices() -> Result<()> {
+    let book = Book::full()?;
+    pay_invoice(&book, 120000)?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_invoice_list(&book, &[]), TIMEOUT)?;
+    process.exp_string(
+        "000001  invoice  ACME   2024-02-01  2024-02-01  2024-03-02  \
+        Assets:Accounts Receivable  1200.00 EUR  paid",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn filters_by_owner_name() -> Result<()> {
+    let book = Book::full()?;
+    add_bill(&book)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_list(&book, &["--owner", "Office Supplies"]),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "B-001  bill  Office Supplies  2024-02-26  2024-02-26  2024-04-30  \
+        Liabilities:Accounts Payable  50.00 EUR  unpaid",
+    )?;
+    assert!(!process.exp_eof()?.contains("ACME"));
+
+    Ok(())
+}
+
+#[test]
+fn filters_by_status() -> Result<()> {
+    let book = Book::full()?;
+    add_unposted_invoice(&book)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_list(&book, &["--status", "unposted"]),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "ID      Type     Owner  Opened      Posted  Due  Account       Total  \
+        Status",
+    )?;
+    process.exp_string(
+        "000002  invoice  ACME   2024-03-01  -       -    -        108.00 EUR  \
+        unposted",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn filters_by_due_date() -> Result<()> {
+    let book = Book::full()?;
+    add_bill(&book)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_list(&book, &["--due-after", "2024-03-02"]),
+        TIMEOUT,
+    )?;
+    process.exp_string("B-001  bill")?;
+    assert!(!process.exp_eof()?.contains("000001"));
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_list(&book, &["--due-before", "2024-03-03"]),
+        TIMEOUT,
+    )?;
+    process.exp_string("000001  invoice")?;
+    assert!(!process.exp_eof()?.contains("B-001"));
+
+    Ok(())
+}
+
+#[test]
+fn tells_when_no_invoice_matches() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_list(&book, &["--status", "paid"]),
+        TIMEOUT,
+    )?;
+    process.exp_string("There is no matching invoice in the book.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                               invoice render                               //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn renders_an_invoice_from_a_template() -> Result<()> {
+    let book = Book::full()?;
+    let template = write_template(
+        &book,
+        "invoice.txt",
+        indoc! {"
+            {{ company.name }}
+            Invoice {{ invoice.id }} of {{ invoice.posted }}, due {{ invoice.due }}
+            Terms: {{ invoice.terms }}
+            To: {{ owner.address.name }}, {{ owner.address.lines | join(', ') }}
+            Job: {{ job.name }} ({{ job.reference }})
+            {% for entry in entries -%}
+            {{ entry.description }}: {{ entry.quantity }} x {{ entry.price }} = {{ entry.value }}
+            {%- for tax in entry.taxes %} + {{ tax.name }} {{ tax.rate }} {{ tax.amount }}{% endfor %}
+            {% endfor -%}
+            {% for tax in taxes -%}
+            {{ tax.name }} ({{ tax.rate }}): {{ tax.amount }}
+            {% endfor -%}
+            Total: {{ totals.total }} {{ invoice.currency }}, {{ totals.due }} due
+        "},
+    )?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_render(
+            &book,
+            &["--template", template.to_str().unwrap(), "000001"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("ACME Consulting")?;
+    process.exp_string("Invoice 000001 of 2024-02-01, due 2024-03-02")?;
+    process.exp_string("Terms: 30 days, 2% discount within 10 days")?;
+    process.exp_string("To: ACME Corp, 1 Main Street, 75001 Paris")?;
+    process.exp_string("Job: Website (PO-42)")?;
+    process.exp_string("Consulting: 10 x 100.00 = 1000.00 + VAT 20% 200.00")?;
+    process.exp_string("VAT (20%): 200.00")?;
+    process.exp_string("Total: 1200.00 EUR, 1200.00 due")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn escapes_values_in_html_templates() -> Result<()> {
+    let book = Book::full()?;
+    book.connection()?
+        .execute("UPDATE invoices SET notes = 'Thanks & <see you>'", [])?;
+    let template =
+        write_template(&book, "invoice.html", "<p>{{ invoice.notes }}</p>")?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_render(
+            &book,
+            &["--template", template.to_str().unwrap(), "000001"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("<p>Thanks &amp; &lt;see you&gt;</p>")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn escapes_values_in_typst_templates() -> Result<()> {
+    let book = Book::full()?;
+    let template = write_template(
+        &book,
+        "invoice.typ",
+        "{{ owner.address.email }} {{ owner.address.fax }}|",
+    )?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_render(
+            &book,
+            &["--template", template.to_str().unwrap(), "000001"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("billing\\@acme.example |")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn renders_bills() -> Result<()> {
+    let book = Book::full()?;
+    add_bill(&book)?;
+    let template = write_template(
+        &book,
+        "bill.txt",
+        "{{ invoice.kind }} {{ invoice.id }} from {{ owner.name }}, \
+        {{ owner.address.lines[0] }}: {{ totals.total }}",
+    )?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_render(
+            &book,
+            &[
+                "--kind",
+                "bill",
+                "--template",
+                template.to_str().unwrap(),
+                "B-001",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process
+        .exp_string("bill B-001 from Office Supplies, 2 High Street: 50.00")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn writes_the_rendered_document_to_a_file() -> Result<()> {
+    let book = Book::full()?;
+    let template = write_template(&book, "invoice.txt", "{{ totals.total }}")?;
+    let output = book.dir.path().join("invoice-000001.txt");
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_render(
+            &book,
+            &[
+                "--template",
+                template.to_str().unwrap(),
+                "--output",
+                output.to_str().unwrap(),
+                "000001",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string(&format!(
+        "The invoice 000001 has been rendered to {}.",
+        output.display()
+    ))?;
+    process.exp_eof()?;
+
+    assert_eq!(fs::read_to_string(&output)?, "1200.00");
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_render_an_unknown_invoice() -> Result<()> {
+    let book = Book::full()?;
+    let template = write_template(&book, "invoice.txt", "{{ totals.total }}")?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_render(
+            &book,
+            &["--template", template.to_str().unwrap(), "000042"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("Error: there is no invoice with the ID 000042.")?;
+    process.exp_string(
+        "Use `gnc invoice list` to see the IDs of the documents.",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_render_undefined_values() -> Result<()> {
+    let book = Book::full()?;
+    let template = write_template(&book, "invoice.txt", "{{ totals.vat }}")?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_render(
+            &book,
+            &["--template", template.to_str().unwrap(), "000001"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("Error: cannot render")?;
+    process.exp_string("undefined value")?;
+    process.exp_string(
+        "See `gnc invoice render --help` for the available values.",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                invoice new                                 //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn creates_an_invoice_with_the_terms_of_the_customer() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_new(
+            &book,
+            &[
+                "--customer",
+                "ACME",
+                "--date",
+                "2024-03-01",
+                "--entry",
+                "Income:Sales=10@100:Consulting",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process
+        .exp_string("The invoice 000002 for 1200.00 EUR has been created.")?;
+    process.exp_eof()?;
+
+    let invoice: (String, String, Option<String>) = book
+        .connection()?
+        .query_row(
+            "SELECT date_opened, terms, post_txn FROM invoices
+            WHERE id = '000002' AND owner_guid = 'cc000000000000000000000000000001'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+    assert_eq!(
+        invoice,
+        (
+            "2024-03-01 10:59:00".to_owned(),
+            "b3000000000000000000000000000001".to_owned(),
+            None
+        )
+    );
+
+    let entry: (String, String, i64, i64, String) = book.connection()?.query_row(
+        "SELECT entries.description, i_acct, quantity_num, i_price_num, i_taxtable
+        FROM entries JOIN invoices ON entries.invoice = invoices.guid
+        WHERE invoices.id = '000002'",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+    )?;
+    assert_eq!(
+        entry,
+        (
+            "Consulting".to_owned(),
+            "a0000000000000000000000000000022".to_owned(),
+            10,
+            100,
+            "b4000000000000000000000000000001".to_owned()
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn ignores_the_tax_table_of_a_customer_without_override() -> Result<()> {
+    let book = Book::full()?;
+    book.connection()?.execute(
+        "UPDATE customers SET tax_override = 0 WHERE name = 'ACME'",
+        [],
+    )?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_new(
+            &book,
+            &["--customer", "ACME", "--entry", "Income:Sales=10@100"],
+        ),
+        TIMEOUT,
+    )?;
+    process
+        .exp_string("The invoice 000002 for 1000.00 EUR has been created.")?;
+    process.exp_eof()?;
+
+    let tax_table: Option<String> = book.query(
+        "SELECT i_taxtable FROM entries
+        JOIN invoices ON entries.invoice = invoices.guid
+        WHERE invoices.id = '000002'",
+    )?;
+    assert_eq!(tax_table, None);
+
+    Ok(())
+}
+
+#[test]
+fn uses_the_default_tax_table_of_the_book() -> Result<()> {
+    let book = Book::full()?;
+    book.connection()?.execute_batch(indoc! {"
+        UPDATE customers SET tax_override = 0, taxtable = NULL WHERE name = 'ACME';
+        INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('f0000000000000000000000000000002', 'options/Business/Default Customer TaxTable', 5, 0, NULL, 0.0, NULL, 'b4000000000000000000000000000001', 0, 1, NULL);
+    "})?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_new(
+            &book,
+            &["--customer", "ACME", "--entry", "Income:Sales=10@100"],
+        ),
+        TIMEOUT,
+    )?;
+    process
+        .exp_string("The invoice 000002 for 1200.00 EUR has been created.")?;
+    process.exp_eof()?;
+
+    let tax_table: String = book.query(
+        "SELECT i_taxtable FROM entries
+        JOIN invoices ON entries.invoice = invoices.guid
+        WHERE invoices.id = '000002'",
+    )?;
+    assert_eq!(tax_table, "b4000000000000000000000000000001");
+
+    Ok(())
+}
+
+#[test]
+fn creates_tax_included_entries_for_customers_whose_prices_include_taxes(
+) -> Result<()> {
+    let book = Book::full()?;
+    book.connection()?.execute(
+        "UPDATE customers SET tax_included = 1 WHERE name = 'ACME'",
+        [],
+    )?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_new(
+            &book,
+            &[
+                "--customer",
+                "ACME",
+                "--entry",
+                "Income:Sales=10@120",
+                "--post",
+                "Assets:Accounts Receivable",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "The invoice 000002 for 1200.00 EUR has been created and posted to \
+        Assets:Accounts Receivable.",
+    )?;
+    process.exp_eof()?;
+
+    let (tax_included, tax_table): (bool, String) =
+        book.connection()?.query_row(
+            "SELECT i_taxincluded, i_taxtable FROM entries
+            JOIN invoices ON entries.invoice = invoices.guid
+            WHERE invoices.id = '000002'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+    assert!(tax_included);
+    assert_eq!(tax_table, "b4000000000000000000000000000001");
+
+    let tax: i64 = book.query(
+        "SELECT value_num FROM splits
+        JOIN invoices ON invoices.post_txn = splits.tx_guid
+        WHERE invoices.id = '000002'
+            AND account_guid = 'a0000000000000000000000000000013'",
+    )?;
+    assert_eq!(tax, -20000);
+
+    Ok(())
+}
+
+#[test]
+fn posts_an_invoice_as_gnucash_does() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_new(
+            &book,
+            &[
+                "--customer",
+                "ACME",
+                "--date",
+                "2024-03-01",
+                "--entry",
+                "Income:Sales=10@100:Consulting",
+                "--post",
+                "Assets:Accounts Receivable",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "The invoice 000002 for 1200.00 EUR has been created and posted to \
+        Assets:Accounts Receivable.",
+    )?;
+    process.exp_eof()?;
+
+    let transaction: (String, String, String) = book.connection()?.query_row(
+        "SELECT transactions.num, transactions.description, transactions.post_date
+        FROM transactions JOIN invoices ON invoices.post_txn = transactions.guid
+        WHERE invoices.id = '000002'",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+    assert_eq!(
+        transaction,
+        (
+            "000002".to_owned(),
+            "ACME".to_owned(),
+            "2024-03-01 10:59:00".to_owned()
+        )
+    );
+
+    let splits: Vec<(String, i64, String, bool)> = book
+        .connection()?
+        .prepare(
+            "SELECT account_guid, value_num, action, lot_guid = invoices.post_lot
+            FROM splits JOIN invoices ON invoices.post_txn = splits.tx_guid
+            WHERE invoices.id = '000002'
+            ORDER BY account_guid",
+        )?
+        .query_map([], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get::<_, Option<bool>>(3)?.unwrap_or(false)))
+        })?
+        .collect::<Result<_, _>>()?;
+    assert_eq!(
+        splits,
+        [
+            (
+                "a000000000000000000000000000000c".to_owned(),
+                120000,
+                "Invoice".to_owned(),
+                true
+            ),
+            (
+                "a0000000000000000000000000000013".to_owned(),
+                -20000,
+                "Invoice".to_owned(),
+                false
+            ),
+            (
+                "a0000000000000000000000000000022".to_owned(),
+                -100000,
+                "Invoice".to_owned(),
+                false
+            ),
+        ]
+    );
+
+    let frames: Vec<String> = book
+        .connection()?
+        .prepare(
+            "SELECT slots.name FROM slots
+            JOIN invoices ON invoices.post_lot = slots.obj_guid
+            WHERE invoices.id = '000002' AND slots.slot_type = 9
+            ORDER BY slots.name",
+        )?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    assert_eq!(frames, ["gncInvoice", "gncOwner"]);
+
+    let owner: (i64, String) = book.connection()?.query_row(
+        "SELECT owner_type.int64_val, owner_guid.guid_val
+        FROM invoices
+        JOIN slots AS owner ON owner.obj_guid = invoices.post_lot
+        JOIN slots AS owner_type ON owner_type.obj_guid = owner.guid_val
+        JOIN slots AS owner_guid ON owner_guid.obj_guid = owner.guid_val
+        WHERE invoices.id = '000002'
+            AND owner.name = 'gncOwner'
+            AND owner_type.name = 'gncOwner/owner-type'
+            AND owner_guid.name = 'gncOwner/owner-guid'",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    assert_eq!(owner, (2, "cc000000000000000000000000000001".to_owned()));
+
+    let mut process =
+        spawn_command(gnucash_toolbox_invoice_list(&book, &[]), TIMEOUT)?;
+    process.exp_string(
+        "000002  invoice  ACME   2024-03-01  2024-03-01  2024-03-31  \
+        Assets:Accounts Receivable  1200.00 EUR  unpaid",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn creates_an_invoice_from_a_file() -> Result<()> {
+    let book = Book::full()?;
+    let file = book.dir.path().join("invoice.toml");
+    fs::write(
+        &file,
+        indoc! {r#"
+            customer = "000001"
+            id = "2024-007"
+            date = 2024-03-01
+            terms = "EOM"
+
+            [[entries]]
+            description = "Hosting"
+            action = "Month"
+            quantity = 2
+            price = "50.00"
+            account = "Income:Sales"
+            discount = 10
+
+            [post]
+            account = "Assets:Accounts Receivable"
+            date = 2024-03-05
+        "#},
+    )?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_new(&book, &["--file", file.to_str().unwrap()]),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "The invoice 2024-007 for 108.00 EUR has been created and posted to \
+        Assets:Accounts Receivable.",
+    )?;
+    process.exp_eof()?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_invoice_list(&book, &[]), TIMEOUT)?;
+    process.exp_string(
+        "2024-007  invoice  ACME   2024-03-01  2024-03-05  2024-04-30  \
+        Assets:Accounts Receivable   108.00 EUR  unpaid",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn increments_the_invoice_counter() -> Result<()> {
+    let book = Book::full()?;
+    book.connection()?.execute_batch(
+        "DELETE FROM slots WHERE obj_guid = 'b0000000000000000000000000000001' AND name = 'counters';",
+    )?;
+
+    for id in ["000002", "000003"] {
+        let mut process = spawn_command(
+            gnucash_toolbox_invoice_new(
+                &book,
+                &["--customer", "ACME", "--entry", "Income:Sales=1@10:Support"],
+            ),
+            TIMEOUT,
+        )?;
+        process.exp_string(&format!(
+            "The invoice {id} for 12.00 EUR has been created."
+        ))?;
+        process.exp_eof()?;
+    }
+
+    assert_eq!(
+        book.query::<i64>(
+            "SELECT int64_val FROM slots WHERE name = 'counters/gncInvoice'"
+        )?,
+        3
+    );
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_create_an_invoice_with_an_existing_id() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_new(
+            &book,
+            &[
+                "--customer",
+                "ACME",
+                "--id",
+                "000001",
+                "--entry",
+                "Income:Sales=1@10",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process
+        .exp_string("Error: there is already an invoice with the ID 000001.")?;
+    process.exp_string("Omit --id to use the next ID of the book.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_post_an_invoice_to_another_account_than_a_receivable() -> Result<()>
+{
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_new(
+            &book,
+            &[
+                "--customer",
+                "ACME",
+                "--entry",
+                "Income:Sales=1@10",
+                "--post",
+                "Assets:Bank:Checking",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "Error: the account Assets:Bank:Checking is not an accounts receivable \
+        account.",
+    )?;
+    process.exp_eof()?;
+
+    assert_eq!(book.query::<i64>("SELECT COUNT(*) FROM invoices")?, 1);
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                invoice pay                                 //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn pays_an_invoice_as_gnucash_does() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_pay(
+            &book,
+            &[
+                "--customer",
+                "ACME",
+                "--account",
+                "Assets:Bank:Checking",
+                "--date",
+                "2024-03-10",
+                "--num",
+                "CHK-1",
+                "1200",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("ID             Paid  Left to pay")?;
+    process.exp_string("000001  1200.00 EUR     0.00 EUR")?;
+    process
+        .exp_string("The payment of 1200.00 EUR for ACME has been recorded.")?;
+    process.exp_eof()?;
+
+    let transaction: (String, String, String) = book.connection()?.query_row(
+        "SELECT description, post_date, slots.string_val
+        FROM transactions JOIN slots ON slots.obj_guid = transactions.guid
+        WHERE num = 'CHK-1' AND slots.name = 'trans-txn-type'",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?;
+    assert_eq!(
+        transaction,
+        (
+            "ACME".to_owned(),
+            "2024-03-10 10:59:00".to_owned(),
+            "P".to_owned()
+        )
+    );
+
+    let splits: Vec<(String, i64, String, Option<String>)> = book
+        .connection()?
+        .prepare(
+            "SELECT account_guid, value_num, action, lot_guid
+            FROM splits JOIN transactions ON transactions.guid = splits.tx_guid
+            WHERE transactions.num = 'CHK-1'
+            ORDER BY account_guid",
+        )?
+        .query_map([], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+        })?
+        .collect::<Result<_, _>>()?;
+    assert_eq!(
+        splits,
+        [
+            (
+                "a0000000000000000000000000000003".to_owned(),
+                120000,
+                "Payment".to_owned(),
+                None
+            ),
+            (
+                "a000000000000000000000000000000c".to_owned(),
+                -120000,
+                "Payment".to_owned(),
+                Some("60000000000000000000000000000002".to_owned())
+            ),
+        ]
+    );
+
+    assert_eq!(
+        book.query::<i64>(
+            "SELECT is_closed FROM lots
+            WHERE guid = '60000000000000000000000000000002'"
+        )?,
+        1
+    );
+
+    let mut process =
+        spawn_command(gnucash_toolbox_invoice_list(&book, &[]), TIMEOUT)?;
+    process.exp_string(
+        "000001  invoice  ACME   2024-02-01  2024-02-01  2024-03-02  \
+        Assets:Accounts Receivable  1200.00 EUR  paid",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn pays_part_of_an_invoice() -> Result<()> {
+    let book = Book::full()?;
+    pay_invoice(&book, 20000)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_pay(
+            &book,
+            &["-c", "ACME", "-a", "Assets:Bank:Checking", "300"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("000001  300.00 EUR   700.00 EUR")?;
+    process
+        .exp_string("The payment of 300.00 EUR for ACME has been recorded.")?;
+    process.exp_eof()?;
+
+    assert_eq!(
+        book.query::<i64>(
+            "SELECT is_closed FROM lots
+            WHERE guid = '60000000000000000000000000000002'"
+        )?,
+        0
+    );
+
+    let mut process =
+        spawn_command(gnucash_toolbox_invoice_list(&book, &[]), TIMEOUT)?;
+    process.exp_string(
+        "000001  invoice  ACME   2024-02-01  2024-02-01  2024-03-02  \
+        Assets:Accounts Receivable  1200.00 EUR  partially paid",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn keeps_an_over_payment_as_a_pre_payment() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_pay(
+            &book,
+            &["-c", "ACME", "-a", "Assets:Bank:Checking", "1500"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("000001       1200.00 EUR     0.00 EUR")?;
+    process.exp_string("pre-payment   300.00 EUR            -")?;
+    process
+        .exp_string("The payment of 1500.00 EUR for ACME has been recorded.")?;
+    process.exp_eof()?;
+
+    let prepayment: (i64, String, i64, String) = book.connection()?.query_row(
+        "SELECT splits.value_num, lots.account_guid, owner_type.int64_val,
+            owner_guid.guid_val
+        FROM splits
+        JOIN lots ON lots.guid = splits.lot_guid
+        JOIN slots AS owner ON owner.obj_guid = lots.guid
+        JOIN slots AS owner_type ON owner_type.obj_guid = owner.guid_val
+        JOIN slots AS owner_guid ON owner_guid.obj_guid = owner.guid_val
+        WHERE owner.name = 'gncOwner'
+            AND owner_type.name = 'gncOwner/owner-type'
+            AND owner_guid.name = 'gncOwner/owner-guid'",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+    )?;
+    assert_eq!(
+        prepayment,
+        (
+            -30000,
+            "a000000000000000000000000000000c".to_owned(),
+            2,
+            "cc000000000000000000000000000001".to_owned()
+        )
+    );
+
+    Ok(())
+}
+
+#[test]
+fn pays_the_given_amount_of_an_invoice() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_pay(
+            &book,
+            &[
+                "-c",
+                "ACME",
+                "-a",
+                "Assets:Bank:Checking",
+                "--invoice",
+                "000001=400",
+                "1000",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("000001       400.00 EUR   800.00 EUR")?;
+    process.exp_string("pre-payment  600.00 EUR            -")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn pays_a_bill() -> Result<()> {
+    let book = Book::full()?;
+    add_bill(&book)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_pay(
+            &book,
+            &[
+                "--vendor",
+                "Office Supplies",
+                "-a",
+                "Assets:Bank:Checking",
+                "50",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("B-001  50.00 EUR     0.00 EUR")?;
+    process.exp_string(
+        "The payment of 50.00 EUR for Office Supplies has been recorded.",
+    )?;
+    process.exp_eof()?;
+
+    let splits: Vec<(String, i64)> = book
+        .connection()?
+        .prepare(
+            "SELECT account_guid, value_num FROM splits
+            WHERE action = 'Payment'
+            ORDER BY account_guid",
+        )?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+    assert_eq!(
+        splits,
+        [
+            ("a0000000000000000000000000000003".to_owned(), -5000),
+            ("a0000000000000000000000000000012".to_owned(), 5000),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_pay_more_than_the_balance_of_an_invoice() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_invoice_pay(
+            &book,
+            &[
+                "-c",
+                "ACME",
+                "-a",
+                "Assets:Bank:Checking",
+                "-i",
+                "000001=1300",
+                "1500",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "Error: 1300 exceeds the 1200 left to pay on the invoice 000001.",
+    )?;
+    process.exp_eof()?;
+
+    assert_eq!(
+        book.query::<i64>(
+            "SELECT COUNT(*) FROM splits WHERE action = 'Payment'"
+        )?,
+        0
+    );
+
+    Ok(())
+}
@@ -0,0 +1,149 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! CLI tests for `gnucash-toolbox migrate`.
+
+// NOTE: rexpect is only compatible with Unix-like systems, so let’s just not
+// compile the CLI tests on Windows.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::pedantic, clippy::restriction)]
+
+mod common;
+
+use std::process::Command;
+
+use eyre::Result;
+use rexpect::session::spawn_command;
+
+use self::common::{
+    add_foreign_currency_transactions, add_trading_accounts, check,
+    enable_trading_accounts, gnc, Book, TIMEOUT,
+};
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  Helpers                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+fn gnucash_toolbox_migrate_trading_accounts(
+    book: &Book,
+    args: &[&str],
+) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.args(["migrate", "trading-accounts"]).args(args);
+    cmd
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                          migrate trading-accounts                          //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn adds_the_missing_trading_splits() -> Result<()> {
+    let book = Book::new()?;
+    add_foreign_currency_transactions(&book)?;
+    enable_trading_accounts(&book)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_migrate_trading_accounts(&book, &[]),
+        TIMEOUT,
+    )?;
+    process.exp_string("New account: Trading\r\n")?;
+    process.exp_string("New account: Trading:CURRENCY\r\n")?;
+    process.exp_string("New account: Trading:CURRENCY:EUR\r\n")?;
+    process.exp_string("New account: Trading:CURRENCY:USD\r\n")?;
+    process.exp_string("4 trading splits added to 2 transactions.")?;
+    process.exp_eof()?;
+
+    let (quantity, value): (i64, i64) = book.connection()?.query_row(
+        "SELECT quantity_num, value_num FROM splits
+        JOIN accounts ON accounts.guid = splits.account_guid
+        WHERE tx_guid = '70000000000000000000000000000020'
+        AND accounts.name = 'USD'",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    assert_eq!((quantity, value), (-100_000, -92000));
+
+    check(&book.path)
+}
+
+#[test]
+fn uses_the_existing_trading_accounts() -> Result<()> {
+    let book = Book::new()?;
+    add_foreign_currency_transactions(&book)?;
+    add_trading_accounts(&book)?;
+    enable_trading_accounts(&book)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_migrate_trading_accounts(&book, &[]),
+        TIMEOUT,
+    )?;
+    process.exp_string("4 trading splits added to 2 transactions.")?;
+    process.exp_eof()?;
+
+    let accounts: i64 = book.query(
+        "SELECT COUNT(*) FROM accounts WHERE account_type = 'TRADING'",
+    )?;
+    assert_eq!(accounts, 4);
+
+    let quantity: i64 = book.query(
+        "SELECT quantity_num FROM splits
+        WHERE tx_guid = '70000000000000000000000000000021'
+        AND account_guid = 'a0000000000000000000000000000052'",
+    )?;
+    assert_eq!(quantity, -37600);
+
+    Ok(())
+}
+
+#[test]
+fn does_not_modify_the_book_on_dry_run() -> Result<()> {
+    let book = Book::new()?;
+    add_foreign_currency_transactions(&book)?;
+    enable_trading_accounts(&book)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_migrate_trading_accounts(&book, &["--dry-run"]),
+        TIMEOUT,
+    )?;
+    process.exp_string("New account: Trading:CURRENCY:USD")?;
+    process.exp_string("4 trading splits to add to 2 transactions.")?;
+    process.exp_eof()?;
+
+    let accounts: i64 = book.query(
+        "SELECT COUNT(*) FROM accounts WHERE account_type = 'TRADING'",
+    )?;
+    assert_eq!(accounts, 0);
+
+    Ok(())
+}
+
+#[test]
+fn fails_when_trading_accounts_are_disabled() -> Result<()> {
+    let book = Book::new()?;
+    add_foreign_currency_transactions(&book)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_migrate_trading_accounts(&book, &[]),
+        TIMEOUT,
+    )?;
+    process.exp_string("Error: the book does not use trading accounts.")?;
+    process.exp_string(
+        "Enable them in GnuCash, in File > Properties > Accounts.",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
@@ -0,0 +1,218 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! CLI tests for `gnucash-toolbox price`.
+
+// NOTE: rexpect is only compatible with Unix-like systems, so let’s just not
+// compile the CLI tests on Windows.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::pedantic, clippy::restriction)]
+
+mod common;
+
+use std::process::Command;
+
+use eyre::Result;
+use rexpect::session::spawn_command;
+
+use self::common::{gnc, Book, TIMEOUT};
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  Helpers                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+fn gnucash_toolbox_price_convert(book: &Book, args: &[&str]) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.args(["price", "convert"]).args(args);
+    cmd
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                               price convert                                //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn converts_with_the_last_price_before_the_date() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_price_convert(
+            &book,
+            &["--date", "2024-02-01", "100", "USD", "EUR"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("100 USD = 92.00 EUR (rate: 0.92)")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn converts_with_the_inverse_of_a_price() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_price_convert(
+            &book,
+            &["--date", "2024-02-01", "100", "EUR", "USD"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("100 EUR = 108.70 USD (rate: 1.086957)")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn triangulates_rates_through_the_base_currency() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_price_convert(
+            &book,
+            &["--date", "2024-02-01", "2", "AAPL", "USD"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("2 AAPL = 392.39 USD (rate: 196.195652)")?;
+    process.exp_eof()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_price_convert(
+            &book,
+            &["--date", "2024-02-01", "--via", "USD", "2", "AAPL", "USD"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "Error: there is no rate from AAPL to USD on 2024-02-01.",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn converts_with_the_nearest_price() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_price_convert(
+            &book,
+            &[
+                "--date",
+                "2024-01-01",
+                "--method",
+                "nearest",
+                "100",
+                "USD",
+                "EUR",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("100 USD = 92.00 EUR (rate: 0.92)")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn converts_with_the_average_price_of_a_period() -> Result<()> {
+    let book = Book::new()?;
+    book.connection()?.execute(
+        "INSERT INTO prices VALUES ('90000000000000000000000000000010', 'c0000000000000000000000000000002', 'c0000000000000000000000000000001', '2024-02-29 10:59:00', 'user:price-editor', 'last', 94, 100)",
+        [],
+    )?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_price_convert(
+            &book,
+            &[
+                "--period", "2024-Q1", "--method", "average", "100", "USD",
+                "EUR",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("100 USD = 93.00 EUR (rate: 0.93)")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn averages_a_year_of_inverse_prices() -> Result<()> {
+    let book = Book::new()?;
+    book.connection()?.execute_batch(
+        "DELETE FROM prices WHERE commodity_guid = 'c0000000000000000000000000000002';
+        INSERT INTO prices
+        WITH RECURSIVE days(day) AS (
+            SELECT 0 UNION ALL SELECT day + 1 FROM days WHERE day < 365
+        )
+        SELECT printf('91%030x', day), 'c0000000000000000000000000000002', 'c0000000000000000000000000000001', date('2024-01-01', '+' || day || ' days') || ' 10:59:00', 'user:price-editor', 'last', 9000 + day, 10000
+        FROM days;",
+    )?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_price_convert(
+            &book,
+            &[
+                "--period", "2024", "--method", "average", "100", "EUR", "USD",
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("100 EUR = 108.92 USD (rate: 1.089172)")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn fails_when_there_is_no_rate() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_price_convert(
+            &book,
+            &["--date", "2024-01-01", "100", "USD", "EUR"],
+        ),
+        TIMEOUT,
+    )?;
+    process
+        .exp_string("Error: there is no rate from USD to EUR on 2024-01-01.")?;
+    process
+        .exp_string("Add a price to the book, or use another rate method.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_convert_an_unknown_commodity() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_price_convert(&book, &["100", "USD", "GBP"]),
+        TIMEOUT,
+    )?;
+    process.exp_string("Error: unknown commodity: GBP.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
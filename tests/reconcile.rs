@@ -0,0 +1,253 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! CLI tests for `gnucash-toolbox reconcile`.
+
+// NOTE: rexpect is only compatible with Unix-like systems, so let’s just not
+// compile the CLI tests on Windows.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::pedantic, clippy::restriction)]
+
+mod common;
+
+use std::process::Command;
+
+use eyre::Result;
+use rexpect::session::spawn_command;
+
+use self::common::{gnc, Book, TIMEOUT};
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  Helpers                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+const PROMPT: &str =
+    "Toggle splits by number, or: a (auto match), d (done), q (quit): ";
+
+fn gnucash_toolbox_reconcile(book: &Book, balance: &str) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.env("TZ", "UTC").args([
+        "reconcile",
+        "--date",
+        "2024-01-31",
+        "--balance",
+        balance,
+        "Assets:Bank:Checking",
+    ]);
+    cmd
+}
+
+fn reconcile_states(book: &Book) -> Result<Vec<(String, String)>> {
+    let states = book
+        .connection()?
+        .prepare(
+            "SELECT guid, reconcile_state FROM splits
+            WHERE account_guid = 'a0000000000000000000000000000003'
+            ORDER BY guid",
+        )?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+
+    Ok(states)
+}
+
+fn state(guid: &str, state: &str) -> (String, String) {
+    (guid.to_owned(), state.to_owned())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                 reconcile                                  //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn matches_the_splits_automatically() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut command = gnucash_toolbox_reconcile(&book, "2657.50");
+    command.arg("--auto");
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string(
+        "Assets:Bank:Checking has been reconciled on 2024-01-31.",
+    )?;
+    process.exp_eof()?;
+
+    assert_eq!(
+        reconcile_states(&book)?,
+        vec![
+            state("50000000000000000000000000000011", "y"),
+            state("50000000000000000000000000000021", "y"),
+            state("50000000000000000000000000000032", "y"),
+            state("50000000000000000000000000000042", "y"),
+        ]
+    );
+
+    let reconcile_date: String = book.query(
+        "SELECT reconcile_date FROM splits
+        WHERE guid = '50000000000000000000000000000042'",
+    )?;
+    assert_eq!(reconcile_date, "2024-01-31 23:59:59");
+
+    Ok(())
+}
+
+#[test]
+fn prefers_a_subset_of_splits_matching_the_balance() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut command = gnucash_toolbox_reconcile(&book, "2700");
+    command.arg("--auto");
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string("has been reconciled")?;
+    process.exp_eof()?;
+
+    assert_eq!(
+        reconcile_states(&book)?,
+        vec![
+            state("50000000000000000000000000000011", "y"),
+            state("50000000000000000000000000000021", "y"),
+            state("50000000000000000000000000000032", "c"),
+            state("50000000000000000000000000000042", "y"),
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn stores_the_last_reconcile_date() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut command = gnucash_toolbox_reconcile(&book, "3457.50");
+    command.arg("--auto");
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string("has been reconciled")?;
+    process.exp_eof()?;
+
+    let last_date: i64 = book.query(
+        "SELECT s.int64_val FROM slots s
+        JOIN slots f ON f.guid_val = s.obj_guid
+        WHERE f.obj_guid = 'a0000000000000000000000000000003'
+        AND f.name = 'reconcile-info' AND s.name = 'reconcile-info/last-date'",
+    )?;
+    assert_eq!(last_date, 1_706_745_599);
+
+    Ok(())
+}
+
+#[test]
+fn refuses_to_auto_match_without_a_matching_combination() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut command = gnucash_toolbox_reconcile(&book, "1234");
+    command.arg("--auto");
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string(
+        "Error: no combination of splits matches the statement balance.",
+    )?;
+    process
+        .exp_string("Run without --auto to select the splits interactively.")?;
+    process.exp_eof()?;
+
+    assert_eq!(
+        reconcile_states(&book)?[3],
+        state("50000000000000000000000000000042", "n")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn lets_the_user_toggle_splits() -> Result<()> {
+    let book = Book::new()?;
+
+    let command = gnucash_toolbox_reconcile(&book, "2657.50");
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string("Reconciling Assets:Bank:Checking up to 2024-01-31:")?;
+    process.exp_string("[x] 1  2024-01-10  Groceries")?;
+    process.exp_string("[ ] 2  2024-01-15  Rent")?;
+    process.exp_regex(r"Difference: +-800\.00")?;
+    process.exp_string(PROMPT)?;
+
+    process.send_line("d")?;
+    process.exp_string(
+        "The difference is -800.00: select the splits matching the statement \
+        first.",
+    )?;
+    process.exp_string(PROMPT)?;
+
+    process.send_line("2")?;
+    process.exp_string("[x] 2  2024-01-15  Rent")?;
+    process.exp_regex(r"Difference: +0\.00")?;
+    process.exp_string(PROMPT)?;
+
+    process.send_line("d")?;
+    process.exp_string(
+        "Assets:Bank:Checking has been reconciled on 2024-01-31.",
+    )?;
+    process.exp_eof()?;
+
+    assert_eq!(
+        reconcile_states(&book)?[3],
+        state("50000000000000000000000000000042", "y")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn saves_nothing_when_quitting() -> Result<()> {
+    let book = Book::new()?;
+
+    let command = gnucash_toolbox_reconcile(&book, "3457.50");
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string(PROMPT)?;
+    process.send_line("q")?;
+    process.exp_string("Reconciliation cancelled, nothing has been saved.")?;
+    process.exp_eof()?;
+
+    assert_eq!(
+        reconcile_states(&book)?[2],
+        state("50000000000000000000000000000032", "c")
+    );
+
+    Ok(())
+}
+
+#[test]
+fn refuses_unknown_accounts() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut command = gnc(&book.path);
+    command.args([
+        "reconcile",
+        "--date",
+        "2024-01-31",
+        "--balance",
+        "0",
+        "Assets:Nowhere",
+    ]);
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string("Error: unknown account Assets:Nowhere.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
@@ -0,0 +1,612 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! CLI tests for `gnucash-toolbox report`.
+
+// NOTE: rexpect is only compatible with Unix-like systems, so let’s just not
+// compile the CLI tests on Windows.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::pedantic, clippy::restriction)]
+
+mod common;
+
+use std::{fs, process::Command};
+
+use eyre::Result;
+use indoc::indoc;
+use rexpect::session::spawn_command;
+
+use self::common::{
+    add_bill, add_foreign_currency_transactions, add_trading_splits, gnc,
+    pay_invoice, Book, TIMEOUT,
+};
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  Helpers                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+fn gnucash_toolbox_report_aging(book: &Book, args: &[&str]) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.args(["report", "aging"]).args(args);
+    cmd
+}
+
+fn gnucash_toolbox_report_fx_gains(book: &Book, args: &[&str]) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.args(["report", "fx-gains"]).args(args);
+    cmd
+}
+
+fn gnucash_toolbox_report_tax(book: &Book, args: &[&str]) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.args(["report", "tax"]).args(args);
+    cmd
+}
+
+fn gnucash_toolbox_report_vat(book: &Book, args: &[&str]) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.args(["report", "vat"]).args(args);
+    cmd
+}
+
+/// Adds the bill from Office Supplies with 10 EUR of VAT on top of its 50 EUR,
+/// posted on 2024-03-05 instead.
+fn add_bill_with_vat(book: &Book) -> Result<()> {
+    add_bill(book)?;
+    book.connection()?.execute_batch(indoc! {"
+        UPDATE transactions SET post_date = '2024-03-05 10:59:00'
+        WHERE guid = '70000000000000000000000000000010';
+        UPDATE invoices SET date_opened = '2024-03-05 10:59:00', date_posted = '2024-03-05 10:59:00'
+        WHERE guid = 'd0000000000000000000000000000003';
+        UPDATE splits SET value_num = -6000, quantity_num = -6000
+        WHERE guid = '50000000000000000000000000000101';
+        INSERT INTO splits VALUES ('50000000000000000000000000000103', '70000000000000000000000000000010', 'a0000000000000000000000000000013', '', '', 'n', NULL, 1000, 100, 1000, 100, NULL);
+        UPDATE entries SET date = '2024-03-05 10:59:00', b_taxable = 1, b_taxtable = 'b4000000000000000000000000000001'
+        WHERE guid = 'e2000000000000000000000000000003';
+    "})?;
+
+    Ok(())
+}
+
+/// Flags Income:Salary and Income:Sales with N261, Expenses:Food with N300,
+/// and Expenses:Rent without any code as tax-related.
+fn flag_tax_related_accounts(book: &Book) -> Result<()> {
+    book.connection()?.execute_batch(indoc! {"
+        INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('a0000000000000000000000000000021', 'tax-related', 1, 1, NULL, 0.0, NULL, NULL, 0, 1, NULL);
+        INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('a0000000000000000000000000000021', 'tax-US', 9, 0, NULL, 0.0, NULL, 'f00000000000000000000000000000a1', 0, 1, NULL);
+        INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('f00000000000000000000000000000a1', 'tax-US/code', 4, 0, 'N261', 0.0, NULL, NULL, 0, 1, NULL);
+        INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('f00000000000000000000000000000a1', 'tax-US/payer-name-source', 4, 0, 'current', 0.0, NULL, NULL, 0, 1, NULL);
+        INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('a0000000000000000000000000000022', 'tax-related', 1, 1, NULL, 0.0, NULL, NULL, 0, 1, NULL);
+        INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('a0000000000000000000000000000022', 'tax-US', 9, 0, NULL, 0.0, NULL, 'f00000000000000000000000000000a2', 0, 1, NULL);
+        INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('f00000000000000000000000000000a2', 'tax-US/code', 4, 0, 'N261', 0.0, NULL, NULL, 0, 1, NULL);
+        INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('f00000000000000000000000000000a2', 'tax-US/payer-name-source', 4, 0, 'parent', 0.0, NULL, NULL, 0, 1, NULL);
+        INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('a0000000000000000000000000000031', 'tax-related', 1, 1, NULL, 0.0, NULL, NULL, 0, 1, NULL);
+        INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('a0000000000000000000000000000031', 'tax-US', 9, 0, NULL, 0.0, NULL, 'f00000000000000000000000000000a3', 0, 1, NULL);
+        INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('f00000000000000000000000000000a3', 'tax-US/code', 4, 0, 'N300', 0.0, NULL, NULL, 0, 1, NULL);
+        INSERT INTO slots (obj_guid, name, slot_type, int64_val, string_val, double_val, timespec_val, guid_val, numeric_val_num, numeric_val_denom, gdate_val) VALUES ('a0000000000000000000000000000032', 'tax-related', 1, 1, NULL, 0.0, NULL, NULL, 0, 1, NULL);
+    "})?;
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                report aging                                //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn shows_invoices_not_due_yet_as_current() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_report_aging(
+            &book,
+            &["--receivable", "--date", "2024-02-15"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "Customer  Currency  Current  0-30  31-60  61-90   90+    Total",
+    )?;
+    process.exp_string(
+        "ACME      EUR       1200.00  0.00   0.00   0.00  0.00  1200.00",
+    )?;
+    process.exp_string(
+        "Total     EUR       1200.00  0.00   0.00   0.00  0.00  1200.00",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn groups_invoices_by_days_past_due() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_report_aging(
+            &book,
+            &["--receivable", "--date", "2024-03-15"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "ACME      EUR          0.00  1200.00   0.00   0.00  0.00  1200.00",
+    )?;
+    process.exp_eof()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_report_aging(
+            &book,
+            &["--receivable", "--date", "2024-04-15"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "ACME      EUR          0.00  0.00  1200.00   0.00  0.00  1200.00",
+    )?;
+    process.exp_eof()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_report_aging(
+            &book,
+            &["--receivable", "--date", "2024-06-15"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "ACME      EUR          0.00  0.00   0.00   0.00  1200.00  1200.00",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn only_counts_the_payments_made_until_the_date() -> Result<()> {
+    let book = Book::full()?;
+    pay_invoice(&book, 20000)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_report_aging(
+            &book,
+            &["--receivable", "--date", "2024-03-09"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "ACME      EUR          0.00  1200.00   0.00   0.00  0.00  1200.00",
+    )?;
+    process.exp_eof()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_report_aging(
+            &book,
+            &["--receivable", "--date", "2024-03-10"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "ACME      EUR          0.00  1000.00   0.00   0.00  0.00  1000.00",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn shows_payables_with_the_due_date_from_the_terms() -> Result<()> {
+    let book = Book::full()?;
+    add_bill(&book)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_report_aging(
+            &book,
+            &["--payable", "--date", "2024-05-01"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "Owner            Currency  Current   0-30  31-60  61-90   90+  Total",
+    )?;
+    process.exp_string(
+        "Office Supplies  EUR          0.00  50.00   0.00   0.00  0.00  50.00",
+    )?;
+    process.exp_string(
+        "Total            EUR          0.00  50.00   0.00   0.00  0.00  50.00",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn tells_when_nothing_is_open() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_report_aging(
+            &book,
+            &["--payable", "--date", "2024-05-01"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("There is no open bill on 2024-05-01.")?;
+    process.exp_eof()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_report_aging(
+            &book,
+            &["--receivable", "--date", "2024-01-31"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("There is no open invoice on 2024-01-31.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn requires_a_side() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_report_aging(&book, &[]), TIMEOUT)?;
+    process.exp_string("<--receivable|--payable>")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                              report fx-gains                               //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn computes_realised_and_unrealised_fx_gains() -> Result<()> {
+    let book = Book::new()?;
+    add_foreign_currency_transactions(&book)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_report_fx_gains(&book, &["--period", "2024-Q1"]),
+        TIMEOUT,
+    )?;
+    process.exp_string("Gains in EUR from 2024-01-01 to 2024-03-31:")?;
+    process.exp_string(
+        "Account                  Currency  Balance    Cost   Value  \
+        Unrealised  Realised",
+    )?;
+    process.exp_string(
+        "Assets:Bank:US Checking  USD        600.00  552.00  570.00       \
+        18.00      8.00",
+    )?;
+    process.exp_string(
+        "Total                                       552.00  570.00       \
+        18.00      8.00",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn gives_the_same_fx_gains_with_trading_accounts() -> Result<()> {
+    let book = Book::new()?;
+    add_foreign_currency_transactions(&book)?;
+    add_trading_splits(&book)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_report_fx_gains(&book, &["--period", "2024-Q1"]),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "Assets:Bank:US Checking  USD        600.00  552.00  570.00       \
+        18.00      8.00",
+    )?;
+    process.exp_string(
+        "Total                                       552.00  570.00       \
+        18.00      8.00",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn only_realises_fx_gains_during_the_period() -> Result<()> {
+    let book = Book::new()?;
+    add_foreign_currency_transactions(&book)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_report_fx_gains(&book, &["--period", "2024-03"]),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "Assets:Bank:US Checking  USD        600.00  552.00  570.00       \
+        18.00      0.00",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn rounds_fx_gains_values_to_the_reporting_currency() -> Result<()> {
+    let book = Book::new()?;
+    book.connection()?.execute_batch(indoc! {"
+        INSERT INTO transactions VALUES ('70000000000000000000000000000022', 'c0000000000000000000000000000002', '', '2024-02-15 10:59:00', '2024-02-15 12:00:00', 'Cashback');
+        INSERT INTO splits VALUES ('50000000000000000000000000000221', '70000000000000000000000000000022', 'a0000000000000000000000000000005', '', '', 'n', NULL, 5, 100, 5, 100, NULL);
+        INSERT INTO splits VALUES ('50000000000000000000000000000222', '70000000000000000000000000000022', 'a0000000000000000000000000000005', '', '', 'n', NULL, 5, 100, 5, 100, NULL);
+        INSERT INTO splits VALUES ('50000000000000000000000000000223', '70000000000000000000000000000022', 'a0000000000000000000000000000005', '', '', 'n', NULL, 5, 100, 5, 100, NULL);
+        INSERT INTO splits VALUES ('50000000000000000000000000000224', '70000000000000000000000000000022', 'a0000000000000000000000000000021', '', '', 'n', NULL, -15, 100, -14, 100, NULL);
+    "})?;
+
+    // Each split is worth 0.046 EUR, which is 0.05 EUR in the book.
+    let mut process = spawn_command(
+        gnucash_toolbox_report_fx_gains(&book, &["--period", "2024-Q1"]),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "Assets:Bank:US Checking  USD          0.15  0.15   0.14       \
+        -0.01      0.00",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_compute_fx_gains_without_a_rate() -> Result<()> {
+    let book = Book::new()?;
+    add_foreign_currency_transactions(&book)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_report_fx_gains(
+            &book,
+            &["--period", "2024", "--currency", "USD"],
+        ),
+        TIMEOUT,
+    )?;
+    process
+        .exp_string("Error: there is no rate from EUR to USD on 2024-01-01.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                 report tax                                 //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn shows_the_totals_of_tax_related_accounts_per_code() -> Result<()> {
+    let book = Book::full()?;
+    flag_tax_related_accounts(&book)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_report_tax(&book, &["--year", "2024"]),
+        TIMEOUT,
+    )?;
+    process.exp_string("Expenses:Rent is tax-related but has no code.")?;
+    process.exp_string("Code  Account        Payer        Amount")?;
+    process.exp_string("N261  Income:Salary  Salary  2500.00 EUR")?;
+    process.exp_string("N261  Income:Sales   Income  1000.00 EUR")?;
+    process.exp_string("      Total                  3500.00 EUR")?;
+    process.exp_string("N300  Expenses:Food            42.50 EUR")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn leaves_out_tax_related_accounts_without_amounts() -> Result<()> {
+    let book = Book::full()?;
+    flag_tax_related_accounts(&book)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_report_tax(&book, &["--year", "2023"]),
+        TIMEOUT,
+    )?;
+    process
+        .exp_string("There is no amount on tax-related accounts in 2023.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn exports_the_tax_report_to_txf() -> Result<()> {
+    let book = Book::full()?;
+    flag_tax_related_accounts(&book)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_report_tax(
+            &book,
+            &["--year", "2024", "--format", "txf"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("V042\r\nAgnucash-toolbox\r\nD")?;
+    process.exp_string("TS\r\nN261\r\nC1\r\nL1\r\nPSalary\r\n$2500.00\r\n^")?;
+    process.exp_string("TS\r\nN261\r\nC1\r\nL1\r\nPIncome\r\n$1000.00\r\n^")?;
+    process.exp_string("TS\r\nN300\r\nC1\r\nL1\r\n$42.50\r\n^")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn exports_the_tax_report_to_csv() -> Result<()> {
+    let book = Book::full()?;
+    flag_tax_related_accounts(&book)?;
+    book.connection()?.execute(
+        "UPDATE slots SET string_val = 'CA-8871'
+        WHERE obj_guid = 'f00000000000000000000000000000a3'",
+        [],
+    )?;
+
+    let output = book.dir.path().join("tax.csv");
+
+    let mut process = spawn_command(
+        gnucash_toolbox_report_tax(
+            &book,
+            &[
+                "--year",
+                "2024",
+                "--format",
+                "csv",
+                "--output",
+                output.to_str().unwrap(),
+            ],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string("The tax report has been written to")?;
+    process.exp_eof()?;
+
+    assert_eq!(
+        fs::read_to_string(&output)?,
+        indoc! {"
+            code,account,payer,currency,amount
+            CA-8871,Expenses:Food,,EUR,42.50
+            N261,Income:Salary,Salary,EUR,2500.00
+            N261,Income:Sales,Income,EUR,1000.00
+        "}
+    );
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_export_codes_which_are_not_txf_codes() -> Result<()> {
+    let book = Book::full()?;
+    flag_tax_related_accounts(&book)?;
+    book.connection()?.execute(
+        "UPDATE slots SET string_val = 'CA-8871'
+        WHERE obj_guid = 'f00000000000000000000000000000a3'",
+        [],
+    )?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_report_tax(
+            &book,
+            &["--year", "2024", "--format", "txf"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "Error: the code CA-8871 of Expenses:Food is not a TXF code.",
+    )?;
+    process.exp_string("Use --format csv to export codes of your own.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                 report vat                                 //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn computes_the_vat_return_of_a_period() -> Result<()> {
+    let book = Book::full()?;
+    add_bill_with_vat(&book)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_report_vat(&book, &["--period", "2024-Q1"]),
+        TIMEOUT,
+    )?;
+    process.exp_string("Kind    Rate  Currency     Base     Tax")?;
+    process.exp_string("Output  20%   EUR       1000.00  200.00")?;
+    process.exp_string("Input   20%   EUR         50.00   10.00")?;
+    process.exp_string("Currency  Output tax  Input tax  Net payable")?;
+    process.exp_string("EUR           200.00      10.00       190.00")?;
+    process.exp_string(
+        "Account          Currency  Documents  Booked  Difference",
+    )?;
+    process.exp_string(
+        "Liabilities:VAT  EUR          190.00  190.00        0.00",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn only_takes_the_documents_posted_during_the_period() -> Result<()> {
+    let book = Book::full()?;
+    add_bill_with_vat(&book)?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_report_vat(&book, &["--period", "2024-03"]),
+        TIMEOUT,
+    )?;
+    process.exp_string("Input  20%   EUR       50.00  10.00")?;
+    process.exp_string("EUR             0.00      10.00       -10.00")?;
+    process.exp_eof()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_report_vat(&book, &["--period", "2023"]),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "There is no tax on the documents posted from 2023-01-01 to \
+        2023-12-31.",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn flags_differences_with_the_vat_accounts() -> Result<()> {
+    let book = Book::full()?;
+    book.connection()?.execute_batch(indoc! {"
+        INSERT INTO transactions VALUES ('70000000000000000000000000000013', 'c0000000000000000000000000000001', '', '2024-03-20 10:59:00', '2024-03-20 12:00:00', 'VAT correction');
+        INSERT INTO splits VALUES ('50000000000000000000000000000131', '70000000000000000000000000000013', 'a0000000000000000000000000000013', '', '', 'n', NULL, -1500, 100, -1500, 100, NULL);
+        INSERT INTO splits VALUES ('50000000000000000000000000000132', '70000000000000000000000000000013', 'a0000000000000000000000000000003', '', '', 'n', NULL, 1500, 100, 1500, 100, NULL);
+    "})?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_report_vat(
+            &book,
+            &["--period", "2024-01-01..2024-03-31"],
+        ),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "Liabilities:VAT  EUR          200.00  215.00       15.00",
+    )?;
+    process.exp_string(
+        "Liabilities:VAT differs from the documents by 15.00 EUR.",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn rejects_an_invalid_period() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_report_vat(&book, &["--period", "2024-Q5"]),
+        TIMEOUT,
+    )?;
+    process.exp_string("invalid period: \"2024-Q5\"")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
@@ -0,0 +1,166 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! CLI tests for `gnucash-toolbox slots`.
+
+// NOTE: rexpect is only compatible with Unix-like systems, so let’s just not
+// compile the CLI tests on Windows.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::pedantic, clippy::restriction)]
+
+mod common;
+
+use std::process::Command;
+
+use eyre::Result;
+use rexpect::session::spawn_command;
+
+use self::common::{gnc, Book, TIMEOUT};
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  Helpers                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+fn gnucash_toolbox_slots(book: &Book, guid: &str) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.args(["slots", guid]);
+    cmd
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                   slots                                    //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn dumps_the_slots_of_the_book() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_slots(&book, "b0000000000000000000000000000001"),
+        TIMEOUT,
+    )?;
+    process
+        .exp_string("Slots of the book b0000000000000000000000000000001:")?;
+    process.exp_string("  counters (list): [...]")?;
+    process.exp_string("  counters[0] (int64): 42")?;
+    process.exp_string("  counters[1] (numeric): 1.5")?;
+    process.exp_string("  options (frame): {...}")?;
+    process.exp_string("  options/Budgeting (frame): {...}")?;
+    process.exp_string(
+        "  options/Budgeting/Default Budget (guid): \
+        b5000000000000000000000000000001",
+    )?;
+    process.exp_string("  options/Business (frame): {...}")?;
+    process.exp_string(
+        "  options/Business/Company Name (string): \"ACME Consulting\"",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn dumps_the_slots_of_an_account() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_slots(&book, "a0000000000000000000000000000003"),
+        TIMEOUT,
+    )?;
+    process
+        .exp_string("Slots of the account a0000000000000000000000000000003:")?;
+    process.exp_string("  color (string): \"#1e90ff\"")?;
+    process.exp_string("  reconcile-info (frame): {...}")?;
+    process.exp_string("  reconcile-info/last-date (int64): 1706745599")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn dumps_the_slots_of_a_transaction() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_slots(&book, "70000000000000000000000000000006"),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "Slots of the transaction 70000000000000000000000000000006:",
+    )?;
+    process.exp_string("  date-posted (gdate): 2024-02-01")?;
+    process.exp_string(
+        "  gncInvoice/invoice-guid (guid): d0000000000000000000000000000001",
+    )?;
+    process
+        .exp_string("  trans-date-due (timespec): 2024-03-02 10:59:00 UTC")?;
+    process.exp_string("  trans-txn-type (string): \"I\"")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn dumps_the_slots_of_a_split() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_slots(&book, "500000000000000000000000000000f1"),
+        TIMEOUT,
+    )?;
+    process
+        .exp_string("Slots of the split 500000000000000000000000000000f1:")?;
+    process.exp_string(
+        "  sched-xaction/account (guid): a0000000000000000000000000000032",
+    )?;
+    process.exp_string("  sched-xaction/debit-formula (string): \"800\"")?;
+    process.exp_string("  sched-xaction/debit-numeric (numeric): 800")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn reports_objects_without_slots() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_slots(&book, "a0000000000000000000000000000004"),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "The account a0000000000000000000000000000004 has no slots.",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn reports_unknown_guids() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_slots(&book, "ffffffffffffffffffffffffffffffff"),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "Error: there is no object with the GUID \
+        ffffffffffffffffffffffffffffffff in the book.",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
@@ -0,0 +1,206 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! CLI tests for `gnucash-toolbox split-book`.
+
+// NOTE: rexpect is only compatible with Unix-like systems, so let’s just not
+// compile the CLI tests on Windows.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::pedantic, clippy::restriction)]
+
+mod common;
+
+use std::{fs, path::PathBuf, process::Command};
+
+use eyre::Result;
+use indoc::indoc;
+use rexpect::session::spawn_command;
+
+use self::common::{balance, check, gnc, query, Book, TIMEOUT};
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  Helpers                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+fn gnucash_toolbox_split_book(book: &Book, args: &[&str]) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.arg("split-book").args(args).arg(new_book(book));
+    cmd
+}
+
+/// Returns the path of the new book.
+fn new_book(book: &Book) -> PathBuf {
+    book.dir.path().join("new.gnucash")
+}
+
+/// Buys 1000 USD for 920 EUR on 2024-01-31, reconciled on both sides.
+fn buy_usd(book: &Book) -> Result<()> {
+    book.connection()?.execute_batch(indoc! {"
+        INSERT INTO transactions VALUES ('70000000000000000000000000000020', 'c0000000000000000000000000000001', '', '2024-01-31 10:59:00', '2024-01-31 12:00:00', 'Buy USD');
+        INSERT INTO splits VALUES ('50000000000000000000000000000201', '70000000000000000000000000000020', 'a0000000000000000000000000000003', '', '', 'y', '2024-01-31 10:59:00', -92000, 100, -92000, 100, NULL);
+        INSERT INTO splits VALUES ('50000000000000000000000000000202', '70000000000000000000000000000020', 'a0000000000000000000000000000005', '', '', 'y', '2024-01-31 10:59:00', 92000, 100, 100000, 100, NULL);
+    "})?;
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                 split-book                                 //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn creates_a_book_with_the_opening_balances() -> Result<()> {
+    let book = Book::new()?;
+    let new = new_book(&book);
+
+    let mut process = spawn_command(
+        gnucash_toolbox_split_book(&book, &["--at", "2024-01-12"]),
+        TIMEOUT,
+    )?;
+    process.exp_string(
+        "1 opening balance transactions, 1 unreconciled splits carried over.",
+    )?;
+    process.exp_string(&format!(
+        "The new book has been created in {}.",
+        new.display()
+    ))?;
+    process.exp_eof()?;
+
+    assert_eq!(balance(&new, "a0000000000000000000000000000003")?, 345_750);
+    assert_eq!(balance(&new, "a0000000000000000000000000000041")?, -345_750);
+    assert_eq!(balance(&new, "a0000000000000000000000000000021")?, 0);
+    assert_eq!(balance(&new, "a0000000000000000000000000000031")?, 0);
+
+    let opening: String = query(
+        &new,
+        "SELECT reconcile_state || ' ' || quantity_num FROM splits
+        JOIN transactions ON transactions.guid = splits.tx_guid
+        WHERE account_guid = 'a0000000000000000000000000000003'
+        AND description = 'Opening Balances'",
+    )?;
+    assert_eq!(opening, "y 350000");
+
+    let carried: String = query(
+        &new,
+        "SELECT post_date || ' ' || reconcile_state || ' ' || quantity_num
+        FROM splits JOIN transactions ON transactions.guid = splits.tx_guid
+        WHERE account_guid = 'a0000000000000000000000000000003'
+        AND description = 'Groceries'",
+    )?;
+    assert_eq!(carried, "2024-01-10 10:59:00 c -4250");
+
+    check(&new)
+}
+
+#[test]
+fn keeps_the_accounts_prices_and_scheduled_transactions() -> Result<()> {
+    let book = Book::full()?;
+    let new = new_book(&book);
+
+    let mut process = spawn_command(
+        gnucash_toolbox_split_book(&book, &["--at", "2025-01-01"]),
+        TIMEOUT,
+    )?;
+    process.exp_string("The new book has been created in")?;
+    process.exp_eof()?;
+
+    for table in ["accounts", "commodities", "prices", "schedxactions"] {
+        let sql = format!("SELECT COUNT(*) FROM {table}");
+        assert_eq!(
+            query::<i64>(&new, &sql)?,
+            book.query::<i64>(&sql)?,
+            "{table}"
+        );
+    }
+
+    let templates: i64 = query(
+        &new,
+        "SELECT COUNT(DISTINCT tx_guid) FROM splits
+        WHERE account_guid = 'a00000000000000000000000000000f1'",
+    )?;
+    assert_eq!(templates, 1);
+
+    let book_guid: String = query(&new, "SELECT guid FROM books")?;
+    assert_ne!(book_guid, "b0000000000000000000000000000001");
+
+    Ok(())
+}
+
+#[test]
+fn opens_each_currency_separately() -> Result<()> {
+    let book = Book::new()?;
+    buy_usd(&book)?;
+    let new = new_book(&book);
+
+    let mut process = spawn_command(
+        gnucash_toolbox_split_book(&book, &["--at", "2024-02-01"]),
+        TIMEOUT,
+    )?;
+    process.exp_string("New account: Equity:Opening Balances:USD")?;
+    process.exp_string(
+        "2 opening balance transactions, 2 unreconciled splits carried over.",
+    )?;
+    process.exp_eof()?;
+
+    assert_eq!(balance(&new, "a0000000000000000000000000000005")?, 100_000);
+
+    let equity: i64 = query(
+        &new,
+        "SELECT SUM(quantity_num) FROM splits
+        JOIN accounts ON accounts.guid = splits.account_guid
+        WHERE accounts.name = 'USD'",
+    )?;
+    assert_eq!(equity, -100_000);
+
+    check(&new)
+}
+
+#[test]
+fn refuses_to_overwrite_an_existing_book() -> Result<()> {
+    let book = Book::new()?;
+    let new = new_book(&book);
+    fs::write(&new, "existing")?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_split_book(&book, &["--at", "2024-01-12"]),
+        TIMEOUT,
+    )?;
+    process.exp_string(&format!("Error: {} already exists.", new.display()))?;
+    process.exp_string("Use --force to overwrite it.")?;
+    process.exp_eof()?;
+
+    assert_eq!(fs::read_to_string(&new)?, "existing");
+
+    Ok(())
+}
+
+#[test]
+fn overwrites_an_existing_book_with_force() -> Result<()> {
+    let book = Book::new()?;
+    let new = new_book(&book);
+    fs::write(&new, "existing")?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_split_book(&book, &["--at", "2024-01-12", "--force"]),
+        TIMEOUT,
+    )?;
+    process.exp_string("The new book has been created in ")?;
+    process.exp_eof()?;
+
+    let transactions: i64 = query(&new, "SELECT COUNT(*) FROM transactions")?;
+    assert!(transactions > 0);
+
+    check(&new)
+}
@@ -0,0 +1,303 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! CLI tests for `gnucash-toolbox sx`.
+
+// NOTE: rexpect is only compatible with Unix-like systems, so let’s just not
+// compile the CLI tests on Windows.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::pedantic, clippy::restriction)]
+
+mod common;
+
+use std::process::Command;
+
+use eyre::Result;
+use rexpect::session::spawn_command;
+
+use self::common::{gnc, Book, TIMEOUT};
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  Helpers                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+fn gnucash_toolbox_sx_list(book: &Book) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.args(["sx", "list"]);
+    cmd
+}
+
+fn gnucash_toolbox_sx_run(book: &Book, until: &str) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.args(["sx", "run", "--until", until]);
+    cmd
+}
+
+fn created_transactions(book: &Book) -> Result<Vec<(String, i64)>> {
+    let transactions = book
+        .connection()?
+        .prepare(
+            "SELECT t.post_date, s.value_num FROM transactions t
+            JOIN splits s ON s.tx_guid = t.guid
+            JOIN slots sl ON sl.obj_guid = t.guid
+            WHERE sl.name = 'from-sched-xaction'
+            AND sl.guid_val = 'e0000000000000000000000000000001'
+            AND s.account_guid = 'a0000000000000000000000000000032'
+            ORDER BY t.post_date",
+        )?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+
+    Ok(transactions)
+}
+
+fn schedule_state(book: &Book) -> Result<(String, i64)> {
+    Ok(book.connection()?.query_row(
+        "SELECT last_occur, instance_count FROM schedxactions
+        WHERE guid = 'e0000000000000000000000000000001'",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  sx list                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn lists_the_scheduled_transactions() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process = spawn_command(gnucash_toolbox_sx_list(&book), TIMEOUT)?;
+    process.exp_string("Rent (e0000000000000000000000000000001)")?;
+    process.exp_string(
+        "Recurrence: every month, starting on 2024-01-15, moved back on \
+        weekends",
+    )?;
+    process.exp_string("Next:       2024-02-15")?;
+    process.exp_string("Ends:       never")?;
+    process.exp_string("Last:       2024-01-15 (1 created)")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn lists_the_end_conditions() -> Result<()> {
+    let book = Book::full()?;
+    book.connection()?.execute_batch(
+        "UPDATE schedxactions SET num_occur = 3, rem_occur = 2",
+    )?;
+
+    let mut process = spawn_command(gnucash_toolbox_sx_list(&book), TIMEOUT)?;
+    process.exp_string("Ends:       after 3 occurrences, 2 remaining")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn lists_disabled_scheduled_transactions() -> Result<()> {
+    let book = Book::full()?;
+    book.connection()?
+        .execute_batch("UPDATE schedxactions SET enabled = 0")?;
+
+    let mut process = spawn_command(gnucash_toolbox_sx_list(&book), TIMEOUT)?;
+    process.exp_string("Next:       disabled")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn tells_when_there_is_no_scheduled_transaction() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut process = spawn_command(gnucash_toolbox_sx_list(&book), TIMEOUT)?;
+    process.exp_string("There is no scheduled transaction in the book.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                   sx run                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn creates_the_due_transactions() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_sx_run(&book, "2024-03-31"), TIMEOUT)?;
+    process.exp_string("2024-02-15  Rent")?;
+    process.exp_string("2024-03-15  Rent")?;
+    process.exp_string("2 transactions created.")?;
+    process.exp_eof()?;
+
+    assert_eq!(
+        created_transactions(&book)?,
+        vec![
+            ("2024-02-15 10:59:00".to_owned(), 80000),
+            ("2024-03-15 10:59:00".to_owned(), 80000),
+        ]
+    );
+    assert_eq!(schedule_state(&book)?, ("20240315".to_owned(), 3));
+
+    Ok(())
+}
+
+#[test]
+fn does_not_create_transactions_twice() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_sx_run(&book, "2024-03-31"), TIMEOUT)?;
+    process.exp_string("2 transactions created.")?;
+    process.exp_eof()?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_sx_run(&book, "2024-03-31"), TIMEOUT)?;
+    process.exp_string("0 transactions created.")?;
+    process.exp_eof()?;
+
+    assert_eq!(created_transactions(&book)?.len(), 2);
+
+    Ok(())
+}
+
+#[test]
+fn moves_occurrences_falling_on_a_weekend() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_sx_run(&book, "2024-06-30"), TIMEOUT)?;
+    process.exp_string("2024-06-14  Rent")?;
+    process.exp_string("5 transactions created.")?;
+    process.exp_eof()?;
+
+    assert_eq!(schedule_state(&book)?, ("20240614".to_owned(), 6));
+
+    Ok(())
+}
+
+#[test]
+fn stops_at_the_end_date() -> Result<()> {
+    let book = Book::full()?;
+    book.connection()?
+        .execute_batch("UPDATE schedxactions SET end_date = '20240301'")?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_sx_run(&book, "2024-12-31"), TIMEOUT)?;
+    process.exp_string("1 transactions created.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn stops_after_the_number_of_occurrences() -> Result<()> {
+    let book = Book::full()?;
+    book.connection()?.execute_batch(
+        "UPDATE schedxactions SET num_occur = 3, rem_occur = 2",
+    )?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_sx_run(&book, "2024-12-31"), TIMEOUT)?;
+    process.exp_string("2 transactions created.")?;
+    process.exp_eof()?;
+
+    let rem_occur: i64 = book.query("SELECT rem_occur FROM schedxactions")?;
+    assert_eq!(rem_occur, 0);
+
+    Ok(())
+}
+
+#[test]
+fn skips_disabled_scheduled_transactions() -> Result<()> {
+    let book = Book::full()?;
+    book.connection()?
+        .execute_batch("UPDATE schedxactions SET enabled = 0")?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_sx_run(&book, "2024-12-31"), TIMEOUT)?;
+    process.exp_string("0 transactions created.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+#[test]
+fn does_not_modify_the_book_on_dry_run() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut command = gnucash_toolbox_sx_run(&book, "2024-03-31");
+    command.arg("--dry-run");
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string("2 transactions to create.")?;
+    process.exp_eof()?;
+
+    assert!(created_transactions(&book)?.is_empty());
+    assert_eq!(schedule_state(&book)?, ("20240115".to_owned(), 1));
+
+    Ok(())
+}
+
+#[test]
+fn evaluates_formulas_with_variables() -> Result<()> {
+    let book = Book::full()?;
+    book.connection()?.execute_batch(
+        "UPDATE slots SET string_val = '(rent + 100) * 1.5'
+        WHERE name LIKE 'sched-xaction/%-formula'",
+    )?;
+
+    let mut command = gnucash_toolbox_sx_run(&book, "2024-02-29");
+    command.args(["--var", "rent=500"]);
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string("1 transactions created.")?;
+    process.exp_eof()?;
+
+    assert_eq!(
+        created_transactions(&book)?,
+        vec![("2024-02-15 10:59:00".to_owned(), 90000)]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn asks_for_missing_variables() -> Result<()> {
+    let book = Book::full()?;
+    book.connection()?.execute_batch(
+        "UPDATE slots SET string_val = 'rent'
+        WHERE name LIKE 'sched-xaction/%-formula'",
+    )?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_sx_run(&book, "2024-02-29"), TIMEOUT)?;
+    process.exp_string(
+        "Error: cannot create Rent on 2024-02-15: invalid formula \"rent\": \
+        the variable rent has no value.",
+    )?;
+    process
+        .exp_string("Give the value of the variable with --var rent=VALUE.")?;
+    process.exp_eof()?;
+
+    assert!(created_transactions(&book)?.is_empty());
+
+    Ok(())
+}
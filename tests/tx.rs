@@ -0,0 +1,536 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! CLI tests for `gnucash-toolbox tx`.
+
+// NOTE: rexpect is only compatible with Unix-like systems, so let’s just not
+// compile the CLI tests on Windows.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::pedantic, clippy::restriction)]
+
+mod common;
+
+use std::{fs, path::PathBuf, process::Command};
+
+use eyre::Result;
+use rexpect::session::spawn_command;
+
+use self::common::{gnc, Book, TIMEOUT};
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  Helpers                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+fn gnucash_toolbox_tx_add(book: &Book) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.args(["tx", "add"]);
+    cmd
+}
+
+fn gnucash_toolbox_tx_apply(book: &Book, journal: &str) -> Result<Command> {
+    let path = write_journal(book, journal)?;
+    let mut cmd = gnc(&book.path);
+    cmd.args(["tx", "apply"]).arg(path);
+    Ok(cmd)
+}
+
+fn write_journal(book: &Book, journal: &str) -> Result<PathBuf> {
+    let path = book.dir.path().join("book.journal");
+    fs::write(&path, journal)?;
+    Ok(path)
+}
+
+fn transaction_count(book: &Book) -> Result<i64> {
+    book.query("SELECT COUNT(*) FROM transactions")
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                   tx add                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn adds_a_balanced_transaction() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut command = gnucash_toolbox_tx_add(&book);
+    command.args([
+        "--date",
+        "2024-02-01",
+        "--description",
+        "Bakery",
+        "--num",
+        "42",
+        "Expenses:Food=12.34",
+        "Assets:Bank:Checking=-12.34",
+    ]);
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string("Transaction ")?;
+    process.exp_string(" added.")?;
+    process.exp_eof()?;
+
+    let (guid, num, post_date): (String, String, String) =
+        book.connection()?.query_row(
+            "SELECT guid, num, post_date FROM transactions
+            WHERE description = 'Bakery'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+
+    assert_eq!(num, "42");
+    assert_eq!(post_date, "2024-02-01 10:59:00");
+
+    let values: Vec<(String, i64, i64)> = book
+        .connection()?
+        .prepare(
+            "SELECT account_guid, value_num, value_denom FROM splits
+            WHERE tx_guid = ?1 ORDER BY value_num",
+        )?
+        .query_map([&guid], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<Result<_, _>>()?;
+
+    assert_eq!(
+        values,
+        vec![
+            ("a0000000000000000000000000000003".to_owned(), -1234, 100),
+            ("a0000000000000000000000000000031".to_owned(), 1234, 100),
+        ]
+    );
+
+    let date_posted: String = book.query(&format!(
+        "SELECT gdate_val FROM slots
+        WHERE obj_guid = '{guid}' AND name = 'date-posted'"
+    ))?;
+    assert_eq!(date_posted, "20240201");
+
+    Ok(())
+}
+
+#[test]
+fn stores_the_notes_in_a_slot() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut command = gnucash_toolbox_tx_add(&book);
+    command.args([
+        "--description",
+        "Bakery",
+        "--notes",
+        "Birthday cake",
+        "Expenses:Food=12.34",
+        "Assets:Bank:Checking=-12.34",
+    ]);
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string(" added.")?;
+    process.exp_eof()?;
+
+    let notes: String = book.query(
+        "SELECT s.string_val FROM slots s
+        JOIN transactions t ON t.guid = s.obj_guid
+        WHERE t.description = 'Bakery' AND s.name = 'notes'",
+    )?;
+    assert_eq!(notes, "Birthday cake");
+
+    Ok(())
+}
+
+#[test]
+fn uses_the_price_for_commodity_splits() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut command = gnucash_toolbox_tx_add(&book);
+    command.args([
+        "--description",
+        "Buy AAPL",
+        "Assets:Broker:AAPL=10@150.25",
+        "Assets:Bank:Checking=-1502.50",
+    ]);
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string(" added.")?;
+    process.exp_eof()?;
+
+    let split: (i64, i64, i64, i64) = book.connection()?.query_row(
+        "SELECT value_num, value_denom, quantity_num, quantity_denom
+        FROM splits WHERE account_guid = 'a0000000000000000000000000000007'",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+    )?;
+    assert_eq!(split, (150250, 100, 100000, 10000));
+
+    let currency: String = book.query(
+        "SELECT currency_guid FROM transactions WHERE description = 'Buy AAPL'",
+    )?;
+    assert_eq!(currency, "c0000000000000000000000000000001");
+
+    Ok(())
+}
+
+#[test]
+fn refuses_unbalanced_transactions() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut command = gnucash_toolbox_tx_add(&book);
+    command.args(["Expenses:Food=12.34", "Assets:Bank:Checking=-12"]);
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string("Error: the transaction is unbalanced by 0.34 EUR.")?;
+    process.exp_eof()?;
+
+    assert_eq!(transaction_count(&book)?, 4);
+
+    Ok(())
+}
+
+#[test]
+fn refuses_unknown_accounts() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut command = gnucash_toolbox_tx_add(&book);
+    command.args(["Expenses:Fod=12.34", "Assets:Bank:Checking=-12.34"]);
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string("Error: unknown account: Expenses:Fod.")?;
+    process.exp_eof()?;
+
+    assert_eq!(transaction_count(&book)?, 4);
+
+    Ok(())
+}
+
+#[test]
+fn requires_a_price_for_commodity_splits() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut command = gnucash_toolbox_tx_add(&book);
+    command.args(["Assets:Broker:AAPL=10", "Assets:Bank:Checking=-1500"]);
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string(
+        "Error: the account Assets:Broker:AAPL is in AAPL but the transaction \
+        is in EUR: a price is needed.",
+    )?;
+    process.exp_eof()?;
+
+    assert_eq!(transaction_count(&book)?, 4);
+
+    Ok(())
+}
+
+#[test]
+fn refuses_to_modify_a_locked_book() -> Result<()> {
+    let book = Book::new()?;
+    book.connection()?
+        .execute("INSERT INTO gnclock VALUES ('desktop', 1234)", [])?;
+
+    let mut command = gnucash_toolbox_tx_add(&book);
+    command.args(["Expenses:Food=12.34", "Assets:Bank:Checking=-12.34"]);
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string(
+        "Error: the book is locked by GnuCash on desktop (PID 1234).",
+    )?;
+    process.exp_eof()?;
+
+    assert_eq!(transaction_count(&book)?, 4);
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  tx apply                                  //
+////////////////////////////////////////////////////////////////////////////////
+
+const JOURNAL: &str = "\
+; February expenses.
+account Expenses:Food
+
+2024-02-01 * (42) Bakery  ; id:bakery-1, birthday cake
+    Expenses:Food             12.34 EUR  ; cake
+    Assets:Bank:Checking
+
+2024/02/03 Buy AAPL
+    Assets:Broker:AAPL        10 AAPL @ 150.25 EUR
+    Assets:Bank:Checking      -1502.50 EUR
+";
+
+#[test]
+fn applies_a_journal() -> Result<()> {
+    let book = Book::new()?;
+
+    let command = gnucash_toolbox_tx_apply(&book, JOURNAL)?;
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string("2 transactions added, 0 already in the book.")?;
+    process.exp_eof()?;
+
+    assert_eq!(transaction_count(&book)?, 6);
+
+    let (num, notes): (String, String) = book.connection()?.query_row(
+        "SELECT num, string_val FROM transactions
+        JOIN slots ON obj_guid = guid AND name = 'notes'
+        WHERE description = 'Bakery'",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    assert_eq!(num, "42");
+    assert_eq!(notes, "birthday cake");
+
+    let (memo, state): (String, String) = book.connection()?.query_row(
+        "SELECT memo, reconcile_state FROM splits
+        JOIN transactions ON tx_guid = transactions.guid
+        WHERE description = 'Bakery' AND value_num = 1234",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    assert_eq!(memo, "cake");
+    assert_eq!(state, "c");
+
+    let quantity: i64 = book.query(
+        "SELECT quantity_num FROM splits
+        WHERE account_guid = 'a0000000000000000000000000000007'",
+    )?;
+    assert_eq!(quantity, 100_000);
+
+    Ok(())
+}
+
+#[test]
+fn skips_transactions_already_applied() -> Result<()> {
+    let book = Book::new()?;
+
+    let command = gnucash_toolbox_tx_apply(&book, JOURNAL)?;
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_eof()?;
+
+    let command = gnucash_toolbox_tx_apply(&book, JOURNAL)?;
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string("0 transactions added, 2 already in the book.")?;
+    process.exp_eof()?;
+
+    assert_eq!(transaction_count(&book)?, 6);
+
+    Ok(())
+}
+
+#[test]
+fn applies_identical_transactions_without_an_id() -> Result<()> {
+    let book = Book::new()?;
+
+    let journal = "\
+2024-02-05 Coffee
+    Expenses:Food             2.50 EUR
+    Assets:Bank:Checking
+
+2024-02-05 Coffee
+    Expenses:Food             2.50 EUR
+    Assets:Bank:Checking
+";
+
+    let command = gnucash_toolbox_tx_apply(&book, journal)?;
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string("2 transactions added, 0 already in the book.")?;
+    process.exp_eof()?;
+
+    let command = gnucash_toolbox_tx_apply(&book, journal)?;
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string("0 transactions added, 2 already in the book.")?;
+    process.exp_eof()?;
+
+    assert_eq!(transaction_count(&book)?, 6);
+
+    Ok(())
+}
+
+#[test]
+fn refuses_journals_with_duplicate_ids() -> Result<()> {
+    let book = Book::new()?;
+
+    let journal = format!(
+        "{JOURNAL}
+2024-02-05 Lunch  ; id:bakery-1
+    Expenses:Food             12.34 EUR
+    Assets:Bank:Checking
+"
+    );
+    let command = gnucash_toolbox_tx_apply(&book, &journal)?;
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string("Error: line 12: duplicate ID: bakery-1.")?;
+    process.exp_eof()?;
+
+    assert_eq!(transaction_count(&book)?, 4);
+
+    Ok(())
+}
+
+#[test]
+fn round_trips_the_cleared_states_of_an_exported_journal() -> Result<()> {
+    let book = Book::new()?;
+    let states = |book: &Book| -> Result<Vec<(String, i64, String)>> {
+        let states = book
+            .connection()?
+            .prepare(
+                "SELECT account_guid, value_num, reconcile_state FROM splits
+                ORDER BY account_guid, value_num",
+            )?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<Result<_, _>>()?;
+        Ok(states)
+    };
+    // Ledger has no reconciled state: reconciled splits come back cleared.
+    let exported: Vec<_> = states(&book)?
+        .into_iter()
+        .map(|(account, value, state)| {
+            let state = match state.as_str() {
+                "y" | "f" => "c".to_owned(),
+                "v" => "n".to_owned(),
+                _ => state,
+            };
+            (account, value, state)
+        })
+        .collect();
+    assert!(exported.iter().any(|(_, _, state)| state == "c"));
+
+    let path = book.dir.path().join("export.journal");
+    let mut command = gnc(&book.path);
+    command.args(["export", "ledger", "-o"]).arg(&path);
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_eof()?;
+
+    book.connection()?.execute_batch(
+        "DELETE FROM slots WHERE obj_guid IN (SELECT guid FROM transactions);
+        DELETE FROM splits;
+        DELETE FROM transactions;",
+    )?;
+
+    let journal = fs::read_to_string(&path)?;
+    let command = gnucash_toolbox_tx_apply(&book, &journal)?;
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string("4 transactions added, 0 already in the book.")?;
+    process.exp_eof()?;
+
+    assert_eq!(states(&book)?, exported);
+
+    Ok(())
+}
+
+#[test]
+fn only_starts_comments_after_whitespace() -> Result<()> {
+    let book = Book::new()?;
+
+    let journal = "\
+2024-02-05 ! Fish;Chips  ; id:fish
+    Expenses:Food             8.50 EUR  ;salt;vinegar
+    Assets:Bank:Checking
+";
+
+    let command = gnucash_toolbox_tx_apply(&book, journal)?;
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string("1 transactions added, 0 already in the book.")?;
+    process.exp_eof()?;
+
+    let (memo, state): (String, String) = book.connection()?.query_row(
+        "SELECT memo, reconcile_state FROM splits
+        JOIN transactions ON tx_guid = transactions.guid
+        WHERE description = 'Fish;Chips' AND value_num = 850",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+    assert_eq!(memo, "salt;vinegar");
+    assert_eq!(state, "n");
+
+    Ok(())
+}
+
+#[test]
+fn does_not_modify_the_book_in_dry_run() -> Result<()> {
+    let book = Book::new()?;
+
+    let mut command = gnucash_toolbox_tx_apply(&book, JOURNAL)?;
+    command.arg("--dry-run");
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string("2 transactions to add, 0 already in the book.")?;
+    process.exp_eof()?;
+
+    assert_eq!(transaction_count(&book)?, 4);
+
+    Ok(())
+}
+
+#[test]
+fn refuses_journals_with_unbalanced_transactions() -> Result<()> {
+    let book = Book::new()?;
+
+    let journal = format!(
+        "{JOURNAL}
+2024-02-05 Lunch
+    Expenses:Food             12.34 EUR
+    Assets:Bank:Checking      -12 EUR
+"
+    );
+    let command = gnucash_toolbox_tx_apply(&book, &journal)?;
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string(
+        "Error: line 12: the transaction is unbalanced by 0.34 EUR.",
+    )?;
+    process.exp_eof()?;
+
+    assert_eq!(transaction_count(&book)?, 4);
+
+    Ok(())
+}
+
+#[test]
+fn refuses_journals_with_unknown_accounts() -> Result<()> {
+    let book = Book::new()?;
+
+    let journal = "\
+2024-02-05 Lunch
+    Expenses:Fod              12.34 EUR
+    Assets:Bank:Checking
+";
+    let command = gnucash_toolbox_tx_apply(&book, journal)?;
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string("Error: line 1: unknown account: Expenses:Fod.")?;
+    process.exp_eof()?;
+
+    assert_eq!(transaction_count(&book)?, 4);
+
+    Ok(())
+}
+
+#[test]
+fn refuses_amounts_in_another_commodity() -> Result<()> {
+    let book = Book::new()?;
+
+    let journal = "\
+2024-02-05 Lunch
+    Expenses:Food             12.34 USD
+    Assets:Bank:Checking
+";
+    let command = gnucash_toolbox_tx_apply(&book, journal)?;
+
+    let mut process = spawn_command(command, TIMEOUT)?;
+    process.exp_string(
+        "Error: line 1: the account Expenses:Food is in EUR, not USD.",
+    )?;
+    process.exp_eof()?;
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+// gnucash-toolbox - A CLI toolbox to work with GnuCash databases.
+// Copyright (C) 2024 Jean-Philippe Cugnet <jean-philippe@cugnet.eu>
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, version 3 of the License.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! CLI tests for `gnucash-toolbox vendor`.
+
+// NOTE: rexpect is only compatible with Unix-like systems, so let’s just not
+// compile the CLI tests on Windows.
+#![cfg(not(target_os = "windows"))]
+#![allow(clippy::pedantic, clippy::restriction)]
+
+mod common;
+
+use std::{fs, process::Command};
+
+use eyre::Result;
+use rexpect::session::spawn_command;
+
+use self::common::{gnc, Book, TIMEOUT};
+
+////////////////////////////////////////////////////////////////////////////////
+//                                  Helpers                                   //
+////////////////////////////////////////////////////////////////////////////////
+
+fn gnucash_toolbox_vendor(book: &Book, args: &[&str]) -> Command {
+    let mut cmd = gnc(&book.path);
+    cmd.arg("vendor").args(args);
+    cmd
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                                vendor list                                 //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn lists_the_vendors() -> Result<()> {
+    let book = Book::full()?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_vendor(&book, &["list"]), TIMEOUT)?;
+    process.exp_string("ID      Name             Currency  Email  Active")?;
+    process.exp_string("000001  Office Supplies  EUR              yes")?;
+    process.exp_eof()?;
+
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+//                               vendor import                                //
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn imports_vendors_and_updates_the_references_to_their_terms() -> Result<()> {
+    let book = Book::full()?;
+    let file = book.dir.path().join("vendors.csv");
+    fs::write(
+        &file,
+        "id,name,currency,terms\n\
+        000001,Office Supplies,EUR,Net 30\n\
+        V-10,Paper Co,EUR,Net 30\n",
+    )?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_vendor(&book, &["import", file.to_str().unwrap()]),
+        TIMEOUT,
+    )?;
+    process.exp_string("1 vendors added, 1 updated.")?;
+    process.exp_eof()?;
+
+    let mut process =
+        spawn_command(gnucash_toolbox_vendor(&book, &["export"]), TIMEOUT)?;
+    process.exp_string(
+        "id,name,active,currency,terms,tax_table,notes,address_name,address1,\
+        address2,address3,address4,phone,fax,email",
+    )?;
+    process.exp_string(
+        "000001,Office Supplies,true,EUR,Net 30,,,Office Supplies Ltd,\
+        2 High Street,,,,,,",
+    )?;
+    process.exp_string("V-10,Paper Co,true,EUR,Net 30,,,,,,,,,,")?;
+    process.exp_eof()?;
+
+    assert_eq!(
+        book.query::<i64>(
+            "SELECT refcount FROM billterms
+            WHERE guid = 'b3000000000000000000000000000001'"
+        )?,
+        3
+    );
+
+    Ok(())
+}
+
+#[test]
+fn fails_to_import_a_credit_limit_for_vendors() -> Result<()> {
+    let book = Book::full()?;
+    let file = book.dir.path().join("vendors.csv");
+    fs::write(&file, "id,credit_limit\n000001,1000\n")?;
+
+    let mut process = spawn_command(
+        gnucash_toolbox_vendor(&book, &["import", file.to_str().unwrap()]),
+        TIMEOUT,
+    )?;
+    process.exp_string("Error: line 2: vendors have no credit limit.")?;
+    process.exp_eof()?;
+
+    Ok(())
+}